@@ -0,0 +1,240 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Closed-loop tracking corrections from an external camera-based tracker or plate solver.
+//!
+//! Protocol: one message per line; `OFFSET <dx> <dy>`: target's offset (in pixels) from the image center
+//! (X: right, Y: down). The response is `OK` or `ERROR <message>`.
+//!
+//! In video tracker handoff mode, once the target has stayed within the image for `HANDOFF_STABLE_TIME` during
+//! tracking, TPTool sends `ROI <x> <y>` (expected pixel coordinates of the target; origin: top left corner)
+//! to all clients on every update, and `LOST` once the target is no longer expected within the image.
+
+use crate::{clock, data, guiding::GuideCorrection, line_server::{ClientId, LineServer}};
+use pointing_utils::uom;
+use std::{error::Error, time::{Duration, Instant}};
+use uom::si::{angle, f64};
+
+/// The target must be expected within the image for this long before handing off to the video tracker.
+const HANDOFF_STABLE_TIME: Duration = Duration::from_secs(3);
+
+/// Arcseconds per radian divided by 1000 (ratio of micrometers to millimeters).
+const ARCSEC_PER_RAD_MILLI: f64 = 206.264806;
+
+#[derive(Copy, Clone, Debug)]
+pub struct CameraParams {
+    /// Image scale (arcseconds per pixel).
+    pub pixel_scale: f64,
+    /// Angle between image "up" and the direction of increasing altitude (positive: counter-clockwise).
+    pub rotation: f64::Angle,
+    /// Whether the image is mirrored horizontally (e.g., due to a diagonal).
+    pub flipped: bool,
+    /// Fraction of the reported offset corrected at once (reduces oscillation due to measurement noise and latency).
+    pub gain: f64,
+    /// Image width and height (pixels), if known.
+    pub sensor_size: Option<(u32, u32)>
+}
+
+/// State of the video tracker handoff.
+#[derive(Default)]
+struct Handoff {
+    /// Since when the target has been expected within the image.
+    stable_since: Option<Instant>,
+    active: bool
+}
+
+pub struct CameraCorrections {
+    server: LineServer,
+    params: CameraParams,
+    /// `None` if video tracker handoff is disabled.
+    handoff: Option<Handoff>
+}
+
+impl CameraCorrections {
+    #[must_use]
+    pub fn new(address: &str, params: CameraParams, handoff: bool) -> Result<CameraCorrections, Box<dyn Error>> {
+        let handoff = if handoff && params.sensor_size.is_none() {
+            log::error!("video tracker handoff requires the camera sensor size; disabled");
+            None
+        } else if handoff {
+            Some(Handoff::default())
+        } else {
+            None
+        };
+
+        Ok(CameraCorrections{ server: LineServer::new(address)?, params, handoff })
+    }
+
+    pub fn local_addr(&self) -> String { self.server.local_addr() }
+
+    /// Returns received corrections; invalid messages are answered immediately.
+    pub fn poll(&mut self) -> Vec<(ClientId, GuideCorrection)> {
+        let mut result = vec![];
+        for (client, line) in self.server.poll() {
+            match parse_offset(&line) {
+                Ok((dx, dy)) => result.push((client, offset_to_correction(dx, dy, &self.params))),
+                Err(e) => self.server.send(client, &format!("ERROR {}", e))
+            }
+        }
+
+        result
+    }
+
+    /// Sends the result of applying a correction back to the client.
+    pub fn reply(&mut self, client: ClientId, result: Result<(), Box<dyn Error>>) {
+        match result {
+            Ok(()) => self.server.send(client, "OK"),
+            Err(e) => self.server.send(client, &format!("ERROR {}", e))
+        }
+    }
+
+    /// Updates the video tracker handoff (if enabled).
+    ///
+    /// # Parameters
+    ///
+    /// * `error` - Position error (target minus mount) in azimuth and altitude; `None` if not tracking.
+    /// * `altitude` - Altitude of the mount.
+    ///
+    pub fn update_handoff(&mut self, error: Option<(f64::Angle, f64::Angle)>, altitude: f64::Angle) {
+        let Some(handoff) = self.handoff.as_mut() else { return; };
+        let params = &self.params;
+        let sensor = params.sensor_size.unwrap();
+        let roi = error
+            .map(|error| error_to_pixel(error, altitude, params, sensor))
+            .filter(|(x, y)| (0.0..sensor.0 as f64).contains(x) && (0.0..sensor.1 as f64).contains(y));
+
+        match roi {
+            Some((x, y)) => {
+                let since = *handoff.stable_since.get_or_insert_with(clock::now);
+                if !handoff.active && clock::elapsed(since) >= HANDOFF_STABLE_TIME {
+                    log::info!("tracking stable; handing off to video tracker");
+                    handoff.active = true;
+                }
+                if handoff.active { self.server.broadcast(&format!("ROI {:.1} {:.1}", x, y)); }
+            },
+
+            None => {
+                handoff.stable_since = None;
+                if handoff.active {
+                    log::info!("target no longer expected within the image; video tracker handoff ended");
+                    handoff.active = false;
+                    self.server.broadcast("LOST");
+                }
+            }
+        }
+    }
+}
+
+/// Returns image scale (arcseconds per pixel) for the specified focal length (mm) and pixel size (µm).
+pub fn pixel_scale(focal_length: f64, pixel_size: f64) -> f64 {
+    ARCSEC_PER_RAD_MILLI * pixel_size / focal_length
+}
+
+fn parse_offset(line: &str) -> Result<(f64, f64), Box<dyn Error>> {
+    let mut tokens = line.split_whitespace();
+    if !tokens.next().is_some_and(|cmd| cmd.eq_ignore_ascii_case("OFFSET")) {
+        return Err(format!("unknown command: {}", line).into());
+    }
+    let dx = tokens.next().ok_or("missing X offset")?.parse::<f64>()?;
+    let dy = tokens.next().ok_or("missing Y offset")?.parse::<f64>()?;
+    if !dx.is_finite() || !dy.is_finite() { return Err("invalid offset".into()); }
+
+    Ok((dx, dy))
+}
+
+/// Converts target's offset in the image into a correction of the tracked position.
+fn offset_to_correction(dx: f64, dy: f64, params: &CameraParams) -> GuideCorrection {
+    let dx = if params.flipped { -dx } else { dx };
+    // image "up" corresponds to increasing altitude (for zero rotation)
+    let (h, v) = (dx, -dy);
+    let (sin_r, cos_r) = params.rotation.get::<angle::radian>().sin_cos();
+    let scale = params.gain * params.pixel_scale / 3600.0;
+
+    GuideCorrection{
+        horizontal: data::deg(scale * (h * cos_r - v * sin_r)),
+        vertical: data::deg(scale * (h * sin_r + v * cos_r))
+    }
+}
+
+/// Converts position error (target minus mount) into the target's expected pixel coordinates in an image
+/// of `sensor` size (origin: top left corner); inverse of `offset_to_correction` (without gain).
+fn error_to_pixel(
+    error: (f64::Angle, f64::Angle),
+    altitude: f64::Angle,
+    params: &CameraParams,
+    sensor: (u32, u32)
+) -> (f64, f64) {
+    // azimuth differences shrink on the sky towards the zenith
+    let cos_alt = altitude.get::<angle::radian>().cos().abs();
+    let scale = 3600.0 / params.pixel_scale;
+    let (h, v) = (data::as_deg(error.0) * cos_alt * scale, data::as_deg(error.1) * scale);
+    let (sin_r, cos_r) = params.rotation.get::<angle::radian>().sin_cos();
+    let (dx, up) = (h * cos_r + v * sin_r, -h * sin_r + v * cos_r);
+    let dx = if params.flipped { -dx } else { dx };
+
+    (sensor.0 as f64 / 2.0 + dx, sensor.1 as f64 / 2.0 - up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_conversion() {
+        let mut params = CameraParams{
+            pixel_scale: 3.6,
+            rotation: data::deg(0.0),
+            flipped: false,
+            gain: 1.0,
+            sensor_size: None
+        };
+
+        let c = offset_to_correction(1000.0, 500.0, &params);
+        assert!((data::as_deg(c.horizontal) - 1.0).abs() < 1.0e-9);
+        assert!((data::as_deg(c.vertical) + 0.5).abs() < 1.0e-9);
+
+        params.rotation = data::deg(90.0);
+        params.flipped = true;
+        params.gain = 0.5;
+        let c = offset_to_correction(1000.0, 0.0, &params);
+        assert!(data::as_deg(c.horizontal).abs() < 1.0e-9);
+        assert!((data::as_deg(c.vertical) + 0.5).abs() < 1.0e-9);
+
+        assert!(parse_offset("OFFSET 1.5 -2").is_ok());
+        assert!(parse_offset("OFFSET 1.5").is_err());
+    }
+
+    #[test]
+    fn pixel_coordinates() {
+        assert!((pixel_scale(1000.0, 5.0) - 1.0313).abs() < 1.0e-4);
+
+        let params = CameraParams{
+            pixel_scale: 2.0,
+            rotation: data::deg(30.0),
+            flipped: true,
+            gain: 1.0,
+            sensor_size: Some((1920, 1080))
+        };
+        let altitude = data::deg(60.0);
+        let c = offset_to_correction(100.0, -50.0, &params);
+        let error = (c.horizontal / altitude.get::<angle::radian>().cos(), c.vertical);
+        let (x, y) = error_to_pixel(error, altitude, &params, (1920, 1080));
+        assert!((x - 1060.0).abs() < 1.0e-6);
+        assert!((y - 490.0).abs() < 1.0e-6);
+    }
+}
@@ -0,0 +1,152 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Camera trigger output: pulses a control line of a serial port or sends a command over TCP.
+
+use std::{error::Error, io::Write, time::{Duration, Instant}};
+
+const SERIAL_BAUD_RATE: u32 = 9600;
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Output used to trigger the camera; in configuration: "dtr:<device>", "rts:<device>" or "tcp:<address>".
+#[derive(Clone, Debug, PartialEq)]
+pub enum TriggerOutput {
+    /// DTR line of a serial port (e.g., "/dev/ttyUSB0" or "COM3").
+    SerialDtr(String),
+    /// RTS line of a serial port.
+    SerialRts(String),
+    /// TCP address to which the trigger command is sent.
+    Network(String)
+}
+
+impl std::fmt::Display for TriggerOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriggerOutput::SerialDtr(device) => write!(f, "dtr:{}", device),
+            TriggerOutput::SerialRts(device) => write!(f, "rts:{}", device),
+            TriggerOutput::Network(address) => write!(f, "tcp:{}", address)
+        }
+    }
+}
+
+impl std::str::FromStr for TriggerOutput {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, param) = s.split_once(':').ok_or_else(|| format!("invalid trigger output: {}", s))?;
+        let param = param.trim();
+        if param.is_empty() { return Err(format!("invalid trigger output: {}", s).into()); }
+        match kind.trim().to_lowercase().as_str() {
+            "dtr" => Ok(TriggerOutput::SerialDtr(param.into())),
+            "rts" => Ok(TriggerOutput::SerialRts(param.into())),
+            "tcp" => Ok(TriggerOutput::Network(param.into())),
+            _ => Err(format!("invalid trigger output type: {}", kind).into())
+        }
+    }
+}
+
+enum Output {
+    Serial{ port: Box<dyn serialport::SerialPort>, dtr: bool },
+    Network{ address: String, command: String }
+}
+
+pub struct CameraTrigger {
+    output: Output,
+    description: String,
+    pulse: Duration,
+    /// End of the current serial line pulse.
+    pulse_end: Option<Instant>
+}
+
+impl CameraTrigger {
+    /// Creates a trigger; `pulse` is the duration of serial line pulses, `command` is sent for network output.
+    #[must_use]
+    pub fn new(output: &TriggerOutput, pulse: Duration, command: &str) -> Result<CameraTrigger, Box<dyn Error>> {
+        let description = output.to_string();
+        let output = match output {
+            TriggerOutput::SerialDtr(device) | TriggerOutput::SerialRts(device) => {
+                let mut port = serialport::new(device, SERIAL_BAUD_RATE).open()?;
+                let dtr = matches!(output, TriggerOutput::SerialDtr(_));
+                set_line(port.as_mut(), dtr, false)?;
+                Output::Serial{ port, dtr }
+            },
+
+            TriggerOutput::Network(address) => Output::Network{ address: address.clone(), command: command.into() }
+        };
+
+        Ok(CameraTrigger{ output, description, pulse, pulse_end: None })
+    }
+
+    pub fn description(&self) -> &str { &self.description }
+
+    /// Triggers the camera: starts a serial line pulse or sends the network command.
+    pub fn trigger(&mut self) -> Result<(), Box<dyn Error>> {
+        match &mut self.output {
+            Output::Serial{ port, dtr } => {
+                set_line(port.as_mut(), *dtr, true)?;
+                self.pulse_end = Some(Instant::now() + self.pulse);
+            },
+
+            Output::Network{ address, command } => {
+                let addr = std::net::ToSocketAddrs::to_socket_addrs(address.as_str())?
+                    .next()
+                    .ok_or_else(|| format!("cannot resolve {}", address))?;
+                let mut stream = std::net::TcpStream::connect_timeout(&addr, NETWORK_TIMEOUT)?;
+                stream.set_write_timeout(Some(NETWORK_TIMEOUT))?;
+                stream.write_all(format!("{}\n", command).as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ends the current serial line pulse once its duration has elapsed; to be called periodically.
+    pub fn poll(&mut self) -> Result<(), Box<dyn Error>> {
+        if !self.pulse_end.is_some_and(|end| Instant::now() >= end) { return Ok(()); }
+        self.pulse_end = None;
+        if let Output::Serial{ port, dtr } = &mut self.output {
+            set_line(port.as_mut(), *dtr, false)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn set_line(port: &mut dyn serialport::SerialPort, dtr: bool, level: bool) -> Result<(), Box<dyn Error>> {
+    if dtr {
+        port.write_data_terminal_ready(level)?;
+    } else {
+        port.write_request_to_send(level)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_output_parsing() {
+        assert_eq!(TriggerOutput::SerialDtr("/dev/ttyUSB0".into()), "dtr:/dev/ttyUSB0".parse().unwrap());
+        assert_eq!(TriggerOutput::SerialRts("COM3".into()), "RTS:COM3".parse().unwrap());
+        assert_eq!(TriggerOutput::Network("127.0.0.1:4050".into()), "tcp:127.0.0.1:4050".parse().unwrap());
+        assert!("usb:foo".parse::<TriggerOutput>().is_err());
+        assert!("dtr:".parse::<TriggerOutput>().is_err());
+        assert!("dtr".parse::<TriggerOutput>().is_err());
+    }
+}
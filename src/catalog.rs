@@ -0,0 +1,76 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Small catalog of bright stars, used as reference objects.
+
+use crate::data::{self, deg, ObserverLocation};
+use pointing_utils::uom;
+use uom::si::{angle, f64};
+
+pub struct Star {
+    pub name: &'static str,
+    /// Right ascension (J2000.0; degrees).
+    pub ra: f64,
+    /// Declination (J2000.0; degrees).
+    pub dec: f64
+}
+
+pub const BRIGHT_STARS: &[Star] = &[
+    Star{ name: "Sirius", ra: 101.2872, dec: -16.7161 },
+    Star{ name: "Canopus", ra: 95.9880, dec: -52.6957 },
+    Star{ name: "Rigil Kentaurus", ra: 219.9021, dec: -60.8340 },
+    Star{ name: "Arcturus", ra: 213.9153, dec: 19.1824 },
+    Star{ name: "Vega", ra: 279.2347, dec: 38.7837 },
+    Star{ name: "Capella", ra: 79.1723, dec: 45.9980 },
+    Star{ name: "Rigel", ra: 78.6345, dec: -8.2016 },
+    Star{ name: "Procyon", ra: 114.8255, dec: 5.2250 },
+    Star{ name: "Achernar", ra: 24.4285, dec: -57.2368 },
+    Star{ name: "Betelgeuse", ra: 88.7929, dec: 7.4071 },
+    Star{ name: "Altair", ra: 297.6958, dec: 8.8683 },
+    Star{ name: "Acrux", ra: 186.6496, dec: -63.0991 },
+    Star{ name: "Aldebaran", ra: 68.9802, dec: 16.5093 },
+    Star{ name: "Antares", ra: 247.3519, dec: -26.4320 },
+    Star{ name: "Spica", ra: 201.2983, dec: -11.1613 },
+    Star{ name: "Pollux", ra: 116.3290, dec: 28.0262 },
+    Star{ name: "Fomalhaut", ra: 344.4127, dec: -29.6222 },
+    Star{ name: "Deneb", ra: 310.3580, dec: 45.2803 },
+    Star{ name: "Regulus", ra: 152.0930, dec: 11.9672 },
+    Star{ name: "Polaris", ra: 37.9546, dec: 89.2641 }
+];
+
+impl Star {
+    /// Returns right ascension and declination of date (precession only; approximate).
+    pub fn ra_dec(&self, t: chrono::DateTime<chrono::Utc>) -> (f64::Angle, f64::Angle) {
+        let years = data::julian_centuries(t) * 100.0;
+        let ra = deg(self.ra);
+        let dec = deg(self.dec);
+        let (sin_ra, cos_ra) = ra.get::<angle::radian>().sin_cos();
+        let tan_dec = dec.get::<angle::radian>().tan();
+
+        // annual precession: RA 3.075 s + 1.336 s * sin(RA) * tan(Dec), Dec 20.04" * cos(RA)
+        let d_ra = (3.075 + 1.336 * sin_ra * tan_dec) * 15.0 / 3600.0 * years;
+        let d_dec = 20.04 / 3600.0 * cos_ra * years;
+
+        (deg(self.ra + d_ra), deg(self.dec + d_dec))
+    }
+
+    /// Returns azimuth and altitude (refraction is not accounted for).
+    pub fn az_alt(&self, observer: &ObserverLocation, t: chrono::DateTime<chrono::Utc>) -> (f64::Angle, f64::Angle) {
+        let (ra, dec) = self.ra_dec(t);
+        data::ra_dec_to_az_alt(ra, dec, observer, t)
+    }
+}
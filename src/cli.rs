@@ -0,0 +1,88 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::mount::MountType;
+use std::path::PathBuf;
+
+#[derive(clap::Parser)]
+#[command(version, about = "Telescope Pointing Tool: following a target in the sky")]
+pub struct Args {
+    /// Run without the text user interface.
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Connect to mount on startup; e.g., "ioptron:/dev/ttyUSB0" or "simulator:127.0.0.1:45501".
+    #[arg(long, value_name = "TYPE:PARAM", value_parser = parse_mount)]
+    pub mount: Option<(MountType, String)>,
+
+    /// Connect to data source on startup; e.g., "127.0.0.1:45500".
+    #[arg(long, value_name = "HOST:PORT")]
+    pub data_source: Option<String>,
+
+    /// Start tracking once the mount is connected and target data are available.
+    #[arg(long)]
+    pub track_on_start: bool,
+
+    /// Configuration file to use instead of the default one.
+    #[arg(long, value_name = "PATH", conflicts_with = "profile")]
+    pub config: Option<PathBuf>,
+
+    /// Configuration profile to use (stored in "tptool-<NAME>.cfg" next to the default configuration file).
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Run the program clock at the specified rate relative to real time (for testing with the internal mount
+    /// simulator and simulated targets; e.g., 10).
+    #[arg(long, value_name = "RATE", value_parser = parse_clock_rate)]
+    pub clock_rate: Option<f64>,
+}
+
+fn parse_mount(s: &str) -> Result<(MountType, String), String> {
+    let (mount_type, param) = s.split_once(':').unwrap_or((s, ""));
+    let mount_type = mount_type.parse::<MountType>().map_err(|e| format!(
+        "{} (expected one of: {})",
+        e, MountType::all().iter().map(|t| t.id().to_lowercase()).collect::<Vec<_>>().join(", ")
+    ))?;
+    if param.is_empty() && !mount_type.backend().param_optional {
+        return Err("missing connection parameter".into());
+    }
+
+    Ok((mount_type, param.to_string()))
+}
+
+fn parse_clock_rate(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        _ => Err(format!("invalid clock rate \"{}\" (expected a positive number)", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mount_arg_parsing() {
+        let parse = |s| parse_mount(s).map(|(t, param)| (t.id(), param));
+        assert_eq!(("Ioptron", "/dev/ttyUSB0".to_string()), parse("ioptron:/dev/ttyUSB0").unwrap());
+        assert_eq!(("Simulator", "127.0.0.1:45501".to_string()), parse("Simulator:127.0.0.1:45501").unwrap());
+        assert_eq!(("InternalSimulator", "".to_string()), parse("internalsimulator").unwrap());
+        assert!(parse_mount("foo:bar").is_err());
+        assert!(parse_mount("ioptron").is_err());
+    }
+}
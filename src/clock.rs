@@ -0,0 +1,112 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Program clock used by tracking, mount speed estimation, timers and the simulators. By default it follows
+//! the system clock; a simulated clock runs at a different rate (e.g., to replay data or test faster than real
+//! time) or only advances when told to (in unit tests).
+
+use std::{cell::RefCell, time::{Duration, Instant}};
+
+enum Clock {
+    System,
+    Simulated{
+        /// Clock time when the simulated clock was started.
+        start: Instant,
+        start_utc: chrono::DateTime<chrono::Utc>,
+        /// Real time when the simulated clock was started.
+        real_start: Instant,
+        /// Sum of manual advances.
+        offset: Duration,
+        /// Clock rate relative to real time (0: the clock only advances via `advance`).
+        rate: f64
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::System);
+}
+
+pub fn now() -> Instant {
+    CLOCK.with(|clock| match &*clock.borrow() {
+        Clock::System => Instant::now(),
+        Clock::Simulated{ start, real_start, offset, rate, .. } =>
+            *start + *offset + real_start.elapsed().mul_f64(*rate)
+    })
+}
+
+/// Returns time elapsed since `t` (zero if `t` is in the future).
+pub fn elapsed(t: Instant) -> Duration {
+    now().saturating_duration_since(t)
+}
+
+/// Returns the current UTC time according to the clock.
+pub fn utc_now() -> chrono::DateTime<chrono::Utc> {
+    let simulated = CLOCK.with(|clock| match &*clock.borrow() {
+        Clock::System => None,
+        Clock::Simulated{ start, start_utc, .. } => Some((*start, *start_utc))
+    });
+    match simulated {
+        None => chrono::Utc::now(),
+        Some((start, start_utc)) => start_utc + chrono::Duration::from_std(elapsed(start)).unwrap_or(chrono::Duration::zero())
+    }
+}
+
+/// Switches to a simulated clock (starting at the current time) running `rate` times faster than real time;
+/// with `rate` = 0 the clock only advances via `advance`.
+pub fn set_simulated(rate: f64) {
+    let (start, start_utc) = (now(), utc_now());
+    CLOCK.with(|clock| *clock.borrow_mut() = Clock::Simulated{
+        start,
+        start_utc,
+        real_start: Instant::now(),
+        offset: Duration::ZERO,
+        rate: rate.max(0.0)
+    });
+}
+
+/// Advances the simulated clock (no effect if the system clock is used).
+pub fn advance(duration: Duration) {
+    CLOCK.with(|clock| match &mut *clock.borrow_mut() {
+        Clock::System => log::warn!("cannot advance the system clock"),
+        Clock::Simulated{ offset, .. } => *offset += duration
+    });
+}
+
+/// Returns the real time corresponding to `duration` of clock time (`None` if the clock does not run by itself).
+pub fn real_duration(duration: Duration) -> Option<Duration> {
+    CLOCK.with(|clock| match &*clock.borrow() {
+        Clock::System => Some(duration),
+        Clock::Simulated{ rate, .. } => if *rate > 0.0 { Some(duration.div_f64(*rate)) } else { None }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_clock_advances_manually() {
+        set_simulated(0.0);
+        let t0 = now();
+        let utc0 = utc_now();
+        advance(Duration::from_secs(10));
+        assert_eq!(Duration::from_secs(10), elapsed(t0));
+        assert_eq!(chrono::Duration::seconds(10), utc_now() - utc0);
+        assert_eq!(None, real_duration(Duration::from_secs(1)));
+    }
+}
@@ -16,44 +16,299 @@
 // along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
 //
 
-use crate::{controller, controller::{ActionAssignments, TargetAction}, data, data::{as_deg, deg}};
+use crate::{camera_tracking, camera_tracking::CameraParams, camera_trigger, controller, controller::{ActionAssignments, AxisResponse, TargetAction}, data, data::{as_deg, as_deg_per_s, deg, deg_per_s}, fov, mount, overlay, refraction, search_pattern, session_log, target_alarm, target_message, target_source, tracking, tui::color_scheme::ColorScheme};
 use configparser::ini::Ini;
+use pointing_utils::uom;
 use std::path::{Path, PathBuf};
 use strum::IntoEnumIterator;
+use uom::si::{f64, length};
 
 const CONFIG_FILE_NAME: &str = "tptool.cfg";
+/// Configuration files of named profiles are called "tptool-<profile>.cfg".
+const PROFILE_FILE_PREFIX: &str = "tptool-";
+const CONFIG_FILE_EXTENSION: &str = ".cfg";
 
 mod sections {
     pub const CONTROLLER: &str = "Controller";
+    /// Prefix of sections of named controller profiles (e.g., "Controller:gamepad").
+    pub const CONTROLLER_PROFILE_PREFIX: &str = "Controller:";
     pub const MAIN: &str = "Main";
+    /// Calibration of the last connected (main) mount.
+    pub const MOUNT_STATE: &str = "MountState";
     pub const REF_POS_PRESETS: &str = "ReferencePositionPresets";
+    pub const LANDMARKS: &str = "Landmarks";
+    pub const SITES: &str = "Sites";
+    /// Lifetime axis travel of all mounts used so far.
+    pub const ODOMETERS: &str = "Odometers";
+    pub const SAVED_ADJUSTMENTS: &str = "SavedAdjustments";
 }
 
 mod keys {
     pub const MOUNT_TYPE: &str = "MountType";
-    pub const MOUNT_SIM_ADDRESS: &str = "MountSimulatorAddr";
-    pub const MOUNT_IOPTRON_DEVICE: &str = "MountIoptronDevice";
+    pub const CONTROLLER_PROFILE: &str = "ControllerProfile";
+    /// Comma-separated hexadecimal IDs of controllers whose events are ignored.
+    pub const DISABLED_CONTROLLERS: &str = "DisabledControllers";
     pub const DATA_SOURCE_ADDRESS: &str = "DataSourceAddr";
+    pub const DATA_SOURCE_AUTO_RECONNECT: &str = "DataSourceAutoReconnect";
+    pub const RECONNECT_LAST_SESSION: &str = "ReconnectLastSession";
+    pub const DATA_SOURCE_FORMAT: &str = "DataSourceFormat";
+    pub const COLOR_SCHEME: &str = "ColorScheme";
+    pub const SIMULATED_TARGET_PARAMS: &str = "SimulatedTargetParams";
     pub const REF_POS_PRESET: &str = "preset";
+    /// Latitude and longitude (degrees), elevation (m) and name, separated by ';'.
+    pub const LANDMARK: &str = "landmark";
+    /// Latitude and longitude (degrees), elevation (m), magnetic declination (degrees) and name, separated by ';'.
+    pub const SITE: &str = "site";
+    /// Lifetime travel of axis 1 and 2 (degrees) and mount identity, separated by ';'.
+    pub const ODOMETER: &str = "odometer";
+    pub const SAVED_ADJUSTMENT: &str = "adjustment";
     pub const MOUNT_AXIS1_REVERSED: &str = "MountAxis1Reversed";
     pub const MOUNT_AXIS2_REVERSED: &str = "MountAxis2Reversed";
+    pub const MOUNT_AXIS_DEAD_ZONE: [&str; 2] = ["MountAxis1DeadZone", "MountAxis2DeadZone"];
+    pub const MOUNT_AXIS_EXPONENT: [&str; 2] = ["MountAxis1Exponent", "MountAxis2Exponent"];
+    pub const MOUNT_AXIS_SENSITIVITY: [&str; 2] = ["MountAxis1Sensitivity", "MountAxis2Sensitivity"];
+    pub const MOUNT_AXIS_RATE_MAPPING: &str = "MountAxisRateMapping";
+    /// If true, during tracking mount axis 1 rotates the adjustment direction and axis 2 changes its magnitude.
+    pub const POLAR_ADJUSTMENT: &str = "PolarAdjustment";
+    pub const OBSERVER_LAT: &str = "ObserverLatitude";
+    pub const OBSERVER_LON: &str = "ObserverLongitude";
+    pub const OBSERVER_ELEVATION: &str = "ObserverElevation";
+    /// Name of the last used site (see `SITES`).
+    pub const CURRENT_SITE: &str = "Site";
+    /// Degrees (positive: magnetic north is east of true north).
+    pub const MAGNETIC_DECLINATION: &str = "MagneticDeclination";
+    /// World Magnetic Model coefficient file (e.g., "WMM.COF").
+    pub const WMM_FILE: &str = "WmmFile";
+    pub const TLE_FILE: &str = "TleFile";
+    pub const SATELLITE_OPTICAL_POSITION: &str = "SatelliteOpticalPosition";
+    pub const TARGET_QUEUE_FILE: &str = "TargetQueueFile";
+    pub const GPS_DEVICE: &str = "GpsDevice";
+    pub const TIME_SYNC_SERVER: &str = "TimeSyncServer";
+    pub const TIME_OFFSET_WARNING: &str = "TimeOffsetWarning";
+    pub const GUIDING_SERVER_ADDRESS: &str = "GuidingServerAddr";
+    pub const GUIDE_RATE: &str = "GuideRate";
+    pub const CAMERA_SERVER_ADDRESS: &str = "CameraServerAddr";
+    pub const CAMERA_PIXEL_SCALE: &str = "CameraPixelScale";
+    pub const CAMERA_ROTATION: &str = "CameraRotation";
+    pub const CAMERA_FLIPPED: &str = "CameraFlipped";
+    pub const CAMERA_CORRECTION_GAIN: &str = "CameraCorrectionGain";
+    /// Millimeters.
+    pub const CAMERA_FOCAL_LENGTH: &str = "CameraFocalLength";
+    /// Micrometers.
+    pub const CAMERA_PIXEL_SIZE: &str = "CameraPixelSize";
+    /// Pixels.
+    pub const CAMERA_SENSOR_WIDTH: &str = "CameraSensorWidth";
+    /// Pixels.
+    pub const CAMERA_SENSOR_HEIGHT: &str = "CameraSensorHeight";
+    pub const VIDEO_TRACKER_HANDOFF: &str = "VideoTrackerHandoff";
+    pub const CAMERA_TRIGGER_OUTPUT: &str = "CameraTriggerOutput";
+    pub const CAMERA_TRIGGER_PULSE: &str = "CameraTriggerPulse";
+    pub const CAMERA_TRIGGER_COMMAND: &str = "CameraTriggerCommand";
+    pub const SESSION_LOG_FORMAT: &str = "SessionLogFormat";
+    pub const STATE_BROADCAST_ADDRESS: &str = "StateBroadcastAddr";
+    pub const TELEMETRY_OUTPUT: &str = "TelemetryOutput";
+    pub const SCRIPT_FILE: &str = "ScriptFile";
+    pub const OVERLAY_OUTPUT: &str = "OverlayOutput";
+    pub const OVERLAY_TEMPLATE: &str = "OverlayTemplate";
+    pub const SESSION_LOG_DIR: &str = "SessionLogDir";
+    pub const SESSION_LOG_FIELDS: &str = "SessionLogFields";
+    pub const TARGET_STALE_TIMEOUT: &str = "TargetStaleTimeout";
+    /// Kilometers.
+    pub const TARGET_ALARM_DISTANCE: &str = "TargetAlarmDistance";
+    /// Degrees.
+    pub const TARGET_ALARM_ALTITUDE: &str = "TargetAlarmAltitude";
+    pub const TARGET_ALARM_SOUND: &str = "TargetAlarmSound";
+    /// Degrees.
+    pub const SUN_AVOIDANCE_RADIUS: &str = "SunAvoidanceRadius";
+    pub const REFRACTION_CORRECTION: &str = "RefractionCorrection";
+    /// Degrees Celsius.
+    pub const AIR_TEMPERATURE: &str = "AirTemperature";
+    /// Hectopascals.
+    pub const AIR_PRESSURE: &str = "AirPressure";
+    /// Degrees.
+    pub const CAMERA_FOV_WIDTH: &str = "CameraFovWidth";
+    /// Degrees.
+    pub const CAMERA_FOV_HEIGHT: &str = "CameraFovHeight";
+    pub const SLEW_SPEED: &str = "SlewSpeed";
+    pub const MOUNT_MAX_ACCELERATION: &str = "MountMaxAcceleration";
+    pub const MOUNT_RATE_SCALE: &str = "MountRateScale";
+    /// Degrees.
+    pub const MOUNT_MAX_AXIS_TRAVEL: &str = "MountMaxAxisTravel";
+    pub const MOUNT_AXES_SWAPPED: &str = "MountAxesSwapped";
+    /// Degrees per second.
+    pub const MOUNT_COMMAND_EPSILON: &str = "MountCommandEpsilon";
+    /// Degrees.
+    pub const MOUNT_ALTITUDE_LIMIT: &str = "MountAltitudeLimit";
+    pub const MOUNT_DRIFT_MONITOR: &str = "MountDriftMonitor";
+    pub const MOUNT_PUSH_TO: &str = "MountPushTo";
+    pub const PARK_ON_EXIT: &str = "ParkOnExit";
+    pub const MOUNT_DRIFT_WARNING: &str = "MountDriftWarning";
+    pub const MOUNT_SPEED_FILTER: &str = "MountSpeedFilter";
+    /// Milliseconds.
+    pub const MAIN_TIMER_INTERVAL: &str = "MainTimerInterval";
+    /// Milliseconds.
+    pub const TRACKING_TIMER_INTERVAL: &str = "TrackingTimerInterval";
+    pub const SLEW_SPEED_PRESETS: &str = "SlewSpeedPresets";
+    /// Preceded by mount type (e.g., "IoptronMinSlewSpeed").
+    pub const MIN_SLEW_SPEED_SUFFIX: &str = "MinSlewSpeed";
+    /// Preceded by mount type (e.g., "IoptronMaxSlewSpeed").
+    pub const MAX_SLEW_SPEED_SUFFIX: &str = "MaxSlewSpeed";
+    pub const TARGET_LOSS_ACTION: &str = "TargetLossAction";
+    pub const TARGET_COAST_TIME: &str = "TargetCoastTime";
+    pub const SEARCH_PATTERN: &str = "SearchPattern";
+    /// Degrees per second.
+    pub const SEARCH_RATE: &str = "SearchRate";
+    /// Degrees.
+    pub const SEARCH_SPACING: &str = "SearchSpacing";
+    pub const TRACKING_PAUSE_MODE: &str = "TrackingPauseMode";
+    /// Seconds.
+    pub const TRACKING_HANDOVER_TIME: &str = "TrackingHandoverTime";
+    /// Degrees per second.
+    pub const TRACKING_TRIM_STEP: &str = "TrackingTrimStep";
+    pub const LOG_LEVEL: &str = "LogLevel";
+    pub const REMOTE_LOG: &str = "RemoteLog";
+    pub const ANGLE_FORMAT: &str = "AngleFormat";
+    pub const AZIMUTH_ORIGIN: &str = "AzimuthOrigin";
+    pub const AZIMUTH_RANGE: &str = "AzimuthRange";
+    pub const SPEED_UNIT: &str = "SpeedUnit";
+    /// Mount type and connection parameter (e.g., "Ioptron:/dev/ttyUSB0").
+    pub const MOUNT_STATE_ID: &str = "Mount";
+    pub const MOUNT_STATE_AXIS_OFS: &str = "AxisOffsets";
+    pub const MOUNT_STATE_ZERO_POS: &str = "ZeroPosition";
+    pub const MOUNT_STATE_TRAVEL: &str = "TotalAxisTravel";
+    pub const MOUNT_STATE_ADJUSTMENT: &str = "Adjustment";
 }
 
 const MAX_NUM_REF_POS_PRESETS: usize = 128;
+const MAX_NUM_SAVED_ADJUSTMENTS: usize = 128;
+const MAX_NUM_LANDMARKS: usize = 128;
+const MAX_NUM_SITES: usize = 128;
+const MAX_NUM_ODOMETERS: usize = 128;
+
+/// Approx. sidereal rate.
+const DEFAULT_GUIDE_RATE_DEG_PER_S: f64 = 0.0042;
+
+const DEFAULT_SLEW_SPEED_PRESETS_DEG_PER_S: [f64; 3] = [5.0, 1.0, 0.1];
+const DEFAULT_MIN_SLEW_SPEED_DEG_PER_S: f64 = 0.01;
+const DEFAULT_MAX_SLEW_SPEED_DEG_PER_S: f64 = 5.0;
+
+const DEFAULT_MOUNT_DRIFT_WARNING_DEG: f64 = 0.1;
+
+const DEFAULT_MAIN_TIMER_INTERVAL_MS: u64 = 250;
+const MAIN_TIMER_INTERVAL_RANGE_MS: (u64, u64) = (50, 2000);
+const DEFAULT_TRACKING_TIMER_INTERVAL_MS: u64 = 500;
+const TRACKING_TIMER_INTERVAL_RANGE_MS: (u64, u64) = (100, 5000);
+const DEFAULT_TRACKING_HANDOVER_TIME_S: f64 = 1.5;
+const DEFAULT_TRACKING_TRIM_STEP_DEG_PER_S: f64 = 0.001;
+
+const DEFAULT_TIME_SYNC_SERVER: &str = "pool.ntp.org:123";
+const DEFAULT_TIME_OFFSET_WARNING_S: f64 = 0.5;
+
+const DEFAULT_CAMERA_PIXEL_SCALE: f64 = 1.0; // arcsec/pixel
+const DEFAULT_CAMERA_CORRECTION_GAIN: f64 = 0.5;
+const DEFAULT_CAMERA_TRIGGER_PULSE_MS: f64 = 200.0;
+const DEFAULT_CAMERA_TRIGGER_COMMAND: &str = "TRIGGER";
+
+const DEFAULT_AIR_TEMPERATURE_C: f64 = 10.0;
+const DEFAULT_AIR_PRESSURE_HPA: f64 = 1010.0;
+
+/// Allowed ranges of numeric values (in the units of the respective keys) checked by `Configuration::validate`.
+const NUMERIC_RANGES: [(&str, f64, f64); 30] = [
+    (keys::OBSERVER_LAT, -90.0, 90.0),
+    (keys::OBSERVER_LON, -180.0, 360.0),
+    (keys::OBSERVER_ELEVATION, -500.0, 100_000.0),
+    (keys::MAGNETIC_DECLINATION, -180.0, 180.0),
+    (keys::TIME_OFFSET_WARNING, 0.0, 3600.0),
+    (keys::GUIDE_RATE, 0.0, 1.0),
+    (keys::CAMERA_PIXEL_SCALE, 0.0, 3600.0),
+    (keys::CAMERA_ROTATION, -360.0, 360.0),
+    (keys::CAMERA_CORRECTION_GAIN, 0.0, 1.0),
+    (keys::CAMERA_FOCAL_LENGTH, 0.0, 100_000.0),
+    (keys::CAMERA_PIXEL_SIZE, 0.0, 1000.0),
+    (keys::CAMERA_SENSOR_WIDTH, 1.0, 100_000.0),
+    (keys::CAMERA_SENSOR_HEIGHT, 1.0, 100_000.0),
+    (keys::CAMERA_TRIGGER_PULSE, 0.0, 10_000.0),
+    (keys::TARGET_STALE_TIMEOUT, 0.0, 3600.0),
+    (keys::TARGET_COAST_TIME, 0.0, 3600.0),
+    (keys::TARGET_ALARM_DISTANCE, 0.0, 1.0e6),
+    (keys::TARGET_ALARM_ALTITUDE, -90.0, 90.0),
+    (keys::SUN_AVOIDANCE_RADIUS, 0.0, 180.0),
+    (keys::AIR_TEMPERATURE, -80.0, 60.0),
+    (keys::AIR_PRESSURE, 300.0, 1100.0),
+    (keys::CAMERA_FOV_WIDTH, 0.0, 180.0),
+    (keys::CAMERA_FOV_HEIGHT, 0.0, 180.0),
+    (keys::SLEW_SPEED, 0.0, 100.0),
+    (keys::MOUNT_MAX_ACCELERATION, 0.0, 100.0),
+    (keys::MOUNT_MAX_AXIS_TRAVEL, 0.0, 36_000.0),
+    (keys::MOUNT_COMMAND_EPSILON, 0.0, 1.0),
+    (keys::MOUNT_ALTITUDE_LIMIT, -90.0, 90.0),
+    (keys::MOUNT_DRIFT_WARNING, 0.0, 180.0),
+    (keys::SEARCH_RATE, 0.0, 100.0),
+    (keys::SEARCH_SPACING, 0.0, 90.0),
+    (keys::TRACKING_HANDOVER_TIME, 0.0, 60.0),
+    (keys::TRACKING_TRIM_STEP, 0.0, 1.0),
+    (keys::MAIN_TIMER_INTERVAL, MAIN_TIMER_INTERVAL_RANGE_MS.0 as f64, MAIN_TIMER_INTERVAL_RANGE_MS.1 as f64),
+    (
+        keys::TRACKING_TIMER_INTERVAL,
+        TRACKING_TIMER_INTERVAL_RANGE_MS.0 as f64,
+        TRACKING_TIMER_INTERVAL_RANGE_MS.1 as f64
+    )
+];
+
+/// Configuration entry which cannot be used (its default is used instead).
+#[derive(Clone)]
+pub struct ConfigIssue {
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    pub problem: String
+}
+
+impl ConfigIssue {
+    fn new(section: &str, key: &str, value: &str, problem: String) -> ConfigIssue {
+        ConfigIssue{ section: section.into(), key: key.into(), value: value.into(), problem }
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {} = \"{}\": {}", self.section, self.key, self.value, self.problem)
+    }
+}
 
 pub struct Configuration {
-    config_file: Ini
+    config_file: Ini,
+    file_path: PathBuf,
+    /// Section of the active controller profile.
+    controller_section: String
 }
 
 impl Configuration {
     pub fn store(&self) -> Result<(), std::io::Error> {
-        self.config_file.write(config_file_path())
+        self.config_file.write(&self.file_path)
+    }
+
+    /// Loads configuration of the specified profile (the default one if `None`).
+    pub fn from_profile(profile: Option<&str>) -> Configuration {
+        Configuration::from_file(profile_file_path(profile))
     }
 
-    pub fn new() -> Configuration {
+    pub fn file_path(&self) -> &Path { &self.file_path }
+
+    /// Returns the profile name (`None` for the default configuration file or a file specified explicitly).
+    pub fn profile(&self) -> Option<String> {
+        profiles().into_iter().find(|p| profile_file_path(Some(p)) == self.file_path)
+    }
+
+    /// Stores a copy of the configuration as the specified profile.
+    pub fn copy_to_profile(&self, profile: &str) -> Result<(), std::io::Error> {
+        self.config_file.write(profile_file_path(Some(profile)))
+    }
+
+    /// Loads configuration from the specified file (which will be created if it does not exist).
+    pub fn from_file(file_path: PathBuf) -> Configuration {
         let mut config_file = Ini::new_cs();
         config_file.set_comment_symbols(&['#']);
-        let file_path = config_file_path();
         if config_file.load(file_path.clone()).is_err() {
             log::info!(
                 "could not load configuration from {}; a new configuration file will be created",
@@ -61,7 +316,11 @@ impl Configuration {
             );
         }
 
-        Configuration{ config_file }
+        let controller_section = controller_section(
+            &config_file.get(sections::MAIN, keys::CONTROLLER_PROFILE).unwrap_or_default()
+        );
+
+        Configuration{ config_file, file_path, controller_section }
     }
 
     fn get_string(&self, section: &str, key: &str) -> Option<String> {
@@ -72,20 +331,73 @@ impl Configuration {
         self.config_file.set(section, key, Some(value.into()));
     }
 
-    pub fn mount_simulator_addr(&self) -> Option<String> {
-        self.get_string(sections::MAIN, keys::MOUNT_SIM_ADDRESS)
+    fn get_f64(&self, section: &str, key: &str) -> Option<f64> {
+        match self.config_file.getfloat(section, key) {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("invalid value of {}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    fn set_f64(&mut self, section: &str, key: &str, value: f64) {
+        self.set_string(section, key, &value.to_string());
+    }
+
+    /// Returns a pair of angles stored as "<deg>,<deg>" (or `None` if empty or invalid).
+    fn get_angle_pair(&self, section: &str, key: &str) -> Option<(f64::Angle, f64::Angle)> {
+        let value = self.get_string(section, key).filter(|s| !s.is_empty())?;
+        match value.split(',').map(|s| s.trim().parse::<f64>()).collect::<Result<Vec<_>, _>>() {
+            Ok(v) if v.len() == 2 => Some((deg(v[0]), deg(v[1]))),
+            _ => { log::warn!("invalid value of {}: {}", key, value); None }
+        }
+    }
+
+    fn set_angle_pair(&mut self, section: &str, key: &str, value: Option<(f64::Angle, f64::Angle)>) {
+        let s = match value {
+            Some((a1, a2)) => format!("{},{}", as_deg(a1), as_deg(a2)),
+            None => "".into()
+        };
+        self.set_string(section, key, &s);
+    }
+
+    pub fn mount_type(&self) -> Option<mount::MountType> {
+        self.get_string(sections::MAIN, keys::MOUNT_TYPE)?.parse::<mount::MountType>().ok()
+    }
+
+    pub fn set_mount_type(&mut self, value: mount::MountType) {
+        self.set_string(sections::MAIN, keys::MOUNT_TYPE, value.into());
     }
 
-    pub fn set_mount_simulator_addr(&mut self, value: &str) {
-        self.set_string(sections::MAIN, keys::MOUNT_SIM_ADDRESS, value);
+    /// Returns the last used connection parameter for the given mount type.
+    pub fn mount_connection_param(&self, mount_type: mount::MountType) -> Option<String> {
+        self.get_string(sections::MAIN, mount_type.backend().config_key)
     }
 
-    pub fn mount_ioptron_device(&self) -> Option<String> {
-        self.get_string(sections::MAIN, keys::MOUNT_IOPTRON_DEVICE)
+    pub fn set_mount_connection_param(&mut self, mount_type: mount::MountType, value: &str) {
+        self.set_string(sections::MAIN, mount_type.backend().config_key, value);
+    }
+
+    /// Returns saved calibration of mount `mount_id` (see `keys::MOUNT_STATE_ID`), if any.
+    pub fn mount_state(&self, mount_id: &str) -> Option<mount::PersistentState> {
+        if self.get_string(sections::MOUNT_STATE, keys::MOUNT_STATE_ID)? != mount_id { return None; }
+
+        Some(mount::PersistentState{
+            axis_ofs: self.get_angle_pair(sections::MOUNT_STATE, keys::MOUNT_STATE_AXIS_OFS)?,
+            zero_pos: self.get_angle_pair(sections::MOUNT_STATE, keys::MOUNT_STATE_ZERO_POS),
+            total_axis_travel: self.get_angle_pair(sections::MOUNT_STATE, keys::MOUNT_STATE_TRAVEL)
+                .unwrap_or((deg(0.0), deg(0.0))),
+            adjustment: self.get_angle_pair(sections::MOUNT_STATE, keys::MOUNT_STATE_ADJUSTMENT)
+        })
     }
 
-    pub fn set_mount_ioptron_device(&mut self, value: &str) {
-        self.set_string(sections::MAIN, keys::MOUNT_IOPTRON_DEVICE, value);
+    pub fn set_mount_state(&mut self, mount_id: &str, value: &mount::PersistentState) {
+        self.set_string(sections::MOUNT_STATE, keys::MOUNT_STATE_ID, mount_id);
+        self.set_angle_pair(sections::MOUNT_STATE, keys::MOUNT_STATE_AXIS_OFS, Some(value.axis_ofs));
+        self.set_angle_pair(sections::MOUNT_STATE, keys::MOUNT_STATE_ZERO_POS, value.zero_pos);
+        self.set_angle_pair(sections::MOUNT_STATE, keys::MOUNT_STATE_TRAVEL, Some(value.total_axis_travel));
+        self.set_angle_pair(sections::MOUNT_STATE, keys::MOUNT_STATE_ADJUSTMENT, value.adjustment);
     }
 
     pub fn data_source_addr(&self) -> Option<String> {
@@ -96,6 +408,611 @@ impl Configuration {
         self.set_string(sections::MAIN, keys::DATA_SOURCE_ADDRESS, value);
     }
 
+    /// Returns whether to reconnect to the last used mount and data source on startup.
+    pub fn reconnect_last_session(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::RECONNECT_LAST_SESSION)
+            .unwrap_or(Some(false))
+            .unwrap_or(false)
+    }
+
+    /// Returns whether to reconnect automatically to a data source after the connection has been lost.
+    pub fn data_source_auto_reconnect(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::DATA_SOURCE_AUTO_RECONNECT)
+            .unwrap_or(Some(true))
+            .unwrap_or(true)
+    }
+
+    pub fn color_scheme(&self) -> ColorScheme {
+        let value = match self.get_string(sections::MAIN, keys::COLOR_SCHEME).filter(|s| !s.is_empty()) {
+            Some(value) => value,
+            None => return Default::default()
+        };
+        match value.parse::<ColorScheme>() {
+            Ok(scheme) => scheme,
+            Err(_) => { log::warn!("invalid color scheme: {}", value); Default::default() }
+        }
+    }
+
+    pub fn set_color_scheme(&mut self, value: ColorScheme) {
+        self.set_string(sections::MAIN, keys::COLOR_SCHEME, value.name());
+    }
+
+    /// Returns format of messages received from a data source.
+    pub fn data_source_format(&self) -> target_message::MessageFormat {
+        let value = match self.get_string(sections::MAIN, keys::DATA_SOURCE_FORMAT).filter(|s| !s.is_empty()) {
+            Some(value) => value,
+            None => return Default::default()
+        };
+        match value.parse::<target_message::MessageFormat>() {
+            Ok(format) => format,
+            Err(_) => { log::warn!("invalid data source format: {}", value); Default::default() }
+        }
+    }
+
+    pub fn simulated_target_params(&self) -> target_source::SimulatedTargetParams {
+        let value = match self.get_string(sections::MAIN, keys::SIMULATED_TARGET_PARAMS) {
+            Some(value) => value,
+            None => return Default::default()
+        };
+        match value.parse::<target_source::SimulatedTargetParams>() {
+            Ok(params) => params,
+            Err(e) => { log::warn!("invalid simulated target parameters \"{}\": {}", value, e); Default::default() }
+        }
+    }
+
+    pub fn set_simulated_target_params(&mut self, value: &target_source::SimulatedTargetParams) {
+        self.set_string(sections::MAIN, keys::SIMULATED_TARGET_PARAMS, &value.to_string());
+    }
+
+    pub fn observer_location(&self) -> Option<data::ObserverLocation> {
+        Some(data::ObserverLocation{
+            lat: deg(self.get_f64(sections::MAIN, keys::OBSERVER_LAT)?),
+            lon: deg(self.get_f64(sections::MAIN, keys::OBSERVER_LON)?),
+            elevation: f64::Length::new::<length::meter>(self.get_f64(sections::MAIN, keys::OBSERVER_ELEVATION)?)
+        })
+    }
+
+    pub fn set_observer_location(&mut self, value: &data::ObserverLocation) {
+        self.set_f64(sections::MAIN, keys::OBSERVER_LAT, as_deg(value.lat));
+        self.set_f64(sections::MAIN, keys::OBSERVER_LON, as_deg(value.lon));
+        self.set_f64(sections::MAIN, keys::OBSERVER_ELEVATION, value.elevation.get::<length::meter>());
+    }
+
+    /// Returns the angle from true north to magnetic north (positive: east; default: 0°).
+    pub fn magnetic_declination(&self) -> f64::Angle {
+        deg(self.get_f64(sections::MAIN, keys::MAGNETIC_DECLINATION).unwrap_or(0.0))
+    }
+
+    pub fn set_magnetic_declination(&mut self, value: f64::Angle) {
+        self.set_f64(sections::MAIN, keys::MAGNETIC_DECLINATION, as_deg(value));
+    }
+
+    /// Returns path of the World Magnetic Model coefficient file used to compute magnetic declination.
+    pub fn wmm_file(&self) -> Option<PathBuf> {
+        self.get_string(sections::MAIN, keys::WMM_FILE).filter(|s| !s.is_empty()).map(PathBuf::from)
+    }
+
+    pub fn sites(&self) -> Vec<data::Site> {
+        let mut result = vec![];
+        let Some(sites) = self.config_file.get_map_ref().get(sections::SITES) else { return result; };
+
+        for idx in 1..=MAX_NUM_SITES {
+            match sites.get(&format!("{}{}", keys::SITE, idx)) {
+                Some(site) => match site.as_deref().unwrap_or_default().parse::<data::Site>() {
+                    Ok(site) => result.push(site),
+                    Err(e) => log::error!("invalid site: {}", e)
+                },
+                None => break
+            }
+        }
+        result
+    }
+
+    /// Replaces all sites.
+    pub fn set_sites(&mut self, sites: &[data::Site]) {
+        self.config_file.remove_section(sections::SITES);
+        for (idx, site) in sites.iter().enumerate() {
+            self.config_file.set(sections::SITES, &format!("{}{}", keys::SITE, idx + 1), Some(site.to_string()));
+        }
+    }
+
+    /// Returns the last used site (if it is still on the list).
+    pub fn current_site(&self) -> Option<data::Site> {
+        let name = self.get_string(sections::MAIN, keys::CURRENT_SITE).filter(|s| !s.is_empty())?;
+        self.sites().into_iter().find(|site| site.name == name)
+    }
+
+    /// Makes `site` the current one: sets observer location and magnetic declination.
+    pub fn use_site(&mut self, site: &data::Site) {
+        self.set_observer_location(&site.location);
+        self.set_magnetic_declination(site.magnetic_declination);
+        self.set_string(sections::MAIN, keys::CURRENT_SITE, &site.name);
+    }
+
+    /// Indicates that the observer location no longer corresponds to a stored site.
+    pub fn clear_current_site(&mut self) {
+        self.config_file.remove_key(sections::MAIN, keys::CURRENT_SITE);
+    }
+
+    /// Returns level of messages written to the log file (default: info).
+    pub fn log_level(&self) -> log::LevelFilter {
+        let value = match self.get_string(sections::MAIN, keys::LOG_LEVEL).filter(|s| !s.is_empty()) {
+            Some(v) => v,
+            None => return log::LevelFilter::Info
+        };
+        match value.parse::<log::LevelFilter>() {
+            Ok(level) => level,
+            Err(_) => { log::warn!("invalid log level: {}", value); log::LevelFilter::Info }
+        }
+    }
+
+    pub fn set_log_level(&mut self, value: log::LevelFilter) {
+        self.set_string(sections::MAIN, keys::LOG_LEVEL, &value.to_string().to_lowercase());
+    }
+
+    /// Returns the remote log collector ("tcp://<host>:<port>" or "syslog://<host>[:<port>]"), if set.
+    pub fn remote_log(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::REMOTE_LOG).filter(|s| !s.is_empty())
+    }
+
+    pub fn gps_device(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::GPS_DEVICE)
+    }
+
+    pub fn set_gps_device(&mut self, value: &str) {
+        self.set_string(sections::MAIN, keys::GPS_DEVICE, value);
+    }
+
+    /// Returns NTP server used to check the system clock; disabled if set to an empty string.
+    pub fn time_sync_server(&self) -> Option<String> {
+        match self.get_string(sections::MAIN, keys::TIME_SYNC_SERVER) {
+            Some(s) => Some(s).filter(|s| !s.is_empty()),
+            None => Some(DEFAULT_TIME_SYNC_SERVER.into())
+        }
+    }
+
+    /// Returns system clock offset (in seconds) above which a warning is shown.
+    pub fn time_offset_warning(&self) -> f64 {
+        self.get_f64(sections::MAIN, keys::TIME_OFFSET_WARNING).unwrap_or(DEFAULT_TIME_OFFSET_WARNING_S)
+    }
+
+    /// Returns address to listen on for guiding input (e.g., "127.0.0.1:4030"); guiding input is disabled if not set.
+    pub fn guiding_server_addr(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::GUIDING_SERVER_ADDRESS).filter(|s| !s.is_empty())
+    }
+
+    /// Returns correction speed corresponding to guide pulses.
+    pub fn guide_rate(&self) -> f64::AngularVelocity {
+        data::deg_per_s(self.get_f64(sections::MAIN, keys::GUIDE_RATE).unwrap_or(DEFAULT_GUIDE_RATE_DEG_PER_S))
+    }
+
+    /// Returns address to listen on for camera-based corrections; disabled if not set.
+    pub fn camera_server_addr(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::CAMERA_SERVER_ADDRESS).filter(|s| !s.is_empty())
+    }
+
+    pub fn camera_params(&self) -> CameraParams {
+        let positive = |key| self.get_f64(sections::MAIN, key).filter(|value| *value > 0.0);
+        // focal length and pixel size (if both set) take precedence over the pixel scale
+        let pixel_scale = match (positive(keys::CAMERA_FOCAL_LENGTH), positive(keys::CAMERA_PIXEL_SIZE)) {
+            (Some(focal_length), Some(pixel_size)) => camera_tracking::pixel_scale(focal_length, pixel_size),
+            _ => self.get_f64(sections::MAIN, keys::CAMERA_PIXEL_SCALE).unwrap_or(DEFAULT_CAMERA_PIXEL_SCALE)
+        };
+        let sensor_size = match (positive(keys::CAMERA_SENSOR_WIDTH), positive(keys::CAMERA_SENSOR_HEIGHT)) {
+            (Some(width), Some(height)) => Some((width.round() as u32, height.round() as u32)),
+            _ => None
+        };
+
+        CameraParams{
+            pixel_scale,
+            rotation: deg(self.get_f64(sections::MAIN, keys::CAMERA_ROTATION).unwrap_or(0.0)),
+            flipped: self.config_file.getbool(sections::MAIN, keys::CAMERA_FLIPPED)
+                .unwrap_or(Some(false))
+                .unwrap_or(false),
+            gain: self.get_f64(sections::MAIN, keys::CAMERA_CORRECTION_GAIN)
+                .unwrap_or(DEFAULT_CAMERA_CORRECTION_GAIN)
+                .max(0.0).min(1.0),
+            sensor_size
+        }
+    }
+
+    /// Returns true if the target's expected pixel coordinates are to be sent to the video tracker
+    /// (connected to the camera corrections server) once tracking is stable.
+    pub fn video_tracker_handoff(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::VIDEO_TRACKER_HANDOFF).unwrap_or(Some(false)).unwrap_or(false)
+    }
+
+    /// Returns output used by the "trigger camera" controller action; disabled if not set.
+    pub fn camera_trigger_output(&self) -> Option<camera_trigger::TriggerOutput> {
+        let value = self.get_string(sections::MAIN, keys::CAMERA_TRIGGER_OUTPUT).filter(|s| !s.is_empty())?;
+        match value.parse::<camera_trigger::TriggerOutput>() {
+            Ok(output) => Some(output),
+            Err(e) => { log::warn!("{}", e); None }
+        }
+    }
+
+    /// Returns duration of the serial line pulse triggering the camera.
+    pub fn camera_trigger_pulse(&self) -> std::time::Duration {
+        let ms = self.get_f64(sections::MAIN, keys::CAMERA_TRIGGER_PULSE).unwrap_or(DEFAULT_CAMERA_TRIGGER_PULSE_MS);
+        std::time::Duration::from_secs_f64(ms.max(0.0) / 1000.0)
+    }
+
+    /// Returns command (sent as a single line) triggering the camera via network.
+    pub fn camera_trigger_command(&self) -> String {
+        self.get_string(sections::MAIN, keys::CAMERA_TRIGGER_COMMAND)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(DEFAULT_CAMERA_TRIGGER_COMMAND.into())
+    }
+
+    /// Returns path of the user script (see `scripting`); scripting is disabled if not set.
+    pub fn script_file(&self) -> Option<PathBuf> {
+        self.get_string(sections::MAIN, keys::SCRIPT_FILE).filter(|s| !s.is_empty()).map(PathBuf::from)
+    }
+
+    /// Returns address to listen on for state broadcast clients (e.g., "0.0.0.0:4040"); disabled if not set.
+    pub fn state_broadcast_addr(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::STATE_BROADCAST_ADDRESS).filter(|s| !s.is_empty())
+    }
+
+    /// Returns metrics output ("influx://<host>:<port>" or "prometheus://<address>"); disabled if not set.
+    pub fn telemetry_output(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::TELEMETRY_OUTPUT).filter(|s| !s.is_empty())
+    }
+
+    /// Returns overlay text output (file path or "udp://<host>:<port>"); overlay output is disabled if not set.
+    pub fn overlay_output(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::OVERLAY_OUTPUT).filter(|s| !s.is_empty())
+    }
+
+    /// Returns overlay text template (see `overlay::render`); "\n" denotes a line break.
+    pub fn overlay_template(&self) -> String {
+        self.get_string(sections::MAIN, keys::OVERLAY_TEMPLATE)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(overlay::DEFAULT_TEMPLATE.into())
+    }
+
+    /// Returns session log format; session logging is disabled if not set.
+    pub fn session_log_format(&self) -> Option<session_log::Format> {
+        let value = self.get_string(sections::MAIN, keys::SESSION_LOG_FORMAT).filter(|s| !s.is_empty())?;
+        match value.parse::<session_log::Format>() {
+            Ok(format) => Some(format),
+            Err(_) => { log::warn!("invalid session log format: {}", value); None }
+        }
+    }
+
+    /// Returns directory for session logs (by default, the same as for the program log).
+    pub fn session_log_dir(&self) -> PathBuf {
+        match self.get_string(sections::MAIN, keys::SESSION_LOG_DIR).filter(|s| !s.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::data_dir().unwrap_or(Path::new("").to_path_buf())
+        }
+    }
+
+    /// Returns fields to include in the session log (by default, all).
+    pub fn session_log_fields(&self) -> Vec<session_log::Field> {
+        match self.get_string(sections::MAIN, keys::SESSION_LOG_FIELDS).filter(|s| !s.trim().is_empty()) {
+            Some(s) => match session_log::parse_fields(&s) {
+                Ok(fields) => fields,
+                Err(e) => { log::warn!("{}; logging all fields", e); session_log::Field::all() }
+            },
+            None => session_log::Field::all()
+        }
+    }
+
+    pub fn slew_speed(&self) -> Option<f64::AngularVelocity> {
+        self.get_f64(sections::MAIN, keys::SLEW_SPEED).map(deg_per_s)
+    }
+
+    pub fn set_slew_speed(&mut self, value: f64::AngularVelocity) {
+        self.set_f64(sections::MAIN, keys::SLEW_SPEED, as_deg_per_s(value));
+    }
+
+    pub fn slew_speed_presets(&self) -> Vec<f64::AngularVelocity> {
+        let presets = match self.get_string(sections::MAIN, keys::SLEW_SPEED_PRESETS) {
+            Some(s) => s.split(',').filter_map(|value| match value.trim().parse::<f64>() {
+                Ok(value) if value > 0.0 => Some(value),
+                _ => { log::warn!("invalid slew speed preset: {}", value); None }
+            }).collect(),
+            None => DEFAULT_SLEW_SPEED_PRESETS_DEG_PER_S.to_vec()
+        };
+
+        presets.into_iter().map(deg_per_s).collect()
+    }
+
+    pub fn set_slew_speed_presets(&mut self, presets: &[f64::AngularVelocity]) {
+        let value = presets.iter().map(|p| as_deg_per_s(*p).to_string()).collect::<Vec<_>>().join(",");
+        self.set_string(sections::MAIN, keys::SLEW_SPEED_PRESETS, &value);
+    }
+
+    /// Returns min. and max. slew speed for the current mount type. Unless configured, max. speed is the one
+    /// reported by the mount (`mount_max`), if any.
+    pub fn slew_speed_limits(
+        &self,
+        mount_max: Option<f64::AngularVelocity>
+    ) -> (f64::AngularVelocity, f64::AngularVelocity) {
+        let mut min = DEFAULT_MIN_SLEW_SPEED_DEG_PER_S;
+        let mut max = mount_max.map(as_deg_per_s).unwrap_or(DEFAULT_MAX_SLEW_SPEED_DEG_PER_S);
+        if let Some(mount_type) = self.mount_type() {
+            let mount_type: &str = mount_type.into();
+            let key = |suffix| format!("{}{}", mount_type, suffix);
+            min = self.get_f64(sections::MAIN, &key(keys::MIN_SLEW_SPEED_SUFFIX)).filter(|v| *v > 0.0).unwrap_or(min);
+            max = self.get_f64(sections::MAIN, &key(keys::MAX_SLEW_SPEED_SUFFIX)).filter(|v| *v >= min).unwrap_or(max);
+        }
+
+        (deg_per_s(min), deg_per_s(max.max(min)))
+    }
+
+    /// Returns max. slewing acceleration (°/s²), if specified.
+    pub fn mount_max_acceleration(&self) -> Option<f64> {
+        self.get_f64(sections::MAIN, keys::MOUNT_MAX_ACCELERATION).filter(|value| *value > 0.0)
+    }
+
+    /// Returns max. total travel (from the zero position) of each mount axis; exceeding it stops the mount.
+    pub fn mount_max_axis_travel(&self) -> f64::Angle {
+        deg(self.get_f64(sections::MAIN, keys::MOUNT_MAX_AXIS_TRAVEL)
+            .filter(|value| *value > 0.0)
+            .unwrap_or(mount::DEFAULT_MAX_TRAVEL_DEG))
+    }
+
+    /// Returns min. altitude (in the mount's own coordinates) to be enforced by the mount driver, overriding
+    /// the one configured in the mount; ignored if the mount's axes are swapped.
+    pub fn mount_altitude_limit(&self) -> Option<f64::Angle> {
+        self.get_f64(sections::MAIN, keys::MOUNT_ALTITUDE_LIMIT).map(deg)
+    }
+
+    /// Returns min. change of axis speed to be commanded to the mount (smaller changes are skipped).
+    pub fn mount_command_epsilon(&self) -> f64::AngularVelocity {
+        deg_per_s(self.get_f64(sections::MAIN, keys::MOUNT_COMMAND_EPSILON).unwrap_or(0.0).max(0.0))
+    }
+
+    /// Returns whether the roles of the main mount's axes are exchanged (axis 1 <-> axis 2).
+    pub fn mount_axes_swapped(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::MOUNT_AXES_SWAPPED)
+            .unwrap_or(Some(false))
+            .unwrap_or(false)
+    }
+
+    /// Returns rate scale (actual / commanded speed) of the main mount's axes.
+    pub fn mount_rate_scale(&self) -> (f64, f64) {
+        let value = match self.get_string(sections::MAIN, keys::MOUNT_RATE_SCALE).filter(|s| !s.is_empty()) {
+            Some(value) => value,
+            None => return (1.0, 1.0)
+        };
+        match value.split(',').map(|s| s.trim().parse::<f64>()).collect::<Result<Vec<_>, _>>() {
+            Ok(v) if v.len() == 2 && v.iter().all(|x| *x > 0.0) => (v[0], v[1]),
+            _ => { log::warn!("invalid value of {}: {}", keys::MOUNT_RATE_SCALE, value); (1.0, 1.0) }
+        }
+    }
+
+    pub fn set_mount_rate_scale(&mut self, value: (f64, f64)) {
+        self.set_string(sections::MAIN, keys::MOUNT_RATE_SCALE, &format!("{},{}", value.0, value.1));
+    }
+
+    /// Returns whether to park the mount(s) when quitting.
+    pub fn park_on_exit(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::PARK_ON_EXIT)
+            .unwrap_or(Some(false))
+            .unwrap_or(false)
+    }
+
+    /// Returns whether to monitor drift of the (main) mount's axes (see `MountWrapper::drift`).
+    pub fn mount_drift_monitor(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::MOUNT_DRIFT_MONITOR)
+            .unwrap_or(Some(false))
+            .unwrap_or(false)
+    }
+
+    /// Returns whether the (main) mount is operated in push-to mode (see `MountWrapper::set_push_to`).
+    pub fn mount_push_to(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::MOUNT_PUSH_TO)
+            .unwrap_or(Some(false))
+            .unwrap_or(false)
+    }
+
+    pub fn set_mount_push_to(&mut self, value: bool) {
+        self.set_string(sections::MAIN, keys::MOUNT_PUSH_TO, &value.to_string());
+    }
+
+    /// Returns axis drift above which a warning is logged.
+    pub fn mount_drift_warning(&self) -> f64::Angle {
+        deg(self.get_f64(sections::MAIN, keys::MOUNT_DRIFT_WARNING).unwrap_or(DEFAULT_MOUNT_DRIFT_WARNING_DEG))
+    }
+
+    /// Returns interval of the main timer (mount position & speed updates, TUI refresh).
+    pub fn main_timer_interval(&self) -> std::time::Duration {
+        self.timer_interval(keys::MAIN_TIMER_INTERVAL, DEFAULT_MAIN_TIMER_INTERVAL_MS, MAIN_TIMER_INTERVAL_RANGE_MS)
+    }
+
+    /// Returns interval of tracking updates (axis speed calculation).
+    pub fn tracking_timer_interval(&self) -> std::time::Duration {
+        self.timer_interval(
+            keys::TRACKING_TIMER_INTERVAL, DEFAULT_TRACKING_TIMER_INTERVAL_MS, TRACKING_TIMER_INTERVAL_RANGE_MS
+        )
+    }
+
+    /// Returns the time of blending axis speeds when switching between manual slewing and tracking
+    /// (zero: switch immediately).
+    pub fn tracking_handover_time(&self) -> std::time::Duration {
+        let secs = self.get_f64(sections::MAIN, keys::TRACKING_HANDOVER_TIME)
+            .filter(|t| t.is_finite() && *t >= 0.0)
+            .unwrap_or(DEFAULT_TRACKING_HANDOVER_TIME_S);
+        std::time::Duration::from_secs_f64(secs)
+    }
+
+    /// Returns the change of the tracking rate trim per key or button press.
+    pub fn tracking_trim_step(&self) -> f64::AngularVelocity {
+        deg_per_s(
+            self.get_f64(sections::MAIN, keys::TRACKING_TRIM_STEP)
+                .filter(|s| s.is_finite() && *s > 0.0)
+                .unwrap_or(DEFAULT_TRACKING_TRIM_STEP_DEG_PER_S)
+        )
+    }
+
+    fn timer_interval(&self, key: &str, default_ms: u64, (min_ms, max_ms): (u64, u64)) -> std::time::Duration {
+        let ms = match self.get_f64(sections::MAIN, key) {
+            None => default_ms,
+            Some(ms) if ms.is_finite() && ms >= min_ms as f64 && ms <= max_ms as f64 => ms.round() as u64,
+            Some(ms) => {
+                let clamped = if ms.is_finite() {
+                    (ms.round().max(0.0) as u64).clamp(min_ms, max_ms)
+                } else {
+                    default_ms
+                };
+                log::warn!(
+                    "{} = {} ms is outside the allowed range {}-{} ms; using {} ms", key, ms, min_ms, max_ms, clamped
+                );
+                clamped
+            }
+        };
+        std::time::Duration::from_millis(ms)
+    }
+
+    pub fn mount_speed_filter(&self) -> data::SpeedFilter {
+        match self.get_string(sections::MAIN, keys::MOUNT_SPEED_FILTER) {
+            Some(s) => s.parse().unwrap_or_else(|e| { log::warn!("{}", e); Default::default() }),
+            None => Default::default()
+        }
+    }
+
+    pub fn angle_display(&self) -> data::AngleDisplay {
+        fn parse<T: std::str::FromStr + Default>(value: Option<String>, name: &str) -> T {
+            match value {
+                Some(value) => value.parse().unwrap_or_else(|_| {
+                    log::warn!("invalid {}: {}", name, value);
+                    T::default()
+                }),
+                None => T::default()
+            }
+        }
+
+        data::AngleDisplay{
+            format: parse(self.get_string(sections::MAIN, keys::ANGLE_FORMAT), "angle format"),
+            az_origin: parse(self.get_string(sections::MAIN, keys::AZIMUTH_ORIGIN), "azimuth origin"),
+            az_range: parse(self.get_string(sections::MAIN, keys::AZIMUTH_RANGE), "azimuth range"),
+            speed_unit: parse(self.get_string(sections::MAIN, keys::SPEED_UNIT), "speed unit")
+        }
+    }
+
+    /// Returns target distance/altitude alarm thresholds; an alarm is disabled if its threshold is not set.
+    pub fn target_alarm_thresholds(&self) -> target_alarm::Thresholds {
+        target_alarm::Thresholds{
+            min_dist: self.get_f64(sections::MAIN, keys::TARGET_ALARM_DISTANCE)
+                .filter(|d| *d > 0.0)
+                .map(f64::Length::new::<length::kilometer>),
+            min_altitude: self.get_f64(sections::MAIN, keys::TARGET_ALARM_ALTITUDE).map(deg)
+        }
+    }
+
+    /// Returns whether to ring the terminal bell when a target alarm is raised.
+    pub fn target_alarm_sound(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::TARGET_ALARM_SOUND)
+            .unwrap_or(Some(true))
+            .unwrap_or(true)
+    }
+
+    /// Returns radius of the exclusion zone around the Sun; the zone is disabled if not set.
+    pub fn sun_avoidance_radius(&self) -> Option<f64::Angle> {
+        self.get_f64(sections::MAIN, keys::SUN_AVOIDANCE_RADIUS).filter(|r| *r > 0.0).map(deg)
+    }
+
+    /// Returns atmospheric conditions used to correct target altitude for refraction; the correction is disabled
+    /// if `None`.
+    pub fn refraction(&self) -> Option<refraction::Atmosphere> {
+        let enabled = self.config_file.getbool(sections::MAIN, keys::REFRACTION_CORRECTION)
+            .unwrap_or(Some(false))
+            .unwrap_or(false);
+        if !enabled { return None; }
+
+        Some(refraction::Atmosphere{
+            temperature: self.get_f64(sections::MAIN, keys::AIR_TEMPERATURE).unwrap_or(DEFAULT_AIR_TEMPERATURE_C),
+            pressure: self.get_f64(sections::MAIN, keys::AIR_PRESSURE)
+                .filter(|p| *p > 0.0)
+                .unwrap_or(DEFAULT_AIR_PRESSURE_HPA)
+        })
+    }
+
+    /// Returns the camera field of view; its height defaults to the width. The FOV indicator is disabled if not set.
+    pub fn camera_fov(&self) -> Option<fov::Fov> {
+        let width = self.get_f64(sections::MAIN, keys::CAMERA_FOV_WIDTH).filter(|w| *w > 0.0)?;
+        let height = self.get_f64(sections::MAIN, keys::CAMERA_FOV_HEIGHT).filter(|h| *h > 0.0).unwrap_or(width);
+        Some(fov::Fov{ width: deg(width), height: deg(height) })
+    }
+
+    pub fn tracking_pause_mode(&self) -> tracking::PauseMode {
+        match self.get_string(sections::MAIN, keys::TRACKING_PAUSE_MODE).filter(|s| !s.is_empty()) {
+            Some(value) => value.parse().unwrap_or_else(|_| {
+                log::warn!("invalid tracking pause mode: {}", value);
+                Default::default()
+            }),
+            None => Default::default()
+        }
+    }
+
+    pub fn target_loss_policy(&self) -> tracking::TargetLossPolicy {
+        let default = tracking::TargetLossPolicy::default();
+        let seconds = |key, default: std::time::Duration| self.get_f64(sections::MAIN, key)
+            .filter(|s| *s >= 0.0)
+            .map(std::time::Duration::from_secs_f64)
+            .unwrap_or(default);
+
+        tracking::TargetLossPolicy{
+            stale_after: seconds(keys::TARGET_STALE_TIMEOUT, default.stale_after),
+            action: match self.get_string(sections::MAIN, keys::TARGET_LOSS_ACTION) {
+                Some(value) => value.parse().unwrap_or_else(|_| {
+                    log::warn!("invalid target loss action: {}", value);
+                    default.action
+                }),
+                None => default.action
+            },
+            coast_time: seconds(keys::TARGET_COAST_TIME, default.coast_time),
+            search: self.search_params()
+        }
+    }
+
+    fn search_params(&self) -> search_pattern::SearchParams {
+        let default = search_pattern::SearchParams::default();
+        let positive = |key| self.get_f64(sections::MAIN, key).filter(|value| *value > 0.0);
+
+        search_pattern::SearchParams{
+            pattern: match self.get_string(sections::MAIN, keys::SEARCH_PATTERN).filter(|s| !s.is_empty()) {
+                Some(value) => value.parse().unwrap_or_else(|_| {
+                    log::warn!("invalid search pattern: {}", value);
+                    default.pattern
+                }),
+                None => default.pattern
+            },
+            rate: positive(keys::SEARCH_RATE).unwrap_or(default.rate),
+            spacing: positive(keys::SEARCH_SPACING).unwrap_or(default.spacing)
+        }
+    }
+
+    pub fn tle_file(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::TLE_FILE)
+    }
+
+    pub fn set_tle_file(&mut self, value: &str) {
+        self.set_string(sections::MAIN, keys::TLE_FILE, value);
+    }
+
+    /// Returns whether to track the optical (light-time and aberration corrected) position of satellites.
+    pub fn satellite_optical_position(&self) -> bool {
+        self.config_file.getbool(sections::MAIN, keys::SATELLITE_OPTICAL_POSITION)
+            .unwrap_or(Some(false))
+            .unwrap_or(false)
+    }
+
+    pub fn set_satellite_optical_position(&mut self, value: bool) {
+        self.set_string(sections::MAIN, keys::SATELLITE_OPTICAL_POSITION, &value.to_string());
+    }
+
+    pub fn target_queue_file(&self) -> Option<String> {
+        self.get_string(sections::MAIN, keys::TARGET_QUEUE_FILE)
+    }
+
+    pub fn set_target_queue_file(&mut self, value: &str) {
+        self.set_string(sections::MAIN, keys::TARGET_QUEUE_FILE, value);
+    }
+
     pub fn ref_pos_presets(&self) -> Vec<data::RefPositionPreset> {
         let mut result = vec![];
         let presets = match self.config_file.get_map_ref().get(sections::REF_POS_PRESETS) {
@@ -122,6 +1039,18 @@ impl Configuration {
         result
     }
 
+    /// Replaces all reference position presets.
+    pub fn set_ref_pos_presets(&mut self, presets: &[data::RefPositionPreset]) {
+        self.config_file.remove_section(sections::REF_POS_PRESETS);
+        for (idx, preset) in presets.iter().enumerate() {
+            self.config_file.set(
+                sections::REF_POS_PRESETS,
+                &format!("{}{}", keys::REF_POS_PRESET, idx + 1),
+                Some(preset.to_string())
+            );
+        }
+    }
+
     pub fn add_ref_pos_preset(&mut self, preset: data::RefPositionPreset) {
         let num_existing = if let Some(presets) = self.config_file.get_map_ref().get(sections::REF_POS_PRESETS) {
             presets.len()
@@ -136,24 +1065,121 @@ impl Configuration {
         );
     }
 
+    pub fn saved_adjustments(&self) -> Vec<data::SavedAdjustment> {
+        let mut result = vec![];
+        let adjustments = match self.config_file.get_map_ref().get(sections::SAVED_ADJUSTMENTS) {
+            Some(a) => a,
+            None => return result
+        };
+
+        for idx in 1..=MAX_NUM_SAVED_ADJUSTMENTS {
+            match adjustments.get(&format!("{}{}", keys::SAVED_ADJUSTMENT, idx)) {
+                Some(value) => match value.as_ref().unwrap().parse::<data::SavedAdjustment>() {
+                    Ok(adjustment) => result.push(adjustment),
+                    Err(e) => log::error!("{}", e)
+                },
+
+                None => break
+            }
+        }
+        result
+    }
+
+    pub fn set_saved_adjustments(&mut self, adjustments: &[data::SavedAdjustment]) {
+        self.config_file.remove_section(sections::SAVED_ADJUSTMENTS);
+        for (idx, adjustment) in adjustments.iter().enumerate() {
+            self.config_file.set(
+                sections::SAVED_ADJUSTMENTS,
+                &format!("{}{}", keys::SAVED_ADJUSTMENT, idx + 1),
+                Some(adjustment.to_string())
+            );
+        }
+    }
+
+    pub fn landmarks(&self) -> Vec<data::Landmark> {
+        let mut result = vec![];
+        let Some(landmarks) = self.config_file.get_map_ref().get(sections::LANDMARKS) else { return result; };
+
+        for idx in 1..=MAX_NUM_LANDMARKS {
+            match landmarks.get(&format!("{}{}", keys::LANDMARK, idx)) {
+                Some(landmark) => match landmark.as_deref().unwrap_or_default().parse::<data::Landmark>() {
+                    Ok(landmark) => result.push(landmark),
+                    Err(e) => log::error!("invalid landmark: {}", e)
+                },
+                None => break
+            }
+        }
+        result
+    }
+
+    /// Replaces all landmarks.
+    pub fn set_landmarks(&mut self, landmarks: &[data::Landmark]) {
+        self.config_file.remove_section(sections::LANDMARKS);
+        for (idx, landmark) in landmarks.iter().enumerate() {
+            self.config_file.set(
+                sections::LANDMARKS,
+                &format!("{}{}", keys::LANDMARK, idx + 1),
+                Some(landmark.to_string())
+            );
+        }
+    }
+
+    pub fn odometers(&self) -> Vec<data::MountOdometer> {
+        let mut result = vec![];
+        let Some(odometers) = self.config_file.get_map_ref().get(sections::ODOMETERS) else { return result; };
+
+        for idx in 1..=MAX_NUM_ODOMETERS {
+            match odometers.get(&format!("{}{}", keys::ODOMETER, idx)) {
+                Some(odometer) => match odometer.as_deref().unwrap_or_default().parse::<data::MountOdometer>() {
+                    Ok(odometer) => result.push(odometer),
+                    Err(e) => log::error!("invalid odometer: {}", e)
+                },
+                None => break
+            }
+        }
+        result
+    }
+
+    /// Replaces all odometers.
+    pub fn set_odometers(&mut self, odometers: &[data::MountOdometer]) {
+        self.config_file.remove_section(sections::ODOMETERS);
+        for (idx, odometer) in odometers.iter().enumerate() {
+            self.config_file.set(
+                sections::ODOMETERS,
+                &format!("{}{}", keys::ODOMETER, idx + 1),
+                Some(odometer.to_string())
+            );
+        }
+    }
+
+    /// Returns the saved lifetime axis travel of `mount` (see `data::MountOdometer::mount`), if any.
+    pub fn odometer(&self, mount: &str) -> Option<data::MountOdometer> {
+        self.odometers().into_iter().find(|odometer| odometer.mount == mount)
+    }
+
     pub fn save_controller_actions(&mut self, actions: &ActionAssignments) {
+        let section = self.controller_section.clone();
         for target_action in TargetAction::iter() {
             let s = if let Some(src_action) = actions.get(target_action) {
                 src_action.serialize()
             } else {
                 "".to_string()
             };
-            self.set_string(sections::CONTROLLER, target_action.config_key(), &s);
+            self.set_string(&section, target_action.config_key(), &s);
         }
     }
 
     pub fn controller_actions(&self) -> ActionAssignments {
+        self.controller_actions_in(&self.controller_section)
+    }
+
+    fn controller_actions_in(&self, section: &str) -> ActionAssignments {
         use crate::controller::SourceAction;
 
         let mut result = ActionAssignments::new();
 
         for target_action in TargetAction::iter() {
-            if let Some(s) = self.get_string(sections::CONTROLLER, target_action.config_key()).map(|s| s.to_string()) {
+            if let Some(s) = self.get_string(section, target_action.config_key()).map(|s| s.to_string()) {
                 match s.parse::<SourceAction>() {
                     Ok(src_action) => result.set(target_action, Some(src_action)),
                     Err(e) => log::warn!("invalid action assignment: {}", e)
@@ -164,17 +1190,243 @@ impl Configuration {
         result
     }
 
+    /// Returns name of the active controller profile (empty for the default one).
+    pub fn controller_profile(&self) -> String {
+        self.controller_section.strip_prefix(sections::CONTROLLER_PROFILE_PREFIX).unwrap_or("").to_string()
+    }
+
+    /// Makes `profile` the active controller profile; subsequent controller-related calls refer to it.
+    pub fn set_controller_profile(&mut self, profile: &str) {
+        self.controller_section = controller_section(profile);
+        self.set_string(sections::MAIN, keys::CONTROLLER_PROFILE, profile);
+    }
+
+    /// Returns names of all controller profiles (the default one has an empty name).
+    pub fn controller_profiles(&self) -> Vec<String> {
+        let mut result: Vec<String> = self.config_file.sections().iter()
+            .filter_map(|s| s.strip_prefix(sections::CONTROLLER_PROFILE_PREFIX).map(|s| s.to_string()))
+            .filter(|s| !s.is_empty())
+            .collect();
+        result.sort();
+        result.insert(0, "".into());
+
+        result
+    }
+
+    /// Creates a new controller profile as a copy of the active one.
+    pub fn add_controller_profile(&mut self, profile: &str) {
+        let entries = self.config_file.get_map_ref().get(&self.controller_section).cloned().unwrap_or_default();
+        let section = controller_section(profile);
+        for (key, value) in entries {
+            self.config_file.set(&section, &key, value);
+        }
+        // also ensures the section gets created (and the profile listed) if the active profile has no entries yet
+        let (axis1_reversed, axis2_reversed) = (self.mount_axis1_reversed(), self.mount_axis2_reversed());
+        self.set_string(&section, keys::MOUNT_AXIS1_REVERSED, &axis1_reversed.to_string());
+        self.set_string(&section, keys::MOUNT_AXIS2_REVERSED, &axis2_reversed.to_string());
+    }
+
+    /// Returns whether events from controller `ctrl_id` are to be handled.
+    pub fn controller_enabled(&self, ctrl_id: u64) -> bool {
+        !self.disabled_controllers().contains(&ctrl_id)
+    }
+
+    pub fn set_controller_enabled(&mut self, ctrl_id: u64, enabled: bool) {
+        let mut disabled = self.disabled_controllers();
+        disabled.retain(|id| *id != ctrl_id);
+        if !enabled { disabled.push(ctrl_id); }
+        let value = disabled.iter().map(|id| format!("{:016X}", id)).collect::<Vec<_>>().join(",");
+        self.set_string(sections::MAIN, keys::DISABLED_CONTROLLERS, &value);
+    }
+
+    fn disabled_controllers(&self) -> Vec<u64> {
+        self.get_string(sections::MAIN, keys::DISABLED_CONTROLLERS).unwrap_or_default()
+            .split(',')
+            .filter_map(|s| u64::from_str_radix(s.trim(), 16).ok())
+            .collect()
+    }
+
+    /// Returns the first profile with actions assigned to controller `ctrl_id`.
+    pub fn controller_profile_for(&self, ctrl_id: u64) -> Option<String> {
+        self.controller_profiles().into_iter()
+            .find(|profile| self.controller_actions_in(&controller_section(profile)).uses_controller(ctrl_id))
+    }
+
+    /// Returns mapping of slewing input to speed (of the active controller profile).
+    pub fn rate_mapping(&self) -> controller::RateMapping {
+        match self.get_string(&self.controller_section, keys::MOUNT_AXIS_RATE_MAPPING) {
+            Some(value) => value.parse().unwrap_or_else(|e| {
+                log::warn!("invalid rate mapping \"{}\": {}", value, e);
+                Default::default()
+            }),
+            None => Default::default()
+        }
+    }
+
+    /// Returns whether tracking adjustments are made in polar mode (of the active controller profile).
+    pub fn polar_adjustment(&self) -> bool {
+        self.config_file.getbool(&self.controller_section, keys::POLAR_ADJUSTMENT)
+            .unwrap_or(Some(false))
+            .unwrap_or(false)
+    }
+
+    pub fn set_polar_adjustment(&mut self, value: bool) {
+        let section = self.controller_section.clone();
+        self.set_string(&section, keys::POLAR_ADJUSTMENT, &value.to_string());
+    }
+
+    /// Returns response of analog controller axes assigned to mount axes 1 and 2.
+    pub fn mount_axes_response(&self) -> [AxisResponse; 2] {
+        let mut result = [AxisResponse::default(); 2];
+        for (i, response) in result.iter_mut().enumerate() {
+            let default = AxisResponse::default();
+            response.dead_zone = self.get_f64(&self.controller_section, keys::MOUNT_AXIS_DEAD_ZONE[i])
+                .unwrap_or(default.dead_zone).max(0.0).min(0.9);
+            response.exponent = self.get_f64(&self.controller_section, keys::MOUNT_AXIS_EXPONENT[i])
+                .unwrap_or(default.exponent).max(0.1).min(10.0);
+            response.sensitivity = self.get_f64(&self.controller_section, keys::MOUNT_AXIS_SENSITIVITY[i])
+                .unwrap_or(default.sensitivity).max(0.0).min(1.0);
+        }
+
+        result
+    }
+
+    pub fn set_mount_axes_response(&mut self, value: &[AxisResponse; 2]) {
+        let section = self.controller_section.clone();
+        for (i, response) in value.iter().enumerate() {
+            self.set_f64(&section, keys::MOUNT_AXIS_DEAD_ZONE[i], response.dead_zone);
+            self.set_f64(&section, keys::MOUNT_AXIS_EXPONENT[i], response.exponent);
+            self.set_f64(&section, keys::MOUNT_AXIS_SENSITIVITY[i], response.sensitivity);
+        }
+    }
+
     pub fn mount_axis1_reversed(&self) -> bool {
-        self.config_file.getbool(sections::CONTROLLER, keys::MOUNT_AXIS1_REVERSED)
+        self.config_file.getbool(&self.controller_section, keys::MOUNT_AXIS1_REVERSED)
             .unwrap_or(Some(false))
             .unwrap_or(false)
     }
 
     pub fn mount_axis2_reversed(&self) -> bool {
-        self.config_file.getbool(sections::CONTROLLER, keys::MOUNT_AXIS2_REVERSED)
+        self.config_file.getbool(&self.controller_section, keys::MOUNT_AXIS2_REVERSED)
             .unwrap_or(Some(false))
             .unwrap_or(false)
     }
+
+    /// Checks for malformed and out-of-range entries (which are otherwise replaced by defaults when read).
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        fn parses<T: std::str::FromStr>(value: &str) -> bool { value.parse::<T>().is_ok() }
+
+        let mut issues = vec![];
+        let main_value = |key: &str| self.get_string(sections::MAIN, key).filter(|s| !s.trim().is_empty());
+
+        for (key, min, max) in NUMERIC_RANGES {
+            let Some(value) = main_value(key) else { continue; };
+            match value.trim().parse::<f64>() {
+                Ok(x) if x.is_finite() && x >= min && x <= max => (),
+                Ok(_) => issues.push(ConfigIssue::new(
+                    sections::MAIN, key, &value, format!("outside the allowed range {} to {}", min, max)
+                )),
+                Err(_) => issues.push(ConfigIssue::new(sections::MAIN, key, &value, "not a number".into()))
+            }
+        }
+
+        let named_values: [(&str, fn(&str) -> bool); 15] = [
+            (keys::MOUNT_TYPE, parses::<mount::MountType>),
+            (keys::COLOR_SCHEME, parses::<ColorScheme>),
+            (keys::DATA_SOURCE_FORMAT, parses::<target_message::MessageFormat>),
+            (keys::SIMULATED_TARGET_PARAMS, parses::<target_source::SimulatedTargetParams>),
+            (keys::LOG_LEVEL, parses::<log::LevelFilter>),
+            (keys::CAMERA_TRIGGER_OUTPUT, parses::<camera_trigger::TriggerOutput>),
+            (keys::SESSION_LOG_FORMAT, parses::<session_log::Format>),
+            (keys::SESSION_LOG_FIELDS, |s| session_log::parse_fields(s).is_ok()),
+            (keys::MOUNT_SPEED_FILTER, parses::<data::SpeedFilter>),
+            (keys::TRACKING_PAUSE_MODE, parses::<tracking::PauseMode>),
+            (keys::TARGET_LOSS_ACTION, parses::<tracking::TargetLossAction>),
+            (keys::SEARCH_PATTERN, parses::<search_pattern::SearchPattern>),
+            (keys::ANGLE_FORMAT, parses::<data::AngleFormat>),
+            (keys::AZIMUTH_ORIGIN, parses::<data::AzimuthOrigin>),
+            (keys::AZIMUTH_RANGE, parses::<data::AzimuthRange>),
+            (keys::SPEED_UNIT, parses::<data::SpeedUnit>)
+        ];
+        for (key, is_valid) in named_values {
+            if let Some(value) = main_value(key).filter(|v| !is_valid(v)) {
+                issues.push(ConfigIssue::new(sections::MAIN, key, &value, "invalid value".into()));
+            }
+        }
+        if let Some(presets) = main_value(keys::SLEW_SPEED_PRESETS) {
+            if presets.split(',').any(|p| !matches!(p.trim().parse::<f64>(), Ok(p) if p > 0.0)) {
+                issues.push(ConfigIssue::new(
+                    sections::MAIN, keys::SLEW_SPEED_PRESETS, &presets, "expected positive numbers".into()
+                ));
+            }
+        }
+
+        let map = self.config_file.get_map_ref();
+        for (section, prefix, is_valid) in [
+            (sections::REF_POS_PRESETS, keys::REF_POS_PRESET, parses::<data::RefPositionPreset> as fn(&str) -> bool),
+            (sections::SAVED_ADJUSTMENTS, keys::SAVED_ADJUSTMENT, parses::<data::SavedAdjustment>),
+            (sections::LANDMARKS, keys::LANDMARK, parses::<data::Landmark>),
+            (sections::SITES, keys::SITE, parses::<data::Site>),
+            (sections::ODOMETERS, keys::ODOMETER, parses::<data::MountOdometer>)
+        ] {
+            let mut entries: Vec<_> = map.get(section).into_iter().flatten()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key, value.as_deref().unwrap_or_default()))
+                .collect();
+            entries.sort_by_key(|(key, _)| key[prefix.len()..].parse::<usize>().unwrap_or(usize::MAX));
+            for (key, value) in entries.into_iter().filter(|(_, value)| !is_valid(value)) {
+                issues.push(ConfigIssue::new(section, key, value, "malformed entry".into()));
+            }
+        }
+
+        let mut ctrl_sections: Vec<_> = map.keys()
+            .filter(|s| *s == sections::CONTROLLER || s.starts_with(sections::CONTROLLER_PROFILE_PREFIX))
+            .collect();
+        ctrl_sections.sort();
+        for section in ctrl_sections {
+            let value = |key: &str| self.get_string(section, key).filter(|s| !s.is_empty());
+            for target_action in TargetAction::iter() {
+                let key = target_action.config_key();
+                if let Some(value) = value(key).filter(|v| !parses::<controller::SourceAction>(v)) {
+                    issues.push(ConfigIssue::new(section, key, &value, "invalid action binding".into()));
+                }
+            }
+            let key = keys::MOUNT_AXIS_RATE_MAPPING;
+            if let Some(value) = value(key).filter(|v| !parses::<controller::RateMapping>(v)) {
+                issues.push(ConfigIssue::new(section, key, &value, "invalid value".into()));
+            }
+        }
+
+        issues
+    }
+
+    /// Replaces the value of the entry described by `issue`.
+    pub fn fix_entry(&mut self, issue: &ConfigIssue, value: &str) {
+        self.set_string(&issue.section, &issue.key, value.trim());
+    }
+
+    /// Removes the entry described by `issue`, so that the default is used.
+    pub fn reset_entry(&mut self, issue: &ConfigIssue) {
+        if issue.section == sections::REF_POS_PRESETS {
+            // invalid presets are skipped; renumber the remaining ones
+            let presets = self.ref_pos_presets();
+            self.set_ref_pos_presets(&presets);
+        } else if issue.section == sections::SAVED_ADJUSTMENTS {
+            let adjustments = self.saved_adjustments();
+            self.set_saved_adjustments(&adjustments);
+        } else if issue.section == sections::LANDMARKS {
+            let landmarks = self.landmarks();
+            self.set_landmarks(&landmarks);
+        } else if issue.section == sections::SITES {
+            let sites = self.sites();
+            self.set_sites(&sites);
+        } else if issue.section == sections::ODOMETERS {
+            let odometers = self.odometers();
+            self.set_odometers(&odometers);
+        } else {
+            self.config_file.remove_key(&issue.section, &issue.key);
+        }
+    }
 }
 
 impl Drop for Configuration {
@@ -185,6 +1437,45 @@ impl Drop for Configuration {
     }
 }
 
+fn controller_section(profile: &str) -> String {
+    if profile.is_empty() {
+        sections::CONTROLLER.into()
+    } else {
+        format!("{}{}", sections::CONTROLLER_PROFILE_PREFIX, profile)
+    }
+}
+
+fn config_dir() -> PathBuf {
+    dirs::config_dir().unwrap_or(Path::new("").to_path_buf())
+}
+
 fn config_file_path() -> PathBuf {
-    Path::new(&dirs::config_dir().or(Some(Path::new("").to_path_buf())).unwrap()).join(CONFIG_FILE_NAME)
+    config_dir().join(CONFIG_FILE_NAME)
+}
+
+/// Returns path of the configuration file of `profile` (of the default configuration if `None`).
+pub fn profile_file_path(profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(profile) => config_dir().join(format!("{}{}{}", PROFILE_FILE_PREFIX, profile, CONFIG_FILE_EXTENSION)),
+        None => config_file_path()
+    }
+}
+
+/// Returns names of existing configuration profiles.
+pub fn profiles() -> Vec<String> {
+    let entries = match std::fs::read_dir(config_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return vec![]
+    };
+    let mut result: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|name| profile_name(&name).map(|p| p.to_string()))
+        .collect();
+    result.sort();
+    result
+}
+
+/// Returns profile name corresponding to configuration file name.
+fn profile_name(file_name: &str) -> Option<&str> {
+    file_name.strip_prefix(PROFILE_FILE_PREFIX)?.strip_suffix(CONFIG_FILE_EXTENSION).filter(|p| !p.is_empty())
 }
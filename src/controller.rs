@@ -16,11 +16,13 @@
 // along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
 //
 
-use pointing_utils::scan_fmt;
+use crate::data::{as_deg_per_s, deg_per_s};
+use pointing_utils::{scan_fmt, uom};
 use std::{cell::RefCell, collections::HashMap, error::Error, rc::Rc};
 use strum::{EnumDiscriminants, IntoEnumIterator};
 use strum_macros as sm;
 use strum_macros::{IntoStaticStr, EnumString};
+use uom::si::f64;
 
 
 mod serialized_event {
@@ -44,6 +46,15 @@ mod serialized_event {
 
 pub use serialized_event::SerializedEvent;
 
+/// Controller currently connected.
+#[derive(Clone, Debug)]
+pub struct ConnectedController {
+    pub id: u64,
+    pub name: String,
+    /// If false, events from the controller are ignored.
+    pub enabled: bool
+}
+
 #[derive(Debug)]
 pub struct StickEvent {
     pub id: u64,
@@ -59,14 +70,20 @@ pub enum EventValue {
 pub struct SourceAction {
     pub ctrl_id: u64,
     pub ctrl_name: String, // only for user information, not used to filter controller events
+    /// Button which has to be held for the action to be triggered (chorded action).
+    pub modifier: Option<SerializedEvent>,
     pub event: SerializedEvent
 }
 
 impl SourceAction {
     pub fn serialize(&self) -> String {
-        format!("[{:016X}]{}", self.ctrl_id, self.event.as_str())
+        match &self.modifier {
+            Some(modifier) => format!("[{:016X}]{}+{}", self.ctrl_id, modifier.as_str(), self.event.as_str()),
+            None => format!("[{:016X}]{}", self.ctrl_id, self.event.as_str())
+        }
     }
 
+    /// Returns `true` if `event` matches, not taking the modifier into account.
     pub fn matches(&self, event: &StickEvent) -> bool {
         self.ctrl_id == event.id && SerializedEvent::from_event(&event.event).as_str() == self.event.as_str()
     }
@@ -79,7 +96,51 @@ impl std::str::FromStr for SourceAction {
         let (ctrl_id, event_str) =
             scan_fmt::scan_fmt!(s, "[{x}]{}", [hex u64], String)?;
 
-        Ok(SourceAction{ ctrl_id, ctrl_name: "".into(), event: SerializedEvent::from_str(&event_str)? })
+        let (modifier, event) = match event_str.split_once('+') {
+            Some((modifier, event)) => {
+                if modifier.is_empty() || event.is_empty() {
+                    return Err(format!("invalid chorded action: {}", event_str).into());
+                }
+                (Some(SerializedEvent::from_str(modifier)?), SerializedEvent::from_str(event)?)
+            },
+            None => (None, SerializedEvent::from_str(&event_str)?)
+        };
+
+        Ok(SourceAction{ ctrl_id, ctrl_name: "".into(), modifier, event })
+    }
+}
+
+/// Buttons currently held on connected controllers (in the order of pressing).
+#[derive(Default)]
+pub struct HeldButtons {
+    buttons: Vec<(u64, String)>
+}
+
+impl HeldButtons {
+    /// Updates the held buttons after `event`.
+    pub fn update(&mut self, event: &StickEvent) {
+        if let stick::Event::Disconnect = event.event {
+            self.buttons.retain(|(id, _)| *id != event.id);
+            return;
+        }
+        if !is_discrete(&event.event) { return; }
+
+        let button = SerializedEvent::from_event(&event.event).as_str().to_string();
+        self.buttons.retain(|(id, b)| *id != event.id || *b != button);
+        if let EventValue::Discrete(true) = event_value(&event.event) {
+            self.buttons.push((event.id, button));
+        }
+    }
+
+    pub fn is_held(&self, ctrl_id: u64, button: &SerializedEvent) -> bool {
+        self.buttons.iter().any(|(id, b)| *id == ctrl_id && b == button.as_str())
+    }
+
+    /// Returns the most recently pressed button of controller `ctrl_id` which is still held, other than `except`.
+    pub fn last_held(&self, ctrl_id: u64, except: &SerializedEvent) -> Option<SerializedEvent> {
+        self.buttons.iter().rev()
+            .find(|(id, b)| *id == ctrl_id && b != except.as_str())
+            .map(|(_, b)| SerializedEvent::from_str(b).unwrap())
     }
 }
 
@@ -94,10 +155,20 @@ pub enum TargetAction {
     MountAxis2Neg, // via controller's discrete button
     StopMount,
     ToggleTracking,
+    TogglePause,
     SaveAdjustment,
     CancelAdjustment,
     IncreaseSlewSpeed,
     DecreaseSlewSpeed,
+    NextTarget,
+    PreviousTarget,
+    ToggleParking,
+    TriggerCamera,
+    TrimAxis1Pos,
+    TrimAxis1Neg,
+    TrimAxis2Pos,
+    TrimAxis2Neg,
+    ResetTrim,
 }
 
 impl TargetAction {
@@ -115,10 +186,20 @@ impl std::fmt::Display for TargetAction  {
             TargetAction::MountAxis2Neg => "Mount axis 2 / negative",
             TargetAction::StopMount => "Stop mount",
             TargetAction::ToggleTracking => "Toggle tracking",
+            TargetAction::TogglePause => "Pause/resume tracking",
             TargetAction::SaveAdjustment => "Save adjustment",
             TargetAction::CancelAdjustment => "Cancel adjustment",
             TargetAction::IncreaseSlewSpeed => "Increase slew speed",
             TargetAction::DecreaseSlewSpeed => "Decrease slew speed",
+            TargetAction::NextTarget => "Next queued target",
+            TargetAction::PreviousTarget => "Previous queued target",
+            TargetAction::ToggleParking => "Park/unpark mount",
+            TargetAction::TriggerCamera => "Trigger camera",
+            TargetAction::TrimAxis1Pos => "Rate trim axis 1 / positive",
+            TargetAction::TrimAxis1Neg => "Rate trim axis 1 / negative",
+            TargetAction::TrimAxis2Pos => "Rate trim axis 2 / positive",
+            TargetAction::TrimAxis2Neg => "Rate trim axis 2 / negative",
+            TargetAction::ResetTrim => "Reset rate trim",
         })
     }
 }
@@ -145,6 +226,124 @@ impl ActionAssignments {
     pub fn set(&mut self, target_action: TargetAction, src_action: Option<SourceAction>) {
         self.map.entry(target_action).and_modify(|e| *e = src_action);
     }
+
+    /// Returns actions to be triggered by `event`. A chorded action whose modifier is held takes precedence over
+    /// a plain one; button releases are passed to all matching actions, regardless of modifiers.
+    pub fn matching(&self, event: &StickEvent, held: &HeldButtons) -> Vec<TargetAction> {
+        let released = matches!(event_value(&event.event), EventValue::Discrete(false));
+        let mut chorded = vec![];
+        let mut plain = vec![];
+        for target_action in TargetAction::iter() {
+            let Some(src_action) = self.get(target_action) else { continue; };
+            if !src_action.matches(event) { continue; }
+            match &src_action.modifier {
+                None => plain.push(target_action),
+                Some(modifier) => if released || held.is_held(event.id, modifier) { chorded.push(target_action); }
+            }
+        }
+
+        if released {
+            chorded.extend(plain);
+            chorded
+        } else {
+            chorded.into_iter().chain(plain).take(1).collect()
+        }
+    }
+
+    /// Returns chorded actions of controller `ctrl_id` which use `modifier`.
+    pub fn chorded_with(&self, ctrl_id: u64, modifier: &SerializedEvent) -> Vec<TargetAction> {
+        TargetAction::iter()
+            .filter(|t| matches!(self.get(*t), Some(s)
+                if s.ctrl_id == ctrl_id && s.modifier.as_ref().is_some_and(|m| m.as_str() == modifier.as_str())))
+            .collect()
+    }
+
+    /// Returns `true` if any action is assigned to controller `ctrl_id`.
+    pub fn uses_controller(&self, ctrl_id: u64) -> bool {
+        self.map.values().any(|src_action| matches!(src_action, Some(s) if s.ctrl_id == ctrl_id))
+    }
+}
+
+/// Response of an analog controller axis.
+#[derive(Copy, Clone, Debug)]
+pub struct AxisResponse {
+    /// Values with magnitude below this are treated as 0.
+    pub dead_zone: f64,
+    /// Exponent of the response curve (1.0: linear; higher values give finer control for small deflections).
+    pub exponent: f64,
+    /// Output scaling factor.
+    pub sensitivity: f64
+}
+
+impl Default for AxisResponse {
+    fn default() -> AxisResponse {
+        AxisResponse{ dead_zone: 0.0, exponent: 1.0, sensitivity: 1.0 }
+    }
+}
+
+impl AxisResponse {
+    /// Converts raw axis value from [-1.0; 1.0] to the output value from [-1.0; 1.0].
+    pub fn apply(&self, value: f64) -> f64 {
+        let magnitude = value.abs();
+        if magnitude <= self.dead_zone { return 0.0; }
+
+        let normalized = ((magnitude - self.dead_zone) / (1.0 - self.dead_zone)).min(1.0);
+        (value.signum() * normalized.powf(self.exponent) * self.sensitivity).max(-1.0).min(1.0)
+    }
+}
+
+/// Mapping of relative slewing input (after applying `AxisResponse`) to slewing speed.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum RateMapping {
+    /// Speed proportional to input; full input gives the current slew speed.
+    #[default]
+    Linear,
+    /// Speed changes exponentially with input, from `min_speed` (for the smallest non-zero input) to the current
+    /// slew speed (for full input); gives fine control over a wide range of speeds.
+    Logarithmic{ min_speed: f64::AngularVelocity }
+}
+
+impl RateMapping {
+    /// Returns speed corresponding to `rel_input` from [-1.0; 1.0] if full input corresponds to `max_speed`.
+    pub fn speed(&self, rel_input: f64, max_speed: f64::AngularVelocity) -> f64::AngularVelocity {
+        if rel_input == 0.0 { return deg_per_s(0.0); }
+
+        match self {
+            RateMapping::Linear => max_speed * rel_input,
+
+            RateMapping::Logarithmic{ min_speed } => {
+                let min_ratio = (as_deg_per_s(*min_speed) / as_deg_per_s(max_speed)).min(1.0);
+                max_speed * rel_input.signum() * min_ratio.powf(1.0 - rel_input.abs().min(1.0))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RateMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RateMapping::Linear => write!(f, "linear"),
+            RateMapping::Logarithmic{ min_speed } => write!(f, "log:{}", as_deg_per_s(*min_speed))
+        }
+    }
+}
+
+impl std::str::FromStr for RateMapping {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "linear" { return Ok(RateMapping::Linear); }
+
+        match s.strip_prefix("log:") {
+            Some(min_speed) => {
+                let min_speed = min_speed.trim().parse::<f64>()?;
+                if min_speed <= 0.0 { return Err("min. speed must be positive".into()); }
+                Ok(RateMapping::Logarithmic{ min_speed: deg_per_s(min_speed) })
+            },
+            None => Err(format!("invalid rate mapping: {}", s).into())
+        }
+    }
 }
 
 pub fn event_value(event: &stick::Event) -> EventValue {
@@ -328,3 +527,48 @@ fn is_discrete(event: &stick::Event) -> bool {
         _ => false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_response() {
+        let response = AxisResponse{ dead_zone: 0.1, exponent: 2.0, sensitivity: 0.5 };
+        assert_eq!(0.0, response.apply(0.05));
+        assert_eq!(0.0, response.apply(-0.1));
+        assert!((response.apply(0.55) - 0.125).abs() < 1.0e-12);
+        assert!((response.apply(-1.0) + 0.5).abs() < 1.0e-12);
+
+        assert!((AxisResponse::default().apply(0.3) - 0.3).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn logarithmic_rate_mapping() {
+        let mapping: RateMapping = "log:0.001".parse().unwrap();
+        let max = deg_per_s(1.0);
+        assert_eq!(0.0, as_deg_per_s(mapping.speed(0.0, max)));
+        assert!((as_deg_per_s(mapping.speed(1.0, max)) - 1.0).abs() < 1.0e-12);
+        assert!((as_deg_per_s(mapping.speed(-0.5, max)) + 0.031_622_776).abs() < 1.0e-8);
+        assert!((as_deg_per_s(mapping.speed(1.0e-6, max)) - 0.001).abs() < 1.0e-6);
+
+        assert!("log:-1".parse::<RateMapping>().is_err());
+        assert_eq!(RateMapping::Linear, "linear".parse().unwrap());
+    }
+
+    #[test]
+    fn chorded_source_action() {
+        let plain: SourceAction = "[03006D041DC21440]JoyX".parse().unwrap();
+        assert!(plain.modifier.is_none());
+        assert_eq!("[03006D041DC21440]JoyX", plain.serialize());
+
+        let chorded: SourceAction = "[03006D041DC21440]BumperL+JoyX".parse().unwrap();
+        assert_eq!(0x03006D041DC21440, chorded.ctrl_id);
+        assert_eq!("BumperL", chorded.modifier.as_ref().unwrap().as_str());
+        assert_eq!("JoyX", chorded.event.as_str());
+        assert_eq!("[03006D041DC21440]BumperL+JoyX", chorded.serialize());
+
+        assert!("[03006D041DC21440]+JoyX".parse::<SourceAction>().is_err());
+        assert!("[03006D041DC21440]BumperL+".parse::<SourceAction>().is_err());
+    }
+}
@@ -19,18 +19,38 @@
 use async_std::stream::Stream;
 use cgmath::{Basis3, Deg, EuclideanSpace, InnerSpace, Point3, Rad, Rotation, Rotation3, Vector3};
 use crate::{
+    camera_tracking::CameraCorrections,
+    camera_trigger::CameraTrigger,
+    clock,
     config::Configuration,
     controller,
     cursive_stepper::CursiveRunnableStepper,
-    data_receiver::DataReceiver,
+    fov::Fov,
+    data_receiver::{self, DataReceiver},
+    gps::GpsReceiver,
+    guiding::GuideServer,
+    keyboard::KeyboardSlewing,
+    latency::LatencyStats,
     mount,
+    overlay::Overlay,
+    refraction::Atmosphere,
+    scripting::Script,
+    session_log::SessionLogger,
+    state_broadcast::StateBroadcast,
+    sun_avoidance::SunAvoidance,
+    target_alarm::TargetAlarms,
+    telemetry::Telemetry,
+    target_message::MessageParser,
+    target_source::{TargetQueue, TargetSource},
+    time_sync::{ClockOffset, NtpClient},
     tracking::Tracking,
     tui,
     tui::TuiData
 };
-use pointing_utils::{cgmath, GeoPos, to_global_unit, uom};
-use std::{cell::{Ref, RefCell}, future::Future, marker::Unpin, pin::Pin, rc::Rc, task::{Context, Poll}};
-use uom::{si::f64, si::{angle, angular_velocity, length, time}};
+use pointing_utils::{cgmath, GeoPos, LatLon, to_global_unit, uom};
+use std::{cell::{Cell, Ref, RefCell}, future::Future, marker::Unpin, pin::Pin, rc::Rc, task::{Context, Poll}};
+use strum_macros as sm;
+use uom::{si::f64, si::{angle, angular_velocity, length, time, velocity}};
 use pasts::notify::Notify;
 
 pub mod timers {
@@ -38,6 +58,256 @@ pub mod timers {
 
     pub const MAIN: TimerId = 1;
     pub const TARGET_LOG: TimerId = 2;
+    pub const TARGET_SOURCE: TimerId = 3;
+    pub const KEYBOARD: TimerId = 4;
+    pub const EXTERNAL_INPUT: TimerId = 5;
+    pub const MOTION: TimerId = 6;
+    pub const MOUNT_STATE: TimerId = 7;
+}
+
+const WGS84_A: f64 = 6_378_137.0; // m
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+#[derive(Copy, Clone, Debug)]
+pub struct ObserverLocation {
+    pub lat: f64::Angle,
+    pub lon: f64::Angle,
+    pub elevation: f64::Length
+}
+
+impl ObserverLocation {
+    pub fn to_geo_pos(&self) -> GeoPos {
+        GeoPos{
+            lat_lon: LatLon{ lat: Deg(as_deg(self.lat)), lon: Deg(as_deg(self.lon)) },
+            elevation: self.elevation
+        }
+    }
+
+    /// Returns observer's position in the Earth-centered, Earth-fixed frame (WGS84 ellipsoid; m).
+    pub fn ecef(&self) -> Vector3<f64> {
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let (sin_lat, cos_lat) = self.lat.get::<angle::radian>().sin_cos();
+        let (sin_lon, cos_lon) = self.lon.get::<angle::radian>().sin_cos();
+        let h = self.elevation.get::<length::meter>();
+        let n = WGS84_A / (1.0 - e2 * sin_lat.powi(2)).sqrt();
+
+        Vector3{
+            x: (n + h) * cos_lat * cos_lon,
+            y: (n + h) * cos_lat * sin_lon,
+            z: (n * (1.0 - e2) + h) * sin_lat
+        }
+    }
+
+    /// Converts an Earth-centered, Earth-fixed vector to observer's local frame (X points north, Y points west,
+    /// Z points up).
+    pub fn ecef_to_local(&self, v: Vector3<f64>) -> Vector3<f64> {
+        let (sin_lat, cos_lat) = self.lat.get::<angle::radian>().sin_cos();
+        let (sin_lon, cos_lon) = self.lon.get::<angle::radian>().sin_cos();
+
+        let north = Vector3{ x: -sin_lat * cos_lon, y: -sin_lat * sin_lon, z: cos_lat };
+        let east = Vector3{ x: -sin_lon, y: cos_lon, z: 0.0 };
+        let up = Vector3{ x: cos_lat * cos_lon, y: cos_lat * sin_lon, z: sin_lat };
+
+        Vector3{ x: v.dot(north), y: -v.dot(east), z: v.dot(up) }
+    }
+}
+
+pub fn julian_date(t: chrono::DateTime<chrono::Utc>) -> f64 {
+    t.timestamp_millis() as f64 / 86_400_000.0 + 2_440_587.5
+}
+
+/// Returns the number of Julian centuries since J2000.0.
+pub fn julian_centuries(t: chrono::DateTime<chrono::Utc>) -> f64 {
+    (julian_date(t) - 2_451_545.0) / 36_525.0
+}
+
+/// Returns the Greenwich mean sidereal time as an angle in [0°; 360°).
+pub fn gmst(t: chrono::DateTime<chrono::Utc>) -> f64::Angle {
+    let days_since_j2000 = julian_date(t) - 2_451_545.0;
+    deg((280.460_618_37 + 360.985_647_366_29 * days_since_j2000).rem_euclid(360.0))
+}
+
+/// Returns the local mean sidereal time at (east-positive) longitude `lon` as an angle in [0°; 360°).
+pub fn local_sidereal_time(t: chrono::DateTime<chrono::Utc>, lon: f64::Angle) -> f64::Angle {
+    deg((as_deg(gmst(t)) + as_deg(lon)).rem_euclid(360.0))
+}
+
+/// Converts horizontal coordinates (azimuth measured from north towards east) to hour angle (in [0°; 360°))
+/// and declination.
+pub fn az_alt_to_ha_dec(azimuth: f64::Angle, altitude: f64::Angle, lat: f64::Angle) -> (f64::Angle, f64::Angle) {
+    let (sin_az, cos_az) = azimuth.get::<angle::radian>().sin_cos();
+    let (sin_alt, cos_alt) = altitude.get::<angle::radian>().sin_cos();
+    let (sin_lat, cos_lat) = lat.get::<angle::radian>().sin_cos();
+
+    let dec = (sin_lat * sin_alt + cos_lat * cos_alt * cos_az).clamp(-1.0, 1.0).asin();
+    let ha = (-sin_az * cos_alt).atan2(cos_lat * sin_alt - sin_lat * cos_alt * cos_az);
+
+    (deg(ha.to_degrees().rem_euclid(360.0)), deg(dec.to_degrees()))
+}
+
+/// Converts horizontal coordinates to right ascension (in [0°; 360°)) and declination of date.
+pub fn az_alt_to_ra_dec(
+    azimuth: f64::Angle,
+    altitude: f64::Angle,
+    observer: &ObserverLocation,
+    t: chrono::DateTime<chrono::Utc>
+) -> (f64::Angle, f64::Angle) {
+    let (ha, dec) = az_alt_to_ha_dec(azimuth, altitude, observer.lat);
+    (deg((as_deg(local_sidereal_time(t, observer.lon)) - as_deg(ha)).rem_euclid(360.0)), dec)
+}
+
+/// Converts equatorial coordinates of date to horizontal coordinates (azimuth measured from north towards east).
+pub fn ra_dec_to_az_alt(
+    ra: f64::Angle,
+    dec: f64::Angle,
+    observer: &ObserverLocation,
+    t: chrono::DateTime<chrono::Utc>
+) -> (f64::Angle, f64::Angle) {
+    let ha = local_sidereal_time(t, observer.lon) - ra;
+    let (sin_ha, cos_ha) = ha.get::<angle::radian>().sin_cos();
+    let (sin_dec, cos_dec) = dec.get::<angle::radian>().sin_cos();
+    let (sin_lat, cos_lat) = observer.lat.get::<angle::radian>().sin_cos();
+
+    let alt = (sin_lat * sin_dec + cos_lat * cos_dec * cos_ha).clamp(-1.0, 1.0).asin();
+    let az = (-cos_dec * sin_ha).atan2(sin_dec * cos_lat - cos_dec * cos_ha * sin_lat);
+
+    (deg(az.to_degrees().rem_euclid(360.0)), deg(alt.to_degrees()))
+}
+
+/// Formats right ascension (or hour angle) as hours, minutes and seconds.
+pub fn format_hms(value: f64::Angle) -> String {
+    let total_s = (as_deg(value).rem_euclid(360.0) / 15.0 * 3600.0).round() as u64 % (24 * 3600);
+    format!("{:02}h{:02}m{:02}s", total_s / 3600, total_s / 60 % 60, total_s % 60)
+}
+
+fn dms(value_deg: f64, signed: bool) -> String {
+    let total_s = (value_deg.abs() * 3600.0).round() as u64;
+    let sign = if value_deg < 0.0 { "-" } else if signed { "+" } else { "" };
+    format!("{}{:02}°{:02}'{:02}\"", sign, total_s / 3600, total_s / 60 % 60, total_s % 60)
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum AngleFormat {
+    /// Decimal degrees (right ascension: decimal hours).
+    #[default]
+    Decimal,
+    /// Degrees, arcminutes and arcseconds (right ascension: hours, minutes and seconds).
+    Dms
+}
+
+/// Direction corresponding to azimuth 0°.
+#[derive(Copy, Clone, Debug, Default, PartialEq, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum AzimuthOrigin {
+    #[default]
+    North,
+    South
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum AzimuthRange {
+    /// [0°; 360°)
+    #[default]
+    Unsigned,
+    /// [-180°; 180°)
+    Signed
+}
+
+/// Sidereal rate (°/s).
+const SIDEREAL_RATE_DEG_PER_S: f64 = 360.0 / 86164.0905;
+
+/// Unit of displayed angular speeds.
+#[derive(Copy, Clone, Debug, Default, PartialEq, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum SpeedUnit {
+    #[default]
+    DegPerS,
+    ArcminPerS,
+    ArcsecPerS,
+    /// Multiples of the sidereal rate.
+    Sidereal
+}
+
+impl SpeedUnit {
+    /// Returns value of 1°/s in this unit, the unit's suffix and the number of decimal places fewer than for °/s.
+    fn conversion(&self) -> (f64, &'static str, usize) {
+        match self {
+            SpeedUnit::DegPerS => (1.0, "°/s", 0),
+            SpeedUnit::ArcminPerS => (60.0, "'/s", 1),
+            SpeedUnit::ArcsecPerS => (3600.0, "\"/s", 3),
+            SpeedUnit::Sidereal => (1.0 / SIDEREAL_RATE_DEG_PER_S, "×sid", 2)
+        }
+    }
+}
+
+/// Formatting of displayed angles.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AngleDisplay {
+    pub format: AngleFormat,
+    pub az_origin: AzimuthOrigin,
+    pub az_range: AzimuthRange,
+    pub speed_unit: SpeedUnit
+}
+
+impl AngleDisplay {
+    /// Returns azimuth (measured clockwise from north) in degrees, converted to the configured convention.
+    pub fn normalize_azimuth(&self, azimuth: f64::Angle) -> f64 {
+        let value = match self.az_origin {
+            AzimuthOrigin::North => as_deg(azimuth),
+            AzimuthOrigin::South => as_deg(azimuth) - 180.0
+        }.rem_euclid(360.0);
+
+        match self.az_range {
+            AzimuthRange::Unsigned => value,
+            AzimuthRange::Signed => if value >= 180.0 { value - 360.0 } else { value }
+        }
+    }
+
+    /// Formats azimuth (measured clockwise from north); `precision` applies to decimal format.
+    pub fn azimuth(&self, azimuth: f64::Angle, precision: usize) -> String {
+        let value = self.normalize_azimuth(azimuth);
+        match self.format {
+            AngleFormat::Decimal => format!("{:.*}°", precision, value),
+            AngleFormat::Dms => dms(value, false)
+        }
+    }
+
+    /// Formats altitude or declination; `precision` applies to decimal format.
+    pub fn altitude(&self, altitude: f64::Angle, precision: usize) -> String {
+        match self.format {
+            AngleFormat::Decimal => format!("{:.*}°", precision, as_deg(altitude)),
+            AngleFormat::Dms => dms(as_deg(altitude), true)
+        }
+    }
+
+    /// Formats right ascension (or hour angle) in hours.
+    pub fn right_ascension(&self, ra: f64::Angle) -> String {
+        match self.format {
+            AngleFormat::Decimal => format!("{:.4}h", as_deg(ra).rem_euclid(360.0) / 15.0),
+            AngleFormat::Dms => format_hms(ra)
+        }
+    }
+
+    /// Formats angular speed; `precision` is the number of decimal places for °/s (reduced for smaller units).
+    pub fn speed(&self, speed: f64::AngularVelocity, precision: usize) -> String {
+        let (factor, suffix, fewer_places) = self.speed_unit.conversion();
+        format!("{:.*}{}", precision.saturating_sub(fewer_places), as_deg_per_s(speed) * factor, suffix)
+    }
+
+    /// Like `speed`, but always with a sign.
+    pub fn signed_speed(&self, speed: f64::AngularVelocity, precision: usize) -> String {
+        let (factor, suffix, fewer_places) = self.speed_unit.conversion();
+        format!("{:+.*}{}", precision.saturating_sub(fewer_places), as_deg_per_s(speed) * factor, suffix)
+    }
+}
+
+/// Converts a geocentric vector from the equatorial frame of date to the Earth-centered, Earth-fixed frame
+/// (nutation and polar motion are neglected).
+pub fn equatorial_to_ecef(v: Vector3<f64>, t: chrono::DateTime<chrono::Utc>) -> Vector3<f64> {
+    let (sin_g, cos_g) = gmst(t).get::<angle::radian>().sin_cos();
+    Vector3{ x: cos_g * v.x + sin_g * v.y, y: -sin_g * v.x + cos_g * v.y, z: v.z }
 }
 
 pub struct RefPositionPreset {
@@ -65,6 +335,182 @@ impl std::str::FromStr for RefPositionPreset {
     }
 }
 
+/// Header of the CSV file with exported reference position presets.
+const REF_POS_PRESETS_CSV_HEADER: &str = "azimuth,altitude,name";
+
+/// Returns presets as CSV (one preset per line: azimuth and altitude in degrees, name).
+pub fn ref_pos_presets_to_csv(presets: &[RefPositionPreset]) -> String {
+    let mut result = format!("{}\n", REF_POS_PRESETS_CSV_HEADER);
+    for preset in presets {
+        result += &format!("{},{},{}\n", as_deg(preset.azimuth), as_deg(preset.altitude), preset.name);
+    }
+    result
+}
+
+/// Parses presets from CSV (see `ref_pos_presets_to_csv`); the header line is optional.
+pub fn ref_pos_presets_from_csv(csv: &str) -> Result<Vec<RefPositionPreset>, Box<dyn std::error::Error>> {
+    let mut result = vec![];
+    for (line_idx, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.eq_ignore_ascii_case(REF_POS_PRESETS_CSV_HEADER) { continue; }
+        let parts: Vec<&str> = line.splitn(3, ',').collect();
+        if parts.len() != 3 {
+            return Err(format!("line {}: expected azimuth, altitude and name", line_idx + 1).into());
+        }
+        let parse = |s: &str| s.trim().parse::<f64>().map_err(|e| format!("line {}: {}", line_idx + 1, e));
+        result.push(RefPositionPreset{
+            azimuth: deg(parse(parts[0])?),
+            altitude: deg(parse(parts[1])?),
+            name: parts[2].trim().into()
+        });
+    }
+    Ok(result)
+}
+
+/// Tracking adjustment stored for a target source (see `Tracking::adjustment`).
+#[derive(Clone)]
+pub struct SavedAdjustment {
+    pub name: String,
+    /// Name of target source or address of data source.
+    pub source: String,
+    pub rel_dir: f64::Angle,
+    pub angle: f64::Angle
+}
+
+impl std::fmt::Display for SavedAdjustment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{};{};{};{}", as_deg(self.rel_dir), as_deg(self.angle), self.source, self.name)
+    }
+}
+
+impl std::str::FromStr for SavedAdjustment {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.splitn(4, ';').collect();
+        if parts.len() != 4 { return Err(format!("invalid saved adjustment: {}", s).into()); }
+        Ok(SavedAdjustment{
+            rel_dir: deg(parts[0].parse::<f64>()?),
+            angle: deg(parts[1].parse::<f64>()?),
+            source: parts[2].into(),
+            name: parts[3].into()
+        })
+    }
+}
+
+/// Named terrestrial point (e.g., a mast) whose direction is calculated from the observer location.
+#[derive(Clone)]
+pub struct Landmark {
+    pub name: String,
+    pub location: ObserverLocation
+}
+
+impl Landmark {
+    /// Returns azimuth and altitude of the landmark as seen by `observer`.
+    pub fn az_alt(&self, observer: &ObserverLocation) -> (f64::Angle, f64::Angle) {
+        calc_az_alt_between_points(&observer.to_geo_pos(), &self.location.to_geo_pos())
+    }
+}
+
+impl std::fmt::Display for Landmark {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{};{};{};{}",
+            as_deg(self.location.lat),
+            as_deg(self.location.lon),
+            self.location.elevation.get::<length::meter>(),
+            self.name
+        )
+    }
+}
+
+impl std::str::FromStr for Landmark {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.splitn(4, ';').collect();
+        if parts.len() != 4 { return Err(format!("invalid landmark: {}", s).into()); }
+        Ok(Landmark{
+            location: ObserverLocation{
+                lat: deg(parts[0].parse::<f64>()?),
+                lon: deg(parts[1].parse::<f64>()?),
+                elevation: f64::Length::new::<length::meter>(parts[2].parse::<f64>()?)
+            },
+            name: parts[3].into()
+        })
+    }
+}
+
+/// Observing site (see `Configuration::use_site`).
+#[derive(Clone)]
+pub struct Site {
+    pub name: String,
+    pub location: ObserverLocation,
+    /// Angle from true north to magnetic north (positive: east).
+    pub magnetic_declination: f64::Angle
+}
+
+impl std::fmt::Display for Site {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{};{};{};{};{}",
+            as_deg(self.location.lat),
+            as_deg(self.location.lon),
+            self.location.elevation.get::<length::meter>(),
+            as_deg(self.magnetic_declination),
+            self.name
+        )
+    }
+}
+
+impl std::str::FromStr for Site {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.splitn(5, ';').collect();
+        if parts.len() != 5 { return Err(format!("invalid site: {}", s).into()); }
+        Ok(Site{
+            location: ObserverLocation{
+                lat: deg(parts[0].parse::<f64>()?),
+                lon: deg(parts[1].parse::<f64>()?),
+                elevation: f64::Length::new::<length::meter>(parts[2].parse::<f64>()?)
+            },
+            magnetic_declination: deg(parts[3].parse::<f64>()?),
+            name: parts[4].into()
+        })
+    }
+}
+
+/// Lifetime travel of a mount's axes (e.g., for maintenance scheduling).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MountOdometer {
+    /// Mount identity (as reported by `Mount::get_info` after connecting).
+    pub mount: String,
+    /// Sum of absolute motion of each axis.
+    pub travel: (f64::Angle, f64::Angle)
+}
+
+impl std::fmt::Display for MountOdometer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{};{};{}", as_deg(self.travel.0), as_deg(self.travel.1), self.mount)
+    }
+}
+
+impl std::str::FromStr for MountOdometer {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.splitn(3, ';').collect();
+        if parts.len() != 3 { return Err(format!("invalid odometer: {}", s).into()); }
+        Ok(MountOdometer{
+            travel: (deg(parts[0].parse::<f64>()?), deg(parts[1].parse::<f64>()?)),
+            mount: parts[2].into()
+        })
+    }
+}
+
 pub struct Slewing {
     // values from [-1.0, 1.0]
     pub axis1_rel: f64,
@@ -103,7 +549,56 @@ pub struct Target {
     pub altitude: f64::Angle,
     pub az_spd: f64::AngularVelocity,
     pub alt_spd: f64::AngularVelocity,
-    pub v_tangential: Vector3<f64> // m/s
+    pub v_tangential: Vector3<f64>, // m/s
+    /// Time when the target data were received or calculated.
+    pub received: std::time::Instant,
+    /// Identifier provided by the data source (if any).
+    pub id: Option<String>
+}
+
+impl Target {
+    /// Creates target data from its position (m) and velocity (m/s) in the observer's frame of reference
+    /// (X points north, Y points west, Z points up).
+    pub fn from_local(position: Point3<f64>, velocity: Vector3<f64>, alt_above_gnd: f64::Length) -> Target {
+        let radians = |value: f64| f64::AngularVelocity::new::<angular_velocity::radian_per_second>(value);
+
+        let r = position.to_vec();
+        let r_len2 = r.magnitude2();
+        let r_len = r_len2.sqrt();
+        let (azimuth, altitude) = to_spherical(position);
+        let v_radial = r * velocity.dot(r) / r_len2;
+        let v_tangential = velocity - v_radial;
+        const ZENITH: Vector3<f64> = Vector3{ x: 0.0, y: 0.0, z: 1.0 };
+        let pos_az = r.cross(ZENITH);
+        let to_zenith = pos_az.cross(r);
+        let v_up_down = to_zenith * v_tangential.dot(to_zenith) / to_zenith.magnitude2();
+        let v_left_right = v_tangential - v_up_down;
+        let ang_speed_az_sign = -r.cross(v_tangential).z.signum();
+        let az_spd = ang_speed_az_sign * radians(v_left_right.magnitude() / (r.x.powi(2) + r.y.powi(2)).sqrt());
+        let alt_spd = v_up_down.z.signum() * radians(v_up_down.magnitude() / r_len);
+
+        Target{
+            dist: f64::Length::new::<length::meter>(r_len),
+            speed: f64::Velocity::new::<velocity::meter_per_second>(velocity.magnitude()),
+            alt_above_gnd,
+            azimuth,
+            altitude,
+            az_spd,
+            alt_spd,
+            v_tangential,
+            received: clock::now(),
+            id: None
+        }
+    }
+
+    pub fn age(&self) -> std::time::Duration { clock::elapsed(self.received) }
+
+    /// Returns the total angular speed of target as seen by the observer.
+    pub fn ang_speed(&self) -> f64::AngularVelocity {
+        f64::AngularVelocity::new::<angular_velocity::radian_per_second>(
+            self.v_tangential.magnitude() / self.dist.get::<length::meter>()
+        )
+    }
 }
 
 struct MountLastPos {
@@ -112,51 +607,218 @@ struct MountLastPos {
     axis2_pos: f64::Angle
 }
 
+/// Filter applied to mount axes' speeds estimated from consecutive positions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SpeedFilter {
+    None,
+    /// Average of the specified number of last raw speeds.
+    MovingAverage(usize),
+    /// Alpha-beta filter of position and speed.
+    AlphaBeta{ alpha: f64, beta: f64 }
+}
+
+impl Default for SpeedFilter {
+    fn default() -> SpeedFilter { SpeedFilter::MovingAverage(4) }
+}
+
+impl std::str::FromStr for SpeedFilter {
+    type Err = String;
+
+    /// Parses "none", "moving_average:<N>" or "alpha_beta:<alpha>,<beta>".
+    fn from_str(s: &str) -> Result<SpeedFilter, String> {
+        let (kind, params) = s.split_once(':').unwrap_or((s, ""));
+        let invalid = || format!("invalid speed filter: {}", s);
+        match kind.trim() {
+            "none" => Ok(SpeedFilter::None),
+
+            "moving_average" => match params.trim().parse::<usize>() {
+                Ok(n) if n > 0 => Ok(SpeedFilter::MovingAverage(n)),
+                _ => Err(invalid())
+            },
+
+            "alpha_beta" => {
+                let (alpha, beta) = params.split_once(',').ok_or_else(invalid)?;
+                let alpha = alpha.trim().parse::<f64>().map_err(|_| invalid())?;
+                let beta = beta.trim().parse::<f64>().map_err(|_| invalid())?;
+                if alpha <= 0.0 || alpha > 1.0 || beta <= 0.0 || beta > 2.0 { return Err(invalid()); }
+                Ok(SpeedFilter::AlphaBeta{ alpha, beta })
+            },
+
+            _ => Err(invalid())
+        }
+    }
+}
+
 pub struct MountSpeed {
+    filter: SpeedFilter,
     last_pos: Option<MountLastPos>,
+    /// Speed calculated from the two last positions.
+    raw_spd: Option<(f64::AngularVelocity, f64::AngularVelocity)>,
     axes_spd: Option<(f64::AngularVelocity, f64::AngularVelocity)>,
+    /// Last raw speeds (°/s), used by the moving average filter.
+    raw_history: std::collections::VecDeque<(f64, f64)>,
+    /// Position (°) and speed (°/s) of each axis estimated by the alpha-beta filter.
+    alpha_beta: Option<[(f64, f64); 2]>
 }
 
 // TODO: make it updatable only from main timer handler
 impl MountSpeed {
-    pub fn new() -> MountSpeed {
-        MountSpeed{ last_pos: None, axes_spd: None }
+    pub fn new(filter: SpeedFilter) -> MountSpeed {
+        MountSpeed{
+            filter,
+            last_pos: None,
+            raw_spd: None,
+            axes_spd: None,
+            raw_history: Default::default(),
+            alpha_beta: None
+        }
     }
 
     pub fn notify_pos(&mut self, axis1_pos: f64::Angle, axis2_pos: f64::Angle) {
         if let Some(last_pos) = &self.last_pos {
-            let dt = time(last_pos.t.elapsed());
-            if dt.get::<time::second>() > 0.0 {
-                self.axes_spd = Some((
-                    Into::<f64::AngularVelocity>::into((axis1_pos - last_pos.axis1_pos) / dt),
-                    Into::<f64::AngularVelocity>::into((axis2_pos - last_pos.axis2_pos) / dt)
-                ));
+            let dt = time(clock::elapsed(last_pos.t)).get::<time::second>();
+            if dt > 0.0 {
+                let raw1 = as_deg(angle_diff(last_pos.axis1_pos, axis1_pos)) / dt;
+                let raw2 = as_deg(angle_diff(last_pos.axis2_pos, axis2_pos)) / dt;
+                self.raw_spd = Some((deg_per_s(raw1), deg_per_s(raw2)));
+                let (spd1, spd2) = self.filter_speed((raw1, raw2), (as_deg(axis1_pos), as_deg(axis2_pos)), dt);
+                self.axes_spd = Some((deg_per_s(spd1), deg_per_s(spd2)));
             }
         }
 
-        self.last_pos = Some(MountLastPos{ t: std::time::Instant::now(), axis1_pos, axis2_pos });
+        self.last_pos = Some(MountLastPos{ t: clock::now(), axis1_pos, axis2_pos });
+    }
+
+    /// Returns filtered speed (°/s) of both axes.
+    fn filter_speed(&mut self, raw: (f64, f64), pos: (f64, f64), dt: f64) -> (f64, f64) {
+        match self.filter {
+            SpeedFilter::None => raw,
+
+            SpeedFilter::MovingAverage(len) => {
+                if self.raw_history.len() >= len { self.raw_history.pop_front(); }
+                self.raw_history.push_back(raw);
+                let n = self.raw_history.len() as f64;
+                let (sum1, sum2) = self.raw_history.iter().fold((0.0, 0.0), |acc, s| (acc.0 + s.0, acc.1 + s.1));
+                (sum1 / n, sum2 / n)
+            },
+
+            SpeedFilter::AlphaBeta{ alpha, beta } => {
+                let mut state = self.alpha_beta.unwrap_or([(pos.0, raw.0), (pos.1, raw.1)]);
+                for ((x, v), measured) in state.iter_mut().zip([pos.0, pos.1]) {
+                    let predicted = *x + *v * dt;
+                    let residual = as_deg(angle_diff(deg(predicted), deg(measured)));
+                    *x = predicted + alpha * residual;
+                    *v += beta * residual / dt;
+                }
+                self.alpha_beta = Some(state);
+                (state[0].1, state[1].1)
+            }
+        }
     }
 
+    /// Returns filtered speed of both axes.
     pub fn get(&self) -> Option<(f64::AngularVelocity, f64::AngularVelocity)> { self.axes_spd }
+
+    /// Returns unfiltered speed of both axes.
+    pub fn get_raw(&self) -> Option<(f64::AngularVelocity, f64::AngularVelocity)> { self.raw_spd }
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct PositionSample {
+    /// Mount axes' positions.
+    pub mount: Option<(f64::Angle, f64::Angle)>,
+    /// Target's azimuth & altitude.
+    pub target: Option<(f64::Angle, f64::Angle)>
+}
+
+/// Recent mount and target positions (oldest first).
+pub struct PositionHistory {
+    samples: std::collections::VecDeque<PositionSample>,
+    max_len: usize
+}
+
+impl PositionHistory {
+    pub fn new(max_len: usize) -> PositionHistory {
+        PositionHistory{ samples: std::collections::VecDeque::with_capacity(max_len), max_len }
+    }
+
+    pub fn push(&mut self, sample: PositionSample) {
+        if self.samples.len() == self.max_len { self.samples.pop_front(); }
+        self.samples.push_back(sample);
+    }
+
+    pub fn samples(&self) -> &std::collections::VecDeque<PositionSample> { &self.samples }
+
+    pub fn max_len(&self) -> usize { self.max_len }
 }
 
 pub struct ProgramState {
+    pub angle_display: AngleDisplay,
+    /// Field of view of the optical train (if configured).
+    pub camera_fov: Option<Fov>,
+    pub camera_corrections: Option<CameraCorrections>,
+    pub camera_trigger: Option<CameraTrigger>,
+    /// Last measured offset of the system clock.
+    pub clock_offset: Option<ClockOffset>,
     pub config: Rc<RefCell<Configuration>>,
     pub controllers: Vec<Pin<Box<dyn pasts::notify::Notify<Event = (u64, stick::Event)>>>>,
-    pub controller_names: Vec<String>,
+    /// Elements correspond to those of `controllers`.
+    pub connected_controllers: Rc<RefCell<Vec<controller::ConnectedController>>>,
     pub cursive_stepper: CursiveRunnableStepper,
     pub data_receiver: DataReceiver,
+    pub data_source_reconnection: Option<data_receiver::Reconnection>,
+    pub gps: Rc<RefCell<Option<GpsReceiver>>>,
+    pub guiding: Option<GuideServer>,
+    pub keyboard_slewing: Rc<RefCell<KeyboardSlewing>>,
+    /// End-to-end latency of following the target.
+    pub latency: Rc<RefCell<LatencyStats>>,
     pub listener: Pin<Box<dyn pasts::notify::Notify<Event = stick::Controller>>>,
     pub mount: Rc<RefCell<Option<mount::MountWrapper>>>,
+    /// Secondary (e.g., piggyback) mount.
+    pub mount2: Rc<RefCell<Option<mount::MountWrapper>>>,
+    pub mount_routing: Rc<Cell<mount::MountRouting>>,
     pub mount_spd: Rc<RefCell<MountSpeed>>,
+    /// Whether a warning about exceeded axis drift has been issued (see `Configuration::mount_drift_warning`).
+    pub mount_drift_warned: bool,
+    pub message_parser: Box<dyn MessageParser>,
+    pub mount_watchdog: mount::Watchdog,
+    pub ntp_client: Option<NtpClient>,
+    /// Mount calibration last saved to configuration.
+    pub saved_mount_state: Option<mount::PersistentState>,
+    pub observer: Rc<RefCell<Option<ObserverLocation>>>,
+    pub overlay: Option<Overlay>,
+    /// If true, tracking will be started once the mount is connected and target data are available.
+    pub pending_tracking_start: bool,
+    pub pos_history: Rc<RefCell<PositionHistory>>,
+    /// Axis rate calibration in progress (or finished, until the dialog is closed).
+    pub rate_calibration: Rc<RefCell<Option<mount::RateCalibration>>>,
+    /// Atmospheric conditions for correcting target altitude (if enabled).
+    pub refraction: Option<Atmosphere>,
     pub slewing: Slewing,
+    /// Whether the stored adjustment for the current target source has been applied since tracking started.
+    pub saved_adjustment_checked: bool,
     pub slew_speed: Rc<RefCell<f64::AngularVelocity>>,
+    pub state_broadcast: Option<StateBroadcast>,
+    pub sun_avoidance: Rc<RefCell<SunAvoidance>>,
+    /// Metrics exporter (if configured).
+    pub telemetry: Option<Telemetry>,
     pub timers: Vec<Timer>,
     pub tracking: Tracking,
     pub tui: Rc<RefCell<Option<TuiData>>>, // always `Some` after program start
     pub target: Rc<RefCell<Option<Target>>>,
+    pub target_alarms: TargetAlarms,
+    pub target_source: Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    pub target_queue: Rc<RefCell<TargetQueue>>,
     pub refresher: tui::Refresher,
-    pub ctrl_actions: controller::ActionAssignments
+    /// User script called periodically.
+    pub script: Option<Script>,
+    pub session_log: Option<SessionLogger>,
+    pub ctrl_actions: Rc<RefCell<controller::ActionAssignments>>,
+    /// Action waiting to be assigned the next suitable controller event.
+    pub ctrl_action_capture: Rc<RefCell<Option<controller::TargetAction>>>,
+    /// Controller buttons currently held (for chorded actions).
+    pub held_buttons: controller::HeldButtons,
+    pub axes_response: Rc<RefCell<[controller::AxisResponse; 2]>>
 }
 
 impl ProgramState {
@@ -179,7 +841,12 @@ impl Timer {
         Timer{
             id,
             timer: Box::pin(pasts::notify::future_fn(
-                move || Box::pin(async_std::task::sleep(interval))
+                move || -> Pin<Box<dyn std::future::Future<Output = ()>>> {
+                    match clock::real_duration(interval) {
+                        Some(duration) => Box::pin(async_std::task::sleep(duration)),
+                        None => Box::pin(std::future::pending())
+                    }
+                }
             ))
         }
     }
@@ -280,4 +947,147 @@ mod tests {
         assert_almost_eq!(deg(-10.0), angle_diff(deg(-10.0), deg(340.0)));
         assert_almost_eq!(deg(10.0), angle_diff(deg(10.0), deg(-340.0)));
     }
+
+    #[test]
+    fn sidereal_time_calculation() {
+        use chrono::TimeZone;
+
+        let j2000 = chrono::Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+        assert!((julian_date(j2000) - 2_451_545.0).abs() < 1.0e-9);
+        assert!((as_deg(gmst(j2000)) - 280.460_618_37).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn horizontal_to_equatorial_conversion() {
+        // object on the meridian, due south
+        let (ha, dec) = az_alt_to_ha_dec(deg(180.0), deg(30.0), deg(50.0));
+        assert!(as_deg(angle_diff(ha, deg(0.0))).abs() < 1.0e-9);
+        assert!((as_deg(dec) - (-10.0)).abs() < 1.0e-9);
+
+        // object rising due east from the equator
+        let (ha, dec) = az_alt_to_ha_dec(deg(90.0), deg(0.0), deg(50.0));
+        assert!((as_deg(ha) - 270.0).abs() < 1.0e-9);
+        assert!(as_deg(dec).abs() < 1.0e-9);
+
+        assert_eq!("06h00m00s", format_hms(deg(90.0)));
+
+        let observer = ObserverLocation{
+            lat: deg(50.0),
+            lon: deg(20.0),
+            elevation: f64::Length::new::<length::meter>(0.0)
+        };
+        let t = chrono::Utc::now();
+        let (ra, dec) = az_alt_to_ra_dec(deg(123.0), deg(45.0), &observer, t);
+        let (az, alt) = ra_dec_to_az_alt(ra, dec, &observer, t);
+        assert!(as_deg(angle_diff(az, deg(123.0))).abs() < 1.0e-9);
+        assert!((as_deg(alt) - 45.0).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn angle_formatting() {
+        let display = AngleDisplay{
+            az_origin: AzimuthOrigin::South,
+            az_range: AzimuthRange::Signed,
+            ..Default::default()
+        };
+        assert!((display.normalize_azimuth(deg(90.0)) - (-90.0)).abs() < 1.0e-9);
+        assert!((display.normalize_azimuth(deg(-170.0)) - 10.0).abs() < 1.0e-9);
+        assert_eq!("270.0°", AngleDisplay::default().azimuth(deg(-90.0), 1));
+
+        let dms = AngleDisplay{ format: AngleFormat::Dms, ..Default::default() };
+        assert_eq!("270°00'00\"", dms.azimuth(deg(-90.0), 1));
+        assert_eq!("-10°30'00\"", dms.altitude(deg(-10.5), 1));
+    }
+
+    #[test]
+    fn speed_formatting() {
+        let display = |speed_unit| AngleDisplay{ speed_unit, ..Default::default() };
+        assert_eq!("0.50°/s", display(SpeedUnit::DegPerS).speed(deg_per_s(0.5), 2));
+        assert_eq!("30.0'/s", display(SpeedUnit::ArcminPerS).speed(deg_per_s(0.5), 2));
+        assert_eq!("-1800.0\"/s", display(SpeedUnit::ArcsecPerS).signed_speed(deg_per_s(-0.5), 4));
+        let sidereal = display(SpeedUnit::Sidereal);
+        assert_eq!("+2.00×sid", sidereal.signed_speed(deg_per_s(2.0 * SIDEREAL_RATE_DEG_PER_S), 4));
+    }
+
+    #[test]
+    fn ecef_to_local_conversion() {
+        let observer = ObserverLocation{
+            lat: deg(52.0),
+            lon: deg(21.0),
+            elevation: f64::Length::new::<length::meter>(100.0)
+        };
+        let up = observer.ecef_to_local(observer.ecef().normalize());
+        let (_, altitude) = to_spherical(Point3::from_vec(up));
+        assert!((as_deg(altitude) - 90.0).abs() < 0.25); // geodetic vs. geocentric vertical
+    }
+
+    #[test]
+    fn speed_filter_parsing() {
+        assert_eq!(Ok(SpeedFilter::None), "none".parse::<SpeedFilter>());
+        assert_eq!(Ok(SpeedFilter::MovingAverage(5)), "moving_average:5".parse::<SpeedFilter>());
+        assert_eq!(
+            Ok(SpeedFilter::AlphaBeta{ alpha: 0.5, beta: 0.1 }),
+            "alpha_beta: 0.5, 0.1".parse::<SpeedFilter>()
+        );
+        assert!("moving_average:0".parse::<SpeedFilter>().is_err());
+        assert!("alpha_beta:0.5".parse::<SpeedFilter>().is_err());
+        assert!("kalman".parse::<SpeedFilter>().is_err());
+    }
+
+    #[test]
+    fn ref_pos_presets_csv() {
+        let presets = vec![
+            RefPositionPreset{ name: "chimney, north".into(), azimuth: deg(12.5), altitude: deg(3.25) },
+            RefPositionPreset{ name: "mast".into(), azimuth: deg(270.0), altitude: deg(-1.0) }
+        ];
+        let parsed = ref_pos_presets_from_csv(&ref_pos_presets_to_csv(&presets)).unwrap();
+        assert_eq!(2, parsed.len());
+        assert_eq!("chimney, north", parsed[0].name);
+        assert_eq!(12.5, as_deg(parsed[0].azimuth));
+        assert_eq!(-1.0, as_deg(parsed[1].altitude));
+
+        assert!(ref_pos_presets_from_csv("1.0,x,name").is_err());
+        assert!(ref_pos_presets_from_csv("1.0,2.0").is_err());
+    }
+
+    #[test]
+    fn landmark_parsing() {
+        let landmark: Landmark = "52.25;21.0;310.5;mast; south".parse().unwrap();
+        assert_eq!("mast; south", landmark.name);
+        assert_eq!(52.25, as_deg(landmark.location.lat));
+        assert_eq!(310.5, landmark.location.elevation.get::<length::meter>());
+        assert_eq!(landmark.to_string(), landmark.to_string().parse::<Landmark>().unwrap().to_string());
+
+        assert!("52.25;21.0;mast".parse::<Landmark>().is_err());
+
+        let site: Site = "-33.5;151.25;20;12.5;backyard".parse().unwrap();
+        assert_eq!("backyard", site.name);
+        assert_eq!(12.5, as_deg(site.magnetic_declination));
+        assert!("-33.5;151.25;20;backyard".parse::<Site>().is_err());
+
+        let odometer: MountOdometer = "12345.5;678;iOptron AZ Mount Pro (FW 210101) on /dev/ttyUSB0".parse().unwrap();
+        assert_eq!("iOptron AZ Mount Pro (FW 210101) on /dev/ttyUSB0", odometer.mount);
+        assert_eq!(678.0, as_deg(odometer.travel.1));
+        assert!("12345.5;iOptron".parse::<MountOdometer>().is_err());
+    }
+
+    #[test]
+    fn moving_average_smooths_speed() {
+        let mut mount_spd = MountSpeed::new(SpeedFilter::MovingAverage(2));
+        assert_eq!((1.0, 0.0), mount_spd.filter_speed((1.0, 0.0), (0.0, 0.0), 0.25));
+        assert_eq!((2.0, 1.0), mount_spd.filter_speed((3.0, 2.0), (0.0, 0.0), 0.25));
+        assert_eq!((4.0, 2.0), mount_spd.filter_speed((5.0, 2.0), (0.0, 0.0), 0.25));
+    }
+
+    #[test]
+    fn mount_speed_uses_program_clock() {
+        clock::set_simulated(0.0);
+        let mut mount_spd = MountSpeed::new(SpeedFilter::None);
+        mount_spd.notify_pos(deg(10.0), deg(20.0));
+        clock::advance(std::time::Duration::from_secs(2));
+        mount_spd.notify_pos(deg(11.0), deg(19.0));
+        let (spd1, spd2) = mount_spd.get().unwrap();
+        assert!((as_deg_per_s(spd1) - 0.5).abs() < 1e-9);
+        assert!((as_deg_per_s(spd2) + 0.5).abs() < 1e-9);
+    }
 }
@@ -19,40 +19,111 @@
 use async_std::{io::prelude::BufReadExt, stream::Stream};
 use crate::data;
 use pasts::notify::Notify;
-use std::{cell::RefCell, error::Error, pin::Pin, rc::{Rc, Weak}, task::{Context, Poll}};
+use std::{
+    cell::RefCell,
+    error::Error,
+    pin::Pin,
+    rc::{Rc, Weak},
+    sync::mpsc,
+    task::{Context, Poll},
+    time::{Duration, Instant}
+};
+
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// Connection attempt performed on a background thread.
+struct PendingConnection {
+    address: String,
+    started: Instant,
+    result: mpsc::Receiver<std::io::Result<std::net::TcpStream>>
+}
 
 pub struct DataReceiver {
-    source: Rc<RefCell<Option<Pin<Box<dyn Notify<Event = Option<Result<String, std::io::Error>>>>>>>>
+    source: Rc<RefCell<Option<Pin<Box<dyn Notify<Event = Option<Result<String, std::io::Error>>>>>>>>,
+    address: Rc<RefCell<Option<String>>>,
+    pending: Rc<RefCell<Option<PendingConnection>>>
 }
 
 impl DataReceiver {
     pub fn new() -> DataReceiver {
-        DataReceiver{ source: Rc::new(RefCell::new(None)) }
+        DataReceiver{
+            source: Rc::new(RefCell::new(None)),
+            address: Rc::new(RefCell::new(None)),
+            pending: Rc::new(RefCell::new(None))
+        }
     }
 
     pub fn connection(&self) -> Connection {
-        Connection{ source: Rc::downgrade(&self.source) }
+        Connection{
+            source: Rc::downgrade(&self.source),
+            address: Rc::downgrade(&self.address),
+            pending: Rc::downgrade(&self.pending)
+        }
+    }
+
+    pub fn is_connected(&self) -> bool { self.source.borrow().is_some() }
+
+    /// Returns address and time elapsed since starting the connection attempt in progress (if any).
+    pub fn connecting(&self) -> Option<(String, Duration)> {
+        self.pending.borrow().as_ref().map(|p| (p.address.clone(), p.started.elapsed()))
+    }
+
+    /// If the connection attempt in progress has finished, returns its address and result (and starts receiving
+    /// data on success).
+    pub fn finish_connecting(&self) -> Option<(String, Result<(), Box<dyn Error>>)> {
+        let result = match self.pending.borrow().as_ref()?.result.try_recv() {
+            Ok(result) => result.map_err(|e| e.into()),
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => Err("connection thread failed".into())
+        };
+        let address = self.pending.borrow_mut().take().unwrap().address;
+        let result = result.map(|stream| self.connection().set_stream(stream.into(), &address));
+
+        Some((address, result))
     }
 }
 
 #[derive(Clone)]
 pub struct Connection {
-    source: Weak<RefCell<Option<Pin<Box<dyn Notify<Event = Option<Result<String, std::io::Error>>>>>>>>
+    source: Weak<RefCell<Option<Pin<Box<dyn Notify<Event = Option<Result<String, std::io::Error>>>>>>>>,
+    address: Weak<RefCell<Option<String>>>,
+    pending: Weak<RefCell<Option<PendingConnection>>>
 }
 
 impl Connection {
-    #[must_use]
-    pub fn connect(&self, address: &str) -> Result<(), Box<dyn Error>> {
-        let stream = futures::executor::block_on(
-            async { async_std::net::TcpStream::connect(address).await }
-        )?;
+    /// Starts connecting on a background thread (replacing any connection attempt in progress); the result is
+    /// obtained via `DataReceiver::finish_connecting`.
+    pub fn start_connecting(&self, address: &str) {
+        let (sender, result) = mpsc::channel();
+        let thread_address = address.to_string();
+        std::thread::spawn(move || { let _ = sender.send(std::net::TcpStream::connect(thread_address)); });
+        *self.pending.upgrade().unwrap().borrow_mut() = Some(PendingConnection{
+            address: address.to_string(),
+            started: Instant::now(),
+            result
+        });
+    }
 
+    /// Abandons the connection attempt in progress (the background thread finishes on its own).
+    pub fn cancel_connecting(&self) {
+        if let Some(pending) = self.pending.upgrade() {
+            if let Some(p) = pending.borrow_mut().take() { log::info!("cancelled connecting to {}", p.address); }
+        }
+    }
+
+    fn set_stream(&self, stream: async_std::net::TcpStream, address: &str) {
         let mut lines = async_std::io::BufReader::new(stream).lines();
         *self.source.upgrade().unwrap().borrow_mut() = Some(Box::pin(
             pasts::notify::poll_fn(move |ctx| Pin::new(&mut lines).poll_next(ctx))
         ));
+        *self.address.upgrade().unwrap().borrow_mut() = Some(address.to_string());
+    }
 
-        Ok(())
+    /// Returns address of the connected data source.
+    pub fn address(&self) -> Option<String> {
+        let connected = self.source.upgrade().map(|s| s.borrow().is_some()).unwrap_or(false);
+        if connected { self.address.upgrade().and_then(|a| a.borrow().clone()) } else { None }
     }
 
     pub fn disconnect(&self) {
@@ -76,7 +147,9 @@ impl Notify for DataReceiver {
                         Some(data) => Poll::Ready(data),
                         None => {
                             must_close = true;
-                            Poll::Pending
+                            Poll::Ready(Err(std::io::Error::new(
+                                std::io::ErrorKind::UnexpectedEof, "connection closed"
+                            )))
                         }
                     },
                     Poll::Pending => Poll::Pending
@@ -89,3 +162,46 @@ impl Notify for DataReceiver {
         result
     }
 }
+
+/// Reconnection attempts (with exponential backoff) to a lost data source.
+pub struct Reconnection {
+    address: String,
+    attempt: u32,
+    next_attempt: Instant
+}
+
+impl Reconnection {
+    pub fn new(address: &str) -> Reconnection {
+        Reconnection{ address: address.into(), attempt: 0, next_attempt: Instant::now() + MIN_RECONNECT_DELAY }
+    }
+
+    pub fn address(&self) -> &str { &self.address }
+
+    /// Number of failed attempts so far.
+    pub fn attempt(&self) -> u32 { self.attempt }
+
+    pub fn is_due(&self) -> bool { Instant::now() >= self.next_attempt }
+
+    pub fn on_failed(&mut self) {
+        self.attempt += 1;
+        self.next_attempt = Instant::now() + reconnect_delay(self.attempt);
+    }
+}
+
+fn reconnect_delay(num_failed_attempts: u32) -> Duration {
+    (MIN_RECONNECT_DELAY * 2u32.saturating_pow(num_failed_attempts.min(16))).min(MAX_RECONNECT_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_delay_grows_exponentially_up_to_max() {
+        assert_eq!(Duration::from_secs(1), reconnect_delay(0));
+        assert_eq!(Duration::from_secs(2), reconnect_delay(1));
+        assert_eq!(Duration::from_secs(32), reconnect_delay(5));
+        assert_eq!(MAX_RECONNECT_DELAY, reconnect_delay(6));
+        assert_eq!(MAX_RECONNECT_DELAY, reconnect_delay(100));
+    }
+}
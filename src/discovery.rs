@@ -0,0 +1,220 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Discovery of data sources and mount simulators on the local network via mDNS/DNS-SD.
+//!
+//! A one-shot ("legacy unicast") query is sent from an ephemeral port, so responders reply directly to it.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant}
+};
+
+pub const DATA_SOURCE_SERVICE: &str = "_tptool-data._tcp.local";
+pub const MOUNT_SIMULATOR_SERVICE: &str = "_mount-sim._tcp.local";
+
+pub const DEFAULT_BROWSE_DURATION: Duration = Duration::from_millis(1500);
+
+const MDNS_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+const HEADER_LEN: usize = 12;
+/// Max. number of compression pointers followed when reading a name.
+const MAX_NAME_JUMPS: usize = 16;
+
+/// Service instance found on the network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiscoveredService {
+    /// Instance name (e.g., "feeder laptop").
+    pub name: String,
+    /// Address and port to connect to.
+    pub address: SocketAddr
+}
+
+impl std::fmt::Display for DiscoveredService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.address)
+    }
+}
+
+/// Queries the network for instances of `service` (e.g., `DATA_SOURCE_SERVICE`); blocks for `duration`.
+#[must_use]
+pub fn browse(service: &str, duration: Duration) -> Result<Vec<DiscoveredService>, Box<dyn Error>> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.send_to(&build_query(service), (MDNS_ADDR, MDNS_PORT))?;
+
+    let mut result: Vec<DiscoveredService> = vec![];
+    let deadline = Instant::now() + duration;
+    let mut buf = [0u8; 9000];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() { break; }
+        socket.set_read_timeout(Some(remaining))?;
+        let (len, sender) = match socket.recv_from(&mut buf) {
+            Ok(r) => r,
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => break,
+            Err(e) => return Err(e.into())
+        };
+        match parse_response(&buf[..len], service, sender.ip()) {
+            Ok(services) => for s in services {
+                if !result.contains(&s) { result.push(s); }
+            },
+            Err(e) => log::debug!("invalid mDNS response from {}: {}", sender, e)
+        }
+    }
+
+    Ok(result)
+}
+
+fn build_query(service: &str) -> Vec<u8> {
+    let mut query = vec![0u8; HEADER_LEN];
+    query[5] = 1; // number of questions
+    write_name(&mut query, service);
+    query.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    query
+}
+
+fn write_name(buf: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+    buf.push(0);
+}
+
+fn read_u16(packet: &[u8], pos: usize) -> Result<u16, Box<dyn Error>> {
+    match packet.get(pos..pos + 2) {
+        Some(b) => Ok(u16::from_be_bytes([b[0], b[1]])),
+        None => Err("unexpected end of packet".into())
+    }
+}
+
+/// Reads a (possibly compressed) name; returns it and the position following it.
+fn read_name(packet: &[u8], mut pos: usize) -> Result<(String, usize), Box<dyn Error>> {
+    let mut labels = vec![];
+    let mut end = None;
+    let mut jumps = 0;
+    loop {
+        let len = *packet.get(pos).ok_or("unexpected end of packet")? as usize;
+        if len == 0 {
+            pos += 1;
+            break;
+        } else if len & 0xC0 == 0xC0 {
+            if end.is_none() { end = Some(pos + 2); }
+            jumps += 1;
+            if jumps > MAX_NAME_JUMPS { return Err("too many name compression pointers".into()); }
+            pos = (read_u16(packet, pos)? & 0x3FFF) as usize;
+        } else {
+            let label = packet.get(pos + 1..pos + 1 + len).ok_or("unexpected end of packet")?;
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            pos += 1 + len;
+        }
+    }
+
+    Ok((labels.join("."), end.unwrap_or(pos)))
+}
+
+/// Returns service instances found in an mDNS response; `sender` is used if no address record is included.
+fn parse_response(packet: &[u8], service: &str, sender: IpAddr) -> Result<Vec<DiscoveredService>, Box<dyn Error>> {
+    if packet.len() < HEADER_LEN { return Err("packet too short".into()); }
+    let num_questions = read_u16(packet, 4)?;
+    let num_records = read_u16(packet, 6)? as usize + read_u16(packet, 8)? as usize + read_u16(packet, 10)? as usize;
+
+    let mut pos = HEADER_LEN;
+    for _ in 0..num_questions {
+        pos = read_name(packet, pos)?.1 + 4;
+    }
+
+    let mut instances = vec![];
+    let mut targets = HashMap::new(); // instance -> (host, port)
+    let mut hosts = HashMap::new(); // host -> IPv4 address
+    for _ in 0..num_records {
+        let (name, p) = read_name(packet, pos)?;
+        let rtype = read_u16(packet, p)?;
+        let rdlen = read_u16(packet, p + 8)? as usize;
+        let rdata = p + 10;
+        if packet.len() < rdata + rdlen { return Err("unexpected end of packet".into()); }
+
+        match rtype {
+            TYPE_PTR if name.eq_ignore_ascii_case(service) => instances.push(read_name(packet, rdata)?.0),
+            TYPE_SRV => {
+                let port = read_u16(packet, rdata + 4)?;
+                targets.insert(name.to_lowercase(), (read_name(packet, rdata + 6)?.0.to_lowercase(), port));
+            },
+            TYPE_A if rdlen == 4 => {
+                let b = &packet[rdata..rdata + 4];
+                hosts.insert(name.to_lowercase(), Ipv4Addr::new(b[0], b[1], b[2], b[3]));
+            },
+            _ => ()
+        }
+        pos = rdata + rdlen;
+    }
+
+    Ok(instances.into_iter().filter_map(|instance| {
+        let (host, port) = targets.get(&instance.to_lowercase())?;
+        let ip = hosts.get(host).map(|ip| IpAddr::V4(*ip)).unwrap_or(sender);
+        let name = instance.strip_suffix(&format!(".{}", service)).unwrap_or(&instance).to_string();
+        Some(DiscoveredService{ name, address: SocketAddr::new(ip, *port) })
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(packet: &mut Vec<u8>, name: &str, rtype: u16, rdata: &[u8]) {
+        write_name(packet, name);
+        packet.extend_from_slice(&rtype.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet.extend_from_slice(&120u32.to_be_bytes());
+        packet.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        packet.extend_from_slice(rdata);
+    }
+
+    #[test]
+    fn response_parsing() {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 2];
+        let mut ptr = vec![];
+        write_name(&mut ptr, "feeder._tptool-data._tcp.local");
+        record(&mut packet, DATA_SOURCE_SERVICE, TYPE_PTR, &ptr);
+        let mut srv = vec![0, 0, 0, 0, 0x09, 0xC4];
+        write_name(&mut srv, "laptop.local");
+        record(&mut packet, "feeder._tptool-data._tcp.local", TYPE_SRV, &srv);
+        record(&mut packet, "laptop.local", TYPE_A, &[192, 168, 1, 20]);
+
+        let sender = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let services = parse_response(&packet, DATA_SOURCE_SERVICE, sender).unwrap();
+        assert_eq!(
+            vec![DiscoveredService{ name: "feeder".into(), address: "192.168.1.20:2500".parse().unwrap() }],
+            services
+        );
+
+        assert!(parse_response(&packet, MOUNT_SIMULATOR_SERVICE, sender).unwrap().is_empty());
+
+        // compressed name
+        let (name, end) = read_name(&[0xC0, 2, 3, b'a', b'b', b'c', 0], 0).unwrap();
+        assert_eq!("abc", name);
+        assert_eq!(2, end);
+    }
+}
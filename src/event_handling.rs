@@ -18,25 +18,45 @@
 
 use cgmath::{Deg, EuclideanSpace, InnerSpace, Point3, Rad, Vector3};
 use crate::{
+    clock,
+    config::Configuration,
     controller,
-    controller::{EventValue, SourceAction, StickEvent, TargetAction},
+    controller::{ActionAssignments, AxisResponse, EventValue, SourceAction, StickEvent, TargetAction},
     cursive_stepper::Running,
     data,
-    data::{as_deg, as_deg_per_s, ProgramState, TimerId, timers},
-    mount::{Mount, MountWrapper},
+    data::{as_deg, ProgramState, TimerId, timers},
+    data_receiver,
+    fov,
+    mount,
+    mount::{Axis, Mount, MountWrapper},
+    overlay,
+    push_to,
+    scripting,
+    session_log,
+    shutdown,
+    sun_avoidance::SunAvoidance,
+    target_alarm,
+    target_source::{TargetQueue, TargetSource},
+    telemetry,
     tracking,
+    time_sync::{ClockOffset, TimeReference},
     tracking::TrackingController,
     tui,
     tui::TuiData,
     upgrade
 };
-use pointing_utils::{cgmath, TargetInfoMessage, uom};
-use std::{cell::RefCell, future::Future, rc::{Rc, Weak}, task::{Poll, Waker}};
-use strum::IntoEnumIterator;
-use uom::{si::f64, si::{angle, angular_velocity, length, velocity}};
+use pointing_utils::{cgmath, uom};
+use std::{cell::RefCell, error::Error, future::Future, rc::{Rc, Weak}, task::{Poll, Waker}};
+use uom::{si::f64, si::{angle, length, velocity}};
 
 pub const SLEW_SPEED_CHANGE_FACTOR: f64 = 1.5;
 
+/// Changes of GPS position smaller than this are ignored.
+const GPS_POS_CHANGE_THRESHOLD_M: f64 = 10.0;
+
+/// Changes of lifetime axis travel smaller than this are not saved.
+const ODOMETER_RESOLUTION_DEG: f64 = 0.01;
+
 // TODO: make configurable
 const CONTROLLER_ID: u64 = 0x03006D041DC21440;
 
@@ -55,27 +75,64 @@ pub async fn event_loop(mut state: ProgramState) {
         .on(|s| &mut s.tracking, nop)
         .on(|s| &mut s.refresher, on_refresher)
         .await;
+
+    shutdown::shutdown(&mut state);
+}
+
+/// Writes tracking statistics summary to a file (in the same directory as the program log); returns its path.
+pub fn write_tracking_summary(stats: &tracking::TrackingStats) -> Result<std::path::PathBuf, Box<dyn Error>> {
+    let path = dirs::data_dir().unwrap_or(std::path::Path::new("").to_path_buf())
+        .join(format!("tptool_summary_{}.txt", chrono::Local::now().format("%Y-%m-%d_%H%M%S")));
+    std::fs::write(&path, format!("TPTool tracking summary ({})\n\n{}", chrono::Local::now(), stats.summary()))?;
+    log::info!("tracking summary written to {}", path.to_string_lossy());
+    Ok(path)
 }
 
 fn on_main_timer(state: &mut ProgramState) {
+    update_mount2_info(state);
+
     let pos = {
         let mut mount = state.mount.borrow_mut();
-        if mount.is_none() { return; }
+        if mount.is_none() {
+            drop(mount);
+            record_pos_history(state, None);
+            return;
+        }
         mount.as_mut().unwrap().position()
     };
+    record_pos_history(state, pos.as_ref().ok().copied());
+    match &pos {
+        Ok(_) => state.mount_watchdog.notify_ok(),
+        Err(e) => {
+            log::warn!("failed to get mount position: {}", e);
+            if state.mount_watchdog.notify_error() {
+                on_mount_connection_lost(state);
+                return;
+            }
+        }
+    }
     if let Ok((axis1, axis2)) = pos {
         state.mount_spd.borrow_mut().notify_pos(axis1, axis2);
-        let a1deg = as_deg(axis1);
-        let azimuth = (if a1deg >= 0.0 && a1deg <= 180.0 { a1deg } else { 360.0 + a1deg }) % 360.0;
-
-        let mut mount_az_str = format!("{:.2}°", azimuth);
-        let mut mount_alt_str = format!("{:.2}°", as_deg(axis2));
+        let mut mount_az_str = state.angle_display.azimuth(axis1, 2);
+        let mut mount_alt_str = state.angle_display.altitude(axis2, 2);
         if let Some((az_spd, alt_spd)) = state.mount_spd.borrow().get() {
-            mount_az_str += &format!("  {:.2}°/s", az_spd.get::<angular_velocity::degree_per_second>());
-            mount_alt_str += &format!("  {:.2}°/s", alt_spd.get::<angular_velocity::degree_per_second>());
+            mount_az_str += &format!("  {}", state.angle_display.speed(az_spd, 2));
+            mount_alt_str += &format!("  {}", state.angle_display.speed(alt_spd, 2));
         }
         tui_s!(state).text_content.mount_az.set_content(mount_az_str);
         tui_s!(state).text_content.mount_alt.set_content(mount_alt_str);
+        // TODO: show pier side and warn about counterweight-up positions once mount axes can be equatorial
+        // (currently they are always treated as azimuth & altitude)
+        if let Some(observer) = state.observer.borrow().as_ref() {
+            let (ha, dec) = data::az_alt_to_ha_dec(axis1, axis2, observer.lat);
+            tui_s!(state).text_content.mount_ha.set_content(state.angle_display.right_ascension(ha));
+            tui_s!(state).text_content.mount_dec.set_content(state.angle_display.altitude(dec, 2));
+        }
+        if let Some((az_spd, alt_spd)) = state.mount_spd.borrow().get_raw() {
+            tui_s!(state).text_content.mount_raw_spd.set_content(
+                format!("az. {}  alt. {}", state.angle_display.speed(az_spd, 2), state.angle_display.speed(alt_spd, 2))
+            );
+        }
 
         tui_s!(state).text_content.mount_total_az_travel.set_content(
             format!("{:.1}°", as_deg(state.mount.borrow().as_ref().unwrap().total_axis_travel().0))
@@ -83,11 +140,314 @@ fn on_main_timer(state: &mut ProgramState) {
         tui_s!(state).text_content.mount_total_alt_travel.set_content(
             format!("{:.1}°", as_deg(state.mount.borrow().as_ref().unwrap().total_axis_travel().1))
         );
+        let (travel, max_travel) = {
+            let mount = state.mount.borrow();
+            let mount = mount.as_ref().unwrap();
+            (mount.total_axis_travel(), mount.max_travel())
+        };
+        tui_s!(state).text_content.mount_travel_budget.set_content(format!(
+            "az. {}  alt. {}", travel_budget(travel.0, max_travel), travel_budget(travel.1, max_travel)
+        ));
+        let odometer = state.mount.borrow().as_ref().unwrap().odometer().travel;
+        tui_s!(state).text_content.mount_odometer.set_content(
+            format!("az. {:.0}°  alt. {:.0}°", as_deg(odometer.0), as_deg(odometer.1))
+        );
+        let warning = state.mount.borrow().as_ref().unwrap().warning();
+        tui_s!(state).text_content.mount_warning.set_content(
+            warning.map(|w| format!("WARNING: {}", w)).unwrap_or_default()
+        );
+        if let Some((drift1, drift2)) = state.mount.borrow().as_ref().unwrap().drift() {
+            tui_s!(state).text_content.mount_drift.set_content(
+                format!("drift: {:+.3}°, {:+.3}°", as_deg(drift1), as_deg(drift2))
+            );
+        }
+
+        check_sun_avoidance(state, (axis1, axis2));
+        update_fov_indicator(state, (axis1, axis2));
+        update_video_tracker_handoff(state, (axis1, axis2));
+        update_push_to_guidance(state, (axis1, axis2));
 
         state.refresh_tui();
     }
 }
 
+fn update_fov_indicator(state: &ProgramState, position: (f64::Angle, f64::Angle)) {
+    let Some(fov) = state.camera_fov else { return; };
+    let Some(mount_spd) = state.mount_spd.borrow().get() else { return; };
+    let text = match state.tracking.pointing_error(position, mount_spd) {
+        Some((error, rate)) => match fov.status(error, rate, position.1) {
+            fov::FovStatus::Inside(Some(t)) => format!("target inside (drifts out in {:.0} s)", t.as_secs_f64()),
+            fov::FovStatus::Inside(None) => "target inside".to_string(),
+            fov::FovStatus::Outside => "target OUTSIDE".to_string()
+        },
+        None => String::new()
+    };
+    tui_s!(state).text_content.tracking_fov.set_content(text);
+}
+
+fn update_video_tracker_handoff(state: &mut ProgramState, position: (f64::Angle, f64::Angle)) {
+    let Some(camera) = state.camera_corrections.as_mut() else { return; };
+    let controller = state.tracking.controller();
+    let error = if controller.is_active() && !controller.is_paused() {
+        let zero_spd = (data::deg_per_s(0.0), data::deg_per_s(0.0));
+        state.tracking.pointing_error(position, zero_spd).map(|(error, _)| error)
+    } else {
+        None
+    };
+    camera.update_handoff(error, position.1);
+}
+
+/// Shows the Push-to panel if the main mount is in push-to mode and updates the guidance towards the target.
+fn update_push_to_guidance(state: &mut ProgramState, position: (f64::Angle, f64::Angle)) {
+    let push_to = state.mount.borrow().as_ref().is_some_and(|m| m.is_push_to());
+    tui::set_push_to_panel_visible(&mut state.cursive_stepper.curs, push_to);
+    if !push_to { return; }
+
+    let zero_spd = (data::deg_per_s(0.0), data::deg_per_s(0.0));
+    let (az, alt, distance) = match state.tracking.pointing_error(position, zero_spd) {
+        Some((error, _)) => push_to::guidance(position, error),
+        None => (String::new(), String::new(), "(no target)".to_string())
+    };
+    tui_s!(state).text_content.push_to_az.set_content(az);
+    tui_s!(state).text_content.push_to_alt.set_content(alt);
+    tui_s!(state).text_content.push_to_distance.set_content(distance);
+}
+
+/// Stops the mount(s) and tracking if the (main) mount is about to enter the exclusion zone around the Sun.
+fn check_sun_avoidance(state: &mut ProgramState, position: (f64::Angle, f64::Angle)) {
+    let Some(observer) = *state.observer.borrow() else { return; };
+    let Some(speed) = state.mount_spd.borrow().get() else { return; };
+    let Some(dist) = state.sun_avoidance.borrow_mut().check_motion(position, speed, &observer) else { return; };
+
+    log::error!("stopping the mount {:.1}° from the Sun", as_deg(dist));
+    on_stop_mount(&[&state.mount, &state.mount2], &state.tracking.controller());
+    tui::msg_box(
+        &mut state.cursive_stepper.curs,
+        &format!("Mount stopped {:.1}° from the Sun (exclusion zone).", as_deg(dist)),
+        "Sun avoidance"
+    );
+}
+
+/// Saves calibration of the main mount to configuration (if changed since the last save).
+pub fn save_mount_state(state: &mut ProgramState) {
+    let mount_state = match state.mount.borrow().as_ref() {
+        Some(mount) => mount::PersistentState{ adjustment: state.tracking.adjustment(), ..mount.persistent_state() },
+        None => return
+    };
+    if state.saved_mount_state == Some(mount_state) { return; }
+
+    let mut config = state.config.borrow_mut();
+    let mount_id = match config.mount_type().and_then(|t| config.mount_connection_param(t).map(|p| t.mount_id(&p))) {
+        Some(id) => id,
+        None => return
+    };
+    config.set_mount_state(&mount_id, &mount_state);
+    if let Err(e) = config.store() {
+        log::error!("error saving configuration: {}", e);
+    }
+    state.saved_mount_state = Some(mount_state);
+}
+
+/// Saves lifetime axis travel of connected mounts to configuration (if changed since the last save).
+pub fn save_odometers(state: &mut ProgramState) {
+    let current: Vec<data::MountOdometer> = [&state.mount, &state.mount2].iter()
+        .filter_map(|mount| mount.borrow().as_ref().map(|m| m.odometer().clone()))
+        .collect();
+    if current.is_empty() { return; }
+
+    let mut config = state.config.borrow_mut();
+    let mut odometers = config.odometers();
+    let differs = |a: &data::MountOdometer, b: &data::MountOdometer| {
+        as_deg(a.travel.0 - b.travel.0).abs() >= ODOMETER_RESOLUTION_DEG
+            || as_deg(a.travel.1 - b.travel.1).abs() >= ODOMETER_RESOLUTION_DEG
+    };
+    let mut changed = false;
+    for odometer in current {
+        match odometers.iter_mut().find(|o| o.mount == odometer.mount) {
+            Some(saved) if !differs(saved, &odometer) => (),
+            Some(saved) => { *saved = odometer; changed = true; },
+            None => { odometers.push(odometer); changed = true; }
+        }
+    }
+    if !changed { return; }
+
+    config.set_odometers(&odometers);
+    if let Err(e) = config.store() {
+        log::error!("error saving configuration: {}", e);
+    }
+}
+
+fn check_mount_drift(state: &mut ProgramState) {
+    let (drift1, drift2) = match state.mount.borrow().as_ref().and_then(|m| m.drift()) {
+        Some(drift) => drift,
+        None => return
+    };
+    log::debug!("mount axis drift: {:+.4}°, {:+.4}°", as_deg(drift1), as_deg(drift2));
+
+    let exceeded = drift1.abs().max(drift2.abs()) > state.config.borrow().mount_drift_warning();
+    if exceeded && !state.mount_drift_warned {
+        log::warn!(
+            "mount axis drift exceeded warning threshold: {:+.3}°, {:+.3}° (slipping clutch or encoder fault?)",
+            as_deg(drift1),
+            as_deg(drift2)
+        );
+    }
+    state.mount_drift_warned = exceeded;
+}
+
+fn update_mount2_info(state: &ProgramState) {
+    let text = match state.mount2.borrow_mut().as_mut() {
+        None => String::new(),
+        Some(mount2) => match mount2.position() {
+            Ok((axis1, axis2)) => format!(
+                "2nd: az. {}  alt. {}", state.angle_display.azimuth(axis1, 2), state.angle_display.altitude(axis2, 2)
+            ),
+            Err(e) => {
+                log::warn!("failed to get secondary mount position: {}", e);
+                "2nd: (no position)".into()
+            }
+        }
+    };
+    tui_s!(state).text_content.mount2_info.set_content(text);
+}
+
+fn on_mount_connection_lost(state: &mut ProgramState) {
+    log::error!("mount connection lost");
+    state.tracking.controller().stop();
+
+    let mount = match state.mount.borrow_mut().take() { Some(m) => m, None => return };
+    let mount_type = state.config.borrow().mount_type();
+    let param = mount_type.and_then(|t| state.config.borrow().mount_connection_param(t));
+    if let (Some(mount_type), Some(param)) = (mount_type, param) {
+        let axes_swapped = state.config.borrow().mount_axes_swapped();
+        state.mount_watchdog.on_connection_lost(mount, mount_type, &param, axes_swapped);
+        tui_s!(state).text_content.mount_name.set_content("(connection lost; reconnecting...)");
+    } else {
+        tui_s!(state).text_content.mount_name.set_content("(connection lost)");
+    }
+    state.refresh_tui();
+}
+
+fn on_mount_watchdog(state: &mut ProgramState) {
+    if !state.mount_watchdog.is_reconnecting() { return; }
+
+    if state.mount.borrow().is_some() {
+        // user has connected to a mount in the meantime
+        state.mount_watchdog.cancel();
+        return;
+    }
+
+    if let Some(mount) = state.mount_watchdog.try_reconnect() {
+        tui_s!(state).text_content.mount_name.set_content(mount_display_name(&mount));
+        *state.mount.borrow_mut() = Some(mount);
+        state.refresh_tui();
+    }
+}
+
+fn on_motion_timer(state: &mut ProgramState) {
+    for mount in [&state.mount, &state.mount2] {
+        if let Some(mount) = mount.borrow_mut().as_mut() {
+            if let Err(e) = mount.update_motion() {
+                log::error!("error when slewing: {}", e);
+            }
+        }
+    }
+
+    for mount in [&state.mount, &state.mount2] {
+        let result = match mount.borrow_mut().as_mut() {
+            Some(mount) => mount.update_nudge(),
+            None => None
+        };
+        let message = match result {
+            Some(Ok(())) => "Move finished.".to_string(),
+            Some(Err(e)) => { log::error!("error when moving by offset: {}", e); format!("Move failed: {}.", e) },
+            None => continue
+        };
+        tui::nudge_dialog::on_progress(&mut state.cursive_stepper.curs, &message);
+    }
+}
+
+/// Returns remaining travel of an axis before reaching `max_travel` (as a bar and in degrees).
+fn travel_budget(travel: f64::Angle, max_travel: f64::Angle) -> String {
+    const BAR_WIDTH: usize = 8;
+    let remaining = (max_travel - travel.abs()).max(data::deg(0.0));
+    let filled = (as_deg(remaining) / as_deg(max_travel) * BAR_WIDTH as f64).ceil() as usize;
+    format!("[{}{}] {:.0}°", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled.min(BAR_WIDTH)), as_deg(remaining))
+}
+
+fn record_pos_history(state: &ProgramState, mount_pos: Option<(f64::Angle, f64::Angle)>) {
+    let target = state.target.borrow().as_ref().map(|t| (t.azimuth, t.altitude));
+    state.pos_history.borrow_mut().push(data::PositionSample{ mount: mount_pos, target });
+}
+
+fn on_gps_timer(state: &mut ProgramState) {
+    let result = match state.gps.borrow_mut().as_mut() {
+        Some(gps) => gps.poll(),
+        None => return
+    };
+
+    match result {
+        Ok(Some(position)) => {
+            let changed = match *state.observer.borrow() {
+                Some(prev) => (prev.ecef() - position.ecef()).magnitude() > GPS_POS_CHANGE_THRESHOLD_M,
+                None => true
+            };
+            if changed {
+                log::info!(
+                    "observer location from GPS: lat. {:.6}°, lon. {:.6}°, elev. {:.1} m",
+                    as_deg(position.lat), as_deg(position.lon), position.elevation.get::<length::meter>()
+                );
+                *state.observer.borrow_mut() = Some(position);
+                state.config.borrow_mut().set_observer_location(&position);
+                state.config.borrow_mut().clear_current_site();
+            }
+        },
+
+        Ok(None) => (),
+
+        Err(e) => {
+            log::error!("GPS error: {}", e);
+            *state.gps.borrow_mut() = None;
+        }
+    }
+}
+
+fn on_time_sync(state: &mut ProgramState) {
+    let mut measurement = None;
+
+    if let Some(client) = state.ntp_client.as_mut() {
+        match client.poll() {
+            Ok(Some(offset)) => measurement = Some(ClockOffset{ offset, reference: TimeReference::Ntp }),
+            Ok(None) => (),
+            Err(e) => {
+                log::error!("time synchronization with {} failed: {}", client.server(), e);
+                state.ntp_client = None;
+            }
+        }
+    }
+
+    // GPS is used only if NTP is not available
+    if state.ntp_client.is_none() {
+        if let Some(offset) = state.gps.borrow().as_ref().and_then(|gps| gps.time_offset()) {
+            measurement = Some(ClockOffset{ offset, reference: TimeReference::Gps });
+        }
+    }
+
+    let Some(measurement) = measurement else { return; };
+    let threshold = state.config.borrow().time_offset_warning();
+    let prev_exceeded = state.clock_offset.is_some_and(|prev| prev.offset.abs() > threshold);
+    if measurement.offset.abs() > threshold && !prev_exceeded {
+        log::warn!("system clock is off by {:+.3} s ({})", measurement.offset, measurement.reference);
+    }
+    state.clock_offset = Some(measurement);
+    tui_s!(state).text_content.clock_offset.set_content(format!(
+        "{:+.3} s ({}){}",
+        measurement.offset,
+        measurement.reference,
+        if measurement.offset.abs() > threshold { "  (!) check system time" } else { "" }
+    ));
+}
+
 fn on_target_log(state: &mut ProgramState) {
     if let Some(target) = state.target.borrow().as_ref() {
         log::info!(
@@ -97,19 +457,275 @@ fn on_target_log(state: &mut ProgramState) {
             target.alt_above_gnd.get::<length::meter>()
         );
     }
+
+    on_session_log(state);
+}
+
+fn on_session_log(state: &mut ProgramState) {
+    if state.session_log.is_none() { return; }
+
+    let target = state.target.borrow();
+    let mount = state.pos_history.borrow().samples().back().and_then(|s| s.mount);
+    if target.is_none() && mount.is_none() { return; }
+
+    let record = session_log::Record{
+        time: chrono::Local::now(),
+        target: target.as_ref(),
+        mount,
+        tracking: state.tracking.is_active(),
+        adjustment: state.tracking.adjustment()
+    };
+    if let Err(e) = state.session_log.as_mut().unwrap().write(&record) {
+        log::error!("failed to write session log: {}; session logging disabled", e);
+        state.session_log = None;
+    }
+}
+
+fn update_status_bar(state: &mut ProgramState) {
+    let mount = match state.mount.borrow().as_ref() {
+        Some(mount) => {
+            let ok = state.pos_history.borrow().samples().back().is_some_and(|s| s.mount.is_some());
+            format!("{} {}", mount.get_info(), if ok { "OK" } else { "ERROR" })
+        },
+        None if state.mount_watchdog.is_reconnecting() => "reconnecting...".into(),
+        None => "-".into()
+    };
+
+    let target_age = state.target.borrow().as_ref().map(|t| t.age());
+    let data = if let Some((address, _)) = state.data_receiver.connecting() {
+        format!("{} (connecting...)", address)
+    } else if let Some(address) = state.data_receiver.connection().address() {
+        match target_age {
+            Some(age) => format!("{} ({:.1} s ago)", address, age.as_secs_f64()),
+            None => format!("{} (no data)", address)
+        }
+    } else if let Some(source) = state.target_source.borrow().as_ref() {
+        source.name()
+    } else {
+        "-".into()
+    };
+
+    let controller = {
+        let controllers = state.connected_controllers.borrow();
+        if controllers.is_empty() {
+            "-".into()
+        } else {
+            controllers.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+        }
+    };
+
+    let utc = clock::utc_now();
+    tui_s!(state).text_content.status_bar.set_content(format!(
+        " Mount: {} │ Data: {} │ Controller: {} │ {} UTC  {} local",
+        mount,
+        data,
+        controller,
+        utc.format("%H:%M:%S"),
+        utc.with_timezone(&chrono::Local).format("%H:%M:%S")
+    ));
+    state.refresh_tui();
+}
+
+fn on_state_broadcast(state: &mut ProgramState) {
+    if state.state_broadcast.is_none() { return; }
+
+    let target = state.target.borrow();
+    let record = session_log::Record{
+        time: chrono::Local::now(),
+        target: target.as_ref(),
+        mount: state.pos_history.borrow().samples().back().and_then(|s| s.mount),
+        tracking: state.tracking.is_active(),
+        adjustment: state.tracking.adjustment()
+    };
+    state.state_broadcast.as_mut().unwrap().publish(&record);
+}
+
+fn on_overlay_update(state: &mut ProgramState) {
+    if state.overlay.is_none() { return; }
+
+    let target = state.target.borrow();
+    let values = overlay::OverlayValues{
+        time: chrono::Local::now(),
+        name: target_source_id(&state.target_source, &state.data_receiver.connection()),
+        target: target.as_ref(),
+        mount: state.pos_history.borrow().samples().back().and_then(|s| s.mount),
+        tracking: state.tracking.is_active(),
+        angle_display: state.angle_display
+    };
+    if let Err(e) = state.overlay.as_mut().unwrap().update(&values) {
+        log::error!("failed to update overlay: {}; overlay output disabled", e);
+        state.overlay = None;
+    }
+}
+
+fn on_telemetry(state: &mut ProgramState) {
+    if state.telemetry.is_none() { return; }
+
+    let mount_pos = state.pos_history.borrow().samples().back().and_then(|s| s.mount);
+    let tracking = state.tracking.is_active();
+    let zero_spd = (data::deg_per_s(0.0), data::deg_per_s(0.0));
+    let metrics = {
+        let latency = state.latency.borrow();
+        telemetry::Metrics{
+            tracking,
+            tracking_error: mount_pos.filter(|_| tracking)
+                .and_then(|pos| state.tracking.pointing_error(pos, zero_spd))
+                .map(|(error, _)| error),
+            mount_spd: state.mount_spd.borrow().get(),
+            commanded_spd: state.tracking.commanded_speeds().filter(|_| tracking),
+            latency: [&latency.network, &latency.processing, &latency.to_command, &latency.command]
+                .map(|stage| stage.average())
+        }
+    };
+    if let Err(e) = state.telemetry.as_mut().unwrap().publish(&metrics) {
+        log::error!("failed to publish telemetry: {}; telemetry disabled", e);
+        state.telemetry = None;
+    }
+}
+
+fn on_script_tick(state: &mut ProgramState) {
+    if state.script.is_none() { return; }
+
+    let result = {
+        let target = state.target.borrow();
+        let tracking = state.tracking.controller();
+        let script_state = scripting::ScriptState{
+            target: target.as_ref(),
+            mount: state.pos_history.borrow().samples().back().and_then(|s| s.mount),
+            mount_spd: state.mount_spd.borrow().get(),
+            tracking: tracking.is_active(),
+            paused: tracking.is_paused(),
+            dry_run: tracking.is_dry_run()
+        };
+        state.script.as_mut().unwrap().tick(&script_state)
+    };
+    let commands = match result {
+        Ok(commands) => commands,
+        Err(e) => {
+            log::error!("script error: {}; script disabled", e);
+            state.script = None;
+            return;
+        }
+    };
+
+    for command in commands {
+        log::debug!("script command: {:?}", command);
+        let tracking = state.tracking.controller();
+        match command {
+            scripting::Command::Slew(axis1, axis2) => if tracking.is_active() {
+                log::warn!("script: cannot slew during tracking");
+            } else {
+                for mount in state.mount_routing.get().routed(&state.mount, &state.mount2) {
+                    if let Some(mount) = mount.borrow_mut().as_mut() {
+                        if let Err(e) = mount.slew(data::deg_per_s(axis1), data::deg_per_s(axis2)) {
+                            log::error!("error when slewing: {}", e);
+                        }
+                    }
+                }
+            },
+            scripting::Command::Stop => on_stop_mount(&[&state.mount, &state.mount2], &tracking),
+            scripting::Command::StartTracking => if !tracking.is_active() {
+                start_tracking(
+                    &mut state.cursive_stepper.curs, &tracking, &state.target, &state.observer, &state.sun_avoidance
+                );
+            },
+            scripting::Command::StopTracking => if tracking.is_active() { tracking.stop(); },
+            scripting::Command::PauseTracking => tracking.pause(),
+            scripting::Command::ResumeTracking => tracking.resume(),
+            scripting::Command::Adjust(horizontal, vertical) => {
+                if let Err(e) = state.tracking.apply_correction(data::deg(horizontal), data::deg(vertical)) {
+                    log::warn!("script: cannot adjust: {}", e);
+                }
+            }
+        }
+    }
+}
+
+fn on_rate_calibration(state: &mut ProgramState) {
+    let mut calibration = state.rate_calibration.borrow_mut();
+    let calibration = match calibration.as_mut() {
+        Some(c) if c.result().is_none() => c,
+        _ => return
+    };
+    match state.mount.borrow_mut().as_mut() {
+        Some(mount) => calibration.step(mount),
+        None => calibration.abort(None)
+    }
+    tui::rate_calibration_dialog::on_progress(&mut state.cursive_stepper.curs, &calibration.description());
 }
 
 fn on_timer(state: &mut ProgramState, idx_id: (usize, TimerId)) -> std::task::Poll<()> {
     let (_, id) = idx_id;
     match id {
-        timers::MAIN => on_main_timer(state),
+        timers::MAIN => {
+            on_gps_timer(state);
+            on_time_sync(state);
+            on_mount_watchdog(state);
+            on_data_source_reconnect(state);
+            on_data_source_connecting(state);
+            update_target_staleness(state);
+            update_tracking_speeds(state);
+            on_main_timer(state);
+            on_state_broadcast(state);
+            on_overlay_update(state);
+            on_telemetry(state);
+            on_script_tick(state);
+            update_status_bar(state);
+            on_rate_calibration(state);
+        },
         timers::TARGET_LOG => on_target_log(state),
+        timers::TARGET_SOURCE => on_target_source_timer(state),
+        timers::KEYBOARD => on_keyboard_timer(state),
+        timers::EXTERNAL_INPUT => {
+            on_guiding_input(state);
+            on_camera_corrections(state);
+            on_camera_trigger_timer(state);
+        },
+        timers::MOTION => on_motion_timer(state),
+        timers::MOUNT_STATE => {
+            save_mount_state(state);
+            save_odometers(state);
+            check_mount_drift(state);
+        },
         _ => ()
     }
 
     Poll::Pending
 }
 
+fn on_keyboard_timer(state: &mut ProgramState) {
+    let events = state.keyboard_slewing.borrow_mut().poll();
+    for (action, pressed) in events {
+        on_controller_action(state, action, EventValue::Discrete(pressed));
+    }
+}
+
+fn on_guiding_input(state: &mut ProgramState) {
+    let corrections = match state.guiding.as_mut() {
+        Some(guiding) => guiding.poll(),
+        None => return
+    };
+
+    for (client, correction) in corrections {
+        let result = state.tracking.apply_correction(correction.horizontal, correction.vertical);
+        if let Err(e) = &result { log::warn!("guide pulse not applied: {}", e); }
+        state.guiding.as_mut().unwrap().reply(client, result);
+    }
+}
+
+fn on_camera_corrections(state: &mut ProgramState) {
+    let corrections = match state.camera_corrections.as_mut() {
+        Some(camera) => camera.poll(),
+        None => return
+    };
+
+    for (client, correction) in corrections {
+        let result = state.tracking.apply_correction(correction.horizontal, correction.vertical);
+        if let Err(e) = &result { log::warn!("camera correction not applied: {}", e); }
+        state.camera_corrections.as_mut().unwrap().reply(client, result);
+    }
+}
+
 fn on_cursive_step(_: &mut ProgramState, running: Running) -> Poll<()> {
     if running.0 {
         Poll::Pending
@@ -129,53 +745,402 @@ fn on_refresher(state: &mut ProgramState, _: ()) -> Poll<()> {
 
 fn on_controller_connected(state: &mut ProgramState, mut controller: stick::Controller) -> Poll<()> {
 
-    let ctrl_str = format!("[{:016X}] {}", controller.id(), controller.name());
+    let name = controller.name().to_string();
+    let ctrl_str = format!("[{:016X}] {}", controller.id(), name);
     log::info!("new controller: {}", ctrl_str);
     state.tui().as_ref().unwrap().text_content.controller_name.set_content(ctrl_str);
     state.refresh_tui();
 
-    state.controller_names.push(controller.name().into());
-    state.controllers.push(
+    if !state.ctrl_actions.borrow().uses_controller(controller.id()) {
+        let profile = state.config.borrow().controller_profile_for(controller.id());
+        if let Some(profile) = profile {
+            log::info!("switching to controller profile \"{}\"", profile);
+            on_controller_profile_selected(&profile, &state.config, &state.ctrl_actions, &state.axes_response);
+        }
+    }
+
+    let id = controller.id();
+    let events: std::pin::Pin<Box<dyn pasts::notify::Notify<Event = (u64, stick::Event)>>> =
         Box::pin(pasts::notify::poll_fn(move |ctx| {
             match std::pin::Pin::new(&mut controller).poll(ctx) {
                 Poll::Ready(event) => Poll::Ready((controller.id(), event)),
                 Poll::Pending => Poll::Pending
             }
-        })),
-    );
+        }));
+
+    // a controller may reappear without a preceding disconnect event (e.g., after a quick unplug/replug);
+    // controllers with the same ID are indistinguishable anyway, so the previous entry is replaced
+    let existing = state.connected_controllers.borrow().iter().position(|c| c.id == id);
+    if let Some(idx) = existing {
+        log::info!("controller [{:016X}] reconnected", id);
+        state.controllers[idx] = events;
+        state.connected_controllers.borrow_mut()[idx].name = name;
+    } else {
+        let enabled = state.config.borrow().controller_enabled(id);
+        if !enabled { log::info!("controller [{:016X}] is disabled", id); }
+        state.controllers.push(events);
+        state.connected_controllers.borrow_mut().push(controller::ConnectedController{ id, name, enabled });
+    }
 
     std::task::Poll::Pending
 }
 
-pub fn on_stop_mount(mount: &Rc<RefCell<Option<MountWrapper>>>, tracking: &TrackingController) {
-    let mut mount = mount.borrow_mut();
-    if let Some(mount) = mount.as_mut() {
-        if let Err(e) = mount.stop() {
-            log::error!("error stopping the mount: {}", e);
+/// Connects to mount (and, unless it is the secondary mount, stores connection parameters in configuration);
+/// returns mount information.
+#[must_use]
+pub fn connect_to_mount(
+    mount_type: mount::MountType,
+    connection_param: &str,
+    mount: &Rc<RefCell<Option<MountWrapper>>>,
+    config: &Rc<RefCell<Configuration>>,
+    tracking: TrackingController,
+    secondary: bool
+) -> Result<String, Box<dyn Error>> {
+    let axes_swapped = !secondary && config.borrow().mount_axes_swapped();
+    let m = mount_type.connect_with_axes(connection_param, axes_swapped).map_err(|e| {
+        log::error!("error connecting to mount at \"{}\": {}", connection_param, e);
+        e
+    })?;
+
+    let info = m.get_info();
+    log::info!("connected to {}", info);
+    let odometer = config.borrow().odometer(&info);
+    let mut wrapper = MountWrapper::new(m);
+    wrapper.set_max_acceleration(config.borrow().mount_max_acceleration());
+    wrapper.set_max_travel(config.borrow().mount_max_axis_travel());
+    if let Some(odometer) = odometer { wrapper.set_odometer(odometer); }
+    wrapper.set_command_epsilon(config.borrow().mount_command_epsilon());
+    if let Some(limit) = config.borrow().mount_altitude_limit() { wrapper.set_altitude_limit(Some(limit)); }
+    if !secondary {
+        wrapper.set_drift_monitor(config.borrow().mount_drift_monitor());
+        wrapper.set_rate_scale(config.borrow().mount_rate_scale());
+        if config.borrow().mount_push_to() {
+            log::info!("push-to mode enabled for {}", info);
+            wrapper.set_push_to(true)?;
         }
-        tracking.stop();
+        if let Some(saved) = config.borrow().mount_state(&mount_type.mount_id(connection_param)) {
+            log::info!("restoring saved calibration of {}", info);
+            wrapper.restore_state(&saved);
+            tracking.restore_adjustment(saved.adjustment);
+        }
+    }
+    wrapper.set_on_max_travel_exceeded(Box::new(
+        move |mount, axis1, axis2| on_max_travel_exceeded(mount, axis1, axis2, tracking.clone())
+    ));
+    let name = mount_display_name(&wrapper);
+    *mount.borrow_mut() = Some(wrapper);
+    if !secondary {
+        config.borrow_mut().set_mount_type(mount_type);
+        config.borrow_mut().set_mount_connection_param(mount_type, connection_param);
+    }
+
+    Ok(name)
+}
+
+fn on_data_source_connected(
+    server_addr: &str,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    config: &Rc<RefCell<Configuration>>
+) {
+    log::info!("connected to data source {}", server_addr);
+    *target_source.borrow_mut() = None;
+    config.borrow_mut().set_data_source_addr(server_addr);
+}
+
+/// Connects to the specified mount and data source. If not specified, in headless mode the previously used ones
+/// (as stored in configuration) are connected to.
+pub fn connect_on_startup(
+    state: &mut ProgramState,
+    mount: Option<(mount::MountType, String)>,
+    data_source: Option<String>,
+    headless: bool
+) {
+    let reconnect = headless || state.config.borrow().reconnect_last_session();
+
+    let mount = mount.or_else(|| if reconnect {
+        let config = state.config.borrow();
+        config.mount_type().and_then(|t| config.mount_connection_param(t).map(|param| (t, param)))
+    } else {
+        None
+    });
+    if let Some((mount_type, param)) = mount {
+        if !headless {
+            // connecting may take a while (e.g., via a serial port); show it before blocking
+            tui_s!(state).text_content.mount_name.set_content(format!("connecting to {}...", param));
+            state.refresh_tui();
+        }
+        match connect_to_mount(mount_type, &param, &state.mount, &state.config, state.tracking.controller(), false) {
+            Ok(info) => tui_s!(state).text_content.mount_name.set_content(info),
+            Err(e) => if !headless {
+                tui_s!(state).text_content.mount_name.set_content("(disconnected)");
+                tui::msg_box(&mut state.cursive_stepper.curs, &format!("Failed to connect to mount: {}.", e), "Error");
+            }
+        }
+    }
+
+    let data_source = data_source.or_else(|| if reconnect {
+        state.config.borrow().data_source_addr().filter(|a| !a.is_empty())
+    } else {
+        None
+    });
+    if let Some(address) = data_source {
+        // the result is handled by `on_data_source_connecting`
+        state.data_receiver.connection().start_connecting(&address);
+    }
+}
+
+/// Stops all connected mounts.
+pub fn on_stop_mount(mounts: &[&Rc<RefCell<Option<MountWrapper>>>], tracking: &TrackingController) {
+    let mut any_connected = false;
+    for mount in mounts {
+        if let Some(mount) = mount.borrow_mut().as_mut() {
+            any_connected = true;
+            if let Err(e) = mount.stop() {
+                log::error!("error stopping the mount: {}", e);
+            }
+        }
+    }
+    if any_connected { tracking.stop(); }
+}
+
+/// Parks the controlled mount(s), or unparks them if any of them is parked.
+pub fn on_toggle_park(
+    mount: &Rc<RefCell<Option<MountWrapper>>>,
+    mount2: &Rc<RefCell<Option<MountWrapper>>>,
+    routing: mount::MountRouting,
+    tracking: &TrackingController,
+    tui: &Rc<RefCell<Option<TuiData>>>
+) -> Result<(), Box<dyn Error>> {
+    let mounts = routing.routed(mount, mount2);
+    let unpark = mounts.iter().any(|m| m.borrow().as_ref().is_some_and(|m| m.is_parked()));
+    if !unpark && tracking.is_active() { tracking.stop(); }
+
+    let mut result = Ok(());
+    for m in mounts {
+        if let Some(m) = m.borrow_mut().as_mut() {
+            let (action, r) = if unpark { ("unpark", m.unpark()) } else { ("park", m.park()) };
+            match r {
+                Ok(()) => log::info!("{}ed {}", action, m.get_info()),
+                Err(e) => {
+                    log::error!("failed to {} {}: {}", action, m.get_info(), e);
+                    result = Err(e);
+                }
+            }
+        }
+    }
+
+    if let Some(m) = mount.borrow().as_ref() {
+        tui!(tui).text_content.mount_name.set_content(mount_display_name(m));
     }
+
+    result
 }
 
-pub fn on_toggle_tracking(tracking: &TrackingController) {
+/// Enables push-to (encoders-only) mode for the controlled mount(s), or disables it if any of them is in push-to
+/// mode. The setting of the main mount is stored in configuration.
+pub fn on_toggle_push_to(
+    mount: &Rc<RefCell<Option<MountWrapper>>>,
+    mount2: &Rc<RefCell<Option<MountWrapper>>>,
+    routing: mount::MountRouting,
+    config: &Rc<RefCell<Configuration>>,
+    tui: &Rc<RefCell<Option<TuiData>>>
+) -> Result<(), Box<dyn Error>> {
+    let mounts = routing.routed(mount, mount2);
+    let enable = !mounts.iter().any(|m| m.borrow().as_ref().is_some_and(|m| m.is_push_to()));
+
+    let mut result = Ok(());
+    for m in &mounts {
+        if let Some(m) = m.borrow_mut().as_mut() {
+            match m.set_push_to(enable) {
+                Ok(()) => log::info!(
+                    "push-to mode {} for {}", if enable { "enabled" } else { "disabled" }, m.get_info()
+                ),
+                Err(e) => {
+                    log::error!("failed to set push-to mode of {}: {}", m.get_info(), e);
+                    result = Err(e);
+                }
+            }
+        }
+    }
+
+    if mounts.iter().any(|m| Rc::ptr_eq(*m, mount)) {
+        if let Some(m) = mount.borrow().as_ref() {
+            config.borrow_mut().set_mount_push_to(m.is_push_to());
+            tui!(tui).text_content.mount_name.set_content(mount_display_name(m));
+        }
+    }
+
+    result
+}
+
+/// Returns mount information followed by its special mode (if any).
+pub fn mount_display_name(mount: &MountWrapper) -> String {
+    if mount.is_parked() {
+        format!("{} (parked)", mount.get_info())
+    } else if mount.is_push_to() {
+        format!("{} (push-to)", mount.get_info())
+    } else {
+        mount.get_info()
+    }
+}
+
+pub fn on_controller_profile_selected(
+    profile: &str,
+    config: &Rc<RefCell<Configuration>>,
+    ctrl_actions: &Rc<RefCell<ActionAssignments>>,
+    axes_response: &Rc<RefCell<[AxisResponse; 2]>>
+) {
+    config.borrow_mut().set_controller_profile(profile);
+    *ctrl_actions.borrow_mut() = config.borrow().controller_actions();
+    *axes_response.borrow_mut() = config.borrow().mount_axes_response();
+}
+
+pub fn on_toggle_tracking(
+    curs: &mut cursive::Cursive,
+    tracking: &TrackingController,
+    target: &RefCell<Option<data::Target>>,
+    observer: &RefCell<Option<data::ObserverLocation>>,
+    sun_avoidance: &Rc<RefCell<SunAvoidance>>
+) {
     if tracking.is_active() {
         tracking.stop();
     } else {
-        tracking.start();
+        start_tracking(curs, tracking, target, observer, sun_avoidance);
+    }
+}
+
+/// Pauses or resumes tracking.
+pub fn on_toggle_pause(tracking: &TrackingController) {
+    if tracking.is_paused() {
+        tracking.resume();
+    } else if tracking.is_active() {
+        tracking.pause();
+    }
+}
+
+/// Starts tracking; asks for confirmation first if the target is within the exclusion zone around the Sun.
+pub fn start_tracking(
+    curs: &mut cursive::Cursive,
+    tracking: &TrackingController,
+    target: &RefCell<Option<data::Target>>,
+    observer: &RefCell<Option<data::ObserverLocation>>,
+    sun_avoidance: &Rc<RefCell<SunAvoidance>>
+) {
+    let sun_dist = match (target.borrow().as_ref(), *observer.borrow()) {
+        (Some(target), Some(observer)) => sun_avoidance.borrow().violation(target.azimuth, target.altitude, &observer),
+        _ => None
+    };
+    match sun_dist {
+        Some(dist) => {
+            log::warn!("target is {:.1}° from the Sun; tracking not started", as_deg(dist));
+            tui::confirm_sun_override(curs, dist, tracking.clone(), Rc::downgrade(sun_avoidance));
+        },
+        None => tracking.start()
+    }
+}
+
+/// Returns identifier of the current target source (its name or the data source address) used for saved adjustments.
+pub fn target_source_id(
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    connection: &data_receiver::Connection
+) -> Option<String> {
+    match target_source.borrow().as_ref() {
+        Some(source) => Some(source.name()),
+        None => connection.address()
     }
 }
 
+/// Once tracking has started, applies the adjustment saved for the current target source (if any and if there is
+/// no adjustment yet).
+fn apply_saved_adjustment(state: &mut ProgramState) {
+    if !state.tracking.is_active() {
+        state.saved_adjustment_checked = false;
+        return;
+    }
+    if state.saved_adjustment_checked { return; }
+    state.saved_adjustment_checked = true;
+
+    if state.tracking.adjustment().is_some() { return; }
+    let source_id = match target_source_id(&state.target_source, &state.data_receiver.connection()) {
+        Some(id) => id,
+        None => return
+    };
+    let saved = state.config.borrow().saved_adjustments().into_iter().rev().find(|adj| adj.source == source_id);
+    if let Some(saved) = saved {
+        log::info!("applying saved adjustment \"{}\" for {}", saved.name, source_id);
+        state.tracking.controller().restore_adjustment(Some((saved.rel_dir, saved.angle)));
+    }
+}
+
+/// Makes the target queue's entry `idx` the current target source.
+pub fn select_queue_entry(
+    idx: usize,
+    queue: &Rc<RefCell<TargetQueue>>,
+    connection: &data_receiver::Connection,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    tui: &Rc<RefCell<Option<TuiData>>>
+) -> Result<(), Box<dyn Error>> {
+    let source = match queue.borrow().entries().get(idx) {
+        Some(entry) => entry.source()?,
+        None => return Err("no such queue entry".into())
+    };
+
+    log::info!("tracking queued target {}", source.name());
+    connection.disconnect();
+    tui!(tui).text_content.target_source.set_content(source.name());
+    *target_source.borrow_mut() = Some(source);
+    queue.borrow_mut().set_current(idx);
+
+    Ok(())
+}
+
+/// Switches to the next (or previous) entry of the target queue.
+pub fn on_queue_step(
+    forward: bool,
+    queue: &Rc<RefCell<TargetQueue>>,
+    connection: &data_receiver::Connection,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    tui: &Rc<RefCell<Option<TuiData>>>
+) {
+    let idx = if forward { queue.borrow().next() } else { queue.borrow().previous() };
+    if let Some(idx) = idx {
+        if let Err(e) = select_queue_entry(idx, queue, connection, target_source, tui) {
+            log::error!("failed to select queued target: {}", e);
+        }
+    }
+}
+
+/// Returns the speed resulting from relative slewing input `rel_input` (for display).
+fn commanded_slew_speed(state: &ProgramState, rel_input: f64) -> f64::AngularVelocity {
+    let max_speed = if state.tracking.is_active() {
+        state.tracking.controller().adjustment_slew_speed()
+    } else {
+        *state.slew_speed.borrow()
+    };
+    state.config.borrow().rate_mapping().speed(rel_input, max_speed)
+}
+
 fn on_controller_action(state: &mut ProgramState, action: TargetAction, value: EventValue) {
     let mut slew_change = false;
 
     match action {
         TargetAction::MountAxis1 => if let EventValue::Analog(value) = value {
-            state.slewing.axis1_rel = if state.config.borrow().mount_axis1_reversed() { -value } else { value };
+            let output = state.axes_response.borrow()[0].apply(value);
+            let commanded = state.angle_display.signed_speed(commanded_slew_speed(state, output), 4);
+            tui_s!(state).text_content.axis1_input.set_content(
+                format!("{:+.3} → {:+.3} ({})", value, output, commanded)
+            );
+            state.slewing.axis1_rel = if state.config.borrow().mount_axis1_reversed() { -output } else { output };
             slew_change = true;
         },
 
         TargetAction::MountAxis2 => if let EventValue::Analog(value) = value {
-            state.slewing.axis2_rel = if state.config.borrow().mount_axis2_reversed() { -value } else { value };
+            let output = state.axes_response.borrow()[1].apply(value);
+            let commanded = state.angle_display.signed_speed(commanded_slew_speed(state, output), 4);
+            tui_s!(state).text_content.axis2_input.set_content(
+                format!("{:+.3} → {:+.3} ({})", value, output, commanded)
+            );
+            state.slewing.axis2_rel = if state.config.borrow().mount_axis2_reversed() { -output } else { output };
             slew_change = true;
         },
 
@@ -200,11 +1165,23 @@ fn on_controller_action(state: &mut ProgramState, action: TargetAction, value: E
         },
 
         TargetAction::StopMount => if let EventValue::Discrete(pressed) = value {
-            if pressed { on_stop_mount(&state.mount, &state.tracking.controller()); }
+            if pressed { on_stop_mount(&[&state.mount, &state.mount2], &state.tracking.controller()); }
         },
 
         TargetAction::ToggleTracking => if let EventValue::Discrete(pressed) = value {
-            if pressed { on_toggle_tracking(&state.tracking.controller()); }
+            if pressed {
+                on_toggle_tracking(
+                    &mut state.cursive_stepper.curs,
+                    &state.tracking.controller(),
+                    &state.target,
+                    &state.observer,
+                    &state.sun_avoidance
+                );
+            }
+        },
+
+        TargetAction::TogglePause => if let EventValue::Discrete(true) = value {
+            on_toggle_pause(&state.tracking.controller());
         },
 
         TargetAction::SaveAdjustment => if let EventValue::Discrete(pressed) = value {
@@ -221,18 +1198,66 @@ fn on_controller_action(state: &mut ProgramState, action: TargetAction, value: E
                     SLEW_SPEED_CHANGE_FACTOR,
                     Rc::downgrade(&state.slew_speed),
                     Rc::downgrade(&state.tui),
+                    Rc::downgrade(&state.config),
+                    Rc::downgrade(&state.mount),
                     &state.tracking.controller(),
                     state.refresher.request()
                 );
             }
         },
 
+        TargetAction::ToggleParking => if let EventValue::Discrete(true) = value {
+            let _ = on_toggle_park(
+                &state.mount,
+                &state.mount2,
+                state.mount_routing.get(),
+                &state.tracking.controller(),
+                &state.tui
+            );
+            state.refresh_tui();
+        },
+
+        TargetAction::TriggerCamera => if let EventValue::Discrete(true) = value {
+            on_trigger_camera(state);
+        },
+
+        TargetAction::TrimAxis1Pos
+        | TargetAction::TrimAxis1Neg
+        | TargetAction::TrimAxis2Pos
+        | TargetAction::TrimAxis2Neg => if let EventValue::Discrete(true) = value {
+            let axis = match action {
+                TargetAction::TrimAxis1Pos | TargetAction::TrimAxis1Neg => Axis::Primary,
+                _ => Axis::Secondary
+            };
+            let positive = matches!(action, TargetAction::TrimAxis1Pos | TargetAction::TrimAxis2Pos);
+            on_trim(&state.tracking.controller(), &state.config.borrow(), axis, positive);
+            update_tracking_speeds(state);
+        },
+
+        TargetAction::ResetTrim => if let EventValue::Discrete(true) = value {
+            state.tracking.controller().reset_trim();
+            update_tracking_speeds(state);
+        },
+
+        TargetAction::NextTarget | TargetAction::PreviousTarget => if let EventValue::Discrete(true) = value {
+            on_queue_step(
+                action == TargetAction::NextTarget,
+                &state.target_queue,
+                &state.data_receiver.connection(),
+                &state.target_source,
+                &state.tui
+            );
+            state.refresh_tui();
+        },
+
         TargetAction::DecreaseSlewSpeed => if let EventValue::Discrete(pressed) = value {
             if pressed {
                 change_slew_speed(
                     1.0 / SLEW_SPEED_CHANGE_FACTOR,
                     Rc::downgrade(&state.slew_speed),
                     Rc::downgrade(&state.tui),
+                    Rc::downgrade(&state.config),
+                    Rc::downgrade(&state.mount),
                     &state.tracking.controller(),
                     state.refresher.request()
                 );
@@ -241,15 +1266,22 @@ fn on_controller_action(state: &mut ProgramState, action: TargetAction, value: E
     }
 
     if slew_change {
-        if state.tracking.is_active() {
-            state.tracking.adjust_slew(state.slewing.axis1_rel, state.slewing.axis2_rel);
-        } else if state.mount.borrow().is_some() {
-            let spd = *state.slew_speed.borrow();
-            if let Err(e) = state.mount.borrow_mut().as_mut().unwrap().slew(
-                spd * state.slewing.axis1_rel,
-                spd * state.slewing.axis2_rel
-            ) {
-                log::error!("error when slewing: {}", e);
+        let rate_mapping = state.config.borrow().rate_mapping();
+        if state.tracking.is_active() && state.config.borrow().polar_adjustment() {
+            state.tracking.adjust_polar(state.slewing.axis1_rel, state.slewing.axis2_rel, &rate_mapping);
+        } else if state.tracking.is_active() {
+            state.tracking.adjust_slew(state.slewing.axis1_rel, state.slewing.axis2_rel, &rate_mapping);
+        } else {
+            for mount in state.mount_routing.get().routed(&state.mount, &state.mount2) {
+                if let Some(mount) = mount.borrow_mut().as_mut() {
+                    let (min_spd, max_spd) = state.config.borrow().slew_speed_limits(mount.max_slew_speed());
+                    let spd = state.slew_speed.borrow().min(max_spd).max(min_spd);
+                    let axis1_spd = rate_mapping.speed(state.slewing.axis1_rel, spd);
+                    let axis2_spd = rate_mapping.speed(state.slewing.axis2_rel, spd);
+                    if let Err(e) = mount.slew(axis1_spd, axis2_spd) {
+                        log::error!("error when slewing: {}", e);
+                    }
+                }
             }
         }
     }
@@ -258,8 +1290,11 @@ fn on_controller_action(state: &mut ProgramState, action: TargetAction, value: E
 fn on_controller_event(state: &mut ProgramState, idx_val: (usize, (u64, stick::Event))) -> std::task::Poll<()> {
     let (index, (id, event)) = idx_val;
 
-    let ctrl_str = format!("[{:016X}] {}", id, state.controller_names[index]);
-    log::info!("new controller: {}", ctrl_str);
+    let (ctrl_name, enabled) = {
+        let connected = state.connected_controllers.borrow();
+        (connected[index].name.clone(), connected[index].enabled)
+    };
+    let ctrl_str = format!("[{:016X}] {}{}", id, ctrl_name, if enabled { "" } else { " (disabled)" });
     state.tui().as_ref().unwrap().text_content.controller_name.set_content(ctrl_str);
     state.refresh_tui();
 
@@ -267,82 +1302,361 @@ fn on_controller_event(state: &mut ProgramState, idx_val: (usize, (u64, stick::E
     state.tui().as_ref().unwrap().text_content.controller_event.set_content(format!("{}", event));
     state.refresh_tui();
 
+    state.held_buttons.update(&StickEvent{ id, event });
+
     if let stick::Event::Disconnect = event {
+        log::info!("controller [{:016X}] disconnected", id);
         state.controllers.remove(index);
-        state.controller_names.remove(index);
+        state.connected_controllers.borrow_mut().remove(index);
+    } else if !enabled {
+        log::trace!("ignoring event from disabled controller [{:016X}]", id);
+    } else if state.ctrl_action_capture.borrow().is_some() {
+        on_capture_ctrl_action(state, index, id, event);
     } else {
-        let mut target_action: Option<TargetAction> = None;
-        for t_act in TargetAction::iter() {
-            if let Some(src_action) = &state.ctrl_actions.get(t_act) {
-                if src_action.matches(&StickEvent{ id, event }) {
-                    target_action = Some(t_act); break;
-                }
-            }
+        let target_actions = state.ctrl_actions.borrow().matching(&StickEvent{ id, event }, &state.held_buttons);
+        for target_action in target_actions {
+            on_controller_action(state, target_action, controller::event_value(&event));
         }
 
-        if let Some(target_action) = target_action {
-            on_controller_action(state, target_action, controller::event_value(&event));
+        if let EventValue::Discrete(false) = controller::event_value(&event) {
+            // releasing a modifier stops slewing via the analog axes chorded with it
+            let modifier = controller::SerializedEvent::from_event(&event);
+            let chorded = state.ctrl_actions.borrow().chorded_with(id, &modifier);
+            for target_action in chorded {
+                if let TargetAction::MountAxis1 | TargetAction::MountAxis2 = target_action {
+                    on_controller_action(state, target_action, EventValue::Analog(0.0));
+                }
+            }
         }
     }
 
     std::task::Poll::Pending
 }
 
+/// Assigns controller event to the action selected in the controller dialog (if the event is suitable).
+/// If another button of the same controller is held, a chorded action is assigned (with that button as modifier).
+fn on_capture_ctrl_action(state: &mut ProgramState, index: usize, id: u64, event: stick::Event) {
+    const MIN_ANALOG_DEFLECTION: f64 = 0.5;
+
+    let target_action = state.ctrl_action_capture.borrow().unwrap();
+    let serialized = controller::SerializedEvent::from_event(&event);
+    let held = state.held_buttons.last_held(id, &serialized);
+    let modifier = match (target_action, controller::event_value(&event)) {
+        (TargetAction::MountAxis1 | TargetAction::MountAxis2, EventValue::Analog(value)) =>
+            if value.abs() >= MIN_ANALOG_DEFLECTION { held } else { return; },
+
+        (TargetAction::MountAxis1 | TargetAction::MountAxis2, EventValue::Discrete(_)) => return,
+
+        // a button alone is assigned once released, as it may turn out to be the modifier of a chord
+        (_, EventValue::Discrete(true)) => if held.is_some() { held } else { return; },
+
+        (_, EventValue::Discrete(false)) => None,
+
+        (_, EventValue::Analog(_)) => return
+    };
+
+    let src_action = SourceAction{
+        ctrl_id: id,
+        ctrl_name: state.connected_controllers.borrow()[index].name.clone(),
+        modifier,
+        event: serialized
+    };
+    log::info!("assigning {} to {}", src_action.serialize(), target_action);
+
+    *state.ctrl_action_capture.borrow_mut() = None;
+    state.ctrl_actions.borrow_mut().set(target_action, Some(src_action));
+    state.config.borrow_mut().save_controller_actions(&state.ctrl_actions.borrow());
+    tui::controller_dialog::on_assignment_changed(
+        &mut state.cursive_stepper.curs, target_action, &state.ctrl_actions.borrow()
+    );
+    state.refresh_tui();
+}
+
 fn on_data_received(state: &mut ProgramState, message: Result<String, std::io::Error>) -> Poll<()> {
-    let radians = |value| f64::AngularVelocity::new::<angular_velocity::radian_per_second>(value);
-
-    let ti = message.unwrap().parse::<TargetInfoMessage>().unwrap();
-    let r = ti.position.0.to_vec();
-    let r_len2 = r.magnitude2();
-    let r_len = r_len2.sqrt();
-    let dist = f64::Length::new::<length::meter>(r_len);
-    let speed = f64::Velocity::new::<velocity::meter_per_second>(ti.velocity.0.magnitude());
-    let (azimuth, altitude) = data::to_spherical(ti.position.0);
-    let v_radial = r * ti.velocity.0.dot(r) / r_len2;
-    let v_tangential = ti.velocity.0 - v_radial;
-    let ang_speed = radians(v_tangential.magnitude() / r_len);
-    const ZENITH: Vector3<f64> = Vector3{ x: 0.0, y: 0.0, z: 1.0 };
-    let pos_az = r.cross(ZENITH);
-    let to_zenith = pos_az.cross(r);
-    let v_up_down = to_zenith * v_tangential.dot(to_zenith) / to_zenith.magnitude2();
-    let v_left_right = v_tangential - v_up_down;
-    let ang_speed_az_sign = -r.cross(v_tangential).z.signum();
-    let ang_speed_az = ang_speed_az_sign * radians(v_left_right.magnitude() / (r.x.powi(2) + r.y.powi(2)).sqrt());
-    let ang_speed_el = v_up_down.z.signum() * radians(v_up_down.magnitude() / r_len);
-
-    *state.target.borrow_mut() = Some(data::Target{
-        dist,
-        azimuth,
-        alt_above_gnd: ti.altitude,
-        altitude,
-        az_spd: ang_speed_az,
-        alt_spd: ang_speed_el,
-        speed: f64::Velocity::new::<velocity::meter_per_second>(ti.velocity.0.magnitude()),
-        v_tangential
-    });
+    let received = std::time::Instant::now();
+    let message = match message {
+        Ok(message) => message,
+        Err(e) => {
+            on_data_source_lost(state, e);
+            return Poll::Pending;
+        }
+    };
+    let msg = match state.message_parser.parse(&message) {
+        Ok(msg) => msg,
+        Err(e) => {
+            log::warn!("skipping malformed data source message ({}): {}", e, message.trim_end());
+            return Poll::Pending;
+        }
+    };
+    // with a timestamped message, predict the target's current position to compensate for latency
+    let now = clock::utc_now();
+    let latency = msg.latency(now);
+    if let Some(latency) = latency { state.latency.borrow_mut().network.add(latency); }
+    let mut target = data::Target::from_local(msg.position_at(now), msg.velocity, msg.altitude);
+    target.id = msg.id;
+    let prev_id = state.target.borrow().as_ref().and_then(|t| t.id.clone());
+    if target.id.is_some() && target.id != prev_id {
+        log::info!("target ID: {}", target.id.as_deref().unwrap());
+    }
+    let id_text = match (&target.id, latency) {
+        (Some(id), Some(latency)) => format!("ID {} ({:.2} s)", id, latency.as_secs_f64()),
+        (Some(id), None) => format!("ID {}", id),
+        (None, Some(latency)) => format!("({:.2} s)", latency.as_secs_f64()),
+        (None, None) => String::new()
+    };
+    tui_s!(state).text_content.target_id.set_content(id_text);
+    if let Some(atmosphere) = &state.refraction { atmosphere.apply(&mut target); }
+    *state.target.borrow_mut() = Some(target);
+    on_target_updated(state);
+    state.latency.borrow_mut().processing.add(received.elapsed());
+
+    Poll::Pending
+}
+
+fn on_data_source_lost(state: &mut ProgramState, error: std::io::Error) {
+    let address = state.config.borrow().data_source_addr().unwrap_or_default();
+    log::error!("lost connection to data source {}: {}", address, error);
+    state.data_receiver.connection().disconnect();
+    tui_s!(state).text_content.target_id.set_content("");
+
+    if state.config.borrow().data_source_auto_reconnect() && !address.is_empty() {
+        state.data_source_reconnection = Some(data_receiver::Reconnection::new(&address));
+        tui_s!(state).text_content.target_source_status.set_content("reconnecting...");
+    } else {
+        tui_s!(state).text_content.target_source.set_content("(connection lost)");
+    }
+    state.refresh_tui();
+}
+
+fn on_data_source_reconnect(state: &mut ProgramState) {
+    let reconnection = match state.data_source_reconnection.as_mut() {
+        Some(r) => r,
+        None => return
+    };
+
+    if state.data_receiver.is_connected() || state.target_source.borrow().is_some() {
+        // user has chosen another target source in the meantime
+        state.data_source_reconnection = None;
+        tui_s!(state).text_content.target_source_status.set_content("");
+        state.refresh_tui();
+        return;
+    }
+
+    if !reconnection.is_due() || state.data_receiver.connecting().is_some() { return; }
+
+    // the result is handled by `on_data_source_connecting`
+    state.data_receiver.connection().start_connecting(reconnection.address());
+}
+
+/// Handles the result of a reconnection attempt started by `on_data_source_reconnect`.
+fn on_data_source_reconnect_finished(state: &mut ProgramState, address: &str, result: Result<(), Box<dyn Error>>) {
+    let reconnection = state.data_source_reconnection.as_mut().unwrap();
+    match result {
+        Ok(()) => {
+            log::info!("reconnected to data source {}", address);
+            state.data_source_reconnection = None;
+            tui_s!(state).text_content.target_source_status.set_content("");
+        },
+
+        Err(e) => {
+            reconnection.on_failed();
+            let attempt = reconnection.attempt();
+            log::warn!("failed to reconnect to data source {} (attempt {}): {}", address, attempt, e);
+            tui_s!(state).text_content.target_source_status.set_content(
+                format!("reconnecting... (attempt {})", attempt + 1)
+            );
+        }
+    }
+}
+
+/// Checks the progress of a connection attempt (started from the data source dialog, on startup
+/// or when reconnecting).
+fn on_data_source_connecting(state: &mut ProgramState) {
+    let (address, elapsed) = match state.data_receiver.connecting() {
+        Some(connecting) => connecting,
+        None => return
+    };
+
+    // a connection started on startup (see `connect_on_startup`) or when reconnecting has no progress dialog;
+    // it is indicated in the "Target" panel instead
+    let in_dialog = tui::data_source_dialog::is_showing_progress(&mut state.cursive_stepper.curs);
+    let reconnecting = !in_dialog && state.data_source_reconnection.is_some();
+
+    match state.data_receiver.finish_connecting() {
+        None => if in_dialog {
+            tui::data_source_dialog::on_connecting(&mut state.cursive_stepper.curs, &address, elapsed);
+        } else {
+            tui_s!(state).text_content.target_source_status.set_content(
+                format!("connecting to {}... ({} s)", address, elapsed.as_secs())
+            );
+        },
+
+        Some((address, result)) => {
+            match &result {
+                Ok(()) => on_data_source_connected(&address, &state.target_source, &state.config),
+                Err(e) => if !reconnecting { log::error!("error connecting to data source \"{}\": {}", address, e) }
+            }
+            if reconnecting {
+                on_data_source_reconnect_finished(state, &address, result);
+            } else if in_dialog {
+                tui::data_source_dialog::on_connection_finished(
+                    &mut state.cursive_stepper.curs, &state.tui, &address, result
+                );
+            } else {
+                tui_s!(state).text_content.target_source_status.set_content("");
+                tui_s!(state).text_content.target_source.set_content(
+                    if result.is_ok() { address } else { "(connection failed)".into() }
+                );
+            }
+        }
+    }
+    state.refresh_tui();
+}
+
+fn on_trigger_camera(state: &mut ProgramState) {
+    match state.camera_trigger.as_mut() {
+        Some(trigger) => match trigger.trigger() {
+            Ok(()) => log::info!("camera triggered"),
+            Err(e) => log::error!("failed to trigger camera via {}: {}", trigger.description(), e)
+        },
+
+        None => log::warn!("camera trigger output not configured")
+    }
+}
+
+fn on_camera_trigger_timer(state: &mut ProgramState) {
+    if let Some(trigger) = state.camera_trigger.as_mut() {
+        if let Err(e) = trigger.poll() { log::error!("failed to end camera trigger pulse: {}", e); }
+    }
+}
+
+/// Changes the tracking rate trim of `axis` by one step (`positive`: increases the speed).
+pub fn on_trim(tracking: &TrackingController, config: &Configuration, axis: Axis, positive: bool) {
+    let step = config.tracking_trim_step();
+    tracking.change_trim(axis, if positive { step } else { -step });
+}
+
+fn update_tracking_speeds(state: &ProgramState) {
+    let dry_run = state.tracking.controller().is_dry_run();
+    let speeds = match state.tracking.commanded_speeds() {
+        Some((axis1, axis2)) => format!(
+            "{}, {}", state.angle_display.signed_speed(axis1, 4), state.angle_display.signed_speed(axis2, 4)
+        ),
+        None => String::new()
+    };
+    let speeds = if state.tracking.controller().is_paused() { format!("{} (paused)", speeds) } else { speeds };
+    let text = if dry_run { format!("{} (dry run)", speeds) } else { speeds }.trim_start().to_string();
+    tui_s!(state).text_content.tracking_speeds.set_content(text);
+
+    let trim = state.tracking.controller().trim();
+    tui_s!(state).text_content.tracking_trim.set_content(
+        if trim == (data::deg_per_s(0.0), data::deg_per_s(0.0)) {
+            "none".to_string()
+        } else {
+            format!("{}, {}", state.angle_display.signed_speed(trim.0, 4), state.angle_display.signed_speed(trim.1, 4))
+        }
+    );
+}
+
+fn update_target_staleness(state: &ProgramState) {
+    let age = match state.target.borrow().as_ref() {
+        Some(target) => target.age(),
+        None => return
+    };
+    let policy = state.tracking.target_loss_policy();
+    let text = if age <= policy.stale_after {
+        String::new()
+    } else if state.tracking.is_active() && policy.action == tracking::TargetLossAction::Coast {
+        format!("STALE {:.0} s (coasting)", age.as_secs_f64())
+    } else if state.tracking.is_active() && policy.action == tracking::TargetLossAction::Search {
+        format!("STALE {:.0} s (searching)", age.as_secs_f64())
+    } else {
+        format!("STALE {:.0} s", age.as_secs_f64())
+    };
+    tui_s!(state).text_content.target_stale.set_content(text);
+}
 
+fn on_target_source_timer(state: &mut ProgramState) {
+    let observer = match *state.observer.borrow() {
+        Some(observer) => observer,
+        None => return
+    };
+
+    let (result, refracted) = match state.target_source.borrow_mut().as_mut() {
+        Some(source) => (source.target(clock::utc_now(), &observer), source.refracted()),
+        None => return
+    };
+
+    match result {
+        Ok(mut target) => {
+            if let Some(atmosphere) = state.refraction.as_ref().filter(|_| refracted) { atmosphere.apply(&mut target); }
+            *state.target.borrow_mut() = Some(target);
+            on_target_updated(state);
+        },
+
+        Err(e) => {
+            log::error!("error calculating target data: {}", e);
+            *state.target_source.borrow_mut() = None;
+            tui_s!(state).text_content.target_source.set_content("(none)");
+            state.refresh_tui();
+        }
+    }
+}
+
+fn on_target_updated(state: &mut ProgramState) {
     {
+        let target = state.target.borrow();
+        let target = target.as_ref().unwrap();
         let tui = &state.tui();
         let tui = tui.as_ref().unwrap();
         let texts = &tui.text_content;
 
-        texts.target_dist.set_content(format!("{:.1} km", dist.get::<length::kilometer>(),));
+        texts.target_dist.set_content(format!("{:.1} km", target.dist.get::<length::kilometer>(),));
+        let display = &state.angle_display;
         texts.target_spd.set_content(format!(
-            "{:.0} km/h  {:.02}°/s",
-            speed.get::<velocity::kilometer_per_hour>(),
-            ang_speed.get::<angular_velocity::degree_per_second>()
+            "{:.0} km/h  {}",
+            target.speed.get::<velocity::kilometer_per_hour>(),
+            display.speed(target.ang_speed(), 2)
         ));
         texts.target_az.set_content(
-            format!("{:.1}°  {:.02}°/s", as_deg(azimuth), as_deg_per_s(ang_speed_az))
+            format!("{}  {}", display.azimuth(target.azimuth, 1), display.speed(target.az_spd, 2))
         );
         texts.target_alt.set_content(
-            format!("{:.1}°  {:.02}°/s", as_deg(altitude), as_deg_per_s(ang_speed_el))
+            format!("{}  {}", display.altitude(target.altitude, 1), display.speed(target.alt_spd, 2))
         );
+        if let Some(observer) = state.observer.borrow().as_ref() {
+            let (ra, dec) = data::az_alt_to_ra_dec(target.azimuth, target.altitude, observer, chrono::Utc::now());
+            texts.target_ra.set_content(display.right_ascension(ra));
+            texts.target_dec.set_content(display.altitude(dec, 2));
+        }
     }
 
+    check_target_alarms(state);
+
+    if state.pending_tracking_start && state.mount.borrow().is_some() {
+        state.pending_tracking_start = false;
+        start_tracking(
+            &mut state.cursive_stepper.curs,
+            &state.tracking.controller(),
+            &state.target,
+            &state.observer,
+            &state.sun_avoidance
+        );
+    }
+
+    apply_saved_adjustment(state);
+
     state.refresh_tui();
+}
 
-    Poll::Pending
+fn check_target_alarms(state: &mut ProgramState) {
+    let raised = match state.target.borrow().as_ref() {
+        Some(target) => state.target_alarms.update(target),
+        None => return
+    };
+    for alarm in &raised { log::warn!("{}", alarm); }
+    if !raised.is_empty() && state.config.borrow().target_alarm_sound() { target_alarm::beep(); }
+    tui_s!(state).text_content.target_alarm.set_content(state.target_alarms.indication());
 }
 
 pub fn on_max_travel_exceeded(
@@ -366,29 +1680,50 @@ pub fn on_max_travel_exceeded(
 
 pub fn on_tracking_state_changed(running: tracking::Running, tui: Weak<RefCell<Option<TuiData>>>) {
     upgrade!(tui);
-    tui.borrow().as_ref().unwrap().text_content.tracking_state.set_content(
-        if running.0 { "enabled" } else { "disabled"}
-    );
+    let tui = tui.borrow();
+    let texts = &tui.as_ref().unwrap().text_content;
+    texts.tracking_state.set_content(if running.0 { "enabled" } else { "disabled"});
+    // during tracking PageUp/PageDown change the adjustment speed instead of the slew speed
+    texts.slew_speed_marker.set_content(if running.0 { "" } else { tui::SPEED_KEYS_MARKER });
+    texts.adjustment_speed_marker.set_content(if running.0 { tui::SPEED_KEYS_MARKER } else { "" });
 }
 
 pub fn change_slew_speed(
     factor: f64,
     slew_speed: Weak<RefCell<f64::AngularVelocity>>,
     tui: Weak<RefCell<Option<TuiData>>>,
+    config: Weak<RefCell<Configuration>>,
+    mount: Weak<RefCell<Option<MountWrapper>>>,
     tracking: &TrackingController,
     refresh_req: Weak<RefCell<tui::RefreshRequest>>
 ) {
     if tracking.is_active() {
         tracking.change_adjustment_slew_speed(factor);
-        // TODO: separately display adjustment speed in the "Status" view
-    } else {
-        upgrade!(slew_speed, tui);
-        let prev = *slew_speed.borrow();
-        *slew_speed.borrow_mut() = (prev * factor).min(data::deg_per_s(5.0)).max(data::deg_per_s(0.01));
-        tui.borrow().as_ref().unwrap().text_content.slew_speed.set_content(
-            format!("{:.02}°/s", data::as_deg_per_s(*slew_speed.borrow()))
+        upgrade!(tui, config);
+        tui.borrow().as_ref().unwrap().text_content.adjustment_speed.set_content(
+            config.borrow().angle_display().speed(tracking.adjustment_slew_speed(), 3)
         );
+    } else {
+        upgrade!(slew_speed, tui, config, mount);
+        let new_value = *slew_speed.borrow() * factor;
+        set_slew_speed(new_value, &slew_speed, &tui, &config, &mount);
     }
 
     refresh_req.upgrade().unwrap().borrow_mut().refresh();
 }
+
+/// Sets slew speed (limited to the range allowed for the current mount) and stores it in configuration.
+pub fn set_slew_speed(
+    value: f64::AngularVelocity,
+    slew_speed: &Rc<RefCell<f64::AngularVelocity>>,
+    tui: &Rc<RefCell<Option<TuiData>>>,
+    config: &Rc<RefCell<Configuration>>,
+    mount: &Rc<RefCell<Option<MountWrapper>>>
+) {
+    let mount_max = mount.borrow().as_ref().and_then(|m| m.max_slew_speed());
+    let (min, max) = config.borrow().slew_speed_limits(mount_max);
+    let value = value.min(max).max(min);
+    *slew_speed.borrow_mut() = value;
+    config.borrow_mut().set_slew_speed(value);
+    tui.borrow().as_ref().unwrap().text_content.slew_speed.set_content(config.borrow().angle_display().speed(value, 2));
+}
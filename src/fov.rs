@@ -0,0 +1,106 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Camera field of view indicator: whether the target is inside the FOV and for how long it will stay there.
+
+use crate::data::{as_deg, as_deg_per_s};
+use pointing_utils::uom;
+use std::time::Duration;
+use uom::si::{angle, f64};
+
+type AngSpeed = f64::AngularVelocity;
+
+/// Field of view of the optical train; its sides are parallel and perpendicular to the horizon
+/// (as for a camera on an alt-az mount).
+#[derive(Copy, Clone, Debug)]
+pub struct Fov {
+    pub width: f64::Angle,
+    pub height: f64::Angle
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FovStatus {
+    /// Target inside the FOV; contains time until it drifts out at current rates (`None` if not drifting).
+    Inside(Option<Duration>),
+    Outside
+}
+
+impl Fov {
+    /// Returns the target's status w.r.t. the FOV centered on the mount's position.
+    ///
+    /// # Parameters
+    ///
+    /// * `error` - Position error (target minus mount) in azimuth and altitude.
+    /// * `rate` - Rate of change of `error`.
+    /// * `altitude` - Altitude of the mount.
+    ///
+    pub fn status(
+        &self,
+        error: (f64::Angle, f64::Angle),
+        rate: (AngSpeed, AngSpeed),
+        altitude: f64::Angle
+    ) -> FovStatus {
+        // azimuth differences shrink on the sky towards the zenith
+        let cos_alt = altitude.get::<angle::radian>().cos().abs();
+        let offsets = [
+            (as_deg(error.0) * cos_alt, as_deg_per_s(rate.0) * cos_alt),
+            (as_deg(error.1), as_deg_per_s(rate.1))
+        ];
+        let half_sizes = [as_deg(self.width) / 2.0, as_deg(self.height) / 2.0];
+
+        let mut time_left: Option<f64> = None;
+        for ((offset, rate), half_size) in offsets.into_iter().zip(half_sizes) {
+            if offset.abs() > half_size { return FovStatus::Outside; }
+            if rate == 0.0 { continue; }
+            let dist_to_edge = if rate > 0.0 { half_size - offset } else { half_size + offset };
+            let t = dist_to_edge / rate.abs();
+            time_left = Some(time_left.map_or(t, |t0| t0.min(t)));
+        }
+
+        FovStatus::Inside(time_left.map(Duration::from_secs_f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{deg, deg_per_s};
+
+    fn time_left(status: FovStatus) -> f64 {
+        match status {
+            FovStatus::Inside(Some(t)) => t.as_secs_f64(),
+            _ => panic!("unexpected status: {:?}", status)
+        }
+    }
+
+    #[test]
+    fn drift_out_time() {
+        let fov = Fov{ width: deg(2.0), height: deg(1.0) };
+        let no_rate = (deg_per_s(0.0), deg_per_s(0.0));
+
+        let t = time_left(fov.status((deg(0.0), deg(0.25)), (deg_per_s(0.0), deg_per_s(0.05)), deg(0.0)));
+        assert!((t - 5.0).abs() < 1.0e-6);
+        let t = time_left(fov.status((deg(0.0), deg(0.25)), (deg_per_s(0.0), deg_per_s(-0.05)), deg(0.0)));
+        assert!((t - 15.0).abs() < 1.0e-6);
+
+        assert_eq!(FovStatus::Inside(None), fov.status((deg(0.5), deg(0.0)), no_rate, deg(0.0)));
+        assert_eq!(FovStatus::Outside, fov.status((deg(0.0), deg(0.6)), no_rate, deg(0.0)));
+        // 1.5° in azimuth at 60° altitude is 0.75° on the sky
+        assert_eq!(FovStatus::Inside(None), fov.status((deg(1.5), deg(0.0)), no_rate, deg(60.0)));
+    }
+}
@@ -0,0 +1,165 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{data, data::deg};
+use pointing_utils::uom;
+use std::{error::Error, io::{Read, Write}};
+use uom::si::{f64, length};
+
+const GPSD_PREFIX: &str = "gpsd:";
+const SERIAL_BAUD_RATE: u32 = 9600;
+const MAX_LINE_LEN: usize = 1024;
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Receives observer position from a GPS receiver producing NMEA sentences.
+pub struct GpsReceiver {
+    source: Box<dyn Read>,
+    description: String,
+    buf: Vec<u8>,
+    /// Approximate offset (in seconds) of UTC reported by the receiver relative to system time.
+    time_offset: Option<f64>
+}
+
+impl GpsReceiver {
+    /// Connects to a GPS receiver.
+    ///
+    /// # Parameters
+    ///
+    /// * `device` - Serial device (e.g., "/dev/ttyACM0" or "COM4") or gpsd address prefixed with "gpsd:"
+    ///     (e.g., "gpsd:localhost:2947").
+    ///
+    pub fn new(device: &str) -> Result<GpsReceiver, Box<dyn Error>> {
+        let source: Box<dyn Read> = if let Some(address) = device.strip_prefix(GPSD_PREFIX) {
+            let mut stream = std::net::TcpStream::connect(address)?;
+            stream.write_all(b"?WATCH={\"enable\":true,\"nmea\":true}\n")?;
+            stream.set_nonblocking(true)?;
+            Box::new(stream)
+        } else {
+            Box::new(serialport::new(device, SERIAL_BAUD_RATE)
+                .timeout(std::time::Duration::from_millis(1))
+                .open()?)
+        };
+
+        Ok(GpsReceiver{ source, description: device.to_string(), buf: vec![], time_offset: None })
+    }
+
+    pub fn description(&self) -> &str { &self.description }
+
+    /// Returns the approximate offset (in seconds) of GPS time relative to system time; does not account for
+    /// the receiver's reporting latency.
+    pub fn time_offset(&self) -> Option<f64> { self.time_offset }
+
+    /// Reads all available data (without blocking) and returns the most recent position fix, if any.
+    pub fn poll(&mut self) -> Result<Option<data::ObserverLocation>, Box<dyn Error>> {
+        let mut chunk = [0u8; 512];
+        loop {
+            match self.source.read(&mut chunk) {
+                Ok(0) => return Err("GPS connection closed".into()),
+                Ok(n) => self.buf.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock || e.kind() == std::io::ErrorKind::TimedOut => break,
+                Err(e) => return Err(e.into())
+            }
+        }
+
+        let mut result = None;
+        while let Some(eol) = self.buf.iter().position(|c| *c == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=eol).collect();
+            let sentence = String::from_utf8_lossy(&line);
+            if let Some(position) = parse_gga(sentence.trim()) {
+                result = Some(position);
+                if let Some(gps_time) = parse_gga_time(sentence.trim()) {
+                    self.time_offset = Some(time_of_day_offset(gps_time, system_time_of_day()));
+                }
+            }
+        }
+        if self.buf.len() > MAX_LINE_LEN { self.buf.clear(); }
+
+        Ok(result)
+    }
+}
+
+/// Parses an NMEA GGA sentence; returns `None` if it is not a valid GGA sentence or there is no position fix.
+pub fn parse_gga(sentence: &str) -> Option<data::ObserverLocation> {
+    let (contents, checksum) = sentence.strip_prefix('$')?.split_once('*')?;
+    let checksum = u8::from_str_radix(checksum.get(..2)?, 16).ok()?;
+    if contents.bytes().fold(0u8, |acc, c| acc ^ c) != checksum { return None; }
+
+    let fields: Vec<&str> = contents.split(',').collect();
+    if fields.len() < 10 || !fields[0].ends_with("GGA") { return None; }
+    if fields[6].parse::<u32>().ok()? == 0 { return None; }
+
+    let parse_coord = |value: &str, num_deg_digits: usize, hemisphere: &str, negative: &str| -> Option<f64> {
+        let degrees = value.get(..num_deg_digits)?.parse::<f64>().ok()?;
+        let minutes = value.get(num_deg_digits..)?.parse::<f64>().ok()?;
+        let result = degrees + minutes / 60.0;
+        Some(if hemisphere == negative { -result } else { result })
+    };
+
+    Some(data::ObserverLocation{
+        lat: deg(parse_coord(fields[2], 2, fields[3], "S")?),
+        lon: deg(parse_coord(fields[4], 3, fields[5], "W")?),
+        elevation: f64::Length::new::<length::meter>(fields[9].parse::<f64>().ok()?)
+    })
+}
+
+/// Returns UTC time of day (in seconds) from a GGA sentence (assumed to be already validated).
+fn parse_gga_time(sentence: &str) -> Option<f64> {
+    let time = sentence.split(',').nth(1)?;
+    let hours = time.get(..2)?.parse::<f64>().ok()?;
+    let minutes = time.get(2..4)?.parse::<f64>().ok()?;
+    let seconds = time.get(4..)?.parse::<f64>().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+fn system_time_of_day() -> f64 {
+    let since_epoch = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    since_epoch.as_secs_f64() % SECONDS_PER_DAY
+}
+
+/// Returns `reference - system` wrapped to [-12 h, 12 h).
+fn time_of_day_offset(reference: f64, system: f64) -> f64 {
+    (reference - system + SECONDS_PER_DAY / 2.0).rem_euclid(SECONDS_PER_DAY) - SECONDS_PER_DAY / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::as_deg;
+
+    #[test]
+    fn gga_parsing() {
+        let pos = parse_gga("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47").unwrap();
+        assert!((as_deg(pos.lat) - 48.1173).abs() < 1.0e-4);
+        assert!((as_deg(pos.lon) - 11.516_667).abs() < 1.0e-4);
+        assert!((pos.elevation.get::<length::meter>() - 545.4).abs() < 1.0e-9);
+
+        // invalid checksum
+        assert!(parse_gga("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*48").is_none());
+        // no fix
+        assert!(parse_gga("$GPGGA,123519,,,,,0,00,,,M,,M,,*6B").is_none());
+    }
+
+    #[test]
+    fn gga_time() {
+        let time = parse_gga_time("$GPGGA,123519.50,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47").unwrap();
+        assert!((time - (12.0 * 3600.0 + 35.0 * 60.0 + 19.5)).abs() < 1.0e-9);
+
+        // across midnight
+        assert!((time_of_day_offset(0.5, SECONDS_PER_DAY - 0.5) - 1.0).abs() < 1.0e-9);
+    }
+}
@@ -0,0 +1,117 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Guiding input: accepts guide pulses from an autoguiding program over TCP.
+//!
+//! Protocol: one command per line; `PULSE <direction> <duration_ms>`, where direction is one of `UP`, `DOWN`,
+//! `LEFT`, `RIGHT` (or `N`, `S`, `W`, `E`: towards increasing/decreasing altitude and azimuth). The response is
+//! `OK` or `ERROR <message>`.
+
+use crate::{data, line_server::{ClientId, LineServer}};
+use pointing_utils::uom;
+use std::error::Error;
+use uom::si::f64;
+
+/// Angular correction (measured on the sky) requested by a guide pulse.
+#[derive(Copy, Clone, Debug)]
+pub struct GuideCorrection {
+    /// Positive: towards increasing azimuth.
+    pub horizontal: f64::Angle,
+    /// Positive: towards increasing altitude.
+    pub vertical: f64::Angle
+}
+
+pub struct GuideServer {
+    server: LineServer,
+    /// Correction speed corresponding to guide pulses.
+    rate: f64::AngularVelocity
+}
+
+impl GuideServer {
+    #[must_use]
+    pub fn new(address: &str, rate: f64::AngularVelocity) -> Result<GuideServer, Box<dyn Error>> {
+        Ok(GuideServer{ server: LineServer::new(address)?, rate })
+    }
+
+    pub fn local_addr(&self) -> String { self.server.local_addr() }
+
+    /// Returns received guide corrections; invalid commands are answered immediately.
+    pub fn poll(&mut self) -> Vec<(ClientId, GuideCorrection)> {
+        let mut result = vec![];
+        for (client, line) in self.server.poll() {
+            match parse_pulse(&line, self.rate) {
+                Ok(correction) => result.push((client, correction)),
+                Err(e) => self.server.send(client, &format!("ERROR {}", e))
+            }
+        }
+
+        result
+    }
+
+    /// Sends the result of applying a correction back to the client.
+    pub fn reply(&mut self, client: ClientId, result: Result<(), Box<dyn Error>>) {
+        match result {
+            Ok(()) => self.server.send(client, "OK"),
+            Err(e) => self.server.send(client, &format!("ERROR {}", e))
+        }
+    }
+}
+
+fn parse_pulse(line: &str, rate: f64::AngularVelocity) -> Result<GuideCorrection, Box<dyn Error>> {
+    let mut tokens = line.split_whitespace();
+    if !tokens.next().is_some_and(|cmd| cmd.eq_ignore_ascii_case("PULSE")) {
+        return Err(format!("unknown command: {}", line).into());
+    }
+
+    let direction = tokens.next().ok_or("missing direction")?.to_uppercase();
+    let duration_ms = tokens.next().ok_or("missing duration")?.parse::<f64>()?;
+    if !(0.0..=60_000.0).contains(&duration_ms) { return Err("invalid duration".into()); }
+
+    let amount = data::deg(data::as_deg_per_s(rate) * duration_ms / 1000.0);
+    let zero = data::deg(0.0);
+
+    match direction.as_str() {
+        "UP" | "N" => Ok(GuideCorrection{ horizontal: zero, vertical: amount }),
+        "DOWN" | "S" => Ok(GuideCorrection{ horizontal: zero, vertical: -amount }),
+        "RIGHT" | "E" => Ok(GuideCorrection{ horizontal: amount, vertical: zero }),
+        "LEFT" | "W" => Ok(GuideCorrection{ horizontal: -amount, vertical: zero }),
+        _ => Err(format!("invalid direction: {}", direction).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::angle;
+
+    #[test]
+    fn pulse_parsing() {
+        let rate = data::deg_per_s(0.5);
+
+        let c = parse_pulse("PULSE up 200", rate).unwrap();
+        assert!((c.vertical.get::<angle::degree>() - 0.1).abs() < 1.0e-9);
+        assert_eq!(0.0, c.horizontal.get::<angle::degree>());
+
+        let c = parse_pulse("pulse W 1000", rate).unwrap();
+        assert!((c.horizontal.get::<angle::degree>() + 0.5).abs() < 1.0e-9);
+
+        assert!(parse_pulse("PULSE X 100", rate).is_err());
+        assert!(parse_pulse("PULSE N", rate).is_err());
+        assert!(parse_pulse("STOP", rate).is_err());
+    }
+}
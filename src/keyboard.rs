@@ -0,0 +1,102 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Keyboard slewing.
+//!
+//! Terminals do not report key releases, so a key is considered released once its auto-repeated presses stop
+//! arriving.
+
+use crate::controller::TargetAction;
+use std::{collections::HashMap, time::{Duration, Instant}};
+
+/// Maximum delay before the first auto-repeated key press.
+const FIRST_REPEAT_TIMEOUT: Duration = Duration::from_millis(600);
+
+/// Maximum interval between subsequent auto-repeated key presses.
+const REPEAT_TIMEOUT: Duration = Duration::from_millis(150);
+
+struct KeyState {
+    last_press: Instant,
+    repeated: bool,
+    /// Whether the press has already been reported by `poll`.
+    reported: bool
+}
+
+#[derive(Default)]
+pub struct KeyboardSlewing {
+    keys: HashMap<TargetAction, KeyState>
+}
+
+impl KeyboardSlewing {
+    /// Registers a key press assigned to `action` (one of `TargetAction::MountAxis*Pos/Neg`).
+    pub fn on_key(&mut self, action: TargetAction) {
+        self.on_key_at(action, Instant::now());
+    }
+
+    fn on_key_at(&mut self, action: TargetAction, now: Instant) {
+        match self.keys.get_mut(&action) {
+            Some(key) => { key.last_press = now; key.repeated = true; },
+            None => { self.keys.insert(action, KeyState{ last_press: now, repeated: false, reported: false }); }
+        }
+    }
+
+    /// Returns emulated press (`true`) and release (`false`) events since the previous call.
+    pub fn poll(&mut self) -> Vec<(TargetAction, bool)> {
+        self.poll_at(Instant::now())
+    }
+
+    fn poll_at(&mut self, now: Instant) -> Vec<(TargetAction, bool)> {
+        let mut result = vec![];
+
+        for (action, key) in self.keys.iter_mut() {
+            if !key.reported {
+                result.push((*action, true));
+                key.reported = true;
+            }
+        }
+
+        self.keys.retain(|action, key| {
+            let timeout = if key.repeated { REPEAT_TIMEOUT } else { FIRST_REPEAT_TIMEOUT };
+            let released = now.duration_since(key.last_press) > timeout;
+            if released { result.push((*action, false)); }
+            !released
+        });
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_release_emulation() {
+        let t0 = Instant::now();
+        let mut kb = KeyboardSlewing::default();
+
+        kb.on_key_at(TargetAction::MountAxis1Pos, t0);
+        assert_eq!(vec![(TargetAction::MountAxis1Pos, true)], kb.poll_at(t0 + Duration::from_millis(10)));
+        assert!(kb.poll_at(t0 + Duration::from_millis(500)).is_empty());
+
+        kb.on_key_at(TargetAction::MountAxis1Pos, t0 + Duration::from_millis(550));
+        assert!(kb.poll_at(t0 + Duration::from_millis(650)).is_empty());
+        assert_eq!(vec![(TargetAction::MountAxis1Pos, false)], kb.poll_at(t0 + Duration::from_millis(750)));
+        assert!(kb.poll_at(t0 + Duration::from_millis(800)).is_empty());
+    }
+}
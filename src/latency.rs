@@ -0,0 +1,100 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Measurement of latencies between receiving target data and commanding the mount.
+
+use std::{collections::VecDeque, time::Duration};
+
+/// Number of samples averaged by `RollingAverage`.
+const NUM_SAMPLES: usize = 50;
+
+#[derive(Default)]
+pub struct RollingAverage {
+    samples: VecDeque<Duration>
+}
+
+impl RollingAverage {
+    pub fn add(&mut self, sample: Duration) {
+        if self.samples.len() == NUM_SAMPLES { self.samples.pop_front(); }
+        self.samples.push_back(sample);
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        if self.samples.is_empty() { return None; }
+        Some(self.samples.iter().sum::<Duration>() / self.samples.len() as u32)
+    }
+
+    pub fn max(&self) -> Option<Duration> { self.samples.iter().max().copied() }
+}
+
+/// Rolling averages of the stages of end-to-end latency.
+#[derive(Default)]
+pub struct LatencyStats {
+    /// From measurement by the data source to receiving the message (only for timestamped messages).
+    pub network: RollingAverage,
+    /// From receiving a message to updating the target.
+    pub processing: RollingAverage,
+    /// From updating the target to commanding the mount by tracking.
+    pub to_command: RollingAverage,
+    /// Duration of sending speed commands to the mount.
+    pub command: RollingAverage
+}
+
+impl LatencyStats {
+    pub fn summary(&self) -> String {
+        let stages = [
+            ("Data source → TPTool", &self.network),
+            ("Message processing", &self.processing),
+            ("Target update → mount cmd.", &self.to_command),
+            ("Mount command", &self.command)
+        ];
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+
+        let mut result = String::new();
+        let mut total = Duration::ZERO;
+        for (name, stage) in stages {
+            match (stage.average(), stage.max()) {
+                (Some(avg), Some(max)) => {
+                    result += &format!("{:<27} {:8.1} ms (max. {:.1} ms)\n", name, ms(avg), ms(max));
+                    total += avg;
+                },
+                _ => result += &format!("{:<27} (no data)\n", name)
+            }
+        }
+        result += &format!("{:<27} {:8.1} ms", "Total (avg.)", ms(total));
+        if self.network.average().is_none() { result += "\n\n(network latency requires timestamped messages)"; }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_last_samples_are_averaged() {
+        let mut avg = RollingAverage::default();
+        assert_eq!(None, avg.average());
+        for i in 0..NUM_SAMPLES + 10 {
+            avg.add(Duration::from_millis(if i < 10 { 1000 } else { 10 }));
+        }
+        assert_eq!(Some(Duration::from_millis(10)), avg.average());
+        assert_eq!(Some(Duration::from_millis(10)), avg.max());
+    }
+}
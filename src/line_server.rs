@@ -0,0 +1,124 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use std::{error::Error, io::{Read, Write}, net::{TcpListener, TcpStream}};
+
+const MAX_LINE_LEN: usize = 1024;
+
+struct Client {
+    stream: TcpStream,
+    buf: Vec<u8>
+}
+
+/// Non-blocking TCP server exchanging text lines with any number of clients; to be polled periodically.
+pub struct LineServer {
+    listener: TcpListener,
+    clients: Vec<Option<Client>>
+}
+
+/// Index of a client in `LineServer`; stays valid until the client disconnects.
+pub type ClientId = usize;
+
+impl LineServer {
+    #[must_use]
+    pub fn new(address: &str) -> Result<LineServer, Box<dyn Error>> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(LineServer{ listener, clients: vec![] })
+    }
+
+    pub fn local_addr(&self) -> String {
+        self.listener.local_addr().map(|a| a.to_string()).unwrap_or_default()
+    }
+
+    pub fn num_clients(&self) -> usize {
+        self.clients.iter().filter(|c| c.is_some()).count()
+    }
+
+    /// Accepts new connections and returns all complete lines received since the previous call.
+    pub fn poll(&mut self) -> Vec<(ClientId, String)> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    if let Err(e) = stream.set_nonblocking(true) {
+                        log::error!("failed to set up connection from {}: {}", address, e);
+                        continue;
+                    }
+                    log::info!("client connected from {}", address);
+                    let client = Some(Client{ stream, buf: vec![] });
+                    match self.clients.iter().position(|c| c.is_none()) {
+                        Some(idx) => self.clients[idx] = client,
+                        None => self.clients.push(client)
+                    }
+                },
+
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+
+                Err(e) => { log::error!("error accepting connection: {}", e); break; }
+            }
+        }
+
+        let mut result = vec![];
+        for (id, slot) in self.clients.iter_mut().enumerate() {
+            let client = match slot { Some(c) => c, None => continue };
+
+            let mut chunk = [0u8; 512];
+            let mut disconnected = false;
+            loop {
+                match client.stream.read(&mut chunk) {
+                    Ok(0) => { disconnected = true; break; },
+                    Ok(n) => client.buf.extend_from_slice(&chunk[..n]),
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                    Err(e) => { log::warn!("client connection error: {}", e); disconnected = true; break; }
+                }
+            }
+
+            while let Some(eol) = client.buf.iter().position(|c| *c == b'\n') {
+                let line: Vec<u8> = client.buf.drain(..=eol).collect();
+                let line = String::from_utf8_lossy(&line).trim().to_string();
+                if !line.is_empty() { result.push((id, line)); }
+            }
+            if client.buf.len() > MAX_LINE_LEN { client.buf.clear(); }
+
+            if disconnected {
+                log::info!("client disconnected");
+                *slot = None;
+            }
+        }
+
+        result
+    }
+
+    /// Sends a line to the specified client.
+    pub fn send(&mut self, id: ClientId, line: &str) {
+        if let Some(Some(client)) = self.clients.get_mut(id) {
+            if let Err(e) = client.stream.write_all(format!("{}\n", line).as_bytes()) {
+                log::warn!("failed to send to client: {}", e);
+                self.clients[id] = None;
+            }
+        }
+    }
+
+    /// Sends a line to all clients.
+    pub fn broadcast(&mut self, line: &str) {
+        for id in 0..self.clients.len() {
+            self.send(id, line);
+        }
+    }
+}
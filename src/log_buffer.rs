@@ -0,0 +1,72 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! In-memory logger keeping the most recent warnings and errors for display in the TUI.
+
+use std::{collections::VecDeque, sync::Mutex};
+
+const MAX_ENTRIES: usize = 500;
+
+static ENTRIES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+pub struct MemoryLogger {
+    level: log::LevelFilter,
+    config: simplelog::Config
+}
+
+impl MemoryLogger {
+    pub fn new(level: log::LevelFilter) -> Box<MemoryLogger> {
+        Box::new(MemoryLogger{ level, config: simplelog::Config::default() })
+    }
+}
+
+impl log::Log for MemoryLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level && !metadata.target().starts_with("cursive_core")
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) { return; }
+
+        let entry = format!(
+            "{} [{}] {}",
+            chrono::Local::now().format("%H:%M:%S"),
+            record.level(),
+            record.args()
+        );
+        if let Ok(mut entries) = ENTRIES.lock() {
+            if entries.len() == MAX_ENTRIES { entries.pop_front(); }
+            entries.push_back(entry);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl simplelog::SharedLogger for MemoryLogger {
+    fn level(&self) -> log::LevelFilter { self.level }
+
+    fn config(&self) -> Option<&simplelog::Config> { Some(&self.config) }
+
+    fn as_log(self: Box<Self>) -> Box<dyn log::Log> { Box::new(*self) }
+}
+
+/// Returns the logged entries (oldest first).
+pub fn entries() -> Vec<String> {
+    ENTRIES.lock().map(|entries| entries.iter().cloned().collect()).unwrap_or_default()
+}
@@ -0,0 +1,182 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Magnetic declination from the World Magnetic Model (WMM).
+
+use crate::data::{deg, julian_date, ObserverLocation};
+use pointing_utils::uom;
+use std::error::Error;
+use uom::si::{angle, f64, length};
+
+/// Reference radius of the model (km).
+const REFERENCE_RADIUS_KM: f64 = 6371.2;
+const WGS84_A_KM: f64 = 6378.137;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Spherical harmonic model of the main geomagnetic field, as published by NOAA (e.g., "WMM.COF").
+pub struct MagneticModel {
+    /// Decimal year.
+    epoch: f64,
+    max_degree: usize,
+    /// Gauss coefficients (nT) indexed by [n][m].
+    g: Vec<Vec<f64>>,
+    h: Vec<Vec<f64>>,
+    /// Secular variation (nT/year).
+    dg: Vec<Vec<f64>>,
+    dh: Vec<Vec<f64>>
+}
+
+impl MagneticModel {
+    pub fn load(path: &std::path::Path) -> Result<MagneticModel, Box<dyn Error>> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Returns the angle from true north to magnetic north (positive: east) at `location` and time `t`.
+    pub fn declination(&self, location: &ObserverLocation, t: chrono::DateTime<chrono::Utc>) -> f64::Angle {
+        let dt = 2000.0 + (julian_date(t) - 2_451_545.0) / 365.25 - self.epoch;
+
+        // geodetic to geocentric spherical coordinates
+        let lat = location.lat.get::<angle::radian>();
+        let lon = location.lon.get::<angle::radian>();
+        let h = location.elevation.get::<length::kilometer>();
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let rc = WGS84_A_KM / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+        let p = (rc + h) * lat.cos();
+        let z = (rc * (1.0 - e2) + h) * lat.sin();
+        let r = p.hypot(z);
+        let lat_gc = (z / r).asin();
+
+        let (sin_lat_gc, cos_lat_gc) = lat_gc.sin_cos();
+        let (p_nm, dp_nm) = schmidt_legendre(self.max_degree, sin_lat_gc, cos_lat_gc);
+
+        let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+        for n in 1..=self.max_degree {
+            let ratio = (REFERENCE_RADIUS_KM / r).powi(n as i32 + 2);
+            for m in 0..=n {
+                let g = self.g[n][m] + dt * self.dg[n][m];
+                let h = self.h[n][m] + dt * self.dh[n][m];
+                let (sin_ml, cos_ml) = (m as f64 * lon).sin_cos();
+                // `dp_nm` is the derivative with respect to colatitude
+                x += ratio * (g * cos_ml + h * sin_ml) * dp_nm[n][m];
+                y += ratio * m as f64 * (g * sin_ml - h * cos_ml) * p_nm[n][m];
+                z -= ratio * (n + 1) as f64 * (g * cos_ml + h * sin_ml) * p_nm[n][m];
+            }
+        }
+        y /= cos_lat_gc.max(1.0e-9);
+
+        // rotate the northward component to the geodetic frame
+        let x = x * (lat_gc - lat).cos() - z * (lat_gc - lat).sin();
+
+        deg(y.atan2(x).to_degrees())
+    }
+}
+
+impl std::str::FromStr for MagneticModel {
+    type Err = Box<dyn Error>;
+
+    /// Parses the coefficient file: header line with the epoch, then lines "n m g h dg dh",
+    /// terminated by a line of 9s (or end of file).
+    fn from_str(s: &str) -> Result<MagneticModel, Box<dyn Error>> {
+        let mut lines = s.lines().filter(|line| !line.trim().is_empty());
+        let epoch = lines.next()
+            .and_then(|header| header.split_whitespace().next())
+            .ok_or("missing header")?
+            .parse::<f64>()?;
+
+        let mut entries = vec![];
+        for line in lines {
+            if line.trim_start().starts_with("9999") { break; }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 { return Err(format!("invalid coefficient line: {}", line).into()); }
+            let n = fields[0].parse::<usize>()?;
+            let m = fields[1].parse::<usize>()?;
+            if n == 0 || m > n { return Err(format!("invalid degree/order: {}", line).into()); }
+            let values = [fields[2], fields[3], fields[4], fields[5]].map(|v| v.parse::<f64>());
+            let [g, h, dg, dh] = values;
+            entries.push((n, m, [g?, h?, dg?, dh?]));
+        }
+
+        let max_degree = entries.iter().map(|(n, _, _)| *n).max().ok_or("no coefficients")?;
+        let zeros = || (0..=max_degree).map(|n| vec![0.0; n + 1]).collect::<Vec<_>>();
+        let mut model = MagneticModel{ epoch, max_degree, g: zeros(), h: zeros(), dg: zeros(), dh: zeros() };
+        for (n, m, [g, h, dg, dh]) in entries {
+            model.g[n][m] = g;
+            model.h[n][m] = h;
+            model.dg[n][m] = dg;
+            model.dh[n][m] = dh;
+        }
+
+        Ok(model)
+    }
+}
+
+/// Returns Schmidt semi-normalized associated Legendre functions of cos(colatitude) and their derivatives
+/// with respect to colatitude, indexed by [n][m].
+fn schmidt_legendre(max_degree: usize, cos_theta: f64, sin_theta: f64) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let mut p: Vec<Vec<f64>> = (0..=max_degree).map(|n| vec![0.0; n + 1]).collect();
+    let mut dp = p.clone();
+    p[0][0] = 1.0;
+
+    for n in 1..=max_degree {
+        let nf = n as f64;
+        if n == 1 {
+            p[1][1] = sin_theta;
+            dp[1][1] = cos_theta;
+        } else {
+            let k = (1.0 - 1.0 / (2.0 * nf)).sqrt();
+            p[n][n] = k * sin_theta * p[n - 1][n - 1];
+            dp[n][n] = k * (cos_theta * p[n - 1][n - 1] + sin_theta * dp[n - 1][n - 1]);
+        }
+        for m in 0..n {
+            let mf = m as f64;
+            let (prev2, dprev2) = if n >= 2 && m <= n - 2 { (p[n - 2][m], dp[n - 2][m]) } else { (0.0, 0.0) };
+            let a = 2.0 * nf - 1.0;
+            let b = ((nf - 1.0).powi(2) - mf * mf).max(0.0).sqrt();
+            let c = (nf * nf - mf * mf).sqrt();
+            p[n][m] = (a * cos_theta * p[n - 1][m] - b * prev2) / c;
+            dp[n][m] = (a * (cos_theta * dp[n - 1][m] - sin_theta * p[n - 1][m]) - b * dprev2) / c;
+        }
+    }
+
+    (p, dp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::as_deg;
+
+    fn location(lat: f64, lon: f64) -> ObserverLocation {
+        ObserverLocation{ lat: deg(lat), lon: deg(lon), elevation: f64::Length::new::<length::meter>(0.0) }
+    }
+
+    #[test]
+    fn calculates_declination() {
+        let t = chrono::DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&chrono::Utc);
+
+        // axial dipole: magnetic north coincides with true north
+        let dipole: MagneticModel = "2025.0 TEST\n1 0 -29000.0 0.0 0.0 0.0\n9999999999\n".parse().unwrap();
+        assert!(as_deg(dipole.declination(&location(45.0, 30.0), t)).abs() < 1.0e-9);
+
+        // tilted dipole; at the equator and lon. 0°: X = -g10, Y = -h11
+        let tilted: MagneticModel = "2025.0 TEST\n1 0 -30000.0 0.0 0.0 0.0\n1 1 0.0 5000.0 0.0 0.0\n".parse().unwrap();
+        let expected = (-5000.0_f64).atan2(30000.0).to_degrees();
+        assert!((as_deg(tilted.declination(&location(0.0, 0.0), t)) - expected).abs() < 1.0e-6);
+
+        assert!("2025.0 TEST\n1 2 0.0 0.0 0.0 0.0\n".parse::<MagneticModel>().is_err());
+    }
+}
@@ -16,77 +16,287 @@
 // along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
 //
 
+mod camera_tracking;
+mod camera_trigger;
+mod catalog;
+mod cli;
+mod clock;
 mod config;
 mod controller;
 mod cursive_stepper;
 mod data;
 mod data_receiver;
+mod discovery;
 mod event_handling;
+mod fov;
+mod gps;
+mod guiding;
+mod keyboard;
+mod latency;
+mod line_server;
+mod log_buffer;
+mod magnetic;
 mod mount;
+mod overlay;
+mod push_to;
+mod refraction;
+mod remote_log;
+mod scripting;
+mod search_pattern;
+mod session_log;
+mod shutdown;
+mod state_broadcast;
+mod sun_avoidance;
+mod target_alarm;
+mod target_message;
+mod target_source;
+mod telemetry;
+mod time_sync;
 mod tracking;
 mod tui;
 
+use clap::Parser;
 use event_handling::on_tracking_state_changed;
-use std::{cell::RefCell, future::Future, rc::Rc};
+use std::{cell::{Cell, RefCell}, future::Future, rc::Rc};
 
-const MAIN_TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
 const TARGET_LOG_TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+const TARGET_SOURCE_TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+const KEYBOARD_TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+const EXTERNAL_INPUT_TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+const MOTION_TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+const MOUNT_STATE_TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+/// Time span of position history (sampled at main timer interval).
+const POS_HISTORY_SPAN: std::time::Duration = std::time::Duration::from_secs(5 * 60);
 
 pub const VERSION_STRING: &'static str = include_str!(concat!(env!("OUT_DIR"), "/version"));
 
 fn main() {
-    set_up_logging();
+    let args = cli::Args::parse();
+    let headless = args.headless;
 
-    #[cfg(target_os = "windows")]
-    unsafe { pdcurses::resize_term(20, 105) };
+    set_up_logging(headless);
+    if let Some(rate) = args.clock_rate {
+        clock::set_simulated(rate);
+        log::warn!("using simulated clock running at {}× real time", rate);
+    }
 
-	let curs = cursive::default();
+    // in headless mode the TUI state is still maintained, but nothing is displayed and no keyboard input is read
+	let curs = if headless {
+        cursive::CursiveRunnable::new(|| Ok::<_, std::convert::Infallible>(cursive::backend::Dummy::init()))
+    } else {
+        cursive::default()
+    };
     let data_receiver = data_receiver::DataReceiver::new();
     let mut listener = stick::Listener::default();
     let mount = Rc::new(RefCell::new(None));
-    let mount_spd = Rc::new(RefCell::new(data::MountSpeed::new()));
+    let mount2 = Rc::new(RefCell::new(None));
+    shutdown::register_mounts(&[&mount, &mount2]);
+    let mount_routing = Rc::new(Cell::new(mount::MountRouting::default()));
     let target = Rc::new(RefCell::new(None));
     let tui = Rc::new(RefCell::new(None));
-    let config = Rc::new(RefCell::new(config::Configuration::new()));
-    let ctrl_actions = config.borrow().controller_actions();
+    let config = Rc::new(RefCell::new(match &args.config {
+        Some(path) => config::Configuration::from_file(path.clone()),
+        None => config::Configuration::from_profile(args.profile.as_deref())
+    }));
+    log::info!("using configuration file {}", config.borrow().file_path().to_string_lossy());
+    log::set_max_level(config.borrow().log_level());
+    if let Some(destination) = config.borrow().remote_log() {
+        match remote_log::start(&destination) {
+            Ok(()) => log::info!("mirroring log to {}", destination),
+            Err(e) => log::error!("failed to set up remote log {}: {}", destination, e)
+        }
+    }
+    let ctrl_actions = Rc::new(RefCell::new(config.borrow().controller_actions()));
+    let site = config.borrow().current_site();
+    if let Some(site) = site {
+        log::info!("using site \"{}\"", site.name);
+        config.borrow_mut().use_site(&site);
+    }
+    let observer = Rc::new(RefCell::new(config.borrow().observer_location()));
+    let axes_response = Rc::new(RefCell::new(config.borrow().mount_axes_response()));
+    let target_loss = config.borrow().target_loss_policy();
+    let pause_mode = config.borrow().tracking_pause_mode();
+    let main_timer_interval = config.borrow().main_timer_interval();
+    let tracking_timer_interval = config.borrow().tracking_timer_interval();
+    let handover_time = config.borrow().tracking_handover_time();
+    let message_parser = target_message::parser(config.borrow().data_source_format());
+    let target_alarms = target_alarm::TargetAlarms::new(config.borrow().target_alarm_thresholds());
+    let sun_avoidance = Rc::new(RefCell::new(sun_avoidance::SunAvoidance::new(config.borrow().sun_avoidance_radius())));
+    let pos_history_len = (POS_HISTORY_SPAN.as_secs_f64() / main_timer_interval.as_secs_f64()).round() as usize;
+    let mount_spd = Rc::new(RefCell::new(data::MountSpeed::new(config.borrow().mount_speed_filter())));
+    let (min_slew_speed, max_slew_speed) = config.borrow().slew_speed_limits(None);
+    // not limited from above yet, as the mount may support a higher speed than the default limit
+    let slew_speed = config.borrow().slew_speed().unwrap_or(max_slew_speed).max(min_slew_speed);
+    let guiding = config.borrow().guiding_server_addr().and_then(|address| {
+        match guiding::GuideServer::new(&address, config.borrow().guide_rate()) {
+            Ok(server) => { log::info!("listening for guiding input on {}", server.local_addr()); Some(server) },
+            Err(e) => { log::error!("failed to start guiding input on {}: {}", address, e); None }
+        }
+    });
+    let gps = config.borrow().gps_device().filter(|device| !device.is_empty()).and_then(|device| {
+        match gps::GpsReceiver::new(&device) {
+            Ok(receiver) => { log::info!("connected to GPS {}", device); Some(receiver) },
+            Err(e) => { log::error!("error connecting to GPS \"{}\": {}", device, e); None }
+        }
+    });
+
+    let camera_corrections = config.borrow().camera_server_addr().and_then(|address| {
+        let handoff = config.borrow().video_tracker_handoff();
+        match camera_tracking::CameraCorrections::new(&address, config.borrow().camera_params(), handoff) {
+            Ok(server) => { log::info!("listening for camera corrections on {}", server.local_addr()); Some(server) },
+            Err(e) => { log::error!("failed to start camera corrections input on {}: {}", address, e); None }
+        }
+    });
+
+    let session_log = config.borrow().session_log_format().and_then(|format| {
+        let dir = config.borrow().session_log_dir();
+        match session_log::SessionLogger::new(&dir, format, config.borrow().session_log_fields()) {
+            Ok(logger) => Some(logger),
+            Err(e) => { log::error!("failed to create session log in {}: {}", dir.to_string_lossy(), e); None }
+        }
+    });
+
+    let camera_trigger = config.borrow().camera_trigger_output().and_then(|output| {
+        let pulse = config.borrow().camera_trigger_pulse();
+        match camera_trigger::CameraTrigger::new(&output, pulse, &config.borrow().camera_trigger_command()) {
+            Ok(trigger) => { log::info!("camera trigger output: {}", trigger.description()); Some(trigger) },
+            Err(e) => { log::error!("failed to set up camera trigger output {}: {}", output, e); None }
+        }
+    });
+
+    let overlay = config.borrow().overlay_output().and_then(|output| {
+        match overlay::Overlay::new(&output, &config.borrow().overlay_template()) {
+            Ok(overlay) => { log::info!("overlay output: {}", overlay.description()); Some(overlay) },
+            Err(e) => { log::error!("failed to set up overlay output {}: {}", output, e); None }
+        }
+    });
+
+    let state_broadcast = config.borrow().state_broadcast_addr().and_then(|address| {
+        match state_broadcast::StateBroadcast::new(&address) {
+            Ok(broadcast) => { log::info!("broadcasting state on {}", broadcast.local_addr()); Some(broadcast) },
+            Err(e) => { log::error!("failed to start state broadcast on {}: {}", address, e); None }
+        }
+    });
+
+    let telemetry = config.borrow().telemetry_output().and_then(|output| {
+        match telemetry::Telemetry::new(&output) {
+            Ok(telemetry) => { log::info!("telemetry output: {}", telemetry.description()); Some(telemetry) },
+            Err(e) => { log::error!("failed to set up telemetry output {}: {}", output, e); None }
+        }
+    });
+
+    let script = config.borrow().script_file().and_then(|path| {
+        match scripting::Script::load(&path) {
+            Ok(script) => { log::info!("loaded script {}", path.to_string_lossy()); Some(script) },
+            Err(e) => { log::error!("failed to load script {}: {}", path.to_string_lossy(), e); None }
+        }
+    });
+
+    let ntp_client = config.borrow().time_sync_server().and_then(|server| {
+        match time_sync::NtpClient::new(&server) {
+            Ok(client) => Some(client),
+            Err(e) => { log::error!("failed to set up time synchronization with {}: {}", server, e); None }
+        }
+    });
+
+    let latency = Rc::new(RefCell::new(latency::LatencyStats::default()));
+    let refraction = config.borrow().refraction();
 
     let mut state = data::ProgramState{
+        angle_display: config.borrow().angle_display(),
+        camera_fov: config.borrow().camera_fov(),
+        camera_corrections,
+        camera_trigger,
+        clock_offset: None,
         config,
         controllers: vec![],
-        controller_names: vec![],
+        connected_controllers: Rc::new(RefCell::new(vec![])),
         cursive_stepper: cursive_stepper::CursiveRunnableStepper{ curs: curs.into_runner() },
         data_receiver,
+        data_source_reconnection: None,
+        gps: Rc::new(RefCell::new(gps)),
+        guiding,
+        keyboard_slewing: Rc::new(RefCell::new(Default::default())),
+        latency: latency.clone(),
         listener: Box::pin(pasts::notify::poll_fn(move |ctx| std::pin::Pin::new(&mut listener).poll(ctx))),
         mount: mount.clone(),
+        mount2: mount2.clone(),
+        mount_routing: mount_routing.clone(),
         mount_spd: mount_spd.clone(),
+        mount_drift_warned: false,
+        message_parser,
+        mount_watchdog: Default::default(),
+        ntp_client,
+        observer,
+        overlay,
+        pending_tracking_start: false,
+        pos_history: Rc::new(RefCell::new(data::PositionHistory::new(pos_history_len))),
+        rate_calibration: Rc::new(RefCell::new(None)),
+        refraction,
         slewing: Default::default(),
-        slew_speed: Rc::new(RefCell::new(data::deg_per_s(5.0))),
+        state_broadcast,
+        sun_avoidance,
+        telemetry,
+        slew_speed: Rc::new(RefCell::new(slew_speed)),
         target: Rc::clone(&target),
+        target_alarms,
+        target_source: Rc::new(RefCell::new(None)),
+        target_queue: Rc::new(RefCell::new(Default::default())),
         timers: vec![
-            data::Timer::new(data::timers::MAIN, MAIN_TIMER_INTERVAL),
-            data::Timer::new(data::timers::TARGET_LOG, TARGET_LOG_TIMER_INTERVAL)
+            data::Timer::new(data::timers::MAIN, main_timer_interval),
+            data::Timer::new(data::timers::TARGET_LOG, TARGET_LOG_TIMER_INTERVAL),
+            data::Timer::new(data::timers::TARGET_SOURCE, TARGET_SOURCE_TIMER_INTERVAL),
+            data::Timer::new(data::timers::KEYBOARD, KEYBOARD_TIMER_INTERVAL),
+            data::Timer::new(data::timers::EXTERNAL_INPUT, EXTERNAL_INPUT_TIMER_INTERVAL),
+            data::Timer::new(data::timers::MOTION, MOTION_TIMER_INTERVAL),
+            data::Timer::new(data::timers::MOUNT_STATE, MOUNT_STATE_TIMER_INTERVAL)
         ],
         tracking: tracking::Tracking::new(
-            data::deg_per_s(5.0),
-            mount,
-            mount_spd,
-            target,
+            tracking::TrackingParams{
+                max_spd: max_slew_speed,
+                mount,
+                mount2,
+                mount_routing,
+                mount_spd,
+                target,
+                target_loss,
+                pause_mode,
+                timer_interval: tracking_timer_interval,
+                handover_time,
+                latency
+            },
             Box::new(cclone!([@weak tui], move |running| on_tracking_state_changed(running, tui.clone())))
         ),
         tui,
         refresher: tui::Refresher::new(),
-        ctrl_actions
+        saved_adjustment_checked: false,
+        saved_mount_state: None,
+        script,
+        session_log,
+        ctrl_actions,
+        ctrl_action_capture: Rc::new(RefCell::new(None)),
+        held_buttons: Default::default(),
+        axes_response
     };
 
     tui::init(&mut state);
 
+    let config_issues = state.config.borrow().validate();
+    for issue in &config_issues { log::warn!("invalid configuration entry {}", issue); }
+    if !headless && !config_issues.is_empty() { tui::show_config_issues(&mut state, config_issues); }
+
+    if headless { println!("Running in headless mode; press Ctrl+C to quit."); }
+    event_handling::connect_on_startup(&mut state, args.mount, args.data_source, headless);
+    state.pending_tracking_start = args.track_on_start;
+
     pasts::Executor::default().block_on(event_handling::event_loop(state));
 }
 
-fn set_up_logging() {
+fn set_up_logging(headless: bool) {
     std::panic::set_hook(Box::new(|info| {
         let backtrace = std::backtrace::Backtrace::force_capture();
         log::error!("{}\n\n{}", info, backtrace);
+        shutdown::on_panic();
     }));
 
     let tz_offset = chrono::Local::now().offset().clone();
@@ -94,16 +304,34 @@ fn set_up_logging() {
         .join(format!("tptool_{}.log", chrono::Local::now().format("%Y-%m-%d_%H%M%S")));
 
     println!("Logging to: {}", logfile.to_string_lossy());
-    simplelog::WriteLogger::init(
-        simplelog::LevelFilter::Info,
-        simplelog::ConfigBuilder::new()
-            .set_target_level(simplelog::LevelFilter::Error)
-            .set_time_offset(time::UtcOffset::from_whole_seconds(tz_offset.local_minus_utc()).unwrap())
-            .set_time_format_custom(simplelog::format_description!(
-                "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:6]"
-            ))
-            .add_filter_ignore_str("cursive_core")
-            .build(),
-        std::fs::File::create(logfile).unwrap()
-    ).unwrap();
+    let config = simplelog::ConfigBuilder::new()
+        .set_target_level(simplelog::LevelFilter::Error)
+        .set_time_offset(time::UtcOffset::from_whole_seconds(tz_offset.local_minus_utc()).unwrap())
+        .set_time_format_custom(simplelog::format_description!(
+            "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:6]"
+        ))
+        .add_filter_ignore_str("cursive_core")
+        .build();
+
+    // the effective level of the log file & terminal output is controlled at runtime via `log::set_max_level`
+    let mut loggers: Vec<Box<dyn simplelog::SharedLogger>> = vec![
+        simplelog::WriteLogger::new(
+            simplelog::LevelFilter::Trace,
+            config.clone(),
+            std::fs::File::create(logfile).unwrap()
+        ),
+        log_buffer::MemoryLogger::new(simplelog::LevelFilter::Warn),
+        remote_log::RemoteLogger::new()
+    ];
+    if headless {
+        loggers.push(simplelog::TermLogger::new(
+            simplelog::LevelFilter::Trace,
+            config,
+            simplelog::TerminalMode::Stdout,
+            simplelog::ColorChoice::Auto
+        ));
+    }
+
+    simplelog::CombinedLogger::init(loggers).unwrap();
+    log::set_max_level(log::LevelFilter::Info);
 }
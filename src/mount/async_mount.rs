@@ -0,0 +1,246 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Non-blocking facade for mounts with slow (e.g., serial) I/O; all communication with the mount happens
+//! on a worker thread.
+
+use crate::mount::{Axis, Mount};
+use pointing_utils::uom;
+use std::{error::Error, sync::{Arc, Mutex, mpsc}, time::{Duration, Instant}};
+use uom::si::f64;
+
+/// Interval of position queries performed by the worker thread.
+const POSITION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// If the last successful position query is older than this, `position` reports an error.
+const MAX_POSITION_AGE: Duration = Duration::from_secs(2);
+
+/// Max. time to wait for completion of park/unpark.
+const PARK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Max. time to wait for the reply to a raw command.
+const RAW_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+enum Command {
+    SlewAxis(Axis, f64::AngularVelocity),
+    Stop,
+    SetAltitudeLimit(Option<f64::Angle>),
+    Park(mpsc::Sender<Result<(), String>>),
+    Unpark(mpsc::Sender<Result<(), String>>),
+    Raw(String, mpsc::Sender<Result<String, String>>)
+}
+
+#[derive(Default)]
+struct Shared {
+    position: Option<((f64::Angle, f64::Angle), Instant)>,
+    position_error: Option<String>,
+    /// Error of the last failed slewing command (reported by the next `slew`/`slew_axis`/`stop` call).
+    command_error: Option<String>,
+    diagnostics: Vec<(String, String)>,
+    warning: Option<String>
+}
+
+pub struct AsyncMount {
+    info: String,
+    details: Vec<(String, String)>,
+    max_slew_speed: Option<f64::AngularVelocity>,
+    rate_resolution: Option<f64::AngularVelocity>,
+    commands: Option<mpsc::Sender<Command>>,
+    shared: Arc<Mutex<Shared>>,
+    worker: Option<std::thread::JoinHandle<()>>,
+    is_query: fn(&str) -> bool
+}
+
+impl AsyncMount {
+    /// Moves `mount` onto a worker thread. The mount is dropped on the worker thread when `AsyncMount` is dropped.
+    pub fn new<M: Mount + Send + 'static>(mut mount: M) -> AsyncMount {
+        let info = mount.get_info();
+        let details = mount.details();
+        let max_slew_speed = mount.max_slew_speed();
+        let rate_resolution = mount.rate_resolution();
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        if let Ok(pos) = mount.position() { shared.lock().unwrap().position = Some((pos, Instant::now())); }
+
+        let (commands, receiver) = mpsc::channel();
+        let worker = std::thread::spawn({
+            let shared = Arc::clone(&shared);
+            move || worker(mount, receiver, shared)
+        });
+
+        AsyncMount{
+            info,
+            details,
+            max_slew_speed,
+            rate_resolution,
+            commands: Some(commands),
+            shared,
+            worker: Some(worker),
+            is_query: |_| false
+        }
+    }
+
+    /// Sets the function recognizing raw commands which only query the mount.
+    pub fn with_query_commands(mut self, is_query: fn(&str) -> bool) -> AsyncMount {
+        self.is_query = is_query;
+        self
+    }
+
+    fn send(&self, command: Command) -> Result<(), Box<dyn Error>> {
+        self.commands.as_ref().unwrap().send(command).map_err(|_| "mount worker thread has stopped".into())
+    }
+
+    fn take_command_error(&self) -> Result<(), Box<dyn Error>> {
+        match self.shared.lock().unwrap().command_error.take() {
+            Some(e) => Err(e.into()),
+            None => Ok(())
+        }
+    }
+
+    fn wait_for(&self, make_command: fn(mpsc::Sender<Result<(), String>>) -> Command) -> Result<(), Box<dyn Error>> {
+        let (sender, receiver) = mpsc::channel();
+        self.send(make_command(sender))?;
+        match receiver.recv_timeout(PARK_TIMEOUT) {
+            Ok(result) => result.map_err(|e| e.into()),
+            Err(_) => Err("no response from mount worker thread".into())
+        }
+    }
+}
+
+impl Drop for AsyncMount {
+    fn drop(&mut self) {
+        // closing the channel makes the worker thread finish
+        self.commands = None;
+        if let Some(worker) = self.worker.take() {
+            if worker.join().is_err() { log::error!("mount worker thread panicked"); }
+        }
+    }
+}
+
+impl Mount for AsyncMount {
+    fn get_info(&self) -> String { self.info.clone() }
+
+    fn details(&self) -> Vec<(String, String)> { self.details.clone() }
+
+    fn diagnostics(&self) -> Vec<(String, String)> { self.shared.lock().unwrap().diagnostics.clone() }
+
+    fn max_slew_speed(&self) -> Option<f64::AngularVelocity> { self.max_slew_speed }
+
+    fn rate_resolution(&self) -> Option<f64::AngularVelocity> { self.rate_resolution }
+
+    fn slew(&mut self, axis1: f64::AngularVelocity, axis2: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        self.slew_axis(Axis::Primary, axis1)?;
+        self.slew_axis(Axis::Secondary, axis2)
+    }
+
+    fn slew_axis(&mut self, axis: Axis, speed: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        self.take_command_error()?;
+        self.send(Command::SlewAxis(axis, speed))
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        // queue the stop even if a previous command has failed
+        let result = self.take_command_error();
+        self.send(Command::Stop)?;
+        result
+    }
+
+    fn position(&mut self) -> Result<(f64::Angle, f64::Angle), Box<dyn Error>> {
+        let shared = self.shared.lock().unwrap();
+        if let Some(e) = &shared.position_error { return Err(e.clone().into()); }
+        match shared.position {
+            Some((pos, t)) if t.elapsed() <= MAX_POSITION_AGE => Ok(pos),
+            Some(_) => Err("no recent position reported by mount".into()),
+            None => Err("position not available yet".into())
+        }
+    }
+
+    fn park(&mut self) -> Result<(), Box<dyn Error>> {
+        self.wait_for(Command::Park)
+    }
+
+    fn unpark(&mut self) -> Result<(), Box<dyn Error>> {
+        self.wait_for(Command::Unpark)
+    }
+
+    fn set_altitude_limit(&mut self, limit: Option<f64::Angle>) {
+        if let Err(e) = self.send(Command::SetAltitudeLimit(limit)) { log::error!("{}", e); }
+    }
+
+    fn warning(&self) -> Option<String> { self.shared.lock().unwrap().warning.clone() }
+
+    fn raw_command(&mut self, command: &str) -> Result<String, Box<dyn Error>> {
+        let (sender, receiver) = mpsc::channel();
+        self.send(Command::Raw(command.into(), sender))?;
+        match receiver.recv_timeout(RAW_COMMAND_TIMEOUT) {
+            Ok(result) => result.map_err(|e| e.into()),
+            Err(_) => Err("no response from mount worker thread".into())
+        }
+    }
+
+    fn is_query_command(&self, command: &str) -> bool { (self.is_query)(command) }
+}
+
+fn worker<M: Mount>(mut mount: M, commands: mpsc::Receiver<Command>, shared: Arc<Mutex<Shared>>) {
+    let mut last_poll = Instant::now();
+    loop {
+        let mut pending = vec![];
+        match commands.recv_timeout(POSITION_POLL_INTERVAL.saturating_sub(last_poll.elapsed())) {
+            Ok(command) => pending.push(command),
+            Err(mpsc::RecvTimeoutError::Timeout) => (),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break
+        }
+        pending.extend(commands.try_iter());
+
+        for (idx, command) in pending.iter().enumerate() {
+            match command {
+                Command::SlewAxis(axis, speed) => {
+                    // a later speed command for the same axis (or a stop) supersedes this one
+                    if pending[idx + 1..].iter().any(|c| match c {
+                        Command::SlewAxis(a, _) => a == axis,
+                        Command::Stop => true,
+                        _ => false
+                    }) {
+                        continue;
+                    }
+                    if let Err(e) = mount.slew_axis(*axis, *speed) {
+                        shared.lock().unwrap().command_error = Some(e.to_string());
+                    }
+                },
+                Command::Stop => if let Err(e) = mount.stop() {
+                    shared.lock().unwrap().command_error = Some(e.to_string());
+                },
+                Command::SetAltitudeLimit(limit) => mount.set_altitude_limit(*limit),
+                Command::Park(reply) => { let _ = reply.send(mount.park().map_err(|e| e.to_string())); },
+                Command::Unpark(reply) => { let _ = reply.send(mount.unpark().map_err(|e| e.to_string())); },
+                Command::Raw(raw, reply) => { let _ = reply.send(mount.raw_command(raw).map_err(|e| e.to_string())); }
+            }
+        }
+
+        if last_poll.elapsed() >= POSITION_POLL_INTERVAL {
+            last_poll = Instant::now();
+            let result = mount.position();
+            let mut shared = shared.lock().unwrap();
+            match result {
+                Ok(pos) => { shared.position = Some((pos, Instant::now())); shared.position_error = None; },
+                Err(e) => shared.position_error = Some(e.to_string())
+            }
+            shared.diagnostics = mount.diagnostics();
+            shared.warning = mount.warning();
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{data, mount::Axis};
+use pointing_utils::uom;
+use std::time::{Duration, Instant};
+use uom::si::f64;
+
+/// Time after a speed change during which the axis motion is not compared (to skip acceleration and latency).
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+/// Position samples further apart are not compared (e.g., after a communication break).
+const MAX_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Compares motion expected from the commanded axis speeds with the reported positions; the accumulated
+/// difference (drift) may indicate a slipping clutch or an encoder fault.
+pub struct DriftMonitor {
+    commanded: [f64::AngularVelocity; 2],
+    last_change: Instant,
+    last_sample: Option<(Instant, (f64::Angle, f64::Angle))>,
+    /// Accumulated reported minus expected motion of primary and secondary axes.
+    drift: (f64::Angle, f64::Angle)
+}
+
+impl DriftMonitor {
+    pub fn new() -> DriftMonitor {
+        DriftMonitor{
+            commanded: [data::deg_per_s(0.0); 2],
+            last_change: Instant::now(),
+            last_sample: None,
+            drift: (data::deg(0.0), data::deg(0.0))
+        }
+    }
+
+    pub fn drift(&self) -> (f64::Angle, f64::Angle) { self.drift }
+
+    pub fn reset(&mut self) {
+        self.drift = (data::deg(0.0), data::deg(0.0));
+        self.last_sample = None;
+    }
+
+    /// Skips comparison of the current motion (e.g., when the mount moves on its own while parking).
+    pub fn interrupt(&mut self) {
+        self.last_sample = None;
+    }
+
+    /// To be called whenever a speed is commanded to the mount.
+    pub fn on_commanded(&mut self, axis: Axis, speed: f64::AngularVelocity, now: Instant) {
+        let idx = match axis { Axis::Primary => 0, Axis::Secondary => 1 };
+        if self.commanded[idx] != speed {
+            self.commanded[idx] = speed;
+            self.last_change = now;
+        }
+    }
+
+    /// To be called with each position (of the internal axes) reported by the mount.
+    pub fn on_position(&mut self, pos: (f64::Angle, f64::Angle), now: Instant) {
+        if let Some((t0, pos0)) = self.last_sample {
+            let dt = now.saturating_duration_since(t0);
+            if t0 >= self.last_change + SETTLE_TIME && dt <= MAX_SAMPLE_INTERVAL {
+                let expected = |speed| data::deg(data::as_deg_per_s(speed) * dt.as_secs_f64());
+                self.drift.0 += data::angle_diff(pos0.0, pos.0) - expected(self.commanded[0]);
+                self.drift.1 += data::angle_diff(pos0.1, pos.1) - expected(self.commanded[1]);
+            }
+        }
+        self.last_sample = Some((now, pos));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::{as_deg, deg, deg_per_s};
+
+    #[test]
+    fn drift_accumulation() {
+        let t0 = Instant::now();
+        let mut monitor = DriftMonitor::new();
+        monitor.on_commanded(Axis::Primary, deg_per_s(1.0), t0);
+
+        // within settle time: ignored
+        monitor.on_position((deg(0.0), deg(0.0)), t0);
+        monitor.on_position((deg(0.5), deg(0.0)), t0 + Duration::from_secs(1));
+        assert_eq!(0.0, as_deg(monitor.drift().0));
+
+        // reported 0.9° instead of the expected 1°
+        monitor.on_position((deg(2.5), deg(0.0)), t0 + Duration::from_secs(3));
+        monitor.on_position((deg(3.4), deg(0.0)), t0 + Duration::from_secs(4));
+        assert!((as_deg(monitor.drift().0) + 0.1).abs() < 1.0e-9);
+        assert!(as_deg(monitor.drift().1).abs() < 1.0e-9);
+    }
+}
@@ -0,0 +1,225 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{clock, data::{as_deg_per_s, deg, deg_per_s}, mount::{AsyncMount, Axis, Mount, motion}};
+use pointing_utils::uom;
+use std::{error::Error, time::{Duration, Instant}};
+use uom::si::f64;
+
+/// Proportional gain of moving to the park position (1/s).
+const PARK_GAIN: f64 = 1.0;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Params {
+    /// Max. axis speed (°/s).
+    max_speed: f64,
+    /// Max. axis acceleration (°/s²); 0 means infinite.
+    accel: f64,
+    /// Max. encoder error (°).
+    noise: f64,
+    /// Response time of each command and query (ms).
+    latency: f64,
+    /// Probability of a command or query getting no reply.
+    drop: f64
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params{ max_speed: 10.0, accel: 5.0, noise: 0.0, latency: 0.0, drop: 0.0 }
+    }
+}
+
+/// Parses parameters in the form "max_speed=<°/s>, accel=<°/s²>, noise=<°>, latency=<ms>, drop=<0-1>"
+/// (all optional).
+fn parse_params(s: &str) -> Result<Params, Box<dyn Error>> {
+    let mut params = Params::default();
+    for item in s.split(',').map(|item| item.trim()).filter(|item| !item.is_empty()) {
+        let (key, value) = item.split_once('=').ok_or(format!("expected key=value, got \"{}\"", item))?;
+        let value = value.trim().parse::<f64>()
+            .ok()
+            .filter(|v| *v >= 0.0)
+            .ok_or(format!("invalid value of {}: {}", key.trim(), value.trim()))?;
+        match key.trim() {
+            "max_speed" => params.max_speed = value,
+            "accel" => params.accel = value,
+            "noise" => params.noise = value,
+            "latency" => params.latency = value,
+            "drop" if value <= 1.0 => params.drop = value,
+            "drop" => return Err(format!("drop probability must not exceed 1: {}", value).into()),
+            _ => return Err(format!("unknown parameter: {}", key.trim()).into())
+        }
+    }
+
+    Ok(params)
+}
+
+#[derive(Default)]
+struct AxisState {
+    /// Position (°).
+    pos: f64,
+    /// Current speed (°/s).
+    speed: f64,
+    /// Requested speed (°/s).
+    requested: f64
+}
+
+/// Mount simulator running within TPTool (does not need an external simulator process).
+pub struct InternalSimulator {
+    params: Params,
+    axes: [AxisState; 2],
+    last_update: Instant,
+    /// State of the pseudorandom number generator (for encoder noise).
+    rng_state: u64,
+    /// If true, the axes are moving to (or are at) the park position (0°, 0°).
+    parking: bool
+}
+
+impl InternalSimulator {
+    /// Creates the simulator; if a response latency is specified, it runs on a worker thread (like a mount
+    /// with slow serial I/O, see `AsyncMount`).
+    pub fn new(params: &str) -> Result<Box<dyn Mount>, Box<dyn Error>> {
+        let simulator = InternalSimulator{
+            params: parse_params(params)?,
+            axes: Default::default(),
+            last_update: clock::now(),
+            rng_state: 0x2545_F491_4F6C_DD1D,
+            parking: false
+        };
+        if simulator.params.latency > 0.0 {
+            Ok(Box::new(AsyncMount::new(simulator)))
+        } else {
+            Ok(Box::new(simulator))
+        }
+    }
+
+    /// Simulates the mount's response time and dropped replies.
+    fn respond(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.params.latency > 0.0 {
+            std::thread::sleep(Duration::from_secs_f64(self.params.latency / 1000.0));
+        }
+        if self.params.drop > 0.0 && (self.random() + 1.0) / 2.0 < self.params.drop {
+            return Err("no reply from mount (simulated)".into());
+        }
+        Ok(())
+    }
+
+    fn update(&mut self) {
+        let now = clock::now();
+        let dt = (now - self.last_update).as_secs_f64();
+        self.last_update = now;
+
+        for axis in &mut self.axes {
+            if self.parking {
+                let to_park = if axis.pos > 180.0 { 360.0 - axis.pos } else { -axis.pos };
+                axis.requested = (to_park * PARK_GAIN).max(-self.params.max_speed).min(self.params.max_speed);
+            }
+            let prev_speed = axis.speed;
+            axis.speed = if self.params.accel > 0.0 {
+                motion::ramp(axis.speed, axis.requested, self.params.accel * dt)
+            } else {
+                axis.requested
+            };
+            axis.pos += 0.5 * (prev_speed + axis.speed) * dt;
+        }
+        self.axes[0].pos = self.axes[0].pos.rem_euclid(360.0);
+    }
+
+    /// Returns a pseudorandom number from [-1, 1].
+    fn random(&mut self) -> f64 {
+        // xorshift64
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 7;
+        self.rng_state ^= self.rng_state << 17;
+        (self.rng_state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    }
+}
+
+impl Mount for InternalSimulator {
+    fn get_info(&self) -> String {
+        let mut faults = String::new();
+        if self.params.latency > 0.0 { faults += &format!(", latency {} ms", self.params.latency); }
+        if self.params.drop > 0.0 { faults += &format!(", {}% dropped", self.params.drop * 100.0); }
+        format!(
+            "Internal simulator ({}°/s, {}°/s²{})", self.params.max_speed, self.params.accel, faults
+        )
+    }
+
+    fn max_slew_speed(&self) -> Option<f64::AngularVelocity> {
+        Some(deg_per_s(self.params.max_speed))
+    }
+
+    fn slew(&mut self, axis1: f64::AngularVelocity, axis2: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        self.slew_axis(Axis::Primary, axis1)?;
+        self.slew_axis(Axis::Secondary, axis2)
+    }
+
+    fn slew_axis(&mut self, axis: Axis, speed: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        self.respond()?;
+        self.update();
+        let max_speed = self.params.max_speed;
+        let idx = match axis { Axis::Primary => 0, Axis::Secondary => 1 };
+        self.axes[idx].requested = as_deg_per_s(speed).max(-max_speed).min(max_speed);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        self.parking = false;
+        self.slew(deg_per_s(0.0), deg_per_s(0.0))
+    }
+
+    fn position(&mut self) -> Result<(f64::Angle, f64::Angle), Box<dyn Error>> {
+        self.respond()?;
+        self.update();
+        let noise = self.params.noise;
+        let noise1 = noise * self.random();
+        let noise2 = noise * self.random();
+        Ok((deg(self.axes[0].pos + noise1), deg(self.axes[1].pos + noise2)))
+    }
+
+    fn park(&mut self) -> Result<(), Box<dyn Error>> {
+        self.respond()?;
+        self.update();
+        self.parking = true;
+        Ok(())
+    }
+
+    fn unpark(&mut self) -> Result<(), Box<dyn Error>> {
+        self.stop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_parsing() {
+        assert_eq!(Params::default(), parse_params("").unwrap());
+        assert_eq!(
+            Params{ max_speed: 3.0, accel: 0.0, noise: 0.001, latency: 0.0, drop: 0.0 },
+            parse_params("max_speed=3, accel=0, noise = 0.001").unwrap()
+        );
+        assert_eq!(
+            Params{ latency: 50.0, drop: 0.1, ..Default::default() },
+            parse_params("latency=50, drop=0.1").unwrap()
+        );
+        assert!(parse_params("speed=3").is_err());
+        assert!(parse_params("accel=-1").is_err());
+        assert!(parse_params("drop=1.5").is_err());
+    }
+}
@@ -16,18 +16,58 @@
 // along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
 //
 
-use crate::{data::deg_per_s, mount::{Axis, Mount}};
+use crate::{data::{as_deg, deg, deg_per_s}, mount::{AsyncMount, Axis, Mount}};
 use pointing_utils::uom;
-use std::error::Error;
+use std::{error::Error, io::Write};
 use uom::si::{f64, angle, angular_velocity};
 
 // HAE69B takes up to 1.8 s to toggle special mode
 const SPECIAL_MODE_SWITCH_MAX_DURATION: std::time::Duration = std::time::Duration::from_secs(5);
 
+/// Length of reply to `:P0#` or `:P1#` ("snnnnnnnnn#"; 0.01″ units).
+const POSITION_REPLY_LEN: usize = 11;
+
+/// Step of axis speeds in special mode (°/s; the speed is specified in 0.01″/s units).
+const RATE_RESOLUTION_DEG_PER_S: f64 = 0.01 / 3600.0;
+
+/// Weight of the newest sample in the average position query duration.
+const ROUNDTRIP_AVG_WEIGHT: f64 = 0.1;
+
+/// Max. time to wait for the first character of the reply to a raw command.
+const RAW_REPLY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
 pub struct Ioptron {
     model: String,
+    mount_id: String,
+    max_speed: Option<f64::AngularVelocity>,
     device: String,
     serial_port: Box<dyn serialport::SerialPort>,
+    /// Queried at connect time (only possible if the mount was not already in special mode).
+    capabilities: Option<Capabilities>,
+    /// If true, the mount is parked and in normal mode.
+    parked: bool,
+    /// Duration of the last position query.
+    roundtrip: Option<std::time::Duration>,
+    /// Exponential moving average of position query durations.
+    avg_roundtrip: Option<std::time::Duration>,
+    /// Min. altitude (position of axis 2); in special mode it is not enforced by the firmware, but by `Ioptron`.
+    alt_limit: Option<f64::Angle>,
+    /// Last reported position of axis 2.
+    last_alt: Option<f64::Angle>,
+    /// Last speed commanded to axis 2.
+    alt_speed: f64::AngularVelocity,
+    /// If true, axis 2 has been stopped at (or refused to move past) the altitude limit.
+    alt_limit_reached: bool
+}
+
+/// Firmware versions (dates: YYMMDD) and settings reported by the mount in normal mode.
+struct Capabilities {
+    mainboard_fw: Option<String>,
+    hand_controller_fw: Option<String>,
+    ra_motor_fw: Option<String>,
+    dec_motor_fw: Option<String>,
+    /// Altitude limit (degrees).
+    alt_limit: Option<i32>
 }
 
 #[derive(Debug)]
@@ -45,7 +85,7 @@ enum InvalidResponseTreatment {
 }
 
 impl Ioptron {
-    /// Creates an iOptron mount instance.
+    /// Creates an iOptron mount instance; serial communication is performed on a worker thread (see `AsyncMount`).
     ///
     /// # Parameters
     ///
@@ -54,6 +94,11 @@ impl Ioptron {
     ///
     #[must_use]
     pub fn new(device: &str) -> Result<Box<dyn Mount>, Box<dyn Error>> {
+        Ok(Box::new(AsyncMount::new(Ioptron::open(device)?).with_query_commands(is_query_command)))
+    }
+
+    /// Connects to the mount; all subsequent calls block until the mount replies.
+    fn open(device: &str) -> Result<Ioptron, Box<dyn Error>> {
         let mut serial_port = serialport::new(device, 115200)
             .data_bits(serialport::DataBits::Eight)
             .flow_control(serialport::FlowControl::None)
@@ -77,22 +122,65 @@ impl Ioptron {
         };
 
         if mount_id.len() < 1 { return Err("mount ID is empty".into()); }
+        let mut capabilities = None;
         if mount_id[0] != b'8' && mount_id[0] != b'9' {
+            capabilities = Some(query_capabilities(&mut serial_port));
             log::debug!("mount not in special mode, switching...");
             toggle_special_mode(&mut serial_port)?;
             log::debug!("switched successfully");
+        } else {
+            log::info!("mount already in special mode; firmware versions not available");
         }
 
-        Ok(Box::new(Ioptron{
+        Ok(Ioptron{
             model,
+            mount_id: String::from_utf8_lossy(&mount_id).into(),
+            max_speed: max_speed_from_id(&String::from_utf8_lossy(&mount_id)).map(deg_per_s),
             device: device.to_string(),
             serial_port,
-        }))
+            alt_limit: configured_alt_limit(&capabilities),
+            capabilities,
+            parked: false,
+            roundtrip: None,
+            avg_roundtrip: None,
+            last_alt: None,
+            alt_speed: deg_per_s(0.0),
+            alt_limit_reached: false
+        })
+    }
+
+    fn send_axis_speed(&mut self, axis: Axis, speed: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        send_cmd_and_get_reply(
+            &mut self.serial_port,
+            format!(
+                ":M{}{:+08}#",
+                if axis == Axis::Primary { "0" } else { "1" },
+                (speed.get::<angular_velocity::degree_per_second>() / RATE_RESOLUTION_DEG_PER_S).round() as i32
+            ),
+            ResponseType::CharsReceived("1".into()),
+            InvalidResponseTreatment::IgnoreAndLog(true)
+        )?;
+        if axis == Axis::Secondary { self.alt_speed = speed; }
+        Ok(())
+    }
+
+    /// Stops axis 2 if it is descending below the altitude limit.
+    fn check_alt_limit(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(limit) = self.alt_limit else { return Ok(()); };
+        if exceeds_alt_limit(self.last_alt, self.alt_speed, limit) {
+            log::warn!("altitude limit ({:.1}°) reached; stopping axis 2", as_deg(limit));
+            self.alt_limit_reached = true;
+            self.send_axis_speed(Axis::Secondary, deg_per_s(0.0))?;
+        } else if self.last_alt.is_some_and(|alt| alt > limit) {
+            self.alt_limit_reached = false;
+        }
+        Ok(())
     }
 }
 
 impl Drop for Ioptron {
     fn drop(&mut self) {
+        if self.parked { return; } // already in normal mode
         let _ = self.stop();
         log::debug!("switching mount back to normal mode...");
         if let Err(e) = toggle_special_mode(&mut self.serial_port) {
@@ -105,55 +193,206 @@ impl Drop for Ioptron {
 
 impl Mount for Ioptron {
     fn get_info(&self) -> String {
-        format!("iOptron {} on {}", self.model, self.device)
+        match self.capabilities.as_ref().and_then(|c| c.mainboard_fw.as_ref()) {
+            Some(fw) => format!("iOptron {} (FW {}) on {}", self.model, fw, self.device),
+            None => format!("iOptron {} on {}", self.model, self.device)
+        }
+    }
+
+    fn details(&self) -> Vec<(String, String)> {
+        let mut result = vec![
+            ("Model".into(), self.model.clone()),
+            ("Mount ID".into(), self.mount_id.clone()),
+            ("Device".into(), self.device.clone()),
+            ("Max. slew speed".into(), match self.max_speed {
+                Some(speed) => format!("{}°/s", speed.get::<angular_velocity::degree_per_second>()),
+                None => "(unknown)".into()
+            })
+        ];
+
+        match &self.capabilities {
+            Some(c) => {
+                let unknown = || "(unknown)".to_string();
+                result.push(("Mainboard firmware".into(), c.mainboard_fw.clone().unwrap_or_else(unknown)));
+                result.push(("Hand controller firmware".into(), c.hand_controller_fw.clone().unwrap_or_else(unknown)));
+                result.push(("RA/axis 1 motor firmware".into(), c.ra_motor_fw.clone().unwrap_or_else(unknown)));
+                result.push(("Dec/axis 2 motor firmware".into(), c.dec_motor_fw.clone().unwrap_or_else(unknown)));
+                result.push((
+                    "Altitude limit".into(),
+                    c.alt_limit.map(|l| format!("{}°", l)).unwrap_or_else(unknown)
+                ));
+            },
+
+            None => result.push(("Firmware".into(), "(not available; mount was already in special mode)".into()))
+        }
+
+        result
     }
 
+    fn diagnostics(&self) -> Vec<(String, String)> {
+        let ms = |d: Option<std::time::Duration>| match d {
+            Some(d) => format!("{:.1} ms", d.as_secs_f64() * 1000.0),
+            None => "(n/a)".into()
+        };
+        vec![
+            ("Position query roundtrip".into(), ms(self.roundtrip)),
+            ("Position query roundtrip (avg.)".into(), ms(self.avg_roundtrip))
+        ]
+    }
+
+    fn max_slew_speed(&self) -> Option<f64::AngularVelocity> { self.max_speed }
+
+    fn rate_resolution(&self) -> Option<f64::AngularVelocity> { Some(deg_per_s(RATE_RESOLUTION_DEG_PER_S)) }
+
     fn slew(&mut self, axis1: f64::AngularVelocity, axis2: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
         self.slew_axis(Axis::Primary, axis1)?;
         self.slew_axis(Axis::Secondary, axis2)
     }
 
     fn slew_axis(&mut self, axis: Axis, speed: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
-        send_cmd_and_get_reply(
-            &mut self.serial_port,
-            format!(
-                ":M{}{:+08}#",
-                if axis == Axis::Primary { "0" } else { "1" },
-                (speed.get::<angular_velocity::degree_per_second>() * 3600.0 * 100.0) as i32
-            ),
-            ResponseType::CharsReceived("1".into()),
-            InvalidResponseTreatment::IgnoreAndLog(true)
-        ).map(|_| ())
+        if let (Axis::Secondary, Some(limit)) = (axis, self.alt_limit) {
+            if exceeds_alt_limit(self.last_alt, speed, limit) {
+                // not an error (e.g., tracking a setting target continues in azimuth); reported via `warning`
+                if !self.alt_limit_reached {
+                    log::warn!("altitude limit ({:.1}°) reached; not descending", as_deg(limit));
+                }
+                self.alt_limit_reached = true;
+                if self.alt_speed != deg_per_s(0.0) { self.send_axis_speed(axis, deg_per_s(0.0))?; }
+                return Ok(());
+            }
+        }
+        self.send_axis_speed(axis, speed)
     }
 
     fn stop(&mut self) -> Result<(), Box<dyn Error>> {
-        self.slew(deg_per_s(0.0), deg_per_s(0.0))
+        if self.parked {
+            // in normal mode (e.g., while moving to the park position)
+            send_cmd_and_get_reply(
+                &mut self.serial_port,
+                ":Q#".into(),
+                ResponseType::CharsReceived("1".into()),
+                InvalidResponseTreatment::IgnoreAndLog(true)
+            )?;
+            Ok(())
+        } else {
+            self.slew(deg_per_s(0.0), deg_per_s(0.0))
+        }
     }
 
     fn position(&mut self) -> Result<(f64::Angle, f64::Angle), Box<dyn Error>> {
-        let pos1 = &send_cmd_and_get_reply(
+        if self.parked { return Err("position not available when parked".into()); }
+
+        // both queries are sent at once and the replies read together, i.e., in a single serial roundtrip
+        let t0 = std::time::Instant::now();
+        let reply = send_cmd_and_get_reply(
             &mut self.serial_port,
-            ":P0#".into(),
-            ResponseType::NumCharsReceived(11),
+            ":P0#:P1#".into(),
+            ResponseType::NumCharsReceived(2 * POSITION_REPLY_LEN),
             InvalidResponseTreatment::Fail
         )?;
-        let pos1 = String::from_utf8(pos1[..10].to_vec())?;
-        let pos1 = pos1.parse::<i32>()?;
+        let roundtrip = t0.elapsed();
+        self.roundtrip = Some(roundtrip);
+        self.avg_roundtrip = Some(match self.avg_roundtrip {
+            Some(avg) => avg.mul_f64(1.0 - ROUNDTRIP_AVG_WEIGHT) + roundtrip.mul_f64(ROUNDTRIP_AVG_WEIGHT),
+            None => roundtrip
+        });
+        let (pos1, pos2) = parse_positions(&reply)?;
+        let (pos1, pos2) = (
+            f64::Angle::new::<angle::second>(pos1 as f64 * 0.01),
+            f64::Angle::new::<angle::second>(pos2 as f64 * 0.01)
+        );
+        self.last_alt = Some(pos2);
+        self.check_alt_limit()?;
+
+        Ok((pos1, pos2))
+    }
+
+    /// Switches the mount to normal mode and parks it at its (hand controller-defined) park position.
+    fn park(&mut self) -> Result<(), Box<dyn Error>> {
+        self.stop()?;
+        log::debug!("switching mount to normal mode for parking...");
+        toggle_special_mode(&mut self.serial_port)?;
+        if let Err(e) = send_cmd_and_get_reply(
+            &mut self.serial_port,
+            ":MP1#".into(),
+            ResponseType::CharsReceived("1".into()),
+            InvalidResponseTreatment::Fail
+        ) {
+            toggle_special_mode(&mut self.serial_port)?;
+            return Err(e);
+        }
+        self.parked = true;
+        Ok(())
+    }
 
-        let pos2 = &send_cmd_and_get_reply(
+    fn unpark(&mut self) -> Result<(), Box<dyn Error>> {
+        send_cmd_and_get_reply(
             &mut self.serial_port,
-            ":P1#".into(),
-            ResponseType::NumCharsReceived(11),
+            ":MP0#".into(),
+            ResponseType::CharsReceived("1".into()),
             InvalidResponseTreatment::Fail
         )?;
-        let pos2 = String::from_utf8(pos2[..10].to_vec())?;
-        let pos2 = pos2.parse::<i32>()?;
+        log::debug!("switching mount back to special mode...");
+        toggle_special_mode(&mut self.serial_port)?;
+        self.parked = false;
+        Ok(())
+    }
 
-        Ok((
-            f64::Angle::new::<angle::second>(pos1 as f64 * 0.01),
-            f64::Angle::new::<angle::second>(pos2 as f64 * 0.01)
-        ))
+    fn set_altitude_limit(&mut self, limit: Option<f64::Angle>) {
+        self.alt_limit = limit.or_else(|| configured_alt_limit(&self.capabilities));
+        match self.alt_limit {
+            Some(limit) => log::info!("enforcing altitude limit of {:.1}°", as_deg(limit)),
+            None => log::warn!("altitude limit unknown; not enforced")
+        }
+    }
+
+    fn warning(&self) -> Option<String> {
+        match self.alt_limit {
+            Some(limit) if self.alt_limit_reached => Some(format!("altitude limit ({:.1}°) reached", as_deg(limit))),
+            _ => None
+        }
+    }
+
+    fn raw_command(&mut self, command: &str) -> Result<String, Box<dyn Error>> {
+        self.serial_port.write_all(command.as_bytes())?;
+        let reply = read_raw_reply(&mut self.serial_port);
+        Ok(if reply.is_empty() { "(no reply)".into() } else { String::from_utf8_lossy(&reply).into() })
+    }
+
+    fn is_query_command(&self, command: &str) -> bool { is_query_command(command) }
+}
+
+/// Returns true if `command` only reads information from the mount.
+fn is_query_command(command: &str) -> bool {
+    let command = command.trim();
+    command.starts_with(":G") || [":MountInfo#", ":FW1#", ":FW2#", ":P0#", ":P1#"].contains(&command)
+}
+
+/// Reads the reply to a raw command: until '#', or until no more characters arrive (not all replies end with '#').
+fn read_raw_reply<T: std::io::Read>(device: &mut T) -> Vec<u8> {
+    let t0 = std::time::Instant::now();
+    let mut reply = vec![];
+    let mut ch = [0u8];
+    while reply.len() < 1024 {
+        if device.read_exact(&mut ch).is_ok() {
+            reply.push(ch[0]);
+            if ch[0] == b'#' { break; }
+        } else if !reply.is_empty() || t0.elapsed() >= RAW_REPLY_TIMEOUT {
+            break;
+        }
     }
+    reply
+}
+
+/// Returns the altitude limit configured in the mount (if known).
+fn configured_alt_limit(capabilities: &Option<Capabilities>) -> Option<f64::Angle> {
+    capabilities.as_ref().and_then(|c| c.alt_limit).map(|limit| deg(limit as f64))
+}
+
+/// Returns true if moving axis 2 with `speed` from `alt` would violate the altitude limit
+/// (descending is not allowed if the position is unknown).
+fn exceeds_alt_limit(alt: Option<f64::Angle>, speed: f64::AngularVelocity, limit: f64::Angle) -> bool {
+    speed < deg_per_s(0.0) && alt.is_none_or(|alt| alt <= limit)
 }
 
 fn model_from_id(id: &str) -> String {
@@ -180,6 +419,75 @@ fn model_from_id(id: &str) -> String {
     }
 }
 
+/// Queries firmware versions and altitude limit (the mount must be in normal mode).
+fn query_capabilities<T: std::io::Read + std::io::Write>(device: &mut T) -> Capabilities {
+    let mut query = |cmd: &str| -> Option<Vec<u8>> {
+        match send_cmd_and_get_reply(
+            device,
+            cmd.into(),
+            ResponseType::EndsWith('#'),
+            InvalidResponseTreatment::Fail
+        ) {
+            Ok(reply) => Some(reply),
+            Err(e) => { log::warn!("{}", e); None }
+        }
+    };
+
+    let (mainboard_fw, hand_controller_fw) = query(":FW1#").and_then(|r| parse_firmware_dates(&r)).unzip();
+    let (ra_motor_fw, dec_motor_fw) = query(":FW2#").and_then(|r| parse_firmware_dates(&r)).unzip();
+    let alt_limit = query(":GAL#").and_then(|r| parse_alt_limit(&r));
+
+    log::info!(
+        "iOptron firmware: mainboard {}, hand controller {}, motors {}/{}",
+        mainboard_fw.as_deref().unwrap_or("?"),
+        hand_controller_fw.as_deref().unwrap_or("?"),
+        ra_motor_fw.as_deref().unwrap_or("?"),
+        dec_motor_fw.as_deref().unwrap_or("?")
+    );
+
+    Capabilities{ mainboard_fw, hand_controller_fw, ra_motor_fw, dec_motor_fw, alt_limit }
+}
+
+/// Parses reply to `:FW1#` or `:FW2#` ("YYMMDDYYMMDD#") into two firmware dates.
+fn parse_firmware_dates(reply: &[u8]) -> Option<(String, String)> {
+    let s = std::str::from_utf8(reply).ok()?.strip_suffix('#')?;
+    if s.len() != 12 || !s.chars().all(|c| c.is_ascii_digit()) { return None; }
+    Some((s[..6].to_string(), s[6..].to_string()))
+}
+
+/// Parses combined replies to `:P0#` and `:P1#` (axis positions in 0.01″ units).
+fn parse_positions(reply: &[u8]) -> Result<(i32, i32), Box<dyn Error>> {
+    if reply.len() != 2 * POSITION_REPLY_LEN {
+        return Err(format!("invalid position reply length: {}", reply.len()).into());
+    }
+    let parse = |r: &[u8]| -> Result<i32, Box<dyn Error>> {
+        Ok(std::str::from_utf8(&r[..POSITION_REPLY_LEN - 1])?.parse::<i32>()?)
+    };
+    Ok((parse(&reply[..POSITION_REPLY_LEN])?, parse(&reply[POSITION_REPLY_LEN..])?))
+}
+
+/// Parses reply to `:GAL#` ("snn#").
+fn parse_alt_limit(reply: &[u8]) -> Option<i32> {
+    std::str::from_utf8(reply).ok()?.strip_suffix('#')?.parse::<i32>().ok()
+}
+
+/// Returns max. slew speed (°/s) of mount model with the specified ID.
+fn max_speed_from_id(id: &str) -> Option<f64> {
+    // all currently supported models slew at up to 1440× sidereal rate
+    match id {
+        "0026" | "0027" | "0028" | "0029" |
+        "0033" | "0034" | "8033" | "8034" |
+        "0035" | "8035" |
+        "0040" | "0041" | "0043" | "0044" |
+        "0050" | "0051" | "8050" | "8051" |
+        "0052" | "8052" |
+        "0066" | "0068" | "8064" |
+        "0070" | "0071" |
+        "0120" | "0121" | "0122" => Some(6.0),
+        _ => None
+    }
+}
+
 fn toggle_special_mode<T: std::io::Read + std::io::Write>(device: &mut T) -> Result<(), Box<dyn Error>> {
     let id_before = send_cmd_and_get_reply(
         device,
@@ -254,3 +562,43 @@ fn send_cmd_and_get_reply<T: std::io::Read + std::io::Write>(
 
     Ok(buf)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn firmware_reply_parsing() {
+        assert_eq!(Some(("210105".into(), "210203".into())), parse_firmware_dates(b"210105210203#"));
+        assert_eq!(None, parse_firmware_dates(b"2101052102#"));
+        assert_eq!(None, parse_firmware_dates(b"210105210203"));
+        assert_eq!(Some(-10), parse_alt_limit(b"-10#"));
+        assert_eq!(Some(5), parse_alt_limit(b"+05#"));
+    }
+
+    #[test]
+    fn position_reply_parsing() {
+        assert_eq!((123456, -5000), parse_positions(b"+000123456#-000005000#").unwrap());
+        assert!(parse_positions(b"+000123456#").is_err());
+        assert!(parse_positions(b"+000123456#-00000x000#").is_err());
+    }
+
+    #[test]
+    fn alt_limit_check() {
+        let limit = deg(10.0);
+        assert!(exceeds_alt_limit(Some(deg(9.9)), deg_per_s(-0.1), limit));
+        assert!(exceeds_alt_limit(None, deg_per_s(-0.1), limit));
+        assert!(!exceeds_alt_limit(Some(deg(9.9)), deg_per_s(0.1), limit));
+        assert!(!exceeds_alt_limit(Some(deg(10.1)), deg_per_s(-0.1), limit));
+    }
+
+    #[test]
+    fn raw_commands() {
+        assert!(is_query_command(":GAL#"));
+        assert!(is_query_command(" :FW1# "));
+        assert!(!is_query_command(":ZZZ#"));
+        assert!(!is_query_command(":MP1#"));
+        assert_eq!(b"210105210203#".to_vec(), read_raw_reply(&mut std::io::Cursor::new(b"210105210203#1")));
+        assert_eq!(b"1".to_vec(), read_raw_reply(&mut std::io::Cursor::new(b"1")));
+    }
+}
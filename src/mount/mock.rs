@@ -0,0 +1,69 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Mount used in unit tests: axes move at exactly the commanded speeds, and time only passes via `advance`.
+
+use crate::{data::{as_deg_per_s, deg, deg_per_s}, mount::{Axis, Mount}};
+use pointing_utils::uom;
+use std::error::Error;
+use uom::si::f64;
+
+pub struct MockMount {
+    pos: [f64::Angle; 2],
+    speed: [f64::AngularVelocity; 2],
+    max_speed: Option<f64::AngularVelocity>
+}
+
+impl MockMount {
+    pub fn new(axis1: f64::Angle, axis2: f64::Angle, max_speed: Option<f64::AngularVelocity>) -> MockMount {
+        MockMount{ pos: [axis1, axis2], speed: [deg_per_s(0.0); 2], max_speed }
+    }
+
+    /// Moves the axes at their current speeds for `secs` seconds.
+    pub fn advance(&mut self, secs: f64) {
+        for (pos, speed) in self.pos.iter_mut().zip(self.speed.iter()) {
+            *pos += deg(as_deg_per_s(*speed) * secs);
+        }
+    }
+
+    pub fn speed(&self) -> (f64::AngularVelocity, f64::AngularVelocity) { (self.speed[0], self.speed[1]) }
+}
+
+impl Mount for MockMount {
+    fn get_info(&self) -> String { "mock mount".into() }
+
+    fn max_slew_speed(&self) -> Option<f64::AngularVelocity> { self.max_speed }
+
+    fn slew(&mut self, axis1: f64::AngularVelocity, axis2: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        self.speed = [axis1, axis2];
+        Ok(())
+    }
+
+    fn slew_axis(&mut self, axis: Axis, speed: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        self.speed[if axis == Axis::Primary { 0 } else { 1 }] = speed;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        self.slew(deg_per_s(0.0), deg_per_s(0.0))
+    }
+
+    fn position(&mut self) -> Result<(f64::Angle, f64::Angle), Box<dyn Error>> {
+        Ok((self.pos[0], self.pos[1]))
+    }
+}
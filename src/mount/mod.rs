@@ -16,16 +16,202 @@
 // along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
 //
 
+mod async_mount;
+mod drift;
+mod internal_simulator;
 mod ioptron;
+#[cfg(test)]
+mod mock;
+mod motion;
+mod nudge;
+mod rate_calibration;
+mod reference;
 mod simulator;
+mod swapped;
+mod throttle;
+mod watchdog;
 
-use crate::data;
+use crate::{clock, data};
 use pointing_utils::uom;
 use std::{error::Error, rc::Rc};
 use uom::si::f64;
 
+pub use async_mount::AsyncMount;
+pub use internal_simulator::InternalSimulator;
 pub use ioptron::Ioptron;
+#[cfg(test)]
+pub use mock::MockMount;
+pub use rate_calibration::RateCalibration;
+pub use reference::{fit_offsets, OffsetFit, RefSighting};
 pub use simulator::Simulator;
+pub use swapped::SwappedAxes;
+pub use watchdog::Watchdog;
+
+/// Default max. total travel of each axis (from the zero position).
+pub const DEFAULT_MAX_TRAVEL_DEG: f64 = 360.0;
+
+const PUSH_TO_ERROR: &str = "slewing disabled in push-to mode";
+
+/// Registration entry of a mount backend (driver).
+pub struct MountBackend {
+    /// Identifier used in the configuration file and on the command line (matched case-insensitively).
+    pub id: &'static str,
+    /// Name shown in the UI.
+    pub name: &'static str,
+    /// Description of the connection parameter shown in the UI.
+    pub param_descr: &'static str,
+    /// If true, the connection parameter may be empty.
+    pub param_optional: bool,
+    /// Configuration key under which the last used connection parameter is stored.
+    pub config_key: &'static str,
+    pub connect: fn(&str) -> Result<Box<dyn Mount>, Box<dyn Error>>
+}
+
+/// Available mount backends; a new driver only needs to be added here.
+static BACKENDS: [MountBackend; 3] = [
+    MountBackend{
+        id: "Simulator",
+        name: "Simulator",
+        param_descr: "address and port:",
+        param_optional: false,
+        config_key: "MountSimulatorAddr",
+        connect: Simulator::new
+    },
+    MountBackend{
+        id: "Ioptron",
+        name: "iOptron",
+        param_descr: "Serial device (e.g., \"/dev/ttyUSB0\" on Linux\nor \"COM3\" on Windows):",
+        param_optional: false,
+        config_key: "MountIoptronDevice",
+        connect: Ioptron::new
+    },
+    MountBackend{
+        id: "InternalSimulator",
+        name: "Internal simulator",
+        param_descr: "Parameters (optional; e.g.,\n\"max_speed=10, accel=5, noise=0.001,\nlatency=50, drop=0.05\"):",
+        param_optional: true,
+        config_key: "MountInternalSimulatorParams",
+        connect: InternalSimulator::new
+    }
+];
+
+/// Identifies a registered mount backend.
+#[derive(Copy, Clone)]
+pub struct MountType(&'static MountBackend);
+
+impl MountType {
+    /// Returns all registered mount types.
+    pub fn all() -> Vec<MountType> {
+        BACKENDS.iter().map(MountType).collect()
+    }
+
+    pub fn backend(&self) -> &'static MountBackend { self.0 }
+
+    pub fn id(&self) -> &'static str { self.0.id }
+
+    /// Returns identifier of a mount of this type connected via `connection_param`.
+    pub fn mount_id(&self, connection_param: &str) -> String {
+        format!("{}:{}", self.id(), connection_param)
+    }
+
+    /// Connects to a mount.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection_param` - Backend-specific connection parameter (see `MountBackend::param_descr`).
+    ///
+    #[must_use]
+    pub fn connect(&self, connection_param: &str) -> Result<Box<dyn Mount>, Box<dyn Error>> {
+        (self.0.connect)(connection_param)
+    }
+
+    /// Connects to a mount (see `connect`); if `axes_swapped` is true, the roles of its axes are exchanged
+    /// (see `SwappedAxes`).
+    #[must_use]
+    pub fn connect_with_axes(
+        &self,
+        connection_param: &str,
+        axes_swapped: bool
+    ) -> Result<Box<dyn Mount>, Box<dyn Error>> {
+        let mount = self.connect(connection_param)?;
+        Ok(if axes_swapped { Box::new(SwappedAxes::new(mount)) } else { mount })
+    }
+}
+
+impl PartialEq for MountType {
+    fn eq(&self, other: &MountType) -> bool { self.id() == other.id() }
+}
+
+impl std::fmt::Debug for MountType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", self.id())
+    }
+}
+
+impl std::str::FromStr for MountType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<MountType, String> {
+        BACKENDS.iter()
+            .find(|backend| backend.id.eq_ignore_ascii_case(s))
+            .map(MountType)
+            .ok_or_else(|| format!("unknown mount type \"{}\"", s))
+    }
+}
+
+impl From<MountType> for &'static str {
+    fn from(mount_type: MountType) -> &'static str { mount_type.id() }
+}
+
+impl From<&MountType> for &'static str {
+    fn from(mount_type: &MountType) -> &'static str { mount_type.id() }
+}
+
+/// Specifies to which mount(s) slewing and tracking commands are sent (if a secondary mount is connected).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum MountRouting {
+    #[default]
+    Main,
+    Secondary,
+    Both
+}
+
+impl MountRouting {
+    pub fn next(&self) -> MountRouting {
+        match self {
+            MountRouting::Main => MountRouting::Secondary,
+            MountRouting::Secondary => MountRouting::Both,
+            MountRouting::Both => MountRouting::Main
+        }
+    }
+
+    /// Returns the mounts (out of `main` and `secondary`) to which commands are routed.
+    pub fn routed<'a, T>(&self, main: &'a T, secondary: &'a T) -> Vec<&'a T> {
+        match self {
+            MountRouting::Main => vec![main],
+            MountRouting::Secondary => vec![secondary],
+            MountRouting::Both => vec![main, secondary]
+        }
+    }
+
+    /// Returns the mount to be used by operations concerning a single mount (e.g., setting reference position).
+    pub fn selected<'a, T>(&self, main: &'a T, secondary: &'a T) -> &'a T {
+        match self {
+            MountRouting::Secondary => secondary,
+            MountRouting::Main | MountRouting::Both => main
+        }
+    }
+}
+
+impl std::fmt::Display for MountRouting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", match self {
+            MountRouting::Main => "main",
+            MountRouting::Secondary => "secondary",
+            MountRouting::Both => "both",
+        })
+    }
+}
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum Axis {
@@ -45,6 +231,12 @@ impl std::fmt::Display for Axis {
 pub trait Mount {
     fn get_info(&self) -> String;
 
+    /// Returns detailed information (name, value), e.g., firmware versions.
+    fn details(&self) -> Vec<(String, String)> { vec![] }
+
+    /// Returns current diagnostic values (name, value), e.g., communication latency.
+    fn diagnostics(&self) -> Vec<(String, String)> { vec![] }
+
     #[must_use]
     fn slew(&mut self, axis1: f64::AngularVelocity, axis2: f64::AngularVelocity) -> Result<(), Box<dyn Error>>;
 
@@ -57,6 +249,67 @@ pub trait Mount {
     /// Returns position of primary and secondary axes.
     #[must_use]
     fn position(&mut self) -> Result<(f64::Angle, f64::Angle), Box<dyn Error>>;
+
+    /// Returns max. axis speed supported by the mount (`None` if unknown).
+    fn max_slew_speed(&self) -> Option<f64::AngularVelocity> { None }
+
+    /// Returns the step of axis speeds accepted by the mount (`None` if continuous or unknown).
+    fn rate_resolution(&self) -> Option<f64::AngularVelocity> { None }
+
+    /// Moves the mount to its park position (if supported; otherwise only stops it).
+    #[must_use]
+    fn park(&mut self) -> Result<(), Box<dyn Error>> { self.stop() }
+
+    /// Prepares a parked mount for slewing.
+    #[must_use]
+    fn unpark(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+
+    /// Sets min. altitude (position of the secondary axis) to be enforced by the mount driver (if supported);
+    /// `None`: use the limit configured in the mount (if any).
+    fn set_altitude_limit(&mut self, _limit: Option<f64::Angle>) {}
+
+    /// Returns a condition the user should be warned about (e.g., reached altitude limit).
+    fn warning(&self) -> Option<String> { None }
+
+    /// Sends a raw protocol command via the mount's transport and returns the reply (for diagnostics).
+    #[must_use]
+    fn raw_command(&mut self, _command: &str) -> Result<String, Box<dyn Error>> {
+        Err("raw commands not supported by this mount".into())
+    }
+
+    /// Returns true if `command` only queries the mount (i.e., is safe to send without confirmation).
+    fn is_query_command(&self, _command: &str) -> bool { false }
+}
+
+/// Placeholder for a mount whose connection has been lost.
+struct Disconnected {
+    info: String
+}
+
+impl Mount for Disconnected {
+    fn get_info(&self) -> String { format!("{} (disconnected)", self.info) }
+
+    fn slew(&mut self, _: f64::AngularVelocity, _: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        Err("mount disconnected".into())
+    }
+
+    fn slew_axis(&mut self, _: Axis, _: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        Err("mount disconnected".into())
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> { Err("mount disconnected".into()) }
+
+    fn position(&mut self) -> Result<(f64::Angle, f64::Angle), Box<dyn Error>> { Err("mount disconnected".into()) }
+}
+
+/// Mount calibration to be retained across program restarts.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PersistentState {
+    pub axis_ofs: (f64::Angle, f64::Angle),
+    pub zero_pos: Option<(f64::Angle, f64::Angle)>,
+    pub total_axis_travel: (f64::Angle, f64::Angle),
+    /// Tracking adjustment (direction relative to target's motion, angular offset); not managed by `MountWrapper`.
+    pub adjustment: Option<(f64::Angle, f64::Angle)>
 }
 
 /// Params: mount wrapper, axis1 travel exceeded, axis2 travel exceeded.
@@ -69,20 +322,161 @@ pub struct MountWrapper {
     /// User-specified zero position (in terms of mount's internal axes' positions).
     zero_pos: Option<(f64::Angle, f64::Angle)>,
     total_axis_travel: (f64::Angle, f64::Angle),
+    /// Max. absolute total travel of each axis; the max travel exceeded callback is called once exceeded.
+    max_travel: f64::Angle,
     last_pos: Option<(f64::Angle, f64::Angle)>,
     max_travel_exceeded_callback: Option<Rc<AxisTravelExceeded>>,
+    /// Lifetime axis travel; unlike `total_axis_travel`, never reset.
+    odometer: data::MountOdometer,
+    /// If set, slewing speed changes are acceleration-limited.
+    motion: Option<motion::MotionShaper>,
+    /// If true, slewing is refused (and the last known position is reported if the mount does not report it).
+    parked: bool,
+    /// If true, the mount is moved by hand (encoders-only "push-to" operation): slewing is refused,
+    /// but the position is still read.
+    push_to: bool,
+    /// Diagnostic comparison of commanded and reported motion (if enabled).
+    drift: Option<drift::DriftMonitor>,
+    /// Actual / commanded speed of primary and secondary axes; commanded speeds are divided by it.
+    rate_scale: (f64, f64),
+    /// Last speeds commanded to primary and secondary axes (before applying `rate_scale`).
+    commanded: [Option<f64::AngularVelocity>; 2],
+    /// Closed-loop move by a specified offset in progress.
+    nudge: Option<nudge::Nudge>,
+    throttle: throttle::CommandThrottle,
+    /// User-specified altitude limit (re-applied after reconnecting).
+    alt_limit: Option<f64::Angle>,
 }
 
 impl MountWrapper {
     pub fn new(wrapped: Box<dyn Mount>) -> MountWrapper {
+        let odometer = data::MountOdometer{ mount: wrapped.get_info(), travel: (data::deg(0.0), data::deg(0.0)) };
         MountWrapper{
             wrapped,
             axis1_ofs: data::deg(0.0),
             axis2_ofs: data::deg(0.0),
             zero_pos: None,
             total_axis_travel: (data::deg(0.0), data::deg(0.0)),
+            max_travel: data::deg(DEFAULT_MAX_TRAVEL_DEG),
             last_pos: None,
             max_travel_exceeded_callback: None,
+            odometer,
+            motion: None,
+            parked: false,
+            push_to: false,
+            drift: None,
+            rate_scale: (1.0, 1.0),
+            commanded: [None; 2],
+            nudge: None,
+            throttle: throttle::CommandThrottle::new(),
+            alt_limit: None,
+        }
+    }
+
+    /// Enables or disables monitoring of axis drift (see `drift`).
+    pub fn set_drift_monitor(&mut self, enabled: bool) {
+        self.drift = if enabled { Some(drift::DriftMonitor::new()) } else { None };
+    }
+
+    /// Returns accumulated difference between reported and commanded motion of the axes (if monitoring is enabled).
+    pub fn drift(&self) -> Option<(f64::Angle, f64::Angle)> {
+        self.drift.as_ref().map(|d| d.drift())
+    }
+
+    pub fn reset_drift(&mut self) {
+        if let Some(drift) = self.drift.as_mut() { drift.reset(); }
+    }
+
+    /// Returns the last speed commanded to `axis` (`None` if unknown, e.g., after reconnecting).
+    pub fn commanded_speed(&self, axis: Axis) -> Option<f64::AngularVelocity> {
+        self.commanded[axis as usize]
+    }
+
+    fn notify_commanded(&mut self, axis: Axis, speed: f64::AngularVelocity) {
+        self.commanded[axis as usize] = Some(speed);
+        if let Some(drift) = self.drift.as_mut() { drift.on_commanded(axis, speed, clock::now()); }
+    }
+
+    pub fn rate_scale(&self) -> (f64, f64) { self.rate_scale }
+
+    pub fn axis_rate_scale(&self, axis: Axis) -> f64 {
+        match axis { Axis::Primary => self.rate_scale.0, Axis::Secondary => self.rate_scale.1 }
+    }
+
+    /// Sets per-axis rate scale (actual / commanded speed; see `RateCalibration`).
+    pub fn set_rate_scale(&mut self, value: (f64, f64)) {
+        self.rate_scale = value;
+    }
+
+    /// Sets min. change of axis speed to be commanded to the mount (smaller changes are skipped).
+    pub fn set_command_epsilon(&mut self, value: f64::AngularVelocity) {
+        self.throttle.set_epsilon(value);
+    }
+
+    /// Converts requested speed to the speed to be commanded to the wrapped mount.
+    fn scaled(&self, axis: Axis, speed: f64::AngularVelocity) -> f64::AngularVelocity {
+        self.limit_speed(speed / self.axis_rate_scale(axis))
+    }
+
+    /// Sets max. acceleration (°/s²) of slewing (`None`: speed changes are applied immediately).
+    /// Note that `stop` always stops the mount immediately.
+    pub fn set_max_acceleration(&mut self, value: Option<f64>) {
+        self.motion = value.filter(|v| *v > 0.0).map(motion::MotionShaper::new);
+    }
+
+    /// Commands the next step of an acceleration-limited speed change (if any); to be called periodically.
+    pub fn update_motion(&mut self) -> Result<(), Box<dyn Error>> {
+        let speeds = match self.motion.as_mut() {
+            Some(motion) => motion.step(clock::now()),
+            None => return Ok(())
+        };
+        for (axis, speed) in [Axis::Primary, Axis::Secondary].into_iter().zip(speeds) {
+            if let Some(speed) = speed {
+                let commanded = self.scaled(axis, speed);
+                self.wrapped.slew_axis(axis, commanded)?;
+                self.notify_commanded(axis, speed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts moving `axis` by `offset` (see `update_nudge`).
+    pub fn start_nudge(&mut self, axis: Axis, offset: f64::Angle) -> Result<(), Box<dyn Error>> {
+        if self.parked { return Err("mount is parked".into()); }
+        if self.push_to { return Err(PUSH_TO_ERROR.into()); }
+        let pos = self.position()?;
+        let current = match axis { Axis::Primary => pos.0, Axis::Secondary => pos.1 };
+        self.nudge = Some(nudge::Nudge::new(axis, current + offset));
+        Ok(())
+    }
+
+    pub fn is_nudging(&self) -> bool { self.nudge.is_some() }
+
+    /// Advances the move started by `start_nudge` using position feedback; to be called periodically.
+    /// Returns the result once the move has finished.
+    pub fn update_nudge(&mut self) -> Option<Result<(), Box<dyn Error>>> {
+        let axis = self.nudge.as_ref()?.axis;
+        let result = self.nudge_step();
+        if let Some(result) = &result {
+            self.nudge = None;
+            let stopped = self.slew_axis(axis, data::deg_per_s(0.0));
+            if result.is_ok() { if let Err(e) = stopped { return Some(Err(e)); } }
+        }
+        result
+    }
+
+    fn nudge_step(&mut self) -> Option<Result<(), Box<dyn Error>>> {
+        let pos = match self.position() {
+            Ok(pos) => pos,
+            Err(e) => return Some(Err(e))
+        };
+        let nudge = self.nudge.as_ref()?;
+        let axis = nudge.axis;
+        let current = match axis { Axis::Primary => pos.0, Axis::Secondary => pos.1 };
+        match nudge.step(current, self.max_slew_speed()) {
+            nudge::NudgeStep::Slew(speed) => self.slew_axis(axis, speed).err().map(Err),
+            nudge::NudgeStep::Finished => Some(Ok(())),
+            nudge::NudgeStep::TimedOut => Some(Err("destination not reached in time".into()))
         }
     }
 
@@ -92,12 +486,25 @@ impl MountWrapper {
     }
 
     pub fn set_reference_position(&mut self, axis1: f64::Angle, axis2: f64::Angle) -> Result<(), Box<dyn Error>> {
-        let (internal1, internal2) = self.wrapped.position()?;
-        self.axis1_ofs = axis1 - internal1;
-        self.axis2_ofs = axis2 - internal2;
+        let sighting = self.capture_sighting(axis1, axis2)?;
+        self.set_reference_sightings(&[sighting]);
         Ok(())
     }
 
+    /// Records that the mount currently points at the reference position `axis1`, `axis2`.
+    pub fn capture_sighting(&mut self, axis1: f64::Angle, axis2: f64::Angle) -> Result<RefSighting, Box<dyn Error>> {
+        let (internal1, internal2) = self.wrapped.position()?;
+        Ok(RefSighting{ reference: (axis1, axis2), internal: (internal1, internal2) })
+    }
+
+    /// Sets axis offsets fitted to `sightings` (which must not be empty).
+    pub fn set_reference_sightings(&mut self, sightings: &[RefSighting]) -> OffsetFit {
+        let fit = reference::fit_offsets(sightings);
+        self.axis1_ofs = fit.offsets.0;
+        self.axis2_ofs = fit.offsets.1;
+        fit
+    }
+
     pub fn zero_position(&self) -> &Option<(f64::Angle, f64::Angle)> { &self.zero_pos }
 
     pub fn set_zero_position(&mut self) -> Result<(), Box<dyn Error>> {
@@ -106,6 +513,7 @@ impl MountWrapper {
             if let Ok((internal1, internal2)) = self.wrapped.position() {
                 self.zero_pos = Some((internal1, internal2));
                 self.total_axis_travel = (data::deg(0.0), data::deg(0.0));
+                self.reset_drift();
                 return Ok(());
             }
         }
@@ -115,6 +523,79 @@ impl MountWrapper {
     pub fn total_axis_travel(&self) -> (f64::Angle, f64::Angle) {
         self.total_axis_travel
     }
+
+    /// Resets the total axis travel (e.g., after untangling the cables) without changing the zero position.
+    pub fn reset_axis_travel(&mut self) {
+        self.total_axis_travel = (data::deg(0.0), data::deg(0.0));
+    }
+
+    pub fn odometer(&self) -> &data::MountOdometer { &self.odometer }
+
+    /// Restores lifetime axis travel (e.g., saved in a previous session).
+    pub fn set_odometer(&mut self, value: data::MountOdometer) { self.odometer = value; }
+
+    pub fn max_travel(&self) -> f64::Angle { self.max_travel }
+
+    pub fn set_max_travel(&mut self, value: f64::Angle) { self.max_travel = value; }
+
+    pub fn is_parked(&self) -> bool { self.parked }
+
+    pub fn is_push_to(&self) -> bool { self.push_to }
+
+    /// Enables or disables push-to mode (see `push_to`); the mount is stopped before enabling.
+    pub fn set_push_to(&mut self, enabled: bool) -> Result<(), Box<dyn Error>> {
+        if enabled == self.push_to { return Ok(()); }
+        if enabled {
+            self.stop()?;
+            if let Some(drift) = self.drift.as_mut() { drift.interrupt(); }
+        } else {
+            self.notify_commanded(Axis::Primary, data::deg_per_s(0.0));
+            self.notify_commanded(Axis::Secondary, data::deg_per_s(0.0));
+        }
+        self.push_to = enabled;
+        Ok(())
+    }
+
+    fn limit_speed(&self, speed: f64::AngularVelocity) -> f64::AngularVelocity {
+        match self.wrapped.max_slew_speed() {
+            Some(max) => speed.min(max).max(-max),
+            None => speed
+        }
+    }
+
+    /// Returns reference & zero position and travel information (without adjustment).
+    pub fn persistent_state(&self) -> PersistentState {
+        PersistentState{
+            axis_ofs: (self.axis1_ofs, self.axis2_ofs),
+            zero_pos: self.zero_pos,
+            total_axis_travel: self.total_axis_travel,
+            adjustment: None
+        }
+    }
+
+    /// Restores reference & zero position and travel information saved with `persistent_state`.
+    pub fn restore_state(&mut self, state: &PersistentState) {
+        (self.axis1_ofs, self.axis2_ofs) = state.axis_ofs;
+        self.zero_pos = state.zero_pos;
+        self.total_axis_travel = state.total_axis_travel;
+    }
+
+    /// Closes connection to the mount, but keeps reference & zero position and travel information
+    /// (to be reused by `reconnect`).
+    pub fn disconnect(&mut self) {
+        let info = self.wrapped.get_info();
+        self.wrapped = Box::new(Disconnected{ info });
+        self.commanded = [None; 2];
+    }
+
+    /// Replaces the (disconnected) mount with a newly connected one; assumes it is the same physical mount.
+    pub fn reconnect(&mut self, mount: Box<dyn Mount>) {
+        self.wrapped = mount;
+        if self.alt_limit.is_some() { self.wrapped.set_altitude_limit(self.alt_limit); }
+        self.commanded = [None; 2];
+        if let Some(motion) = self.motion.as_mut() { motion.reset(); }
+        if let Some(drift) = self.drift.as_mut() { drift.interrupt(); }
+    }
 }
 
 impl Mount for MountWrapper {
@@ -122,27 +603,93 @@ impl Mount for MountWrapper {
         self.wrapped.get_info()
     }
 
+    fn details(&self) -> Vec<(String, String)> {
+        self.wrapped.details()
+    }
+
+    fn diagnostics(&self) -> Vec<(String, String)> {
+        let mut result = self.wrapped.diagnostics();
+        result.extend(self.throttle.diagnostics());
+        result
+    }
+
+    fn max_slew_speed(&self) -> Option<f64::AngularVelocity> {
+        self.wrapped.max_slew_speed()
+    }
+
+    fn rate_resolution(&self) -> Option<f64::AngularVelocity> {
+        self.wrapped.rate_resolution()
+    }
+
     fn slew(&mut self, axis1: f64::AngularVelocity, axis2: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
-        self.wrapped.slew(axis1, axis2)
+        if self.parked { return Err("mount is parked".into()); }
+        if self.push_to { return Err(PUSH_TO_ERROR.into()); }
+        let (axis1, axis2) = (self.limit_speed(axis1), self.limit_speed(axis2));
+        match self.motion.as_mut() {
+            Some(motion) => {
+                motion.set_requested(Axis::Primary, axis1);
+                motion.set_requested(Axis::Secondary, axis2);
+                self.update_motion()
+            },
+            None => {
+                let send1 = self.throttle.should_send(self.commanded_speed(Axis::Primary), axis1);
+                let send2 = self.throttle.should_send(self.commanded_speed(Axis::Secondary), axis2);
+                if !send1 && !send2 { return Ok(()); }
+                let commanded = (self.scaled(Axis::Primary, axis1), self.scaled(Axis::Secondary, axis2));
+                self.wrapped.slew(commanded.0, commanded.1)?;
+                self.notify_commanded(Axis::Primary, axis1);
+                self.notify_commanded(Axis::Secondary, axis2);
+                Ok(())
+            }
+        }
     }
 
     fn slew_axis(&mut self, axis: Axis, speed: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
-        self.wrapped.slew_axis(axis, speed)
+        if self.parked { return Err("mount is parked".into()); }
+        if self.push_to { return Err(PUSH_TO_ERROR.into()); }
+        let speed = self.limit_speed(speed);
+        match self.motion.as_mut() {
+            Some(motion) => {
+                motion.set_requested(axis, speed);
+                self.update_motion()
+            },
+            None => {
+                if !self.throttle.should_send(self.commanded_speed(axis), speed) { return Ok(()); }
+                let commanded = self.scaled(axis, speed);
+                self.wrapped.slew_axis(axis, commanded)?;
+                self.notify_commanded(axis, speed);
+                Ok(())
+            }
+        }
     }
 
     fn stop(&mut self) -> Result<(), Box<dyn Error>> {
-        self.wrapped.stop()
+        // always forwarded, as a mount may still be moving to its park position
+        self.wrapped.stop()?;
+        if self.parked || self.push_to { return Ok(()); }
+        if let Some(motion) = self.motion.as_mut() { motion.reset(); }
+        self.nudge = None;
+        self.notify_commanded(Axis::Primary, data::deg_per_s(0.0));
+        self.notify_commanded(Axis::Secondary, data::deg_per_s(0.0));
+        Ok(())
     }
 
     fn position(&mut self) -> Result<(f64::Angle, f64::Angle), Box<dyn Error>> {
-        let (internal1, internal2) = self.wrapped.position()?;
+        let (internal1, internal2) = match self.wrapped.position() {
+            Ok(pos) => pos,
+            // a parked mount may not report its position (e.g., iOptron outside special mode)
+            Err(_) if self.parked && self.last_pos.is_some() => self.last_pos.unwrap(),
+            Err(e) => return Err(e)
+        };
         if let Some((last_axis1_pos, last_axis2_pos)) = self.last_pos {
-            let max_travel = data::deg(360.0); // TODO: make it configurable
+            let max_travel = self.max_travel;
 
             let was_axis1_exceeded = self.total_axis_travel.0.abs() > max_travel;
             let was_axis2_exceeded = self.total_axis_travel.1.abs() > max_travel;
             self.total_axis_travel.0 += data::angle_diff(last_axis1_pos, internal1);
             self.total_axis_travel.1 += data::angle_diff(last_axis2_pos, internal2);
+            self.odometer.travel.0 += data::angle_diff(last_axis1_pos, internal1).abs();
+            self.odometer.travel.1 += data::angle_diff(last_axis2_pos, internal2).abs();
             let axis1_exceeded = self.total_axis_travel.0.abs() > max_travel;
             let axis2_exceeded = self.total_axis_travel.1.abs() > max_travel;
 
@@ -152,6 +699,46 @@ impl Mount for MountWrapper {
             }
         }
         self.last_pos = Some((internal1, internal2));
+        if !self.parked && !self.push_to {
+            if let Some(drift) = self.drift.as_mut() {
+                drift.on_position((internal1, internal2), clock::now());
+            }
+        }
         Ok((self.axis1_ofs + internal1, self.axis2_ofs + internal2))
     }
+
+    fn park(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.parked { return Ok(()); }
+        if let Some(motion) = self.motion.as_mut() { motion.reset(); }
+        self.wrapped.park()?;
+        self.parked = true;
+        if let Some(drift) = self.drift.as_mut() { drift.interrupt(); }
+        Ok(())
+    }
+
+    fn unpark(&mut self) -> Result<(), Box<dyn Error>> {
+        if !self.parked { return Ok(()); }
+        self.wrapped.unpark()?;
+        self.parked = false;
+        self.notify_commanded(Axis::Primary, data::deg_per_s(0.0));
+        self.notify_commanded(Axis::Secondary, data::deg_per_s(0.0));
+        Ok(())
+    }
+
+    fn set_altitude_limit(&mut self, limit: Option<f64::Angle>) {
+        self.alt_limit = limit;
+        self.wrapped.set_altitude_limit(limit)
+    }
+
+    fn warning(&self) -> Option<String> {
+        self.wrapped.warning()
+    }
+
+    fn raw_command(&mut self, command: &str) -> Result<String, Box<dyn Error>> {
+        self.wrapped.raw_command(command)
+    }
+
+    fn is_query_command(&self, command: &str) -> bool {
+        self.wrapped.is_query_command(command)
+    }
 }
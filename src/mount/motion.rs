@@ -0,0 +1,123 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{data::{as_deg_per_s, deg_per_s}, mount::Axis};
+use pointing_utils::uom;
+use std::time::{Duration, Instant};
+use uom::si::f64;
+
+/// Time step assumed when starting from a steady state.
+const NOMINAL_STEP: Duration = Duration::from_millis(100);
+/// Max. time step (e.g., if updates have been delayed).
+const MAX_STEP: Duration = Duration::from_millis(500);
+
+/// Ramps commanded axis speeds towards the requested ones with limited acceleration.
+pub struct MotionShaper {
+    /// Max. acceleration (°/s²).
+    max_accel: f64,
+    /// Requested speeds of primary and secondary axes (°/s).
+    requested: [f64; 2],
+    /// Last commanded speeds of primary and secondary axes (°/s).
+    commanded: [f64; 2],
+    /// `None` if commanded speeds have reached the requested ones.
+    last_update: Option<Instant>
+}
+
+impl MotionShaper {
+    /// Creates a motion shaper with the specified max. acceleration (°/s²).
+    pub fn new(max_accel: f64) -> MotionShaper {
+        MotionShaper{ max_accel, requested: [0.0; 2], commanded: [0.0; 2], last_update: None }
+    }
+
+    pub fn set_requested(&mut self, axis: Axis, speed: f64::AngularVelocity) {
+        self.requested[index(axis)] = as_deg_per_s(speed);
+    }
+
+    /// Resets the state after the mount has been stopped.
+    pub fn reset(&mut self) {
+        self.requested = [0.0; 2];
+        self.commanded = [0.0; 2];
+        self.last_update = None;
+    }
+
+    /// Advances the speeds; returns the new speeds to be commanded (`None` for axes whose speed does not change).
+    pub fn step(&mut self, now: Instant) -> [Option<f64::AngularVelocity>; 2] {
+        let dt = match self.last_update {
+            Some(t) => now.saturating_duration_since(t).min(MAX_STEP),
+            None => NOMINAL_STEP
+        };
+        let max_change = self.max_accel * dt.as_secs_f64();
+
+        let mut result = [None; 2];
+        for i in 0..2 {
+            let new_speed = ramp(self.commanded[i], self.requested[i], max_change);
+            if new_speed != self.commanded[i] {
+                self.commanded[i] = new_speed;
+                result[i] = Some(deg_per_s(new_speed));
+            }
+        }
+
+        self.last_update = if self.commanded == self.requested { None } else { Some(now) };
+
+        result
+    }
+}
+
+fn index(axis: Axis) -> usize {
+    match axis {
+        Axis::Primary => 0,
+        Axis::Secondary => 1
+    }
+}
+
+/// Returns `current` changed towards `target` by at most `max_change`.
+pub fn ramp(current: f64, target: f64, max_change: f64) -> f64 {
+    if (target - current).abs() <= max_change {
+        target
+    } else {
+        current + max_change * (target - current).signum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramp_limits_speed_change() {
+        assert_eq!(1.5, ramp(1.0, 3.0, 0.5));
+        assert_eq!(0.5, ramp(1.0, -3.0, 0.5));
+        assert_eq!(1.2, ramp(1.0, 1.2, 0.5));
+    }
+
+    #[test]
+    fn shaper_reaches_requested_speed_and_stops_updating() {
+        let mut shaper = MotionShaper::new(1.0);
+        shaper.set_requested(Axis::Primary, deg_per_s(0.15));
+        let t0 = Instant::now();
+
+        let speeds = shaper.step(t0);
+        assert!((as_deg_per_s(speeds[0].unwrap()) - 0.1).abs() < 1.0e-9);
+        assert!(speeds[1].is_none());
+
+        let speeds = shaper.step(t0 + Duration::from_millis(100));
+        assert!((as_deg_per_s(speeds[0].unwrap()) - 0.15).abs() < 1.0e-9);
+
+        assert!(shaper.step(t0 + Duration::from_millis(200))[0].is_none());
+    }
+}
@@ -0,0 +1,89 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Closed-loop move of a mount axis by an exact angular offset.
+
+use crate::{clock, data::{angle_diff, as_deg, as_deg_per_s, deg_per_s}, mount::Axis};
+use pointing_utils::uom;
+use std::time::{Duration, Instant};
+use uom::si::f64;
+
+/// Position error below which the move is finished.
+const TOLERANCE_DEG: f64 = 0.002;
+/// Axis speed per position error (1/s).
+const GAIN: f64 = 1.0;
+const MIN_SPEED_DEG_PER_S: f64 = 0.01;
+const MAX_SPEED_DEG_PER_S: f64 = 2.0;
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct Nudge {
+    pub axis: Axis,
+    /// Destination position of the axis.
+    pub destination: f64::Angle,
+    started: Instant
+}
+
+pub enum NudgeStep {
+    /// Slew the axis at the specified speed.
+    Slew(f64::AngularVelocity),
+    Finished,
+    TimedOut
+}
+
+impl Nudge {
+    pub fn new(axis: Axis, destination: f64::Angle) -> Nudge {
+        Nudge{ axis, destination, started: clock::now() }
+    }
+
+    /// Returns what to do next given the current axis position; `max_speed` is the mount's max. axis speed.
+    pub fn step(&self, position: f64::Angle, max_speed: Option<f64::AngularVelocity>) -> NudgeStep {
+        if clock::elapsed(self.started) > TIMEOUT { return NudgeStep::TimedOut; }
+
+        let error = as_deg(angle_diff(position, self.destination));
+        if error.abs() < TOLERANCE_DEG { return NudgeStep::Finished; }
+
+        let max_speed = max_speed.map_or(MAX_SPEED_DEG_PER_S, as_deg_per_s).min(MAX_SPEED_DEG_PER_S);
+        let speed = (error.abs() * GAIN).max(MIN_SPEED_DEG_PER_S).min(max_speed);
+
+        NudgeStep::Slew(deg_per_s(error.signum() * speed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::deg;
+
+    #[test]
+    fn nudge_speed() {
+        let nudge = Nudge::new(Axis::Primary, deg(0.5));
+
+        match nudge.step(deg(357.0), None) {
+            NudgeStep::Slew(speed) => assert_eq!(MAX_SPEED_DEG_PER_S, as_deg_per_s(speed)),
+            _ => panic!("expected slewing")
+        }
+        match nudge.step(deg(0.6), Some(deg_per_s(5.0))) {
+            NudgeStep::Slew(speed) => assert!((as_deg_per_s(speed) + 0.1).abs() < 1.0e-9),
+            _ => panic!("expected slewing")
+        }
+        match nudge.step(deg(0.5001), None) {
+            NudgeStep::Finished => (),
+            _ => panic!("expected finish")
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Measurement of the actual axis rate of a mount during a timed slew.
+
+use crate::{clock, data, mount::{Axis, Mount, MountWrapper}};
+use pointing_utils::uom;
+use std::{error::Error, time::{Duration, Instant}};
+use uom::si::f64;
+
+/// Time after starting the slew before measurement begins (to skip acceleration).
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+enum Phase {
+    Settling{ until: Instant },
+    Measuring{ start_time: Instant, start_pos: f64::Angle },
+    Finished(Result<f64, String>)
+}
+
+/// Slews an axis at a constant speed and determines its rate scale (actual / commanded speed).
+pub struct RateCalibration {
+    axis: Axis,
+    speed: f64::AngularVelocity,
+    duration: Duration,
+    /// Rate scale in effect during the measurement.
+    current_scale: f64,
+    phase: Phase
+}
+
+impl RateCalibration {
+    /// Starts slewing `axis` of `mount` at `speed` for `duration` (plus settling time).
+    #[must_use]
+    pub fn start(
+        mount: &mut MountWrapper,
+        axis: Axis,
+        speed: f64::AngularVelocity,
+        duration: Duration
+    ) -> Result<RateCalibration, Box<dyn Error>> {
+        let current_scale = mount.axis_rate_scale(axis);
+        mount.slew_axis(axis, speed)?;
+
+        Ok(RateCalibration{
+            axis,
+            speed,
+            duration,
+            current_scale,
+            phase: Phase::Settling{ until: clock::now() + SETTLE_TIME }
+        })
+    }
+
+    pub fn axis(&self) -> Axis { self.axis }
+
+    /// Returns measured rate scale once finished.
+    pub fn result(&self) -> Option<&Result<f64, String>> {
+        match &self.phase { Phase::Finished(result) => Some(result), _ => None }
+    }
+
+    pub fn description(&self) -> String {
+        match &self.phase {
+            Phase::Settling{ .. } => format!("Slewing axis {}, waiting for constant speed...", self.axis),
+            Phase::Measuring{ start_time, .. } => format!(
+                "Measuring axis {}: {:.0} s left...",
+                self.axis, self.duration.saturating_sub(clock::elapsed(*start_time)).as_secs_f64()
+            ),
+            Phase::Finished(Ok(scale)) => format!("Axis {}: measured rate scale {:.4}.", self.axis, scale),
+            Phase::Finished(Err(e)) => format!("Axis {}: measurement failed: {}.", self.axis, e)
+        }
+    }
+
+    /// Advances the measurement; to be called periodically. Stops the mount when finished.
+    pub fn step(&mut self, mount: &mut MountWrapper) {
+        let result = self.try_step(mount);
+        if let Err(e) = result {
+            self.abort(Some(mount));
+            self.phase = Phase::Finished(Err(e.to_string()));
+        }
+    }
+
+    /// Stops the mount (if connected) if the measurement is in progress.
+    pub fn abort(&mut self, mount: Option<&mut MountWrapper>) {
+        if self.result().is_none() {
+            if let Some(Err(e)) = mount.map(|m| m.stop()) { log::error!("failed to stop mount: {}", e); }
+            self.phase = Phase::Finished(Err("aborted".into()));
+        }
+    }
+
+    fn try_step(&mut self, mount: &mut MountWrapper) -> Result<(), Box<dyn Error>> {
+        let axis = self.axis;
+        let axis_pos = |mount: &mut MountWrapper| -> Result<f64::Angle, Box<dyn Error>> {
+            let pos = mount.position()?;
+            Ok(match axis { Axis::Primary => pos.0, Axis::Secondary => pos.1 })
+        };
+
+        match self.phase {
+            Phase::Settling{ until } => if clock::now() >= until {
+                self.phase = Phase::Measuring{ start_time: clock::now(), start_pos: axis_pos(mount)? };
+            },
+
+            Phase::Measuring{ start_time, start_pos } => if clock::elapsed(start_time) >= self.duration {
+                let travel = data::angle_diff(start_pos, axis_pos(mount)?);
+                let elapsed = clock::elapsed(start_time);
+                mount.stop()?;
+                let scale = rate_scale(travel, elapsed, self.speed, self.current_scale)?;
+                log::info!("rate calibration of axis {}: scale {:.4}", self.axis, scale);
+                self.phase = Phase::Finished(Ok(scale));
+            },
+
+            Phase::Finished(_) => ()
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns rate scale (actual / commanded speed) from the measured travel; `current_scale` was in effect
+/// when slewing at `requested_speed`.
+fn rate_scale(
+    travel: f64::Angle,
+    elapsed: Duration,
+    requested_speed: f64::AngularVelocity,
+    current_scale: f64
+) -> Result<f64, Box<dyn Error>> {
+    let measured_speed = data::as_deg(travel) / elapsed.as_secs_f64();
+    let scale = measured_speed / data::as_deg_per_s(requested_speed) * current_scale;
+    if !(0.5..=2.0).contains(&scale) {
+        return Err(format!("implausible rate scale {:.4} (did the axis move?)", scale).into());
+    }
+
+    Ok(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{deg, deg_per_s};
+
+    #[test]
+    fn rate_scale_calculation() {
+        // axis moved 1% slower than requested
+        let scale = rate_scale(deg(19.8), Duration::from_secs(20), deg_per_s(1.0), 1.0).unwrap();
+        assert!((scale - 0.99).abs() < 1.0e-9);
+
+        // with scale already applied, the commanded speed was 1 / 0.99 °/s
+        let scale = rate_scale(deg(-10.0), Duration::from_secs(10), deg_per_s(-1.0), 0.99).unwrap();
+        assert!((scale - 0.99).abs() < 1.0e-9);
+
+        assert!(rate_scale(deg(0.0), Duration::from_secs(10), deg_per_s(1.0), 1.0).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Reference position fitted to multiple sightings.
+
+use crate::data::{angle_diff, as_deg, deg};
+use pointing_utils::uom;
+use uom::si::f64;
+
+/// Known position (e.g., of a landmark) the mount was pointed at, and the corresponding position reported
+/// by the mount.
+#[derive(Copy, Clone, Debug)]
+pub struct RefSighting {
+    /// Azimuth and altitude.
+    pub reference: (f64::Angle, f64::Angle),
+    /// Positions of primary and secondary axes reported by the mount.
+    pub internal: (f64::Angle, f64::Angle)
+}
+
+/// Result of fitting axis offsets to sightings.
+#[derive(Copy, Clone, Debug)]
+pub struct OffsetFit {
+    /// Offsets to be added to the internal positions of primary and secondary axes.
+    pub offsets: (f64::Angle, f64::Angle),
+    /// RMS residual of each axis.
+    pub rms_residual: (f64::Angle, f64::Angle)
+}
+
+/// Returns axis offsets minimizing the sum of squared residuals over `sightings` (which must not be empty).
+pub fn fit_offsets(sightings: &[RefSighting]) -> OffsetFit {
+    assert!(!sightings.is_empty());
+
+    let fit_axis = |reference: fn(&RefSighting) -> f64::Angle, internal: fn(&RefSighting) -> f64::Angle| {
+        // offsets are taken relative to the first one to avoid problems with wrap-around
+        let first = reference(&sightings[0]) - internal(&sightings[0]);
+        let deltas: Vec<f64> = sightings.iter()
+            .map(|s| as_deg(angle_diff(first, reference(s) - internal(s))))
+            .collect();
+        let n = deltas.len() as f64;
+        let mean = deltas.iter().sum::<f64>() / n;
+        let rms = (deltas.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n).sqrt();
+        (first + deg(mean), deg(rms))
+    };
+
+    let (offset1, rms1) = fit_axis(|s| s.reference.0, |s| s.internal.0);
+    let (offset2, rms2) = fit_axis(|s| s.reference.1, |s| s.internal.1);
+
+    OffsetFit{ offsets: (offset1, offset2), rms_residual: (rms1, rms2) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_are_averaged_across_wrap_around() {
+        let sightings = [
+            RefSighting{ reference: (deg(359.9), deg(10.1)), internal: (deg(0.0), deg(0.0)) },
+            RefSighting{ reference: (deg(0.1), deg(19.9)), internal: (deg(0.0), deg(10.0)) }
+        ];
+        let fit = fit_offsets(&sightings);
+
+        assert!(as_deg(angle_diff(deg(0.0), fit.offsets.0)).abs() < 1.0e-9);
+        assert!((as_deg(fit.offsets.1) - 10.0).abs() < 1.0e-9);
+        assert!((as_deg(fit.rms_residual.0) - 0.1).abs() < 1.0e-9);
+        assert!((as_deg(fit.rms_residual.1) - 0.1).abs() < 1.0e-9);
+    }
+}
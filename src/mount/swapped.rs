@@ -0,0 +1,94 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Mount with exchanged roles of axes (e.g., for an OTA attached rotated by 90°).
+
+use crate::mount::{Axis, Mount};
+use pointing_utils::uom;
+use std::error::Error;
+use uom::si::f64;
+
+/// Presents the secondary axis of the wrapped mount as primary and vice versa.
+pub struct SwappedAxes {
+    wrapped: Box<dyn Mount>
+}
+
+impl SwappedAxes {
+    pub fn new(wrapped: Box<dyn Mount>) -> SwappedAxes {
+        SwappedAxes{ wrapped }
+    }
+}
+
+fn other(axis: Axis) -> Axis {
+    match axis { Axis::Primary => Axis::Secondary, Axis::Secondary => Axis::Primary }
+}
+
+impl Mount for SwappedAxes {
+    fn get_info(&self) -> String { format!("{} (axes swapped)", self.wrapped.get_info()) }
+
+    fn details(&self) -> Vec<(String, String)> { self.wrapped.details() }
+
+    fn diagnostics(&self) -> Vec<(String, String)> { self.wrapped.diagnostics() }
+
+    fn slew(&mut self, axis1: f64::AngularVelocity, axis2: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        self.wrapped.slew(axis2, axis1)
+    }
+
+    fn slew_axis(&mut self, axis: Axis, speed: f64::AngularVelocity) -> Result<(), Box<dyn Error>> {
+        self.wrapped.slew_axis(other(axis), speed)
+    }
+
+    fn stop(&mut self) -> Result<(), Box<dyn Error>> { self.wrapped.stop() }
+
+    fn position(&mut self) -> Result<(f64::Angle, f64::Angle), Box<dyn Error>> {
+        self.wrapped.position().map(|(axis1, axis2)| (axis2, axis1))
+    }
+
+    fn max_slew_speed(&self) -> Option<f64::AngularVelocity> { self.wrapped.max_slew_speed() }
+
+    fn rate_resolution(&self) -> Option<f64::AngularVelocity> { self.wrapped.rate_resolution() }
+
+    fn park(&mut self) -> Result<(), Box<dyn Error>> { self.wrapped.park() }
+
+    fn unpark(&mut self) -> Result<(), Box<dyn Error>> { self.wrapped.unpark() }
+
+    /// Not supported: the wrapped mount's driver limits its own axis 2, which here is not the altitude axis
+    /// (only the limit configured in the mount itself remains in effect).
+    fn set_altitude_limit(&mut self, limit: Option<f64::Angle>) {
+        if limit.is_some() { log::warn!("altitude limit cannot be enforced with swapped axes; ignored"); }
+    }
+
+    fn warning(&self) -> Option<String> { self.wrapped.warning() }
+
+    fn raw_command(&mut self, command: &str) -> Result<String, Box<dyn Error>> { self.wrapped.raw_command(command) }
+
+    fn is_query_command(&self, command: &str) -> bool { self.wrapped.is_query_command(command) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data::{as_deg, deg}, mount::MockMount};
+
+    #[test]
+    fn positions_are_exchanged() {
+        let mut mount = SwappedAxes::new(Box::new(MockMount::new(deg(10.0), deg(20.0), None)));
+        let (axis1, axis2) = mount.position().unwrap();
+        assert_eq!((20.0, 10.0), (as_deg(axis1), as_deg(axis2)));
+    }
+}
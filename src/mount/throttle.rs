@@ -0,0 +1,77 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Skipping of speed commands which differ negligibly from the previous ones.
+
+use crate::data::deg_per_s;
+use pointing_utils::uom;
+use uom::si::f64;
+
+pub struct CommandThrottle {
+    /// Commands changing the axis speed by less than this are skipped (0: only identical commands are skipped).
+    epsilon: f64::AngularVelocity,
+    sent: u64,
+    skipped: u64
+}
+
+impl CommandThrottle {
+    pub fn new() -> CommandThrottle {
+        CommandThrottle{ epsilon: deg_per_s(0.0), sent: 0, skipped: 0 }
+    }
+
+    pub fn set_epsilon(&mut self, value: f64::AngularVelocity) { self.epsilon = value; }
+
+    /// Returns whether to send speed `new` to an axis last commanded `prev`; updates counters.
+    pub fn should_send(&mut self, prev: Option<f64::AngularVelocity>, new: f64::AngularVelocity) -> bool {
+        let zero = deg_per_s(0.0);
+        let negligible = match prev {
+            // stopping is never skipped
+            Some(prev) if new == zero => prev == zero,
+            Some(prev) => prev == new || (new - prev).abs() < self.epsilon,
+            None => false
+        };
+        if negligible { self.skipped += 1; } else { self.sent += 1; }
+        !negligible
+    }
+
+    /// Returns counters of sent and skipped commands (for diagnostics).
+    pub fn diagnostics(&self) -> Vec<(String, String)> {
+        vec![
+            ("Speed commands sent".into(), self.sent.to_string()),
+            ("Speed commands skipped".into(), self.skipped.to_string())
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_changes_are_skipped() {
+        let mut throttle = CommandThrottle::new();
+        throttle.set_epsilon(deg_per_s(0.001));
+
+        assert!(throttle.should_send(None, deg_per_s(0.1)));
+        assert!(!throttle.should_send(Some(deg_per_s(0.1)), deg_per_s(0.1005)));
+        assert!(throttle.should_send(Some(deg_per_s(0.1)), deg_per_s(0.102)));
+        assert!(throttle.should_send(Some(deg_per_s(0.0005)), deg_per_s(0.0)));
+        assert!(!throttle.should_send(Some(deg_per_s(0.0)), deg_per_s(0.0)));
+        assert_eq!((3, 2), (throttle.sent, throttle.skipped));
+    }
+}
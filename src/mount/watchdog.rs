@@ -0,0 +1,100 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::mount::{MountType, MountWrapper};
+use std::time::{Duration, Instant};
+
+/// Number of consecutive failed mount position queries after which the connection is considered lost.
+const MAX_CONSECUTIVE_ERRORS: usize = 8;
+
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+struct LostConnection {
+    mount: MountWrapper,
+    mount_type: MountType,
+    connection_param: String,
+    /// See `MountType::connect_with_axes`.
+    axes_swapped: bool,
+    last_attempt: Instant
+}
+
+/// Detects mount connection failures and periodically attempts to reconnect.
+#[derive(Default)]
+pub struct Watchdog {
+    consecutive_errors: usize,
+    lost: Option<LostConnection>
+}
+
+impl Watchdog {
+    pub fn notify_ok(&mut self) {
+        self.consecutive_errors = 0;
+    }
+
+    /// Returns `true` if the connection is now considered lost.
+    pub fn notify_error(&mut self) -> bool {
+        self.consecutive_errors += 1;
+        self.consecutive_errors == MAX_CONSECUTIVE_ERRORS
+    }
+
+    pub fn is_reconnecting(&self) -> bool { self.lost.is_some() }
+
+    /// Disconnects `mount` and starts reconnection attempts.
+    pub fn on_connection_lost(
+        &mut self,
+        mut mount: MountWrapper,
+        mount_type: MountType,
+        connection_param: &str,
+        axes_swapped: bool
+    ) {
+        mount.disconnect();
+        self.consecutive_errors = 0;
+        self.lost = Some(LostConnection{
+            mount,
+            mount_type,
+            connection_param: connection_param.into(),
+            axes_swapped,
+            last_attempt: Instant::now()
+        });
+    }
+
+    pub fn cancel(&mut self) {
+        self.lost = None;
+    }
+
+    /// Attempts to reconnect (if enough time has passed since the previous attempt); returns the reconnected mount
+    /// on success.
+    pub fn try_reconnect(&mut self) -> Option<MountWrapper> {
+        let lost = self.lost.as_mut()?;
+        if lost.last_attempt.elapsed() < RECONNECT_INTERVAL { return None; }
+        lost.last_attempt = Instant::now();
+
+        match lost.mount_type.connect_with_axes(&lost.connection_param, lost.axes_swapped) {
+            Ok(m) => {
+                log::info!("reconnected to {}", m.get_info());
+                let mut lost = self.lost.take().unwrap();
+                lost.mount.reconnect(m);
+                Some(lost.mount)
+            },
+
+            Err(e) => {
+                log::warn!("failed to reconnect to mount at \"{}\": {}", lost.connection_param, e);
+                None
+            }
+        }
+    }
+}
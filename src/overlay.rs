@@ -0,0 +1,148 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Text overlay output for video recording (e.g., OBS text source or ffmpeg `drawtext` with `reload=1`).
+
+use crate::data::{AngleDisplay, Target};
+use pointing_utils::uom;
+use std::{error::Error, net::UdpSocket, path::PathBuf};
+use uom::si::{f64, length, velocity};
+
+pub const DEFAULT_TEMPLATE: &str = "{name}\\nDist: {dist}  Speed: {speed}\\nAz: {az}  Alt: {alt}";
+
+/// Values substituted into an overlay template.
+pub struct OverlayValues<'a> {
+    pub time: chrono::DateTime<chrono::Local>,
+    /// Target source name or data source address.
+    pub name: Option<String>,
+    pub target: Option<&'a Target>,
+    /// Mount axes' positions.
+    pub mount: Option<(f64::Angle, f64::Angle)>,
+    pub tracking: bool,
+    pub angle_display: AngleDisplay
+}
+
+enum Destination {
+    File(PathBuf),
+    Udp(UdpSocket)
+}
+
+pub struct Overlay {
+    destination: Destination,
+    template: String,
+    last_text: Option<String>
+}
+
+impl Overlay {
+    /// Creates an overlay writing to a file or, if `output` is "udp://<host>:<port>", sending UDP datagrams.
+    #[must_use]
+    pub fn new(output: &str, template: &str) -> Result<Overlay, Box<dyn Error>> {
+        let destination = match output.strip_prefix("udp://") {
+            Some(address) => {
+                let socket = UdpSocket::bind("0.0.0.0:0")?;
+                socket.connect(address)?;
+                Destination::Udp(socket)
+            },
+            None => Destination::File(PathBuf::from(output))
+        };
+
+        Ok(Overlay{ destination, template: template.replace("\\n", "\n"), last_text: None })
+    }
+
+    pub fn description(&self) -> String {
+        match &self.destination {
+            Destination::File(path) => path.to_string_lossy().into(),
+            Destination::Udp(socket) =>
+                format!("udp://{}", socket.peer_addr().map(|a| a.to_string()).unwrap_or_default())
+        }
+    }
+
+    /// Outputs the overlay text (a file is only rewritten if the text has changed).
+    #[must_use]
+    pub fn update(&mut self, values: &OverlayValues) -> Result<(), Box<dyn Error>> {
+        let text = render(&self.template, values);
+
+        match &self.destination {
+            Destination::File(path) => {
+                if self.last_text.as_ref() == Some(&text) { return Ok(()); }
+                // write to a temporary file first, so that readers never see a partially written one
+                let tmp_path = path.with_extension("tmp");
+                std::fs::write(&tmp_path, &text)?;
+                std::fs::rename(&tmp_path, path)?;
+            },
+
+            Destination::Udp(socket) => { socket.send(text.as_bytes())?; }
+        }
+        self.last_text = Some(text);
+
+        Ok(())
+    }
+}
+
+/// Substitutes placeholders: `{time}`, `{name}`, `{dist}` (km), `{speed}` (km/h), `{alt_above_gnd}` (m), `{az}`,
+/// `{alt}`, `{mount_axis1}`, `{mount_axis2}`, `{tracking}`. Unavailable values are replaced with "-".
+pub fn render(template: &str, values: &OverlayValues) -> String {
+    let ad = &values.angle_display;
+    let target = values.target;
+    let missing = || "-".to_string();
+    let meters = |value: f64::Length| format!("{:.0} m", value.get::<length::meter>());
+    let km = |value: f64::Length| format!("{:.1} km", value.get::<length::kilometer>());
+    let km_per_h = |value: f64::Velocity| format!("{:.0} km/h", value.get::<velocity::kilometer_per_hour>());
+
+    let substitutions: [(&str, String); 10] = [
+        ("{time}", values.time.format("%H:%M:%S").to_string()),
+        ("{name}", values.name.clone().unwrap_or_else(missing)),
+        ("{dist}", target.map_or_else(missing, |t| km(t.dist))),
+        ("{speed}", target.map_or_else(missing, |t| km_per_h(t.speed))),
+        ("{alt_above_gnd}", target.map_or_else(missing, |t| meters(t.alt_above_gnd))),
+        ("{az}", target.map_or_else(missing, |t| ad.azimuth(t.azimuth, 1))),
+        ("{alt}", target.map_or_else(missing, |t| ad.altitude(t.altitude, 1))),
+        ("{mount_axis1}", values.mount.map_or_else(missing, |m| ad.azimuth(m.0, 1))),
+        ("{mount_axis2}", values.mount.map_or_else(missing, |m| ad.altitude(m.1, 1))),
+        ("{tracking}", if values.tracking { "TRACKING".into() } else { "".into() })
+    ];
+
+    let mut result = template.to_string();
+    for (placeholder, value) in &substitutions {
+        result = result.replace(placeholder, value);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::deg;
+
+    #[test]
+    fn template_rendering() {
+        let values = OverlayValues{
+            time: chrono::Local::now(),
+            name: Some("ISS".into()),
+            target: None,
+            mount: Some((deg(90.0), deg(45.3))),
+            tracking: true,
+            angle_display: Default::default()
+        };
+        assert_eq!(
+            "ISS [TRACKING]\nmount: 90.0° 45.3°, dist: -",
+            render("{name} [{tracking}]\nmount: {mount_axis1} {mount_axis2}, dist: {dist}", &values)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Guidance for moving the mount by hand in push-to (encoders-only) mode.
+
+use crate::{data::{as_deg, deg}, sun_avoidance};
+use pointing_utils::uom;
+use uom::si::f64;
+
+/// Position error (per axis or total) below which the mount is considered to be on target.
+const ON_TARGET_DEG: f64 = 0.05;
+
+/// Returns the direction in which to push the mount along an axis and the remaining angle; `arrows` are used
+/// for positive and negative `error` (target minus mount position), respectively.
+pub fn axis_guidance(error: f64::Angle, arrows: (char, char)) -> String {
+    let arrow = if as_deg(error).abs() < ON_TARGET_DEG {
+        '•'
+    } else if error > deg(0.0) {
+        arrows.0
+    } else {
+        arrows.1
+    };
+    format!("{} {:.2}°", arrow, as_deg(error).abs())
+}
+
+/// Returns guidance for azimuth (arrows: → clockwise, ← counterclockwise) and altitude (↑, ↓), and the total
+/// angular distance to the target.
+pub fn guidance(
+    mount_pos: (f64::Angle, f64::Angle),
+    error: (f64::Angle, f64::Angle)
+) -> (String, String, String) {
+    let target_pos = (mount_pos.0 + error.0, mount_pos.1 + error.1);
+    let distance = sun_avoidance::separation(mount_pos, target_pos);
+    let distance_str = if as_deg(distance) < ON_TARGET_DEG {
+        format!("{:.2}° (ON TARGET)", as_deg(distance))
+    } else {
+        format!("{:.2}°", as_deg(distance))
+    };
+    (axis_guidance(error.0, ('→', '←')), axis_guidance(error.1, ('↑', '↓')), distance_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_towards_target() {
+        assert_eq!("→ 12.50°", axis_guidance(deg(12.5), ('→', '←')));
+        assert_eq!("↓ 3.00°", axis_guidance(deg(-3.0), ('↑', '↓')));
+        assert_eq!("• 0.01°", axis_guidance(deg(-0.01), ('↑', '↓')));
+
+        let (_, _, distance) = guidance((deg(100.0), deg(30.0)), (deg(0.0), deg(0.02)));
+        assert_eq!("0.02° (ON TARGET)", distance);
+    }
+}
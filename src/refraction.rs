@@ -0,0 +1,81 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Atmospheric refraction.
+
+use crate::data::{as_deg, deg, Target};
+use pointing_utils::uom;
+use uom::si::f64;
+
+/// Refraction is not calculated for lower altitudes (the formula diverges below approx. -5°).
+const MIN_ALTITUDE_DEG: f64 = -1.0;
+
+/// Step used to calculate the derivative of refraction with respect to altitude.
+const DERIVATIVE_STEP_DEG: f64 = 0.01;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Atmosphere {
+    /// Degrees Celsius.
+    pub temperature: f64,
+    /// Hectopascals.
+    pub pressure: f64
+}
+
+impl Atmosphere {
+    /// Returns the refraction (apparent minus true altitude) at the specified true altitude.
+    pub fn refraction(&self, altitude: f64::Angle) -> f64::Angle {
+        deg(self.refraction_deg(as_deg(altitude)))
+    }
+
+    /// Corrects the target's altitude (and its rate of change) for refraction.
+    pub fn apply(&self, target: &mut Target) {
+        let h = as_deg(target.altitude);
+        let d = DERIVATIVE_STEP_DEG;
+        let derivative = (self.refraction_deg(h + d) - self.refraction_deg(h - d)) / (2.0 * d);
+
+        target.altitude += deg(self.refraction_deg(h));
+        target.alt_spd = target.alt_spd * (1.0 + derivative);
+    }
+
+    /// Uses Sæmundsson's formula (true altitude in degrees).
+    fn refraction_deg(&self, altitude: f64) -> f64 {
+        let h = altitude.max(MIN_ALTITUDE_DEG).min(90.0);
+        let arcmin = 1.02 / (h + 10.3 / (h + 5.11)).to_radians().tan();
+        let scale = (self.pressure / 1010.0) * (283.0 / (273.0 + self.temperature));
+
+        // the formula gives a small negative value at the zenith
+        (arcmin * scale / 60.0).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refraction_at_standard_conditions() {
+        let atm = Atmosphere{ temperature: 10.0, pressure: 1010.0 };
+        let r = |alt| as_deg(atm.refraction(deg(alt))) * 60.0;
+        assert!((r(0.0) - 29.0).abs() < 1.0);
+        assert!((r(10.0) - 5.3).abs() < 0.2);
+        assert!(r(90.0) < 0.01);
+
+        let cold = Atmosphere{ temperature: -20.0, pressure: 1010.0 };
+        assert!(as_deg(cold.refraction(deg(5.0))) > as_deg(atm.refraction(deg(5.0))));
+    }
+}
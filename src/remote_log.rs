@@ -0,0 +1,184 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Mirroring of log output to a remote collector: a TCP endpoint ("tcp://<host>:<port>"; one line per entry)
+//! or a syslog server ("syslog://<host>[:<port>]"; RFC 5424 messages over UDP, default port 514).
+//!
+//! Entries are sent by a background thread; while the collector is unreachable, they are dropped.
+
+use std::{
+    error::Error,
+    io::Write,
+    net::{TcpStream, ToSocketAddrs, UdpSocket},
+    sync::{mpsc, Mutex},
+    time::{Duration, Instant}
+};
+
+/// Max. number of entries waiting to be sent; further ones are dropped.
+const QUEUE_LEN: usize = 1000;
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+const TCP_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_SYSLOG_PORT: u16 = 514;
+const APP_NAME: &str = "tptool";
+const SYSLOG_FACILITY_USER: u8 = 1;
+
+static SENDER: Mutex<Option<mpsc::SyncSender<Entry>>> = Mutex::new(None);
+
+struct Entry {
+    time: chrono::DateTime<chrono::Local>,
+    level: log::Level,
+    text: String
+}
+
+enum Destination {
+    Tcp(String),
+    Syslog(UdpSocket)
+}
+
+/// Forwards log entries to the remote collector once `start` has been called.
+pub struct RemoteLogger {
+    config: simplelog::Config
+}
+
+impl RemoteLogger {
+    pub fn new() -> Box<RemoteLogger> {
+        Box::new(RemoteLogger{ config: simplelog::Config::default() })
+    }
+}
+
+impl log::Log for RemoteLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        !metadata.target().starts_with("cursive_core")
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) { return; }
+
+        if let Ok(sender) = SENDER.lock() {
+            if let Some(sender) = sender.as_ref() {
+                let _ = sender.try_send(Entry{
+                    time: chrono::Local::now(),
+                    level: record.level(),
+                    text: record.args().to_string()
+                });
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl simplelog::SharedLogger for RemoteLogger {
+    fn level(&self) -> log::LevelFilter { log::LevelFilter::Trace }
+
+    fn config(&self) -> Option<&simplelog::Config> { Some(&self.config) }
+
+    fn as_log(self: Box<Self>) -> Box<dyn log::Log> { Box::new(*self) }
+}
+
+/// Starts mirroring the log to `destination` ("tcp://<host>:<port>" or "syslog://<host>[:<port>]").
+#[must_use]
+pub fn start(destination: &str) -> Result<(), Box<dyn Error>> {
+    let destination = if let Some(address) = destination.strip_prefix("tcp://") {
+        Destination::Tcp(address.to_string())
+    } else if let Some(address) = destination.strip_prefix("syslog://") {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        if address.contains(':') {
+            socket.connect(address)?;
+        } else {
+            socket.connect((address, DEFAULT_SYSLOG_PORT))?;
+        }
+        Destination::Syslog(socket)
+    } else {
+        return Err(format!("expected tcp://<host>:<port> or syslog://<host>[:<port>], got \"{}\"", destination).into());
+    };
+
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_LEN);
+    std::thread::Builder::new().name("remote log".into()).spawn(move || send_entries(receiver, destination))?;
+    *SENDER.lock().map_err(|_| "remote log unavailable")? = Some(sender);
+
+    Ok(())
+}
+
+fn send_entries(receiver: mpsc::Receiver<Entry>, destination: Destination) {
+    let mut stream: Option<TcpStream> = None;
+    let mut last_attempt: Option<Instant> = None;
+
+    for entry in receiver {
+        match &destination {
+            Destination::Syslog(socket) => {
+                let _ = socket.send(syslog_message(&entry, std::process::id()).as_bytes());
+            },
+
+            Destination::Tcp(address) => {
+                if stream.is_none() && last_attempt.is_none_or(|t| t.elapsed() >= RECONNECT_INTERVAL) {
+                    last_attempt = Some(Instant::now());
+                    stream = connect(address).ok();
+                }
+                if let Some(s) = stream.as_mut() {
+                    if s.write_all(format!("{}\n", line(&entry)).as_bytes()).is_err() { stream = None; }
+                }
+            }
+        }
+    }
+}
+
+fn connect(address: &str) -> Result<TcpStream, Box<dyn Error>> {
+    let address = address.to_socket_addrs()?.next().ok_or("address not resolved")?;
+    let stream = TcpStream::connect_timeout(&address, TCP_TIMEOUT)?;
+    stream.set_write_timeout(Some(TCP_TIMEOUT))?;
+
+    Ok(stream)
+}
+
+/// Returns entry formatted like in the log file.
+fn line(entry: &Entry) -> String {
+    format!("{} [{}] {}", entry.time.format("%Y-%m-%d %H:%M:%S%.3f"), entry.level, entry.text)
+}
+
+/// Returns entry as an RFC 5424 syslog message (facility: user).
+fn syslog_message(entry: &Entry, pid: u32) -> String {
+    let severity = match entry.level {
+        log::Level::Error => 3,
+        log::Level::Warn => 4,
+        log::Level::Info => 6,
+        log::Level::Debug | log::Level::Trace => 7
+    };
+
+    format!(
+        "<{}>1 {} - {} {} - - {}",
+        SYSLOG_FACILITY_USER * 8 + severity,
+        entry.time.to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        APP_NAME,
+        pid,
+        entry.text
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_entries() {
+        let entry = Entry{ time: chrono::Local::now(), level: log::Level::Warn, text: "mount stopped".into() };
+        let message = syslog_message(&entry, 1234);
+        assert!(message.starts_with("<12>1 "));
+        assert!(message.ends_with(" - tptool 1234 - - mount stopped"));
+        assert!(line(&entry).ends_with(" [WARN] mount stopped"));
+    }
+}
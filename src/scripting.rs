@@ -0,0 +1,202 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! User scripts (in the Rhai language) reading program state and issuing commands.
+//!
+//! A script is run once when loaded; afterwards its function `on_tick(state)` (if defined) is called periodically.
+//! Within `on_tick`, `this` refers to an object map preserved between calls. `state` is an object map:
+//!
+//! - `time`: seconds since the script was loaded
+//! - `target`: `()` if there is no target, otherwise a map with `az`, `alt` (°), `az_spd`, `alt_spd` (°/s),
+//!   `dist` (m), `speed` (m/s), `age` (seconds since the last update)
+//! - `mount`: `()` if the mount's position is unknown, otherwise a map with `axis1`, `axis2` (°) and (if known)
+//!   `axis1_spd`, `axis2_spd` (°/s)
+//! - `tracking`: a map with `active`, `paused`, `dry_run` (booleans)
+//!
+//! Available commands (executed after `on_tick` returns): `slew(axis1_spd, axis2_spd)` (°/s; ignored during
+//! tracking), `stop()`, `start_tracking()`, `stop_tracking()`, `pause_tracking()`, `resume_tracking()`,
+//! `adjust(horizontal, vertical)` (shifts the tracked position, °), `log(message)`.
+
+use crate::{clock, data::{as_deg, as_deg_per_s}, data::Target};
+use pointing_utils::uom;
+use rhai::{Dynamic, Map};
+use std::{cell::RefCell, error::Error, path::Path, rc::Rc};
+use uom::si::{f64, length, velocity};
+
+const TICK_FUNCTION: &str = "on_tick";
+
+/// Max. number of operations of a single script run or `on_tick` call (guards against infinite loops,
+/// which would otherwise freeze the program).
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Max. depth of script function calls (guards against runaway recursion).
+const MAX_CALL_LEVELS: usize = 32;
+
+/// Command issued by a script.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Speeds of primary and secondary axis (°/s).
+    Slew(f64, f64),
+    Stop,
+    StartTracking,
+    StopTracking,
+    PauseTracking,
+    ResumeTracking,
+    /// Horizontal and vertical shift of the tracked position (°).
+    Adjust(f64, f64)
+}
+
+/// Program state passed to scripts.
+pub struct ScriptState<'a> {
+    pub target: Option<&'a Target>,
+    pub mount: Option<(f64::Angle, f64::Angle)>,
+    pub mount_spd: Option<(f64::AngularVelocity, f64::AngularVelocity)>,
+    pub tracking: bool,
+    pub paused: bool,
+    pub dry_run: bool
+}
+
+pub struct Script {
+    engine: rhai::Engine,
+    ast: rhai::AST,
+    scope: rhai::Scope<'static>,
+    /// Bound as `this` in `on_tick`.
+    this: Dynamic,
+    commands: Rc<RefCell<Vec<Command>>>,
+    loaded: std::time::Instant
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Script, Box<dyn Error>> {
+        let source = std::fs::read_to_string(path)?;
+        Script::new(&source)
+    }
+
+    pub fn new(source: &str) -> Result<Script, Box<dyn Error>> {
+        let commands = Rc::new(RefCell::new(vec![]));
+        let mut engine = rhai::Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+
+        macro_rules! register {
+            ($name:expr, |$($arg:ident: $t:ty),*| $command:expr) => {
+                let c = commands.clone();
+                engine.register_fn($name, move |$($arg: $t),*| c.borrow_mut().push($command));
+            };
+        }
+        register!("slew", |axis1: f64, axis2: f64| Command::Slew(axis1, axis2));
+        register!("stop", | | Command::Stop);
+        register!("start_tracking", | | Command::StartTracking);
+        register!("stop_tracking", | | Command::StopTracking);
+        register!("pause_tracking", | | Command::PauseTracking);
+        register!("resume_tracking", | | Command::ResumeTracking);
+        register!("adjust", |horizontal: f64, vertical: f64| Command::Adjust(horizontal, vertical));
+        engine.register_fn("log", |message: &str| log::info!("script: {}", message));
+        engine.on_print(|s| log::info!("script: {}", s));
+        engine.on_debug(|s, _, _| log::debug!("script: {}", s));
+
+        let ast = engine.compile(source)?;
+        let mut scope = rhai::Scope::new();
+        engine.run_ast_with_scope(&mut scope, &ast)?;
+
+        Ok(Script{ engine, ast, scope, this: Map::new().into(), commands, loaded: clock::now() })
+    }
+
+    /// Calls the script's `on_tick` function (if defined) and returns the commands it issued.
+    pub fn tick(&mut self, state: &ScriptState) -> Result<Vec<Command>, Box<dyn Error>> {
+        if !self.ast.iter_functions().any(|f| f.name == TICK_FUNCTION) { return Ok(vec![]); }
+
+        let state = self.state_map(state);
+        let options = rhai::CallFnOptions::new().eval_ast(false).rewind_scope(true).bind_this_ptr(&mut self.this);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options, &mut self.scope, &self.ast, TICK_FUNCTION, (state,)
+        );
+        let commands = self.commands.replace(vec![]);
+        result?;
+
+        Ok(commands)
+    }
+
+    fn state_map(&self, state: &ScriptState) -> Map {
+        let mut map = Map::new();
+        map.insert("time".into(), clock::elapsed(self.loaded).as_secs_f64().into());
+        map.insert("target".into(), state.target.map_or(Dynamic::UNIT, |target| {
+            let mut t = Map::new();
+            t.insert("az".into(), as_deg(target.azimuth).into());
+            t.insert("alt".into(), as_deg(target.altitude).into());
+            t.insert("az_spd".into(), as_deg_per_s(target.az_spd).into());
+            t.insert("alt_spd".into(), as_deg_per_s(target.alt_spd).into());
+            t.insert("dist".into(), target.dist.get::<length::meter>().into());
+            t.insert("speed".into(), target.speed.get::<velocity::meter_per_second>().into());
+            t.insert("age".into(), target.age().as_secs_f64().into());
+            t.into()
+        }));
+        map.insert("mount".into(), state.mount.map_or(Dynamic::UNIT, |(axis1, axis2)| {
+            let mut m = Map::new();
+            m.insert("axis1".into(), as_deg(axis1).into());
+            m.insert("axis2".into(), as_deg(axis2).into());
+            if let Some((spd1, spd2)) = state.mount_spd {
+                m.insert("axis1_spd".into(), as_deg_per_s(spd1).into());
+                m.insert("axis2_spd".into(), as_deg_per_s(spd2).into());
+            }
+            m.into()
+        }));
+        let mut tracking = Map::new();
+        tracking.insert("active".into(), state.tracking.into());
+        tracking.insert("paused".into(), state.paused.into());
+        tracking.insert("dry_run".into(), state.dry_run.into());
+        map.insert("tracking".into(), tracking.into());
+
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_issues_commands() {
+        let mut script = Script::new(r#"
+            fn on_tick(state) {
+                if this.ticks == () { this.ticks = 0; }
+                this.ticks += 1;
+                if state.target == () && this.ticks == 2 { slew(0.5, -1.0); stop_tracking(); }
+            }
+        "#).unwrap();
+        let state = ScriptState{
+            target: None, mount: None, mount_spd: None, tracking: true, paused: false, dry_run: false
+        };
+
+        assert!(script.tick(&state).unwrap().is_empty());
+        assert_eq!(vec![Command::Slew(0.5, -1.0), Command::StopTracking], script.tick(&state).unwrap());
+        assert!(script.tick(&state).unwrap().is_empty());
+    }
+
+    #[test]
+    fn runaway_script_fails() {
+        assert!(Script::new("loop {}").is_err());
+        assert!(Script::new("fn f(x) { f(x + 1) } f(0);").is_err());
+
+        let mut script = Script::new("fn on_tick(state) { while true {} }").unwrap();
+        let state = ScriptState{
+            target: None, mount: None, mount_spd: None, tracking: false, paused: false, dry_run: false
+        };
+        assert!(script.tick(&state).is_err());
+    }
+}
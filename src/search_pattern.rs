@@ -0,0 +1,112 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Scan patterns around the predicted position of a lost target.
+
+use std::time::Duration;
+use strum_macros as sm;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum SearchPattern {
+    /// Archimedean spiral.
+    #[default]
+    Spiral,
+    /// Row-by-row scans of squares growing by `SearchParams::spacing` on each side with every pass.
+    Raster
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SearchParams {
+    pub pattern: SearchPattern,
+    /// Speed along the scan path (°/s).
+    pub rate: f64,
+    /// Distance between spiral turns or raster rows (°).
+    pub spacing: f64
+}
+
+impl Default for SearchParams {
+    fn default() -> SearchParams {
+        SearchParams{ pattern: SearchPattern::Spiral, rate: 0.5, spacing: 0.5 }
+    }
+}
+
+/// Returns offset (horizontal, vertical; degrees on the sky) from the search center after `elapsed` time of scanning.
+pub fn offset(params: &SearchParams, elapsed: Duration) -> (f64, f64) {
+    let path_len = params.rate * elapsed.as_secs_f64();
+    match params.pattern {
+        SearchPattern::Spiral => spiral_offset(path_len, params.spacing),
+        SearchPattern::Raster => raster_offset(path_len, params.spacing)
+    }
+}
+
+fn spiral_offset(path_len: f64, spacing: f64) -> (f64, f64) {
+    // for r = spacing * θ / 2π, the path length is approximately spacing * θ² / 4π
+    let theta = (4.0 * std::f64::consts::PI * path_len / spacing).sqrt();
+    let r = spacing * theta / (2.0 * std::f64::consts::PI);
+    (r * theta.cos(), r * theta.sin())
+}
+
+fn raster_offset(path_len: f64, spacing: f64) -> (f64, f64) {
+    let mut pos = (0.0, 0.0);
+    let mut remaining = path_len;
+    let mut pass = 1;
+    loop {
+        let half = pass as f64 * spacing;
+        for row in 0..=2 * pass {
+            let y = -half + row as f64 * spacing;
+            let (x0, x1) = if row % 2 == 0 { (-half, half) } else { (half, -half) };
+            for waypoint in [(x0, y), (x1, y)] {
+                let len = (waypoint.0 - pos.0).hypot(waypoint.1 - pos.1);
+                if remaining <= len {
+                    let f = if len > 0.0 { remaining / len } else { 0.0 };
+                    return (pos.0 + f * (waypoint.0 - pos.0), pos.1 + f * (waypoint.1 - pos.1));
+                }
+                remaining -= len;
+                pos = waypoint;
+            }
+        }
+        pass += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spiral_expands() {
+        let params = SearchParams{ pattern: SearchPattern::Spiral, rate: 1.0, spacing: 0.5 };
+        let dist = |secs: u64| { let (x, y) = offset(&params, Duration::from_secs(secs)); x.hypot(y) };
+        assert_eq!(0.0, dist(0));
+        assert!(dist(10) < dist(20) && dist(20) < dist(40));
+        // path length grows approximately as the square of the radius
+        assert!((dist(40) / dist(10) - 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn raster_rows() {
+        let params = SearchParams{ pattern: SearchPattern::Raster, rate: 1.0, spacing: 1.0 };
+        let at = |secs: f64| offset(&params, Duration::from_secs_f64(secs));
+        let start_len = 2.0f64.sqrt();
+        assert!((at(start_len).0 + 1.0).abs() < 1.0e-9 && (at(start_len).1 + 1.0).abs() < 1.0e-9);
+        // first row ends at (1, -1), then the second row starts at (1, 0)
+        assert!((at(start_len + 2.0).0 - 1.0).abs() < 1.0e-9);
+        assert!((at(start_len + 3.0).1).abs() < 1.0e-9);
+    }
+}
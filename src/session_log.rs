@@ -0,0 +1,201 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Per-session log of target and mount positions in CSV or JSON Lines format.
+
+use crate::data::{angle_diff, as_deg, as_deg_per_s, Target};
+use pointing_utils::uom;
+use std::{error::Error, io::Write, path::Path};
+use strum::IntoEnumIterator;
+use strum_macros as sm;
+use uom::si::{f64, length, velocity};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, sm::EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum Format {
+    #[strum(serialize = "csv")]
+    Csv,
+    #[strum(serialize = "jsonl", serialize = "json")]
+    JsonLines
+}
+
+/// Logged field; names (as used in the configuration file and in the log header) are in snake case.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, sm::EnumIter, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Field {
+    Time,
+    TargetAz,
+    TargetAlt,
+    TargetDist,
+    TargetSpeed,
+    TargetAltAboveGnd,
+    TargetAzSpd,
+    TargetAltSpd,
+    MountAxis1,
+    MountAxis2,
+    ErrorAz,
+    ErrorAlt,
+    Tracking,
+    AdjustmentDir,
+    AdjustmentAngle
+}
+
+impl Field {
+    pub fn all() -> Vec<Field> { Field::iter().collect() }
+
+    pub fn name(&self) -> &'static str { self.into() }
+}
+
+/// Data logged at a single moment.
+pub struct Record<'a> {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub target: Option<&'a Target>,
+    /// Mount axes' positions.
+    pub mount: Option<(f64::Angle, f64::Angle)>,
+    pub tracking: bool,
+    /// Tracking adjustment (direction relative to target's motion, angle).
+    pub adjustment: Option<(f64::Angle, f64::Angle)>
+}
+
+enum Value {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+    Missing
+}
+
+pub struct SessionLogger {
+    file: std::io::BufWriter<std::fs::File>,
+    format: Format,
+    fields: Vec<Field>
+}
+
+impl SessionLogger {
+    /// Creates a new log file in `dir`.
+    #[must_use]
+    pub fn new(dir: &Path, format: Format, fields: Vec<Field>) -> Result<SessionLogger, Box<dyn Error>> {
+        let extension = match format { Format::Csv => "csv", Format::JsonLines => "jsonl" };
+        let path = dir.join(format!("tptool_session_{}.{}", chrono::Local::now().format("%Y-%m-%d_%H%M%S"), extension));
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        log::info!("session log: {}", path.to_string_lossy());
+
+        if format == Format::Csv {
+            writeln!(file, "{}", fields.iter().map(|f| f.name()).collect::<Vec<_>>().join(","))?;
+        }
+        file.flush()?;
+
+        Ok(SessionLogger{ file, format, fields })
+    }
+
+    #[must_use]
+    pub fn write(&mut self, record: &Record) -> Result<(), Box<dyn Error>> {
+        let line = match self.format {
+            Format::Csv => self.fields.iter().map(|field| match field_value(*field, record) {
+                Value::Number(x) => format!("{}", x),
+                Value::Bool(b) => format!("{}", b),
+                Value::Text(s) => s,
+                Value::Missing => "".into()
+            }).collect::<Vec<_>>().join(","),
+
+            Format::JsonLines => to_json(&self.fields, record)
+        };
+
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()?;
+
+        Ok(())
+    }
+}
+
+fn field_value(field: Field, record: &Record) -> Value {
+    let number = |x: Option<f64>| x.map_or(Value::Missing, Value::Number);
+    let target = record.target;
+
+    match field {
+        Field::Time => Value::Text(record.time.format("%Y-%m-%dT%H:%M:%S%.3f%:z").to_string()),
+        Field::TargetAz => number(target.map(|t| as_deg(t.azimuth))),
+        Field::TargetAlt => number(target.map(|t| as_deg(t.altitude))),
+        Field::TargetDist => number(target.map(|t| t.dist.get::<length::meter>())),
+        Field::TargetSpeed => number(target.map(|t| t.speed.get::<velocity::meter_per_second>())),
+        Field::TargetAltAboveGnd => number(target.map(|t| t.alt_above_gnd.get::<length::meter>())),
+        Field::TargetAzSpd => number(target.map(|t| as_deg_per_s(t.az_spd))),
+        Field::TargetAltSpd => number(target.map(|t| as_deg_per_s(t.alt_spd))),
+        Field::MountAxis1 => number(record.mount.map(|m| as_deg(m.0))),
+        Field::MountAxis2 => number(record.mount.map(|m| as_deg(m.1))),
+        Field::ErrorAz => number(target.zip(record.mount).map(|(t, m)| as_deg(angle_diff(t.azimuth, m.0)))),
+        Field::ErrorAlt => number(target.zip(record.mount).map(|(t, m)| as_deg(angle_diff(t.altitude, m.1)))),
+        Field::Tracking => Value::Bool(record.tracking),
+        Field::AdjustmentDir => number(record.adjustment.map(|a| as_deg(a.0))),
+        Field::AdjustmentAngle => number(record.adjustment.map(|a| as_deg(a.1))),
+    }
+}
+
+/// Formats the specified fields of a record as a single-line JSON object (non-finite numbers become `null`).
+pub fn to_json(fields: &[Field], record: &Record) -> String {
+    format!("{{{}}}", fields.iter().map(|field| format!(
+        "\"{}\":{}",
+        field.name(),
+        match field_value(*field, record) {
+            Value::Number(x) if x.is_finite() => format!("{}", x),
+            Value::Number(_) => "null".into(),
+            Value::Bool(b) => format!("{}", b),
+            Value::Text(s) => format!("\"{}\"", s),
+            Value::Missing => "null".into()
+        }
+    )).collect::<Vec<_>>().join(","))
+}
+
+/// Parses a comma-separated list of field names.
+pub fn parse_fields(s: &str) -> Result<Vec<Field>, Box<dyn Error>> {
+    s.split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.parse::<Field>().map_err(|_| Box::<dyn Error>::from(format!("unknown session log field: {}", name))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::deg;
+
+    #[test]
+    fn field_list_parsing() {
+        assert_eq!(
+            vec![Field::Time, Field::MountAxis1, Field::ErrorAlt],
+            parse_fields("time, mount_axis1,error_alt").unwrap()
+        );
+        assert!(parse_fields("time,foo").is_err());
+        assert_eq!("target_alt_above_gnd", Field::TargetAltAboveGnd.name());
+    }
+
+    #[test]
+    fn non_finite_json_numbers() {
+        let record = Record{
+            time: chrono::Local::now(),
+            target: None,
+            mount: Some((deg(std::primitive::f64::NAN), deg(0.0))),
+            tracking: false,
+            adjustment: None
+        };
+        assert_eq!(
+            "{\"mount_axis1\":null,\"mount_axis2\":0}",
+            to_json(&[Field::MountAxis1, Field::MountAxis2], &record)
+        );
+    }
+}
@@ -0,0 +1,102 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Orderly program shutdown: stopping tracking and the mount(s), optional parking, disconnecting the data source,
+//! saving state and flushing logs.
+
+use crate::{
+    data::{as_deg_per_s, MountSpeed, ProgramState},
+    event_handling,
+    mount::{Mount, MountWrapper},
+    tracking::TrackingController
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+
+/// Mount axis speed above which the mount is considered to be moving.
+const MOVING_SPEED_DEG_PER_S: f64 = 0.01;
+
+thread_local! {
+    /// Mounts to stop if the program panics.
+    static MOUNTS: RefCell<Vec<Weak<RefCell<Option<MountWrapper>>>>> = RefCell::new(vec![]);
+}
+
+/// Registers mounts to be stopped by `on_panic`.
+pub fn register_mounts(mounts: &[&Rc<RefCell<Option<MountWrapper>>>]) {
+    MOUNTS.with(|m| *m.borrow_mut() = mounts.iter().map(|mount| Rc::downgrade(mount)).collect());
+}
+
+/// Returns true if tracking is active or the (main) mount's axes are moving.
+pub fn mount_moving(mount_spd: &MountSpeed, tracking: &TrackingController) -> bool {
+    tracking.is_active() || mount_spd.get().is_some_and(|(spd1, spd2)| {
+        as_deg_per_s(spd1).abs().max(as_deg_per_s(spd2).abs()) > MOVING_SPEED_DEG_PER_S
+    })
+}
+
+/// Performs the shutdown sequence; called once the event loop has finished.
+pub fn shutdown(state: &mut ProgramState) {
+    log::info!("shutting down");
+
+    let tracking = state.tracking.controller();
+    if tracking.is_active() { tracking.stop(); }
+
+    let park = state.config.borrow().park_on_exit();
+    for mount in [&state.mount, &state.mount2] {
+        if let Some(mount) = mount.borrow_mut().as_mut() {
+            if let Err(e) = mount.stop() { log::error!("error stopping {}: {}", mount.get_info(), e); }
+            if park && !mount.is_parked() {
+                match mount.park() {
+                    Ok(()) => log::info!("parked {}", mount.get_info()),
+                    Err(e) => log::error!("failed to park {}: {}", mount.get_info(), e)
+                }
+            }
+        }
+    }
+
+    let connection = state.data_receiver.connection();
+    connection.cancel_connecting();
+    connection.disconnect();
+
+    event_handling::save_mount_state(state);
+    event_handling::save_odometers(state);
+    let stats = tracking.stats();
+    if !stats.time_tracked.is_zero() {
+        log::info!("tracking summary:\n{}", stats.summary());
+        if let Err(e) = event_handling::write_tracking_summary(&stats) {
+            log::error!("failed to write tracking summary: {}", e);
+        }
+    }
+
+    log::info!("shutdown complete");
+    log::logger().flush();
+}
+
+/// Called from the panic hook: stops the registered mounts (unless they are in use by the panicking code)
+/// and flushes logs.
+pub fn on_panic() {
+    let _ = MOUNTS.try_with(|mounts| {
+        let Ok(mounts) = mounts.try_borrow() else { return; };
+        for mount in mounts.iter().filter_map(|m| m.upgrade()) {
+            if let Ok(mut mount) = mount.try_borrow_mut() {
+                if let Some(mount) = mount.as_mut() {
+                    if let Err(e) = mount.stop() { log::error!("error stopping the mount: {}", e); }
+                }
+            }
+        }
+    });
+    log::logger().flush();
+}
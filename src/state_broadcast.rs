@@ -0,0 +1,59 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Broadcasting of target and mount state over TCP.
+//!
+//! Each connected client receives one JSON object per line (field names as in the session log), e.g.:
+//! `{"time":"2024-06-01T21:03:15.250+02:00","target_az":123.4,"target_alt":35.2,"target_speed":220,...}`.
+//! Lines received from clients are ignored.
+
+use crate::{line_server::LineServer, session_log::{self, Field, Record}};
+use std::error::Error;
+
+const FIELDS: [Field; 9] = [
+    Field::Time,
+    Field::TargetAz,
+    Field::TargetAlt,
+    Field::TargetSpeed,
+    Field::TargetAzSpd,
+    Field::TargetAltSpd,
+    Field::MountAxis1,
+    Field::MountAxis2,
+    Field::Tracking
+];
+
+pub struct StateBroadcast {
+    server: LineServer
+}
+
+impl StateBroadcast {
+    #[must_use]
+    pub fn new(address: &str) -> Result<StateBroadcast, Box<dyn Error>> {
+        Ok(StateBroadcast{ server: LineServer::new(address)? })
+    }
+
+    pub fn local_addr(&self) -> String { self.server.local_addr() }
+
+    /// Accepts new clients and sends them the current state.
+    pub fn publish(&mut self, record: &Record) {
+        let _ = self.server.poll();
+        if self.server.num_clients() > 0 {
+            self.server.broadcast(&session_log::to_json(&FIELDS, record));
+        }
+    }
+}
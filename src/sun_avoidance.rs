@@ -0,0 +1,137 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Exclusion zone around the Sun which the mount is not allowed to enter.
+
+use cgmath::EuclideanSpace;
+use crate::{
+    clock,
+    data::{as_deg_per_s, deg, spherical_to_unit, ObserverLocation},
+    target_source::{Body, SolarSystemBody, TargetSource}
+};
+use pointing_utils::{cgmath, uom};
+use std::time::{Duration, Instant};
+use uom::si::f64;
+
+/// Duration of the exclusion zone override confirmed by the user.
+pub const OVERRIDE_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// Mount position is predicted this far ahead when checking its motion.
+const LOOKAHEAD: Duration = Duration::from_secs(2);
+
+pub struct SunAvoidance {
+    /// Exclusion zone radius; the zone is disabled if `None`.
+    radius: Option<f64::Angle>,
+    override_until: Option<Instant>,
+    /// Whether the mount has been stopped at the zone (cleared once it no longer approaches the Sun).
+    stopped: bool
+}
+
+impl SunAvoidance {
+    pub fn new(radius: Option<f64::Angle>) -> SunAvoidance {
+        SunAvoidance{ radius, override_until: None, stopped: false }
+    }
+
+    pub fn radius(&self) -> Option<f64::Angle> { self.radius }
+
+    /// Disables the exclusion zone for `OVERRIDE_DURATION`.
+    pub fn set_override(&mut self) {
+        log::warn!("Sun avoidance overridden for {} min", OVERRIDE_DURATION.as_secs() / 60);
+        self.override_until = Some(clock::now() + OVERRIDE_DURATION);
+    }
+
+    pub fn is_overridden(&self) -> bool {
+        self.override_until.is_some_and(|t| clock::now() < t)
+    }
+
+    /// Returns angular distance between the direction and the Sun, if the direction lies within the (active)
+    /// exclusion zone.
+    pub fn violation(
+        &self,
+        azimuth: f64::Angle,
+        altitude: f64::Angle,
+        observer: &ObserverLocation
+    ) -> Option<f64::Angle> {
+        let radius = self.radius?;
+        if self.is_overridden() { return None; }
+        let (sun_az, sun_alt) = sun_az_alt(observer)?;
+        let dist = separation((azimuth, altitude), (sun_az, sun_alt));
+        if dist < radius { Some(dist) } else { None }
+    }
+
+    /// Checks the mount's motion; returns the Sun's angular distance if the mount, moving at `speed` from
+    /// `position` (azimuth, altitude), is about to enter (or go deeper into) the exclusion zone.
+    ///
+    /// Returns `None` on subsequent calls until the mount no longer approaches the zone.
+    pub fn check_motion(
+        &mut self,
+        position: (f64::Angle, f64::Angle),
+        speed: (f64::AngularVelocity, f64::AngularVelocity),
+        observer: &ObserverLocation
+    ) -> Option<f64::Angle> {
+        let travel = |speed| deg(as_deg_per_s(speed) * LOOKAHEAD.as_secs_f64());
+        let predicted = (position.0 + travel(speed.0), position.1 + travel(speed.1));
+
+        let approaching = match self.violation(predicted.0, predicted.1, observer) {
+            Some(predicted_dist) => match sun_az_alt(observer) {
+                Some(sun) => predicted_dist < separation(position, sun),
+                None => false
+            },
+            None => false
+        };
+
+        if !approaching {
+            self.stopped = false;
+            return None;
+        }
+        if self.stopped { return None; }
+        self.stopped = true;
+
+        self.violation(position.0, position.1, observer)
+            .or_else(|| self.violation(predicted.0, predicted.1, observer))
+    }
+}
+
+/// Returns the Sun's current azimuth and altitude as seen by `observer`.
+pub fn sun_az_alt(observer: &ObserverLocation) -> Option<(f64::Angle, f64::Angle)> {
+    match SolarSystemBody::new(Body::Sun).target(clock::utc_now(), observer) {
+        Ok(sun) => Some((sun.azimuth, sun.altitude)),
+        Err(e) => { log::error!("failed to calculate the Sun's position: {}", e); None }
+    }
+}
+
+/// Returns angular distance between two directions given as (azimuth, altitude).
+pub fn separation(dir1: (f64::Angle, f64::Angle), dir2: (f64::Angle, f64::Angle)) -> f64::Angle {
+    let v1 = spherical_to_unit(dir1.0, dir1.1).to_vec();
+    let v2 = spherical_to_unit(dir2.0, dir2.1).to_vec();
+    let cos = cgmath::InnerSpace::dot(v1, v2).clamp(-1.0, 1.0);
+    deg(cos.acos().to_degrees())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::as_deg;
+
+    #[test]
+    fn angular_separation() {
+        assert!((as_deg(separation((deg(10.0), deg(0.0)), (deg(350.0), deg(0.0)))) - 20.0).abs() < 1.0e-9);
+        assert!((as_deg(separation((deg(0.0), deg(80.0)), (deg(180.0), deg(80.0)))) - 20.0).abs() < 1.0e-9);
+        assert!((as_deg(separation((deg(0.0), deg(30.0)), (deg(0.0), deg(30.0))))).abs() < 1.0e-6);
+    }
+}
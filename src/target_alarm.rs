@@ -0,0 +1,127 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Alerts raised when the target crosses configured distance/altitude thresholds.
+
+use crate::data::{as_deg, deg, Target};
+use pointing_utils::uom;
+use std::io::Write;
+use uom::si::{f64, length};
+
+/// A raised alarm is cleared once the distance exceeds its threshold by this factor.
+const DIST_HYSTERESIS: f64 = 1.05;
+/// A raised alarm is cleared once the altitude exceeds its threshold by this amount.
+const ALTITUDE_HYSTERESIS_DEG: f64 = 0.5;
+
+#[derive(Clone, Copy, Default)]
+pub struct Thresholds {
+    /// Alarm when the target comes closer than this.
+    pub min_dist: Option<f64::Length>,
+    /// Alarm when the target descends below this altitude.
+    pub min_altitude: Option<f64::Angle>
+}
+
+pub struct TargetAlarms {
+    thresholds: Thresholds,
+    dist_raised: bool,
+    altitude_raised: bool
+}
+
+impl TargetAlarms {
+    pub fn new(thresholds: Thresholds) -> TargetAlarms {
+        TargetAlarms{ thresholds, dist_raised: false, altitude_raised: false }
+    }
+
+    /// Checks `target` against the thresholds; returns descriptions of alarms raised by this call.
+    pub fn update(&mut self, target: &Target) -> Vec<String> {
+        let mut raised = vec![];
+
+        if let Some(min_dist) = self.thresholds.min_dist {
+            if !self.dist_raised && target.dist < min_dist {
+                self.dist_raised = true;
+                raised.push(format!(
+                    "target distance below {:.1} km ({:.1} km)",
+                    min_dist.get::<length::kilometer>(),
+                    target.dist.get::<length::kilometer>()
+                ));
+            } else if self.dist_raised && target.dist > min_dist * DIST_HYSTERESIS {
+                self.dist_raised = false;
+            }
+        }
+
+        if let Some(min_altitude) = self.thresholds.min_altitude {
+            if !self.altitude_raised && target.altitude < min_altitude {
+                self.altitude_raised = true;
+                raised.push(format!(
+                    "target altitude below {:.1}° ({:.1}°)", as_deg(min_altitude), as_deg(target.altitude)
+                ));
+            } else if self.altitude_raised && target.altitude > min_altitude + deg(ALTITUDE_HYSTERESIS_DEG) {
+                self.altitude_raised = false;
+            }
+        }
+
+        raised
+    }
+
+    /// Returns short indication of currently raised alarms (empty if none).
+    pub fn indication(&self) -> String {
+        match (self.dist_raised, self.altitude_raised) {
+            (false, false) => String::new(),
+            (true, false) => "ALARM: dist.".into(),
+            (false, true) => "ALARM: alt.".into(),
+            (true, true) => "ALARM: dist., alt.".into()
+        }
+    }
+}
+
+/// Rings the terminal bell.
+pub fn beep() {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{Point3, Vector3};
+    use pointing_utils::cgmath;
+
+    fn target(x: f64, z: f64) -> Target {
+        Target::from_local(Point3::new(x, 0.0, z), Vector3::new(0.0, 0.0, 0.0), f64::Length::new::<length::meter>(z))
+    }
+
+    #[test]
+    fn alarms_are_raised_once() {
+        let mut alarms = TargetAlarms::new(Thresholds{
+            min_dist: Some(f64::Length::new::<length::kilometer>(5.0)),
+            min_altitude: None
+        });
+
+        assert!(alarms.update(&target(6000.0, 100.0)).is_empty());
+        assert_eq!(1, alarms.update(&target(4000.0, 100.0)).len());
+        assert!(alarms.update(&target(3000.0, 100.0)).is_empty());
+        assert_eq!("ALARM: dist.", alarms.indication());
+        // within hysteresis
+        assert!(alarms.update(&target(5100.0, 100.0)).is_empty());
+        assert!(alarms.update(&target(4900.0, 100.0)).is_empty());
+
+        assert!(alarms.update(&target(6000.0, 100.0)).is_empty());
+        assert!(alarms.indication().is_empty());
+        assert_eq!(1, alarms.update(&target(4000.0, 100.0)).len());
+    }
+}
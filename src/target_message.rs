@@ -0,0 +1,335 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Parsing of target data messages received from a data source.
+
+use chrono::TimeZone;
+use pointing_utils::{cgmath, TargetInfoMessage, uom};
+use cgmath::{Point3, Vector3};
+use std::{error::Error, time::Duration};
+use strum_macros as sm;
+use uom::si::{f64, length};
+
+/// Prefix of text messages of protocol version 2, followed by `<timestamp>;<id>;` and a version 1 message.
+const TEXT_V2_PREFIX: &str = "v2;";
+
+/// Latency above which a message's timestamp is considered implausible (e.g., due to unsynchronized clocks)
+/// and not used for prediction.
+const MAX_LATENCY: Duration = Duration::from_secs(10);
+
+/// Target position and velocity in the observer's frame of reference (X points north, Y points west, Z points up).
+pub struct TargetMessage {
+    /// Meters.
+    pub position: Point3<f64>,
+    /// Meters per second.
+    pub velocity: Vector3<f64>,
+    pub altitude: f64::Length,
+    /// Time of measurement (protocol version 2 only).
+    pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    /// Target identifier (protocol version 2 only).
+    pub id: Option<String>
+}
+
+impl TargetMessage {
+    /// Returns time elapsed since the measurement (`None` if the message has no timestamp or it is implausible).
+    pub fn latency(&self, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+        let latency = (now - self.timestamp?).to_std().ok()?;
+        if latency <= MAX_LATENCY { Some(latency) } else { None }
+    }
+
+    /// Returns target position extrapolated to `now` (if the message is timestamped).
+    pub fn position_at(&self, now: chrono::DateTime<chrono::Utc>) -> Point3<f64> {
+        match self.latency(now) {
+            Some(latency) => self.position + self.velocity * latency.as_secs_f64(),
+            None => self.position
+        }
+    }
+}
+
+/// Parses Unix time in seconds (may be fractional).
+fn parse_timestamp(s: &str) -> Result<chrono::DateTime<chrono::Utc>, Box<dyn Error>> {
+    let secs = s.trim().parse::<f64>().map_err(|_| format!("invalid timestamp: {}", s))?;
+    chrono::Utc.timestamp_opt(secs.floor() as i64, (secs.fract() * 1.0e9) as u32)
+        .single()
+        .ok_or(format!("invalid timestamp: {}", s).into())
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum MessageFormat {
+    /// Detected for each line (JSON if it begins with '{').
+    #[default]
+    Auto,
+    /// Text format of `pointing_utils::TargetInfoMessage`, optionally preceded by `v2;<timestamp>;<id>;`.
+    Text,
+    /// {"position": [x, y, z], "velocity": [vx, vy, vz], "altitude": altitude} (one object per line);
+    /// protocol version 2 adds optional "timestamp" and "id".
+    Json
+}
+
+pub trait MessageParser {
+    fn parse(&self, line: &str) -> Result<TargetMessage, Box<dyn Error>>;
+}
+
+struct TextParser;
+
+impl MessageParser for TextParser {
+    fn parse(&self, line: &str) -> Result<TargetMessage, Box<dyn Error>> {
+        let (timestamp, id, line) = match line.strip_prefix(TEXT_V2_PREFIX) {
+            Some(rest) => {
+                let mut fields = rest.splitn(3, ';');
+                let timestamp = parse_timestamp(fields.next().unwrap())?;
+                let id = fields.next().ok_or("missing target ID")?.trim();
+                let rest = fields.next().ok_or("missing target data")?;
+                (Some(timestamp), Some(id.to_string()).filter(|id| !id.is_empty()), rest)
+            },
+            None => (None, None, line)
+        };
+
+        let ti = line.parse::<TargetInfoMessage>().map_err(|e| format!("invalid message: {:?}", e))?;
+        Ok(TargetMessage{ position: ti.position.0, velocity: ti.velocity.0, altitude: ti.altitude, timestamp, id })
+    }
+}
+
+struct JsonParser;
+
+impl MessageParser for JsonParser {
+    fn parse(&self, line: &str) -> Result<TargetMessage, Box<dyn Error>> {
+        let value = json::parse(line)?;
+        let vector = |key: &str| -> Result<[f64; 3], Box<dyn Error>> {
+            let items = value.get(key).and_then(|v| v.as_array()).ok_or(format!("missing array \"{}\"", key))?;
+            let numbers = items.iter().map(|v| v.as_f64()).collect::<Option<Vec<_>>>();
+            match numbers.as_deref() {
+                Some(&[x, y, z]) => Ok([x, y, z]),
+                _ => Err(format!("\"{}\" must contain 3 numbers", key).into())
+            }
+        };
+        let [x, y, z] = vector("position")?;
+        let [vx, vy, vz] = vector("velocity")?;
+        let altitude = value.get("altitude").and_then(|v| v.as_f64()).ok_or("missing number \"altitude\"")?;
+        let timestamp = match value.get("timestamp") {
+            Some(json::Value::Number(secs)) => Some(parse_timestamp(&secs.to_string())?),
+            Some(json::Value::String(secs)) => Some(parse_timestamp(secs)?),
+            Some(_) => return Err("\"timestamp\" must be a number".into()),
+            None => None
+        };
+        let id = match value.get("id") {
+            Some(json::Value::String(id)) => Some(id.clone()),
+            Some(json::Value::Number(id)) => Some(id.to_string()),
+            Some(_) => return Err("\"id\" must be a string or number".into()),
+            None => None
+        };
+
+        Ok(TargetMessage{
+            position: Point3::new(x, y, z),
+            velocity: Vector3::new(vx, vy, vz),
+            altitude: f64::Length::new::<length::meter>(altitude),
+            timestamp,
+            id
+        })
+    }
+}
+
+struct AutoParser;
+
+impl MessageParser for AutoParser {
+    fn parse(&self, line: &str) -> Result<TargetMessage, Box<dyn Error>> {
+        if line.trim_start().starts_with('{') { JsonParser.parse(line) } else { TextParser.parse(line) }
+    }
+}
+
+pub fn parser(format: MessageFormat) -> Box<dyn MessageParser> {
+    match format {
+        MessageFormat::Auto => Box::new(AutoParser),
+        MessageFormat::Text => Box::new(TextParser),
+        MessageFormat::Json => Box::new(JsonParser)
+    }
+}
+
+/// Minimal JSON reader (sufficient for target messages).
+mod json {
+    use std::{collections::HashMap, error::Error, iter::Peekable, str::Chars};
+
+    #[derive(Debug, PartialEq)]
+    pub enum Value {
+        Null,
+        Bool(bool),
+        Number(f64),
+        String(String),
+        Array(Vec<Value>),
+        Object(HashMap<String, Value>)
+    }
+
+    impl Value {
+        pub fn get(&self, key: &str) -> Option<&Value> {
+            match self { Value::Object(map) => map.get(key), _ => None }
+        }
+
+        pub fn as_f64(&self) -> Option<f64> {
+            match self { Value::Number(n) => Some(*n), _ => None }
+        }
+
+        pub fn as_array(&self) -> Option<&[Value]> {
+            match self { Value::Array(items) => Some(items), _ => None }
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Value, Box<dyn Error>> {
+        let mut chars = s.chars().peekable();
+        let value = parse_value(&mut chars)?;
+        skip_whitespace(&mut chars);
+        if chars.peek().is_some() { return Err("unexpected characters after JSON value".into()); }
+        Ok(value)
+    }
+
+    fn skip_whitespace(chars: &mut Peekable<Chars>) {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) { chars.next(); }
+    }
+
+    fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), Box<dyn Error>> {
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected '{}', found '{}'", expected, c).into()),
+            None => Err(format!("expected '{}', found end of line", expected).into())
+        }
+    }
+
+    fn parse_value(chars: &mut Peekable<Chars>) -> Result<Value, Box<dyn Error>> {
+        skip_whitespace(chars);
+        match chars.peek().copied() {
+            Some('{') => {
+                chars.next();
+                let mut map = HashMap::new();
+                skip_whitespace(chars);
+                if chars.peek() == Some(&'}') { chars.next(); return Ok(Value::Object(map)); }
+                loop {
+                    skip_whitespace(chars);
+                    if chars.next() != Some('"') { return Err("expected object key".into()); }
+                    let key = parse_string(chars)?;
+                    expect(chars, ':')?;
+                    map.insert(key, parse_value(chars)?);
+                    skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => (),
+                        Some('}') => return Ok(Value::Object(map)),
+                        _ => return Err("expected ',' or '}'".into())
+                    }
+                }
+            },
+
+            Some('[') => {
+                chars.next();
+                let mut items = vec![];
+                skip_whitespace(chars);
+                if chars.peek() == Some(&']') { chars.next(); return Ok(Value::Array(items)); }
+                loop {
+                    items.push(parse_value(chars)?);
+                    skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => (),
+                        Some(']') => return Ok(Value::Array(items)),
+                        _ => return Err("expected ',' or ']'".into())
+                    }
+                }
+            },
+
+            Some('"') => { chars.next(); Ok(Value::String(parse_string(chars)?)) },
+
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let mut s = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_digit() || "+-.eE".contains(c)) { break; }
+                    s.push(c);
+                    chars.next();
+                }
+                Ok(Value::Number(s.parse::<f64>().map_err(|_| format!("invalid number: {}", s))?))
+            },
+
+            Some(_) => {
+                let word: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_alphabetic())).collect();
+                match word.as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    _ => Err(format!("unexpected token: \"{}\"", word).into())
+                }
+            },
+
+            None => Err("unexpected end of line".into())
+        }
+    }
+
+    /// Parses string contents (after the opening quote).
+    fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, Box<dyn Error>> {
+        let mut result = String::new();
+        loop {
+            match chars.next() {
+                Some('"') => return Ok(result),
+                Some('\\') => match chars.next() {
+                    Some('n') => result.push('\n'),
+                    Some('t') => result.push('\t'),
+                    Some('u') => {
+                        let code: String = (0..4).filter_map(|_| chars.next()).collect();
+                        let c = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32);
+                        result.push(c.ok_or(format!("invalid escape: \\u{}", code))?);
+                    },
+                    Some(c) => result.push(c),
+                    None => return Err("unterminated string".into())
+                },
+                Some(c) => result.push(c),
+                None => return Err("unterminated string".into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_message_parsing() {
+        let msg = JsonParser.parse(
+            r#"{"position": [11000.0, 5000, -7e3], "velocity": [220.5, 0, 0], "altitude": 7000, "name": "A\"1"}"#
+        ).unwrap();
+        assert_eq!(Point3::new(11000.0, 5000.0, -7000.0), msg.position);
+        assert_eq!(Vector3::new(220.5, 0.0, 0.0), msg.velocity);
+        assert_eq!(7000.0, msg.altitude.get::<length::meter>());
+
+        assert!(JsonParser.parse(r#"{"position": [1, 2], "velocity": [1, 2, 3], "altitude": 0}"#).is_err());
+        assert!(JsonParser.parse(r#"{"position": [1, 2, 3], "velocity": [1, 2, 3]}"#).is_err());
+        assert!(JsonParser.parse(r#"{"position": [1, 2, 3], "velocity": [1, 2, 3], "altitude": 0"#).is_err());
+        assert!(AutoParser.parse("{garbage").is_err());
+    }
+
+    #[test]
+    fn v2_json_message_parsing() {
+        let msg = JsonParser.parse(
+            r#"{"position":[1000,0,0],"velocity":[100,0,0],"altitude":0,"timestamp":1700000000.5,"id":"AB12"}"#
+        ).unwrap();
+        assert_eq!(Some("AB12"), msg.id.as_deref());
+        let now = chrono::Utc.timestamp_opt(1_700_000_001, 0).single().unwrap();
+        assert_eq!(Some(Duration::from_millis(500)), msg.latency(now));
+        assert_eq!(Point3::new(1050.0, 0.0, 0.0), msg.position_at(now));
+
+        let legacy = JsonParser.parse(r#"{"position": [1000, 0, 0], "velocity": [100, 0, 0], "altitude": 0}"#).unwrap();
+        assert!(legacy.timestamp.is_none() && legacy.id.is_none());
+        assert_eq!(Point3::new(1000.0, 0.0, 0.0), legacy.position_at(now));
+    }
+}
@@ -0,0 +1,304 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Low-precision ephemerides of the Sun, the Moon and the planets.
+//!
+//! Planets: Keplerian elements from "Approximate Positions of the Planets" (E.M. Standish, JPL), valid for
+//! 1800-2050 AD (error below 1′ for the inner planets, a few ′ for the outer ones).
+//! Moon: truncated lunar theory from the Astronomical Almanac (error ~0.3°).
+
+use cgmath::{EuclideanSpace, InnerSpace, Point3, Vector3};
+use crate::{data, data::ObserverLocation, target_source::TargetSource};
+use pointing_utils::{cgmath, uom};
+use std::error::Error;
+use strum_macros as sm;
+use uom::si::{f64, length};
+
+const AU: f64 = 149_597_870_700.0; // m
+
+#[derive(Copy, Clone, Debug, PartialEq, sm::EnumIter)]
+pub enum Body {
+    Sun,
+    Moon,
+    Mercury,
+    Venus,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune
+}
+
+impl std::fmt::Display for Body {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "{}", match self {
+            Body::Sun => "Sun",
+            Body::Moon => "Moon",
+            Body::Mercury => "Mercury",
+            Body::Venus => "Venus",
+            Body::Mars => "Mars",
+            Body::Jupiter => "Jupiter",
+            Body::Saturn => "Saturn",
+            Body::Uranus => "Uranus",
+            Body::Neptune => "Neptune",
+        })
+    }
+}
+
+/// Keplerian elements (J2000 ecliptic and equinox) and their rates per Julian century.
+struct OrbitalElements {
+    a: (f64, f64), // AU
+    e: (f64, f64),
+    incl: (f64, f64), // degrees
+    mean_lon: (f64, f64), // degrees
+    lon_perihelion: (f64, f64), // degrees
+    lon_asc_node: (f64, f64) // degrees
+}
+
+const EARTH_MOON_BARYCENTER: OrbitalElements = OrbitalElements{
+    a: (1.000_002_61, 0.000_005_62),
+    e: (0.016_711_23, -0.000_043_92),
+    incl: (-0.000_015_31, -0.012_946_68),
+    mean_lon: (100.464_571_66, 35_999.372_449_81),
+    lon_perihelion: (102.937_681_93, 0.323_273_64),
+    lon_asc_node: (0.0, 0.0)
+};
+
+fn planet_elements(body: Body) -> Option<OrbitalElements> {
+    match body {
+        Body::Mercury => Some(OrbitalElements{
+            a: (0.387_099_27, 0.000_000_37),
+            e: (0.205_635_93, 0.000_019_06),
+            incl: (7.004_979_02, -0.005_947_49),
+            mean_lon: (252.250_323_50, 149_472.674_111_75),
+            lon_perihelion: (77.457_796_28, 0.160_476_89),
+            lon_asc_node: (48.330_765_93, -0.125_340_81)
+        }),
+        Body::Venus => Some(OrbitalElements{
+            a: (0.723_335_66, 0.000_003_90),
+            e: (0.006_776_72, -0.000_041_07),
+            incl: (3.394_676_05, -0.000_788_90),
+            mean_lon: (181.979_099_50, 58_517.815_387_29),
+            lon_perihelion: (131.602_467_18, 0.002_683_29),
+            lon_asc_node: (76.679_842_55, -0.277_694_18)
+        }),
+        Body::Mars => Some(OrbitalElements{
+            a: (1.523_710_34, 0.000_018_47),
+            e: (0.093_394_10, 0.000_078_82),
+            incl: (1.849_691_42, -0.008_131_31),
+            mean_lon: (-4.553_432_05, 19_140.302_684_99),
+            lon_perihelion: (-23.943_629_59, 0.444_410_88),
+            lon_asc_node: (49.559_538_91, -0.292_573_43)
+        }),
+        Body::Jupiter => Some(OrbitalElements{
+            a: (5.202_887_00, -0.000_116_07),
+            e: (0.048_386_24, -0.000_132_53),
+            incl: (1.304_396_95, -0.001_837_14),
+            mean_lon: (34.396_440_51, 3_034.746_127_75),
+            lon_perihelion: (14.728_479_83, 0.212_526_68),
+            lon_asc_node: (100.473_909_09, 0.204_691_06)
+        }),
+        Body::Saturn => Some(OrbitalElements{
+            a: (9.536_675_94, -0.001_250_60),
+            e: (0.053_861_79, -0.000_509_91),
+            incl: (2.485_991_87, 0.001_936_09),
+            mean_lon: (49.954_244_23, 1_222.493_622_01),
+            lon_perihelion: (92.598_878_31, -0.418_972_16),
+            lon_asc_node: (113.662_424_48, -0.288_677_94)
+        }),
+        Body::Uranus => Some(OrbitalElements{
+            a: (19.189_164_64, -0.001_961_76),
+            e: (0.047_257_44, -0.000_043_97),
+            incl: (0.772_637_83, -0.002_429_39),
+            mean_lon: (313.238_104_51, 428.482_027_85),
+            lon_perihelion: (170.954_276_30, 0.408_052_81),
+            lon_asc_node: (74.016_925_03, 0.042_405_89)
+        }),
+        Body::Neptune => Some(OrbitalElements{
+            a: (30.069_922_76, 0.000_262_91),
+            e: (0.008_590_48, 0.000_051_05),
+            incl: (1.770_043_47, 0.000_353_72),
+            mean_lon: (-55.120_029_69, 218.459_453_25),
+            lon_perihelion: (44.964_762_27, -0.322_414_64),
+            lon_asc_node: (131.784_225_74, -0.005_086_64)
+        }),
+        Body::Sun | Body::Moon => None
+    }
+}
+
+/// Returns heliocentric position (AU) in the J2000 ecliptic frame.
+fn heliocentric_position(elements: &OrbitalElements, t_cent: f64) -> Vector3<f64> {
+    let value = |e: (f64, f64)| e.0 + e.1 * t_cent;
+
+    let a = value(elements.a);
+    let e = value(elements.e);
+    let incl = value(elements.incl).to_radians();
+    let mean_lon = value(elements.mean_lon);
+    let lon_perihelion = value(elements.lon_perihelion);
+    let lon_asc_node = value(elements.lon_asc_node);
+
+    let arg_perihelion = (lon_perihelion - lon_asc_node).to_radians();
+    let mean_anomaly = (mean_lon - lon_perihelion).to_radians();
+    let ecc_anomaly = solve_kepler(mean_anomaly, e);
+
+    let x_orb = a * (ecc_anomaly.cos() - e);
+    let y_orb = a * (1.0 - e.powi(2)).sqrt() * ecc_anomaly.sin();
+
+    let (sin_w, cos_w) = arg_perihelion.sin_cos();
+    let (sin_n, cos_n) = lon_asc_node.to_radians().sin_cos();
+    let (sin_i, cos_i) = incl.sin_cos();
+
+    Vector3{
+        x: (cos_w * cos_n - sin_w * sin_n * cos_i) * x_orb + (-sin_w * cos_n - cos_w * sin_n * cos_i) * y_orb,
+        y: (cos_w * sin_n + sin_w * cos_n * cos_i) * x_orb + (-sin_w * sin_n + cos_w * cos_n * cos_i) * y_orb,
+        z: sin_w * sin_i * x_orb + cos_w * sin_i * y_orb
+    }
+}
+
+/// Solves Kepler's equation E - e·sin(E) = M (angles in radians).
+fn solve_kepler(mean_anomaly: f64, e: f64) -> f64 {
+    let m = mean_anomaly.rem_euclid(std::f64::consts::TAU);
+    let mut ecc_anomaly = if e < 0.8 { m } else { std::f64::consts::PI };
+    for _ in 0..30 {
+        let delta = (ecc_anomaly - e * ecc_anomaly.sin() - m) / (1.0 - e * ecc_anomaly.cos());
+        ecc_anomaly -= delta;
+        if delta.abs() < 1.0e-12 { break; }
+    }
+
+    ecc_anomaly
+}
+
+/// Returns geocentric position of the Moon (m) in the ecliptic frame of date.
+fn moon_position(t_cent: f64) -> Vector3<f64> {
+    let sin_deg = |x: f64| x.to_radians().sin();
+    let cos_deg = |x: f64| x.to_radians().cos();
+    let t = t_cent;
+
+    let lon = 218.32 + 481_267.881 * t
+        + 6.29 * sin_deg(135.0 + 477_198.87 * t)
+        - 1.27 * sin_deg(259.3 - 413_335.36 * t)
+        + 0.66 * sin_deg(235.7 + 890_534.22 * t)
+        + 0.21 * sin_deg(269.9 + 954_397.74 * t)
+        - 0.19 * sin_deg(357.5 + 35_999.05 * t)
+        - 0.11 * sin_deg(186.5 + 966_404.03 * t);
+
+    let lat = 5.13 * sin_deg(93.3 + 483_202.02 * t)
+        + 0.28 * sin_deg(228.2 + 960_400.89 * t)
+        - 0.28 * sin_deg(318.3 + 6_003.15 * t)
+        - 0.17 * sin_deg(217.6 - 407_332.21 * t);
+
+    let parallax = 0.9508
+        + 0.0518 * cos_deg(135.0 + 477_198.87 * t)
+        + 0.0095 * cos_deg(259.3 - 413_335.36 * t)
+        + 0.0078 * cos_deg(235.7 + 890_534.22 * t)
+        + 0.0028 * cos_deg(269.9 + 954_397.74 * t);
+
+    let dist = pointing_utils::EARTH_RADIUS_M / sin_deg(parallax);
+
+    Vector3{ x: cos_deg(lat) * cos_deg(lon), y: cos_deg(lat) * sin_deg(lon), z: sin_deg(lat) } * dist
+}
+
+/// Precesses a vector from the J2000 ecliptic frame to the ecliptic frame of date (approximately, by applying
+/// the general precession in longitude).
+fn precess_ecliptic(v: Vector3<f64>, t_cent: f64) -> Vector3<f64> {
+    let (sin_p, cos_p) = (1.396_971 * t_cent).to_radians().sin_cos();
+    Vector3{ x: cos_p * v.x - sin_p * v.y, y: sin_p * v.x + cos_p * v.y, z: v.z }
+}
+
+fn ecliptic_to_equatorial(v: Vector3<f64>, t_cent: f64) -> Vector3<f64> {
+    let (sin_e, cos_e) = (23.439_291 - 0.013_004_2 * t_cent).to_radians().sin_cos();
+    Vector3{ x: v.x, y: cos_e * v.y - sin_e * v.z, z: sin_e * v.y + cos_e * v.z }
+}
+
+/// Returns geocentric position of `body` (m) in the equatorial frame of date.
+pub fn geocentric_equatorial(body: Body, t: chrono::DateTime<chrono::Utc>) -> Vector3<f64> {
+    let t_cent = data::julian_centuries(t);
+
+    let ecliptic = match body {
+        Body::Moon => moon_position(t_cent),
+
+        Body::Sun => precess_ecliptic(-heliocentric_position(&EARTH_MOON_BARYCENTER, t_cent), t_cent) * AU,
+
+        _ => {
+            let earth = heliocentric_position(&EARTH_MOON_BARYCENTER, t_cent);
+            let planet = heliocentric_position(&planet_elements(body).unwrap(), t_cent);
+            precess_ecliptic(planet - earth, t_cent) * AU
+        }
+    };
+
+    ecliptic_to_equatorial(ecliptic, t_cent)
+}
+
+pub struct SolarSystemBody {
+    body: Body
+}
+
+impl SolarSystemBody {
+    pub fn new(body: Body) -> SolarSystemBody {
+        SolarSystemBody{ body }
+    }
+
+    fn local_position(&self, t: chrono::DateTime<chrono::Utc>, observer: &ObserverLocation) -> Vector3<f64> {
+        let ecef = data::equatorial_to_ecef(geocentric_equatorial(self.body, t), t);
+        observer.ecef_to_local(ecef - observer.ecef())
+    }
+}
+
+impl TargetSource for SolarSystemBody {
+    fn name(&self) -> String { self.body.to_string() }
+
+    fn target(
+        &mut self,
+        t: chrono::DateTime<chrono::Utc>,
+        observer: &ObserverLocation
+    ) -> Result<data::Target, Box<dyn Error>> {
+        let position = self.local_position(t, observer);
+        let velocity = self.local_position(t + chrono::Duration::seconds(1), observer) - position; // per 1 s
+        let alt_above_gnd = f64::Length::new::<length::meter>(
+            geocentric_equatorial(self.body, t).magnitude() - pointing_utils::EARTH_RADIUS_M
+        );
+
+        Ok(data::Target::from_local(Point3::from_vec(position), velocity, alt_above_gnd))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn ra_dec_deg(v: Vector3<f64>) -> (f64, f64) {
+        (v.y.atan2(v.x).to_degrees().rem_euclid(360.0), (v.z / v.magnitude()).asin().to_degrees())
+    }
+
+    #[test]
+    fn sun_position_at_equinox() {
+        // March equinox of 2024: 2024-03-20 03:06 UTC
+        let t = chrono::Utc.with_ymd_and_hms(2024, 3, 20, 3, 6, 0).unwrap();
+        let (ra, dec) = ra_dec_deg(geocentric_equatorial(Body::Sun, t));
+        assert!(ra < 0.05 || ra > 359.95);
+        assert!(dec.abs() < 0.05);
+    }
+
+    #[test]
+    fn kepler_equation_solution() {
+        let e = 0.2;
+        let ecc_anomaly = solve_kepler(1.0, e);
+        assert!((ecc_anomaly - e * ecc_anomaly.sin() - 1.0).abs() < 1.0e-12);
+    }
+}
@@ -0,0 +1,94 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Stationary targets.
+
+use cgmath::{EuclideanSpace, Point3, Vector3};
+use crate::{data, data::ObserverLocation, target_source::TargetSource};
+use pointing_utils::{cgmath, uom};
+use std::error::Error;
+use uom::si::{f64, length};
+
+/// Distance at which a `FixedDirection` target is placed (affects only the displayed distance).
+const FIXED_DIRECTION_DIST: f64 = 100_000.0; // m
+
+/// Target at a fixed azimuth and altitude.
+pub struct FixedDirection {
+    name: String,
+    azimuth: f64::Angle,
+    altitude: f64::Angle
+}
+
+impl FixedDirection {
+    pub fn new(name: &str, azimuth: f64::Angle, altitude: f64::Angle) -> FixedDirection {
+        FixedDirection{ name: name.into(), azimuth, altitude }
+    }
+}
+
+impl TargetSource for FixedDirection {
+    fn name(&self) -> String { self.name.clone() }
+
+    fn target(
+        &mut self,
+        _t: chrono::DateTime<chrono::Utc>,
+        _observer: &ObserverLocation
+    ) -> Result<data::Target, Box<dyn Error>> {
+        let position = data::spherical_to_unit(self.azimuth, self.altitude).to_vec() * FIXED_DIRECTION_DIST;
+
+        Ok(data::Target::from_local(
+            Point3::from_vec(position),
+            Vector3{ x: 0.0, y: 0.0, z: 0.0 },
+            f64::Length::new::<length::meter>(0.0)
+        ))
+    }
+
+    fn refracted(&self) -> bool { false }
+}
+
+/// Target at a fixed geographic position (e.g., a landmark).
+pub struct GroundPoint {
+    name: String,
+    location: ObserverLocation
+}
+
+impl GroundPoint {
+    pub fn new(name: &str, location: ObserverLocation) -> GroundPoint {
+        GroundPoint{ name: name.into(), location }
+    }
+}
+
+impl TargetSource for GroundPoint {
+    fn name(&self) -> String { self.name.clone() }
+
+    fn target(
+        &mut self,
+        _t: chrono::DateTime<chrono::Utc>,
+        observer: &ObserverLocation
+    ) -> Result<data::Target, Box<dyn Error>> {
+        let position = observer.ecef_to_local(self.location.ecef() - observer.ecef());
+
+        Ok(data::Target::from_local(
+            Point3::from_vec(position),
+            Vector3{ x: 0.0, y: 0.0, z: 0.0 },
+            self.location.elevation
+        ))
+    }
+
+    // terrestrial refraction is not modeled
+    fn refracted(&self) -> bool { false }
+}
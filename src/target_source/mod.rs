@@ -0,0 +1,49 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+mod ephemeris;
+mod fixed;
+mod queue;
+mod simulated;
+mod tle;
+
+use crate::data;
+use std::error::Error;
+
+pub use ephemeris::{Body, SolarSystemBody};
+pub use fixed::{FixedDirection, GroundPoint};
+pub use queue::{load_queue_file, QueueEntry, TargetQueue};
+pub use simulated::{SimulatedPath, SimulatedTarget, SimulatedTargetParams};
+pub use tle::{load_tle_file, satellite_name, Satellite};
+
+/// Source of target data calculated locally (as opposed to being received from a data source over the network).
+pub trait TargetSource {
+    fn name(&self) -> String;
+
+    /// Returns target data at the specified time, as seen by `observer`.
+    #[must_use]
+    fn target(
+        &mut self,
+        t: chrono::DateTime<chrono::Utc>,
+        observer: &data::ObserverLocation
+    ) -> Result<data::Target, Box<dyn Error>>;
+
+    /// Returns whether the target's altitude is to be corrected for atmospheric refraction (not the case if it is
+    /// the apparent one already, e.g., captured by pointing the mount).
+    fn refracted(&self) -> bool { true }
+}
@@ -0,0 +1,189 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! List of targets to be tracked one after another.
+//!
+//! Queue file format: one entry per line, fields separated by `;`, lines starting with `#` are ignored:
+//!
+//! ```text
+//! <name>; azalt; <azimuth °>; <altitude °>
+//! <name>; latlon; <latitude °>; <longitude °>; <elevation m>
+//! <name>; tle; <TLE line 1>; <TLE line 2>
+//! ```
+
+use crate::{
+    data::{deg, ObserverLocation},
+    target_source::{FixedDirection, GroundPoint, Satellite, TargetSource}
+};
+use pointing_utils::uom;
+use std::{error::Error, path::Path};
+use uom::si::{f64, length};
+
+enum Position {
+    AzAlt(f64::Angle, f64::Angle),
+    Ground(ObserverLocation),
+    Orbit(sgp4::Elements)
+}
+
+pub struct QueueEntry {
+    name: String,
+    position: Position
+}
+
+impl QueueEntry {
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn kind(&self) -> &'static str {
+        match self.position {
+            Position::AzAlt(..) => "az/alt",
+            Position::Ground(_) => "lat/lon",
+            Position::Orbit(_) => "TLE"
+        }
+    }
+
+    /// Creates a target source corresponding to the entry.
+    pub fn source(&self) -> Result<Box<dyn TargetSource>, Box<dyn Error>> {
+        Ok(match &self.position {
+            Position::AzAlt(azimuth, altitude) => Box::new(FixedDirection::new(&self.name, *azimuth, *altitude)),
+            Position::Ground(location) => Box::new(GroundPoint::new(&self.name, *location)),
+            Position::Orbit(elements) => Box::new(Satellite::new(elements)?)
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct TargetQueue {
+    entries: Vec<QueueEntry>,
+    /// Index of the entry being tracked.
+    current: Option<usize>
+}
+
+impl TargetQueue {
+    pub fn new(entries: Vec<QueueEntry>) -> TargetQueue {
+        TargetQueue{ entries, current: None }
+    }
+
+    pub fn entries(&self) -> &[QueueEntry] { &self.entries }
+
+    pub fn current(&self) -> Option<usize> { self.current }
+
+    pub fn set_current(&mut self, idx: usize) {
+        if idx < self.entries.len() { self.current = Some(idx); }
+    }
+
+    /// Returns index of the entry following the current one (wraps around).
+    pub fn next(&self) -> Option<usize> {
+        if self.entries.is_empty() { return None; }
+        Some(match self.current {
+            Some(idx) => (idx + 1) % self.entries.len(),
+            None => 0
+        })
+    }
+
+    /// Returns index of the entry preceding the current one (wraps around).
+    pub fn previous(&self) -> Option<usize> {
+        if self.entries.is_empty() { return None; }
+        Some(match self.current {
+            Some(idx) => (idx + self.entries.len() - 1) % self.entries.len(),
+            None => self.entries.len() - 1
+        })
+    }
+}
+
+pub fn load_queue_file(path: &Path) -> Result<Vec<QueueEntry>, Box<dyn Error>> {
+    parse_queue(&std::fs::read_to_string(path)?)
+}
+
+fn parse_queue(contents: &str) -> Result<Vec<QueueEntry>, Box<dyn Error>> {
+    let mut result = vec![];
+    for (line_idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        result.push(parse_entry(line).map_err(|e| format!("line {}: {}", line_idx + 1, e))?);
+    }
+
+    Ok(result)
+}
+
+fn parse_entry(line: &str) -> Result<QueueEntry, Box<dyn Error>> {
+    let fields: Vec<&str> = line.split(';').map(|s| s.trim()).collect();
+    if fields.len() < 2 || fields[0].is_empty() { return Err("expected name and entry type".into()); }
+
+    let name = fields[0].to_string();
+    let expect_fields = |num: usize| -> Result<(), Box<dyn Error>> {
+        if fields.len() == num { Ok(()) } else { Err(format!("expected {} fields, got {}", num, fields.len()).into()) }
+    };
+
+    let position = match fields[1].to_lowercase().as_str() {
+        "azalt" => {
+            expect_fields(4)?;
+            Position::AzAlt(deg(fields[2].parse::<f64>()?), deg(fields[3].parse::<f64>()?))
+        },
+
+        "latlon" => {
+            expect_fields(5)?;
+            Position::Ground(ObserverLocation{
+                lat: deg(fields[2].parse::<f64>()?),
+                lon: deg(fields[3].parse::<f64>()?),
+                elevation: f64::Length::new::<length::meter>(fields[4].parse::<f64>()?)
+            })
+        },
+
+        "tle" => {
+            expect_fields(4)?;
+            Position::Orbit(sgp4::Elements::from_tle(Some(name.clone()), fields[2].as_bytes(), fields[3].as_bytes())?)
+        },
+
+        other => return Err(format!("unknown entry type \"{}\"", other).into())
+    };
+
+    Ok(QueueEntry{ name, position })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_parsing() {
+        let entries = parse_queue(
+            "# comment\n\
+             Tower; azalt; 123.5; 2.0\n\
+             \n\
+             Hilltop; LatLon; 50.1; 19.5; 350\n"
+        ).unwrap();
+        assert_eq!(2, entries.len());
+        assert_eq!("Tower", entries[0].name());
+        assert_eq!("az/alt", entries[0].kind());
+        assert_eq!("lat/lon", entries[1].kind());
+
+        assert!(parse_queue("Tower; azalt; 123.5").is_err());
+        assert!(parse_queue("Tower; radec; 1; 2").is_err());
+    }
+
+    #[test]
+    fn queue_navigation() {
+        let mut queue = TargetQueue::new(parse_queue("A; azalt; 0; 0\nB; azalt; 0; 0\nC; azalt; 0; 0").unwrap());
+        assert_eq!(Some(0), queue.next());
+        assert_eq!(Some(2), queue.previous());
+        queue.set_current(2);
+        assert_eq!(Some(0), queue.next());
+        assert_eq!(Some(1), queue.previous());
+        assert_eq!(None, TargetQueue::default().next());
+    }
+}
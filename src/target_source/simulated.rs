@@ -0,0 +1,204 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Simulated targets for practicing.
+
+use cgmath::{Point3, Vector3};
+use crate::{clock, data, data::{as_deg, deg, ObserverLocation}, target_source::TargetSource};
+use pointing_utils::{cgmath, uom};
+use std::error::Error;
+use strum_macros as sm;
+use uom::si::{angle, f64, length, velocity};
+
+/// Length of a straight-line pass (after which it repeats).
+const STRAIGHT_PASS_LENGTH: f64 = 60_000.0; // m
+
+#[derive(Copy, Clone, Debug, PartialEq, sm::EnumIter)]
+pub enum SimulatedPath {
+    /// Straight line passing the observer; repeats indefinitely.
+    Straight,
+    /// Circle around the observer.
+    Circular
+}
+
+impl std::fmt::Display for SimulatedPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self { SimulatedPath::Straight => "straight", SimulatedPath::Circular => "circular" })
+    }
+}
+
+impl std::str::FromStr for SimulatedPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "straight" => Ok(SimulatedPath::Straight),
+            "circular" => Ok(SimulatedPath::Circular),
+            _ => Err(format!("invalid path: {}", s))
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SimulatedTargetParams {
+    pub path: SimulatedPath,
+    pub speed: f64::Velocity,
+    /// Altitude above the observer.
+    pub altitude: f64::Length,
+    /// Direction of travel (for a circular path: initial direction of travel; clockwise if east of north).
+    pub course: f64::Angle,
+    /// Horizontal distance of closest approach (straight path) or circle radius (circular path).
+    pub distance: f64::Length
+}
+
+impl Default for SimulatedTargetParams {
+    fn default() -> SimulatedTargetParams {
+        SimulatedTargetParams{
+            path: SimulatedPath::Straight,
+            speed: f64::Velocity::new::<velocity::kilometer_per_hour>(800.0),
+            altitude: f64::Length::new::<length::meter>(8000.0),
+            course: deg(90.0),
+            distance: f64::Length::new::<length::meter>(5000.0)
+        }
+    }
+}
+
+impl std::fmt::Display for SimulatedTargetParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{};{};{};{};{}",
+            self.path,
+            self.speed.get::<velocity::kilometer_per_hour>(),
+            self.altitude.get::<length::meter>(),
+            as_deg(self.course),
+            self.distance.get::<length::meter>()
+        )
+    }
+}
+
+impl std::str::FromStr for SimulatedTargetParams {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(';').map(|p| p.trim()).collect();
+        if parts.len() != 5 { return Err("expected 5 fields".into()); }
+
+        Ok(SimulatedTargetParams{
+            path: parts[0].parse()?,
+            speed: f64::Velocity::new::<velocity::kilometer_per_hour>(parts[1].parse()?),
+            altitude: f64::Length::new::<length::meter>(parts[2].parse()?),
+            course: deg(parts[3].parse()?),
+            distance: f64::Length::new::<length::meter>(parts[4].parse()?)
+        })
+    }
+}
+
+/// Target moving along a simple path, specified relative to the observer.
+pub struct SimulatedTarget {
+    params: SimulatedTargetParams,
+    start: chrono::DateTime<chrono::Utc>
+}
+
+impl SimulatedTarget {
+    pub fn new(params: SimulatedTargetParams) -> Result<SimulatedTarget, Box<dyn Error>> {
+        if params.speed.get::<velocity::meter_per_second>() <= 0.0 { return Err("speed must be positive".into()); }
+        if params.distance.get::<length::meter>() <= 0.0 && params.path == SimulatedPath::Circular {
+            return Err("radius must be positive".into());
+        }
+
+        Ok(SimulatedTarget{ params, start: clock::utc_now() })
+    }
+}
+
+impl TargetSource for SimulatedTarget {
+    fn name(&self) -> String { format!("simulated ({})", self.params.path) }
+
+    fn target(
+        &mut self,
+        t: chrono::DateTime<chrono::Utc>,
+        _observer: &ObserverLocation
+    ) -> Result<data::Target, Box<dyn Error>> {
+        let elapsed = (t - self.start).num_milliseconds() as f64 / 1000.0;
+        let (position, velocity) = position_and_velocity(&self.params, elapsed);
+
+        Ok(data::Target::from_local(position, velocity, self.params.altitude))
+    }
+}
+
+/// Returns position and velocity (m, m/s) in the observer's local frame at `elapsed` seconds since the start.
+fn position_and_velocity(params: &SimulatedTargetParams, elapsed: f64) -> (Point3<f64>, Vector3<f64>) {
+    let speed = params.speed.get::<velocity::meter_per_second>();
+    let dist = params.distance.get::<length::meter>();
+    let alt = params.altitude.get::<length::meter>();
+    let (sin_c, cos_c) = params.course.get::<angle::radian>().sin_cos();
+
+    match params.path {
+        SimulatedPath::Straight => {
+            // local frame: X points north, Y points west
+            let dir = Vector3{ x: cos_c, y: -sin_c, z: 0.0 };
+            let closest = Vector3{ x: sin_c, y: cos_c, z: 0.0 } * dist;
+            let period = STRAIGHT_PASS_LENGTH / speed;
+            let s = (elapsed.rem_euclid(period) - period / 2.0) * speed;
+            let pos = closest + dir * s;
+
+            (Point3{ x: pos.x, y: pos.y, z: alt }, dir * speed)
+        },
+
+        SimulatedPath::Circular => {
+            // start due north of the observer (if course is 90°) and move clockwise as seen from above
+            let phase = params.course.get::<angle::radian>() - std::f64::consts::FRAC_PI_2 + elapsed * speed / dist;
+            let (sin_p, cos_p) = phase.sin_cos();
+            let pos = Point3{ x: dist * cos_p, y: -dist * sin_p, z: alt };
+            let vel = Vector3{ x: -speed * sin_p, y: -speed * cos_p, z: 0.0 };
+
+            (pos, vel)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{EuclideanSpace, InnerSpace};
+
+    #[test]
+    fn straight_path_passes_closest_point_mid_period() {
+        let params = SimulatedTargetParams{
+            path: SimulatedPath::Straight,
+            speed: f64::Velocity::new::<velocity::meter_per_second>(100.0),
+            altitude: f64::Length::new::<length::meter>(1000.0),
+            course: deg(90.0),
+            distance: f64::Length::new::<length::meter>(2000.0)
+        };
+        let (pos, vel) = position_and_velocity(&params, STRAIGHT_PASS_LENGTH / 100.0 / 2.0);
+        // heading east, passing 2 km north of the observer
+        assert!((pos.x - 2000.0).abs() < 1.0e-6);
+        assert!(pos.y.abs() < 1.0e-6);
+        assert!((vel.y - (-100.0)).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn circular_path_keeps_radius() {
+        let params = SimulatedTargetParams{ path: SimulatedPath::Circular, ..Default::default() };
+        for t in [0.0, 10.0, 100.0] {
+            let (pos, vel) = position_and_velocity(&params, t);
+            let horizontal = Vector3{ x: pos.x, y: pos.y, z: 0.0 };
+            assert!((horizontal.magnitude() - 5000.0).abs() < 1.0e-6);
+            assert!(horizontal.dot(vel).abs() < 1.0e-6);
+            assert!((pos.to_vec().z - 8000.0).abs() < 1.0e-9);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use cgmath::{EuclideanSpace, InnerSpace, Point3, Vector3};
+use crate::{data, data::ObserverLocation, target_source::TargetSource};
+use pointing_utils::{cgmath, uom};
+use std::{error::Error, path::Path};
+use uom::si::{f64, length};
+
+/// Earth's rotation rate (rad/s).
+const EARTH_ROT_RATE: f64 = 7.292_115_855_3e-5;
+
+/// Speed of light (m/s).
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Loads orbital elements from a file containing TLEs in 2- or 3-line (i.e., preceded by object name) format.
+pub fn load_tle_file(path: &Path) -> Result<Vec<sgp4::Elements>, Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = contents.lines().map(|l| l.trim_end()).filter(|l| !l.is_empty()).collect();
+
+    let mut result = vec![];
+    let mut idx = 0;
+    while idx < lines.len() {
+        if lines[idx].starts_with("1 ") && idx + 1 < lines.len() && lines[idx + 1].starts_with("2 ") {
+            result.push(sgp4::Elements::from_tle(None, lines[idx].as_bytes(), lines[idx + 1].as_bytes())?);
+            idx += 2;
+        } else if idx + 2 < lines.len() && lines[idx + 1].starts_with("1 ") && lines[idx + 2].starts_with("2 ") {
+            let name = lines[idx].trim_start_matches("0 ").trim().to_string();
+            result.push(sgp4::Elements::from_tle(Some(name), lines[idx + 1].as_bytes(), lines[idx + 2].as_bytes())?);
+            idx += 3;
+        } else {
+            return Err(format!("invalid TLE entry: \"{}\"", lines[idx]).into());
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn satellite_name(elements: &sgp4::Elements) -> String {
+    match &elements.object_name {
+        Some(name) => format!("{} ({})", name, elements.norad_id),
+        None => format!("{}", elements.norad_id)
+    }
+}
+
+pub struct Satellite {
+    name: String,
+    epoch: chrono::NaiveDateTime,
+    constants: sgp4::Constants,
+    /// If true, the optical (light-time and aberration corrected) position is calculated instead of the geometric one.
+    optical: bool
+}
+
+impl Satellite {
+    pub fn new(elements: &sgp4::Elements) -> Result<Satellite, Box<dyn Error>> {
+        Ok(Satellite{
+            name: satellite_name(elements),
+            epoch: elements.datetime,
+            constants: sgp4::Constants::from_elements(elements)?,
+            optical: false
+        })
+    }
+
+    pub fn set_optical(&mut self, optical: bool) { self.optical = optical; }
+
+    /// Returns position and velocity (m, m/s) at `t` in the TEME frame.
+    fn propagate(&self, t: chrono::DateTime<chrono::Utc>) -> Result<(Vector3<f64>, Vector3<f64>), Box<dyn Error>> {
+        let minutes = (t.naive_utc() - self.epoch).num_microseconds().ok_or("time out of range")? as f64 / 60.0e6;
+        let prediction = self.constants.propagate(sgp4::MinutesSinceEpoch(minutes))?;
+        Ok((Vector3::from(prediction.position) * 1000.0, Vector3::from(prediction.velocity) * 1000.0))
+    }
+}
+
+impl TargetSource for Satellite {
+    fn name(&self) -> String { self.name.clone() }
+
+    fn target(
+        &mut self,
+        t: chrono::DateTime<chrono::Utc>,
+        observer: &ObserverLocation
+    ) -> Result<data::Target, Box<dyn Error>> {
+        let (pos, vel) = self.propagate(t)?;
+        let (mut pos_ecef, mut vel_ecef) = teme_to_ecef(pos, vel, t);
+        let mut rel_pos = pos_ecef - observer.ecef();
+
+        if self.optical {
+            // the light reaching the observer at `t` was emitted earlier; both positions are rotated to the ECEF
+            // frame at `t`, so that their difference is taken in the inertial frame
+            let light_time = chrono::Duration::microseconds((rel_pos.magnitude() / SPEED_OF_LIGHT * 1.0e6) as i64);
+            let (pos, vel) = self.propagate(t - light_time)?;
+            (pos_ecef, vel_ecef) = teme_to_ecef(pos, vel, t);
+            rel_pos = pos_ecef - observer.ecef();
+
+            // aberration due to the observer's velocity (Earth's rotation)
+            let obs = observer.ecef();
+            let obs_vel = Vector3{ x: -EARTH_ROT_RATE * obs.y, y: EARTH_ROT_RATE * obs.x, z: 0.0 };
+            rel_pos = apply_aberration(rel_pos, obs_vel);
+        }
+
+        let position = observer.ecef_to_local(rel_pos);
+        let velocity = observer.ecef_to_local(vel_ecef);
+        let alt_above_gnd = f64::Length::new::<length::meter>(pos_ecef.magnitude() - pointing_utils::EARTH_RADIUS_M);
+
+        Ok(data::Target::from_local(Point3::from_vec(position), velocity, alt_above_gnd))
+    }
+}
+
+/// Converts position and velocity from the True Equator Mean Equinox frame (used by SGP4) to the Earth-centered,
+/// Earth-fixed frame (polar motion is neglected).
+fn teme_to_ecef(pos: Vector3<f64>, vel: Vector3<f64>, t: chrono::DateTime<chrono::Utc>) -> (Vector3<f64>, Vector3<f64>) {
+    let pos_ecef = data::equatorial_to_ecef(pos, t);
+    let vel_rotated = data::equatorial_to_ecef(vel, t);
+    let vel_ecef = Vector3{
+        x: vel_rotated.x + EARTH_ROT_RATE * pos_ecef.y,
+        y: vel_rotated.y - EARTH_ROT_RATE * pos_ecef.x,
+        z: vel_rotated.z
+    };
+
+    (pos_ecef, vel_ecef)
+}
+
+/// Returns `rel_pos` (target position relative to the observer) shifted to the apparent direction as seen by
+/// an observer moving with `obs_vel` (first-order approximation; the distance is preserved).
+fn apply_aberration(rel_pos: Vector3<f64>, obs_vel: Vector3<f64>) -> Vector3<f64> {
+    let dist = rel_pos.magnitude();
+    (rel_pos / dist + obs_vel / SPEED_OF_LIGHT).normalize() * dist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aberration_shifts_towards_observer_motion() {
+        let rel_pos = Vector3{ x: 1.0e6, y: 0.0, z: 0.0 };
+        let shifted = apply_aberration(rel_pos, Vector3{ x: 0.0, y: 400.0, z: 0.0 });
+        assert!((shifted.magnitude() - rel_pos.magnitude()).abs() < 1.0e-6);
+        // approx. 0.28"
+        let angle = (shifted.y / shifted.x).atan().to_degrees() * 3600.0;
+        assert!((angle - 0.275).abs() < 0.001);
+    }
+}
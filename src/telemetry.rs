@@ -0,0 +1,218 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Export of tracking metrics for long-term monitoring (e.g., graphing in Grafana).
+//!
+//! Metrics are either sent as InfluxDB line protocol in UDP datagrams (output "influx://<host>:<port>", e.g., to
+//! Telegraf's `socket_listener` or InfluxDB's UDP service), or served in the Prometheus text format
+//! (output "prometheus://<address>", e.g., "prometheus://0.0.0.0:9184"; any HTTP GET path is answered).
+
+use crate::data::{as_deg, as_deg_per_s};
+use pointing_utils::uom;
+use std::{error::Error, io::{Read, Write}, net::{TcpListener, TcpStream, UdpSocket}, time::Duration};
+use uom::si::f64;
+
+/// Measurement name (InfluxDB) and metric name prefix (Prometheus).
+const NAME: &str = "tptool";
+
+/// Scrape requests longer than this are rejected.
+const MAX_REQUEST_LEN: usize = 8192;
+
+/// Current values of exported metrics; unavailable ones are omitted.
+pub struct Metrics {
+    pub tracking: bool,
+    /// Position error (tracked position minus mount position) of each axis.
+    pub tracking_error: Option<(f64::Angle, f64::Angle)>,
+    /// Measured axis speeds of the main mount.
+    pub mount_spd: Option<(f64::AngularVelocity, f64::AngularVelocity)>,
+    /// Axis speeds calculated by tracking.
+    pub commanded_spd: Option<(f64::AngularVelocity, f64::AngularVelocity)>,
+    /// Average latencies: data source → TPTool, message processing, target update → mount command, mount command.
+    pub latency: [Option<Duration>; 4]
+}
+
+impl Metrics {
+    /// Returns names (without prefix) and values of available metrics.
+    fn values(&self) -> Vec<(&'static str, f64)> {
+        let mut result = vec![("tracking", if self.tracking { 1.0 } else { 0.0 })];
+        if let Some((e1, e2)) = self.tracking_error {
+            result.push(("tracking_error_axis1_deg", as_deg(e1)));
+            result.push(("tracking_error_axis2_deg", as_deg(e2)));
+        }
+        if let Some((s1, s2)) = self.mount_spd {
+            result.push(("mount_speed_axis1_deg_per_s", as_deg_per_s(s1)));
+            result.push(("mount_speed_axis2_deg_per_s", as_deg_per_s(s2)));
+        }
+        if let Some((s1, s2)) = self.commanded_spd {
+            result.push(("commanded_speed_axis1_deg_per_s", as_deg_per_s(s1)));
+            result.push(("commanded_speed_axis2_deg_per_s", as_deg_per_s(s2)));
+        }
+        let latency_names =
+            ["latency_network_ms", "latency_processing_ms", "latency_to_command_ms", "latency_command_ms"];
+        for (name, latency) in latency_names.iter().zip(self.latency.iter()) {
+            if let Some(latency) = latency { result.push((name, latency.as_secs_f64() * 1000.0)); }
+        }
+
+        result
+    }
+}
+
+/// Returns metrics as a single line in InfluxDB line protocol (timestamp is assigned by the receiver).
+pub fn to_line_protocol(metrics: &Metrics) -> String {
+    let fields: Vec<String> = metrics.values().iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+    format!("{} {}", NAME, fields.join(","))
+}
+
+/// Returns metrics in Prometheus text exposition format.
+pub fn to_prometheus(metrics: &Metrics) -> String {
+    let mut result = String::new();
+    for (name, value) in metrics.values() {
+        result += &format!("# TYPE {}_{} gauge\n{}_{} {}\n", NAME, name, NAME, name, value);
+    }
+
+    result
+}
+
+/// Connection of a Prometheus scraper waiting for the complete request.
+struct Scrape {
+    stream: TcpStream,
+    request: Vec<u8>
+}
+
+enum Destination {
+    Influx(UdpSocket),
+    Prometheus{ listener: TcpListener, pending: Vec<Scrape> }
+}
+
+pub struct Telemetry {
+    destination: Destination
+}
+
+impl Telemetry {
+    #[must_use]
+    pub fn new(output: &str) -> Result<Telemetry, Box<dyn Error>> {
+        let destination = if let Some(address) = output.strip_prefix("influx://") {
+            let socket = UdpSocket::bind("0.0.0.0:0")?;
+            socket.connect(address)?;
+            Destination::Influx(socket)
+        } else if let Some(address) = output.strip_prefix("prometheus://") {
+            let listener = TcpListener::bind(address)?;
+            listener.set_nonblocking(true)?;
+            Destination::Prometheus{ listener, pending: vec![] }
+        } else {
+            return Err(format!("expected influx://<host>:<port> or prometheus://<address>, got \"{}\"", output).into());
+        };
+
+        Ok(Telemetry{ destination })
+    }
+
+    pub fn description(&self) -> String {
+        match &self.destination {
+            Destination::Influx(socket) =>
+                format!("influx://{}", socket.peer_addr().map(|a| a.to_string()).unwrap_or_default()),
+            Destination::Prometheus{ listener, .. } =>
+                format!("prometheus://{}", listener.local_addr().map(|a| a.to_string()).unwrap_or_default())
+        }
+    }
+
+    /// Sends the metrics (InfluxDB) or answers pending scrape requests (Prometheus); to be called periodically.
+    #[must_use]
+    pub fn publish(&mut self, metrics: &Metrics) -> Result<(), Box<dyn Error>> {
+        match &mut self.destination {
+            Destination::Influx(socket) => match socket.send(to_line_protocol(metrics).as_bytes()) {
+                // the receiver is not running (yet); keep trying
+                Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => (),
+                result => { result?; }
+            },
+
+            Destination::Prometheus{ listener, pending } => {
+                loop {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            stream.set_nonblocking(true)?;
+                            pending.push(Scrape{ stream, request: vec![] });
+                        },
+                        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                        Err(e) => return Err(e.into())
+                    }
+                }
+                // completed or failed scrapes are dropped, which closes their connections
+                pending.retain_mut(|scrape| !serve(scrape, metrics));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the request and, once complete, sends the response; returns true if the connection is finished.
+fn serve(scrape: &mut Scrape, metrics: &Metrics) -> bool {
+    let mut chunk = [0u8; 1024];
+    loop {
+        match scrape.stream.read(&mut chunk) {
+            Ok(0) => return true,
+            Ok(n) => scrape.request.extend_from_slice(&chunk[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(_) => return true
+        }
+    }
+    if scrape.request.len() > MAX_REQUEST_LEN { return true; }
+    if !scrape.request.windows(4).any(|w| w == b"\r\n\r\n") { return false; }
+
+    let response = if scrape.request.starts_with(b"GET ") {
+        let body = to_prometheus(metrics);
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\
+            Connection: close\r\n\r\n{}",
+            body.len(), body
+        )
+    } else {
+        "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    };
+    if let Err(e) = scrape.stream.write_all(response.as_bytes()) {
+        log::warn!("failed to send metrics: {}", e);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{deg, deg_per_s};
+
+    #[test]
+    fn formats_metrics() {
+        let metrics = Metrics{
+            tracking: true,
+            tracking_error: Some((deg(0.5), deg(-0.25))),
+            mount_spd: None,
+            commanded_spd: Some((deg_per_s(1.5), deg_per_s(0.0))),
+            latency: [None, Some(Duration::from_millis(250)), None, None]
+        };
+        let line = to_line_protocol(&metrics);
+        assert!(line.starts_with("tptool tracking=1,tracking_error_axis1_deg="));
+        assert!(line.contains(",commanded_speed_axis2_deg_per_s=0,"));
+        assert!(line.ends_with(",latency_processing_ms=250"));
+        assert!(!line.contains("mount_speed"));
+        assert_eq!(1, line.matches(' ').count());
+
+        let text = to_prometheus(&metrics);
+        assert!(text.starts_with("# TYPE tptool_tracking gauge\ntptool_tracking 1\n"));
+        assert!(text.contains("\ntptool_latency_processing_ms 250\n"));
+    }
+}
@@ -0,0 +1,148 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Measurement of the system clock offset.
+
+use std::{error::Error, net::UdpSocket, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
+
+const NTP_PACKET_LEN: usize = 48;
+/// Leap indicator: 0, version: 4, mode: 3 (client).
+const NTP_CLIENT_HEADER: u8 = 0x23;
+const NTP_MODE_SERVER: u8 = 4;
+/// Seconds between 1900-01-01 (NTP epoch) and 1970-01-01 (Unix epoch).
+const NTP_UNIX_EPOCH_DIFF: u64 = 2_208_988_800;
+const QUERY_INTERVAL: Duration = Duration::from_secs(64);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Source of the reference time used to determine the system clock offset.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TimeReference {
+    Ntp,
+    Gps
+}
+
+impl std::fmt::Display for TimeReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self { TimeReference::Ntp => "NTP", TimeReference::Gps => "GPS" })
+    }
+}
+
+/// Measured offset of the system clock.
+#[derive(Copy, Clone, Debug)]
+pub struct ClockOffset {
+    /// Reference minus system time (in seconds).
+    pub offset: f64,
+    pub reference: TimeReference
+}
+
+/// Periodically queries an NTP server (SNTP) without blocking.
+pub struct NtpClient {
+    server: String,
+    socket: UdpSocket,
+    /// Transmit timestamp and time of the pending request.
+    pending: Option<(u64, Instant)>,
+    last_query: Option<Instant>
+}
+
+impl NtpClient {
+    /// Creates a client of `server` (e.g., "pool.ntp.org:123").
+    pub fn new(server: &str) -> Result<NtpClient, Box<dyn Error>> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(server)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(NtpClient{ server: server.to_string(), socket, pending: None, last_query: None })
+    }
+
+    pub fn server(&self) -> &str { &self.server }
+
+    /// Sends a query if due and processes the reply, if any; returns the offset (reference minus system time)
+    /// in seconds when a new measurement is available.
+    pub fn poll(&mut self) -> Result<Option<f64>, Box<dyn Error>> {
+        let mut result = None;
+
+        if let Some((t0, sent)) = self.pending {
+            let mut buf = [0u8; NTP_PACKET_LEN];
+            match self.socket.recv(&mut buf) {
+                Ok(n) => {
+                    let t3 = ntp_timestamp(SystemTime::now());
+                    if n < NTP_PACKET_LEN { return Err("malformed NTP reply".into()); }
+                    if buf[0] & 0x07 != NTP_MODE_SERVER || buf[1] == 0 {
+                        return Err("invalid NTP reply".into());
+                    }
+                    let read_ts = |ofs: usize| u64::from_be_bytes(buf[ofs..ofs + 8].try_into().unwrap());
+                    if read_ts(24) == t0 {
+                        result = Some(calc_offset(t0, read_ts(32), read_ts(40), t3));
+                        self.pending = None;
+                    }
+                },
+
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => if sent.elapsed() > REPLY_TIMEOUT {
+                    log::warn!("no reply from NTP server {}", self.server);
+                    self.pending = None;
+                },
+
+                Err(e) => return Err(e.into())
+            }
+        }
+
+        if self.pending.is_none() && self.last_query.is_none_or(|t| t.elapsed() >= QUERY_INTERVAL) {
+            let t0 = ntp_timestamp(SystemTime::now());
+            let mut request = [0u8; NTP_PACKET_LEN];
+            request[0] = NTP_CLIENT_HEADER;
+            request[40..48].copy_from_slice(&t0.to_be_bytes());
+            self.socket.send(&request)?;
+            self.pending = Some((t0, Instant::now()));
+            self.last_query = Some(Instant::now());
+        }
+
+        Ok(result)
+    }
+}
+
+/// Converts time to the NTP 64-bit fixed-point format.
+fn ntp_timestamp(time: SystemTime) -> u64 {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs() + NTP_UNIX_EPOCH_DIFF;
+    let frac = ((since_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    (secs << 32) | frac
+}
+
+fn ntp_to_secs(timestamp: u64) -> f64 {
+    (timestamp >> 32) as f64 + (timestamp & 0xFFFF_FFFF) as f64 / (1u64 << 32) as f64
+}
+
+/// Returns clock offset (in seconds) from request transmit (`t0`), server receive (`t1`), server transmit (`t2`)
+/// and reply receive (`t3`) timestamps.
+fn calc_offset(t0: u64, t1: u64, t2: u64, t3: u64) -> f64 {
+    ((ntp_to_secs(t1) - ntp_to_secs(t0)) + (ntp_to_secs(t2) - ntp_to_secs(t3))) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_calculation() {
+        let t0 = ntp_timestamp(UNIX_EPOCH + Duration::from_secs(1_000_000));
+        let at = |secs: f64| t0 + (secs * (1u64 << 32) as f64) as u64;
+
+        // server clock 1.5 s ahead, 0.2 s round trip
+        let offset = calc_offset(t0, at(1.6), at(1.6), at(0.2));
+        assert!((offset - 1.5).abs() < 1.0e-6);
+    }
+}
@@ -18,24 +18,82 @@
 
 use cgmath::{Basis3, Deg, EuclideanSpace, InnerSpace, Point3, Rad, Rotation, Rotation3, Vector3};
 use crate::{
+    clock,
+    controller::RateMapping,
     data,
+    latency::LatencyStats,
     data::{angle_diff, as_deg, as_deg_per_s, deg, deg_per_s, time, MountSpeed},
     mount,
-    mount::{Axis, Mount}
+    mount::{Axis, Mount},
+    search_pattern,
+    search_pattern::SearchParams
 };
 use pasts::notify::Notify;
 use pointing_utils::{cgmath, uom};
-use std::{cell::RefCell, error::Error, pin::Pin, rc::{Rc, Weak}, task::{Context, Poll, Waker}};
+use std::{cell::{Cell, RefCell}, error::Error, pin::Pin, rc::{Rc, Weak}, task::{Context, Poll, Waker}, time::Duration};
+use strum_macros as sm;
 use uom::si::{angle, f64};
 
 // TODO: convert to const `angular_velocity::degree_per_second` once supported
 const MATCH_POS_SPD_DEG_PER_S: f64 = 0.25;
 const MAX_ADJUSTMENT_SPD_DEG_PER_S: f64 = 0.5;
+/// Max. rate of rotating the adjustment direction in polar adjustment mode.
+const POLAR_ROTATION_SPD_DEG_PER_S: f64 = 45.0;
 
-const TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+/// After resuming from pause, the position error is closed gradually during this time.
+const RESUME_RAMP: Duration = Duration::from_secs(3);
+
+/// Interval used to calculate the speed along the search pattern.
+const SEARCH_SPD_DT: Duration = Duration::from_millis(100);
+
+/// Limits azimuth correction magnification near zenith.
+const MIN_COS_ALT_FOR_CORRECTION: f64 = 0.05;
 
 pub type AngSpeed = f64::AngularVelocity;
 
+/// What to do when target data have not been updated for a while.
+#[derive(Copy, Clone, Debug, PartialEq, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum TargetLossAction {
+    /// Keep following the target's extrapolated position (at its last known angular rates) for some time.
+    Coast,
+    /// Like `Coast`, but scan around the extrapolated position (see `search_pattern`).
+    Search,
+    Stop
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TargetLossPolicy {
+    /// Age of target data after which the target is considered lost.
+    pub stale_after: Duration,
+    pub action: TargetLossAction,
+    /// Time to coast (or search) before stopping (if `action` is `Coast` or `Search`).
+    pub coast_time: Duration,
+    pub search: SearchParams
+}
+
+impl Default for TargetLossPolicy {
+    fn default() -> TargetLossPolicy {
+        TargetLossPolicy{
+            stale_after: Duration::from_secs(2),
+            action: TargetLossAction::Coast,
+            coast_time: Duration::from_secs(10),
+            search: Default::default()
+        }
+    }
+}
+
+/// Mount behavior while tracking is paused.
+#[derive(Copy, Clone, Debug, Default, PartialEq, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum PauseMode {
+    /// Keep moving at the last commanded speeds.
+    #[default]
+    Coast,
+    /// Stop the mount.
+    Hold
+}
+
 #[derive(Clone)]
 pub struct TrackingController {
     state: Weak<RefCell<State>>,
@@ -56,6 +114,64 @@ impl TrackingController {
         self.state.upgrade().unwrap().borrow().timer.is_some()
     }
 
+    /// Pauses tracking (see `PauseMode`); target data are still monitored.
+    pub fn pause(&self) {
+        let state = self.state.upgrade().unwrap();
+        let mut state = state.borrow_mut();
+        if state.timer.is_none() || state.paused { return; }
+        log::info!("pause tracking");
+        state.paused = true;
+    }
+
+    /// Resumes paused tracking.
+    pub fn resume(&self) {
+        let state = self.state.upgrade().unwrap();
+        let mut state = state.borrow_mut();
+        if !state.paused { return; }
+        log::info!("resume tracking");
+        state.paused = false;
+        state.resumed = Some(clock::now());
+        state.last_sample = None;
+        state.quantizers = Default::default();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.upgrade().unwrap().borrow().paused
+    }
+
+    /// In dry-run mode tracking computes axis speeds, but does not command the mount.
+    pub fn set_dry_run(&self, value: bool) {
+        log::info!("tracking dry run {}", if value { "enabled" } else { "disabled" });
+        self.state.upgrade().unwrap().borrow_mut().dry_run = value;
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.state.upgrade().unwrap().borrow().dry_run
+    }
+
+    /// Returns current adjustment (see `Tracking::adjustment`).
+    pub fn adjustment(&self) -> Option<(f64::Angle, f64::Angle)> {
+        self.state.upgrade().unwrap().borrow().adjustment.as_ref().map(|adj| (adj.rel_dir, adj.angle))
+    }
+
+    /// Restores a previously saved adjustment (see `Tracking::adjustment`).
+    pub fn restore_adjustment(&self, value: Option<(f64::Angle, f64::Angle)>) {
+        self.state.upgrade().unwrap().borrow_mut().adjustment = value.map(|(rel_dir, angle)| Adjustment{ rel_dir, angle });
+    }
+
+    pub fn stats(&self) -> TrackingStats {
+        self.state.upgrade().unwrap().borrow().stats.clone()
+    }
+
+    pub fn reset_stats(&self) {
+        self.state.upgrade().unwrap().borrow_mut().stats = Default::default();
+    }
+
+    /// Returns max. speed of manual adjustments during tracking.
+    pub fn adjustment_slew_speed(&self) -> AngSpeed {
+        self.state.upgrade().unwrap().borrow().adjustment_slew_speed
+    }
+
     pub fn change_adjustment_slew_speed(&self, factor: f64) {
         let state = self.state.upgrade().unwrap();
         let mut state = state.borrow_mut();
@@ -63,6 +179,100 @@ impl TrackingController {
             .max(deg_per_s(0.025))
             .min(deg_per_s(MAX_ADJUSTMENT_SPD_DEG_PER_S));
     }
+
+    /// Returns offsets added to the tracking speeds of both axes.
+    pub fn trim(&self) -> (AngSpeed, AngSpeed) {
+        self.state.upgrade().unwrap().borrow().trim
+    }
+
+    /// Changes the offset added to the tracking speed of `axis` by `delta`.
+    pub fn change_trim(&self, axis: Axis, delta: AngSpeed) {
+        let state = self.state.upgrade().unwrap();
+        let mut state = state.borrow_mut();
+        let trim = match axis { Axis::Primary => &mut state.trim.0, Axis::Secondary => &mut state.trim.1 };
+        *trim += delta;
+        // avoid showing accumulated rounding errors as tiny non-zero values
+        if as_deg_per_s(*trim).abs() < 1.0e-9 { *trim = deg_per_s(0.0); }
+        log::info!(
+            "tracking rate trim: axis 1 {:+.4}°/s, axis 2 {:+.4}°/s",
+            as_deg_per_s(state.trim.0),
+            as_deg_per_s(state.trim.1)
+        );
+    }
+
+    pub fn reset_trim(&self) {
+        log::info!("reset tracking rate trim");
+        self.state.upgrade().unwrap().borrow_mut().trim = (deg_per_s(0.0), deg_per_s(0.0));
+    }
+}
+
+/// Tracking performance statistics (of the first mount to which tracking is routed).
+#[derive(Clone)]
+pub struct TrackingStats {
+    pub time_tracked: Duration,
+    num_samples: usize,
+    /// Sums of squared position errors of primary and secondary axes (in degrees squared).
+    sum_sq_error: (f64, f64),
+    pub max_error: (f64::Angle, f64::Angle),
+    /// Number of saved manual adjustments.
+    pub num_adjustments: usize,
+    /// Max. absolute axis speeds calculated by tracking.
+    pub max_speed: (AngSpeed, AngSpeed)
+}
+
+impl Default for TrackingStats {
+    fn default() -> TrackingStats {
+        TrackingStats{
+            time_tracked: Duration::ZERO,
+            num_samples: 0,
+            sum_sq_error: (0.0, 0.0),
+            max_error: (deg(0.0), deg(0.0)),
+            num_adjustments: 0,
+            max_speed: (deg_per_s(0.0), deg_per_s(0.0))
+        }
+    }
+}
+
+impl TrackingStats {
+    fn add_sample(&mut self, interval: Duration, error: (f64::Angle, f64::Angle), speed: (AngSpeed, AngSpeed)) {
+        self.time_tracked += interval;
+        self.num_samples += 1;
+        self.sum_sq_error.0 += as_deg(error.0).powi(2);
+        self.sum_sq_error.1 += as_deg(error.1).powi(2);
+        self.max_error.0 = self.max_error.0.max(error.0.abs());
+        self.max_error.1 = self.max_error.1.max(error.1.abs());
+        self.max_speed.0 = self.max_speed.0.max(speed.0.abs());
+        self.max_speed.1 = self.max_speed.1.max(speed.1.abs());
+    }
+
+    /// Returns RMS position error of primary and secondary axes.
+    pub fn rms_error(&self) -> Option<(f64::Angle, f64::Angle)> {
+        if self.num_samples == 0 { return None; }
+        let n = self.num_samples as f64;
+        Some((deg((self.sum_sq_error.0 / n).sqrt()), deg((self.sum_sq_error.1 / n).sqrt())))
+    }
+
+    /// Returns a human-readable summary.
+    pub fn summary(&self) -> String {
+        let secs = self.time_tracked.as_secs();
+        let mut result = format!("Time tracked:      {}:{:02}:{:02}\n", secs / 3600, secs / 60 % 60, secs % 60);
+        match self.rms_error() {
+            Some((rms1, rms2)) => {
+                result += &format!("RMS error:         az. {:.4}°, alt. {:.4}°\n", as_deg(rms1), as_deg(rms2));
+                result += &format!(
+                    "Max. error:        az. {:.4}°, alt. {:.4}°\n", as_deg(self.max_error.0), as_deg(self.max_error.1)
+                );
+            },
+            None => result += "RMS error:         (no data)\n"
+        }
+        result += &format!("Adjustments saved: {}\n", self.num_adjustments);
+        result += &format!(
+            "Max. axis speeds:  az. {:.3}°/s, alt. {:.3}°/s\n",
+            as_deg_per_s(self.max_speed.0),
+            as_deg_per_s(self.max_speed.1)
+        );
+        result
+    }
 }
 
 pub struct Running(pub bool);
@@ -76,18 +286,64 @@ struct State {
     callback: Box<OnTrackingStateChanged>,
     adjusting: bool,
     adjustment: Option<Adjustment>,
-    adjustment_slew_speed: AngSpeed
+    adjustment_slew_speed: AngSpeed,
+    coasting: bool,
+    dry_run: bool,
+    /// Last axis speeds calculated by tracking.
+    commanded: Option<(AngSpeed, AngSpeed)>,
+    stats: TrackingStats,
+    /// Time of the last statistics sample.
+    last_sample: Option<std::time::Instant>,
+    timer_interval: Duration,
+    /// Per axis of the main and the secondary mount.
+    quantizers: [[RateQuantizer; 2]; 2],
+    paused: bool,
+    pause_mode: PauseMode,
+    /// Time of the last resumption from pause.
+    resumed: Option<std::time::Instant>,
+    /// Reception time of the target data last used for commanding the mount.
+    commanded_target: Option<std::time::Instant>,
+    /// Duration of blending axis speeds when switching between manual slewing and tracking (zero: no blending).
+    handover_time: Duration,
+    handover: Option<Handover>,
+    /// Offsets of axis speeds (relative to the target's) requested by manual adjustment.
+    manual_spd: (AngSpeed, AngSpeed),
+    /// Rates of change of adjustment's direction and angle requested in polar adjustment mode.
+    polar_spd: Option<(AngSpeed, AngSpeed)>,
+    /// Offsets added to the tracking speed of each axis (rate trim); kept when tracking stops.
+    trim: (AngSpeed, AngSpeed)
 }
 
 impl State {
-    fn new(callback: Box<OnTrackingStateChanged>) -> State {
+    fn new(
+        callback: Box<OnTrackingStateChanged>,
+        timer_interval: Duration,
+        pause_mode: PauseMode,
+        handover_time: Duration
+    ) -> State {
         State{
             timer: None,
             waker: None,
             callback,
             adjusting: false,
             adjustment: None,
-            adjustment_slew_speed: deg_per_s(MAX_ADJUSTMENT_SPD_DEG_PER_S)
+            adjustment_slew_speed: deg_per_s(MAX_ADJUSTMENT_SPD_DEG_PER_S),
+            coasting: false,
+            dry_run: false,
+            commanded: None,
+            stats: Default::default(),
+            last_sample: None,
+            timer_interval,
+            quantizers: Default::default(),
+            paused: false,
+            pause_mode,
+            resumed: None,
+            commanded_target: None,
+            handover_time,
+            handover: None,
+            manual_spd: (deg_per_s(0.0), deg_per_s(0.0)),
+            polar_spd: None,
+            trim: (deg_per_s(0.0), deg_per_s(0.0))
         }
     }
 
@@ -98,7 +354,11 @@ impl State {
     }
 
     fn start_tracking(&mut self) {
-        self.timer = Some(data::Timer::new(0, TIMER_INTERVAL));
+        self.timer = Some(data::Timer::new(0, self.timer_interval));
+        self.quantizers = Default::default();
+        self.paused = false;
+        self.resumed = None;
+        self.start_handover();
         (*self.callback)(Running(true));
     }
 
@@ -106,10 +366,57 @@ impl State {
         self.timer = None;
         self.adjusting = false;
         self.adjustment = None;
+        self.coasting = false;
+        self.commanded = None;
+        self.last_sample = None;
+        self.paused = false;
+        self.handover = None;
+        self.polar_spd = None;
         (*self.callback)(Running(false));
     }
+
+    fn start_handover(&mut self) {
+        self.handover = if self.handover_time.is_zero() { None } else { Some(Handover::new()) };
+    }
+
+    /// Returns progress (0.0-1.0) of the handover in progress; it ends once the progress reaches 1.0.
+    fn handover_progress(&mut self) -> Option<f64> {
+        let elapsed = clock::elapsed(self.handover.as_ref()?.start);
+        let progress = (elapsed.as_secs_f64() / self.handover_time.as_secs_f64()).min(1.0);
+        if progress >= 1.0 { self.handover = None; }
+        Some(progress)
+    }
+
+    /// Returns `speed` to command to `axis` of the main (`mount_idx` = 0) or secondary mount, blended with
+    /// the axis speed at the start of the handover (`current` is used if not yet known).
+    fn handover_speed(
+        &mut self,
+        mount_idx: usize,
+        axis: Axis,
+        current: Option<AngSpeed>,
+        speed: AngSpeed,
+        progress: Option<f64>
+    ) -> AngSpeed {
+        let (Some(progress), Some(handover)) = (progress, self.handover.as_mut()) else { return speed; };
+        let from = *handover.from[mount_idx][axis as usize].get_or_insert(current.unwrap_or(speed));
+        blend_speed(from, speed, progress)
+    }
 }
 
+/// Blending of axis speeds when switching between manual slewing and tracking.
+struct Handover {
+    start: std::time::Instant,
+    /// Speeds of the axes of the main and the secondary mount at the start (recorded when first commanded).
+    from: [[Option<AngSpeed>; 2]; 2]
+}
+
+impl Handover {
+    fn new() -> Handover {
+        Handover{ start: clock::now(), from: Default::default() }
+    }
+}
+
+#[derive(Copy, Clone)]
 struct Adjustment {
     /// Angle of rotation of tangent velocity around target position vector.
     rel_dir: f64::Angle,
@@ -118,91 +425,255 @@ struct Adjustment {
 }
 
 pub struct Tracking {
+    /// Max. axis speed used if the mount does not report it.
     max_spd: AngSpeed,
     mount: Rc<RefCell<Option<mount::MountWrapper>>>,
+    /// Secondary (e.g., piggyback) mount.
+    mount2: Rc<RefCell<Option<mount::MountWrapper>>>,
+    mount_routing: Rc<Cell<mount::MountRouting>>,
     mount_spd: Rc<RefCell<MountSpeed>>, // TODO: make it unwriteable from here
     state: Rc<RefCell<State>>,
     target: Rc<RefCell<Option<data::Target>>>, // TODO: make it unwriteable from here
+    target_loss: TargetLossPolicy,
+    latency: Rc<RefCell<LatencyStats>>
+}
+
+/// Parameters of `Tracking::new`.
+pub struct TrackingParams {
+    /// Max. axis speed used if the mount does not report it.
+    pub max_spd: AngSpeed,
+    pub mount: Rc<RefCell<Option<mount::MountWrapper>>>,
+    /// Secondary (e.g., piggyback) mount.
+    pub mount2: Rc<RefCell<Option<mount::MountWrapper>>>,
+    pub mount_routing: Rc<Cell<mount::MountRouting>>,
+    pub mount_spd: Rc<RefCell<MountSpeed>>,
+    pub target: Rc<RefCell<Option<data::Target>>>,
+    pub target_loss: TargetLossPolicy,
+    pub pause_mode: PauseMode,
+    /// Interval of tracking updates.
+    pub timer_interval: Duration,
+    /// Duration of blending axis speeds when switching between manual slewing and tracking.
+    pub handover_time: Duration,
+    pub latency: Rc<RefCell<LatencyStats>>
 }
 
 impl Tracking {
-    pub fn new(
-        max_spd: AngSpeed,
-        mount: Rc<RefCell<Option<mount::MountWrapper>>>,
-        mount_spd: Rc<RefCell<MountSpeed>>,
-        target: Rc<RefCell<Option<data::Target>>>,
-        callback: Box<OnTrackingStateChanged>
-    ) -> Tracking {
+    pub fn new(params: TrackingParams, callback: Box<OnTrackingStateChanged>) -> Tracking {
+        let TrackingParams{
+            max_spd,
+            mount,
+            mount2,
+            mount_routing,
+            mount_spd,
+            target,
+            target_loss,
+            pause_mode,
+            timer_interval,
+            handover_time,
+            latency
+        } = params;
+
         Tracking{
             max_spd,
             mount,
+            mount2,
+            mount_routing,
             mount_spd,
-            state: Rc::new(RefCell::new(State::new(callback))),
-            target
+            state: Rc::new(RefCell::new(State::new(callback, timer_interval, pause_mode, handover_time))),
+            target,
+            target_loss,
+            latency
         }
     }
 
+    pub fn target_loss_policy(&self) -> TargetLossPolicy { self.target_loss }
+
+    /// Returns the connected mounts to which tracking and manual adjustments are routed.
+    fn routed_mounts(&self) -> Vec<Rc<RefCell<Option<mount::MountWrapper>>>> {
+        self.mount_routing.get().routed(&self.mount, &self.mount2)
+            .into_iter()
+            .filter(|m| m.borrow().is_some())
+            .cloned()
+            .collect()
+    }
+
     fn on_timer(&mut self) -> Result<(), Box<dyn Error>> {
-        if self.mount.borrow().is_none() {
+        let mounts = self.routed_mounts();
+        if mounts.is_empty() {
             return Err("mount not connected".into());
         }
 
-        if self.state.borrow().adjusting { return Ok(()); }
+        if self.state.borrow().adjusting {
+            // during a handover speeds are updated periodically (otherwise only by `adjust_slew`)
+            if self.state.borrow().handover.is_some() {
+                let progress = self.state.borrow_mut().handover_progress();
+                self.slew_adjusting(progress);
+            }
+            return Ok(());
+        }
 
-        if self.mount_spd.borrow().get().is_none() {
+        self.update_polar_adjustment();
+
+        if Rc::ptr_eq(&mounts[0], &self.mount) && self.mount_spd.borrow().get().is_none() {
             log::debug!("waiting for mount speed estimation");
             return Ok(());
         }
 
-        let (mount_az, mount_alt) = match self.mount.borrow_mut().as_mut().unwrap().position() {
-            Ok(p) => p,
-            Err(e) => {
-                log::warn!("failed to get mount position: {}", e);
-                return Ok(());
-            }
-        };
-        // calling `MountWrapper::position` might have triggered the max travel exceeded callback and disabled tracking
-        if self.state.borrow().timer.is_none() { return Ok(()); }
-
-        let az_delta;
-        let alt_delta;
-        let target_az_spd;
-        let target_alt_spd;
+        let mut tracked_pos;
+        let mut target_spd;
+        let mut search_time = None;
+        let mut target_age = None;
         {
             let t = self.target.borrow();
             let target = t.as_ref().ok_or::<Box<dyn Error>>("no target".into())?;
 
-            let (target_az, target_alt) = if let Some(adj) = self.state.borrow().adjustment.as_ref() {
-                get_adjusted_pos(target.azimuth, target.altitude, target.v_tangential, adj)
-            } else {
-                (target.azimuth, target.altitude)
-            };
+            let age = target.age();
+            let mut extrapolate_by = Duration::ZERO;
+            if age > self.target_loss.stale_after {
+                let policy = self.target_loss;
+                if policy.action == TargetLossAction::Stop || age > policy.stale_after + policy.coast_time {
+                    log::warn!("target lost (no data for {:.1} s); stopping", age.as_secs_f64());
+                    drop(t);
+                    for mount in &mounts { mount.borrow_mut().as_mut().unwrap().stop()?; }
+                    self.state.borrow_mut().stop_tracking();
+                    return Ok(());
+                }
+
+                if !self.state.borrow().coasting {
+                    if policy.action == TargetLossAction::Search {
+                        log::warn!("target data stale; searching");
+                    } else {
+                        log::warn!("target data stale; coasting");
+                    }
+                    self.state.borrow_mut().coasting = true;
+                }
+                extrapolate_by = age;
+                if policy.action == TargetLossAction::Search { search_time = Some(age - policy.stale_after); }
+            } else if self.state.borrow().coasting {
+                log::info!("target data resumed");
+                let mut state = self.state.borrow_mut();
+                state.coasting = false;
+                // the mount may be far from the target after searching; re-lock gradually
+                if self.target_loss.action == TargetLossAction::Search { state.resumed = Some(clock::now()); }
+            }
 
-            az_delta = angle_diff(mount_az, target_az);
-            alt_delta = angle_diff(mount_alt, target_alt);
-            target_az_spd = target.az_spd;
-            target_alt_spd = target.alt_spd;
+            tracked_pos = tracked_position(target, extrapolate_by, self.state.borrow().adjustment.as_ref());
+            target_spd = (target.az_spd, target.alt_spd);
+            if self.state.borrow().commanded_target != Some(target.received) {
+                self.state.borrow_mut().commanded_target = Some(target.received);
+                target_age = Some(age);
+            }
         }
 
-        log::debug!("az. delta = {:.1}°, alt. delta = {:.1}°", as_deg(az_delta), as_deg(alt_delta));
+        if let Some(search_time) = search_time {
+            let (offset, offset_spd) = search_axis_offsets(&self.target_loss.search, search_time, tracked_pos.1);
+            tracked_pos = (tracked_pos.0 + offset.0, (tracked_pos.1 + offset.1).min(deg(90.0)).max(deg(-90.0)));
+            target_spd = (target_spd.0 + offset_spd.0, target_spd.1 + offset_spd.1);
+        }
 
-        self.update_axis(Axis::Primary, az_delta, target_az_spd)?;
-        self.update_axis(Axis::Secondary, alt_delta, target_alt_spd)?;
+        let trim = self.state.borrow().trim;
+        target_spd = (target_spd.0 + trim.0, target_spd.1 + trim.1);
+
+        if self.state.borrow().paused {
+            if self.state.borrow().pause_mode == PauseMode::Hold && !self.state.borrow().dry_run {
+                for mount in &mounts {
+                    let mut mount = mount.borrow_mut();
+                    let mount = mount.as_mut().unwrap();
+                    if mount.is_push_to() { continue; }
+                    for axis in [Axis::Primary, Axis::Secondary] {
+                        mount.slew_axis(axis, deg_per_s(0.0))?;
+                    }
+                }
+            }
+            self.state.borrow_mut().commanded = None;
+            return Ok(());
+        }
+
+        // after resuming, ramp up the closing of position error to avoid an abrupt speed change
+        let resume_ramp = match self.state.borrow().resumed {
+            Some(t) => (clock::elapsed(t).as_secs_f64() / RESUME_RAMP.as_secs_f64()).min(1.0),
+            None => 1.0
+        };
+
+        let handover = self.state.borrow_mut().handover_progress();
+
+        let mut commanded = None;
+        for mount in &mounts {
+            let mount_pos = match mount.borrow_mut().as_mut().unwrap().position() {
+                Ok(p) => p,
+                Err(e) => {
+                    log::warn!("failed to get mount position: {}", e);
+                    continue;
+                }
+            };
+            // calling `MountWrapper::position` might have triggered the max travel exceeded callback
+            // and disabled tracking
+            if self.state.borrow().timer.is_none() { return Ok(()); }
+
+            let max_spd = mount.borrow().as_ref().unwrap().max_slew_speed().unwrap_or(self.max_spd);
+            let mut step = control_step(mount_pos, tracked_pos, target_spd, max_spd);
+            if resume_ramp < 1.0 {
+                step.speed.0 = target_spd.0 + (step.speed.0 - target_spd.0) * resume_ramp;
+                step.speed.1 = target_spd.1 + (step.speed.1 - target_spd.1) * resume_ramp;
+            }
+            log::debug!("az. delta = {:.1}°, alt. delta = {:.1}°", as_deg(step.error.0), as_deg(step.error.1));
+
+            // in push-to mode the mount is moved by hand; the error is still computed for stats & guidance
+            let push_to = mount.borrow().as_ref().unwrap().is_push_to();
+            if !self.state.borrow().dry_run && !push_to {
+                let mount_idx = if Rc::ptr_eq(mount, &self.mount) { 0 } else { 1 };
+                let mut mount = mount.borrow_mut();
+                let mount = mount.as_mut().unwrap();
+                let resolution = mount.rate_resolution();
+                for (axis, speed) in [(Axis::Primary, step.speed.0), (Axis::Secondary, step.speed.1)] {
+                    let mut state = self.state.borrow_mut();
+                    let speed = state.handover_speed(mount_idx, axis, mount.commanded_speed(axis), speed, handover);
+                    let speed = state.quantizers[mount_idx][axis as usize].quantize(speed, resolution);
+                    drop(state);
+                    // needless commands are skipped by `MountWrapper` (see `CommandThrottle`)
+                    let prev_speed = mount.commanded_speed(axis);
+                    let t_start = std::time::Instant::now();
+                    mount.slew_axis(axis, speed)?;
+                    if mount.commanded_speed(axis) != prev_speed {
+                        self.latency.borrow_mut().command.add(t_start.elapsed());
+                    }
+                }
+                if let Some(age) = target_age.take() { self.latency.borrow_mut().to_command.add(age); }
+            }
+            if commanded.is_none() {
+                commanded = Some(step.speed);
+                self.add_stats_sample(step.error, step.speed);
+            }
+        }
+        self.state.borrow_mut().commanded = commanded;
 
         Ok(())
     }
 
-    fn update_axis(
-        &mut self,
-        axis: Axis,
-        pos_delta: f64::Angle,
-        target_spd: f64::AngularVelocity,
-    ) -> Result<(), Box<dyn Error>> {
-        let mut spd = target_spd + deg_per_s(as_deg(pos_delta) * MATCH_POS_SPD_DEG_PER_S);
-        if spd < -self.max_spd { spd = -self.max_spd; } else if spd > self.max_spd { spd = self.max_spd; }
-        self.mount.borrow_mut().as_mut().unwrap().slew_axis(axis, spd)?;
+    fn add_stats_sample(&self, error: (f64::Angle, f64::Angle), speed: (AngSpeed, AngSpeed)) {
+        let now = clock::now();
+        let mut state = self.state.borrow_mut();
+        // longer intervals (e.g., during manual adjustments) are not counted in full
+        let max_interval = 2 * state.timer_interval;
+        let interval = state.last_sample.map_or(Duration::ZERO, |t| now.saturating_duration_since(t).min(max_interval));
+        state.stats.add_sample(interval, error, speed);
+        state.last_sample = Some(now);
+    }
 
-        Ok(())
+    /// Returns the position error (tracked position minus `mount_pos`) of each axis and the rate of its change
+    /// given `mount_spd`; `None` if there is no target.
+    pub fn pointing_error(
+        &self,
+        mount_pos: (f64::Angle, f64::Angle),
+        mount_spd: (AngSpeed, AngSpeed)
+    ) -> Option<((f64::Angle, f64::Angle), (AngSpeed, AngSpeed))> {
+        let t = self.target.borrow();
+        let target = t.as_ref()?;
+        let age = target.age();
+        let extrapolate_by = if age > self.target_loss.stale_after { age } else { Duration::ZERO };
+        let tracked_pos = tracked_position(target, extrapolate_by, self.state.borrow().adjustment.as_ref());
+
+        Some((position_error(mount_pos, tracked_pos), (target.az_spd - mount_spd.0, target.alt_spd - mount_spd.1)))
     }
 
     pub fn controller(&self) -> TrackingController {
@@ -213,21 +684,98 @@ impl Tracking {
         self.state.borrow().timer.is_some()
     }
 
+    /// Returns the last axis speeds calculated by tracking (commanded to the mount unless in dry-run mode).
+    pub fn commanded_speeds(&self) -> Option<(AngSpeed, AngSpeed)> {
+        self.state.borrow().commanded
+    }
+
+    /// Returns current adjustment: direction relative to target's motion and angular offset.
+    pub fn adjustment(&self) -> Option<(f64::Angle, f64::Angle)> {
+        self.state.borrow().adjustment.as_ref().map(|adj| (adj.rel_dir, adj.angle))
+    }
+
     /// Parameters are between [-1.0; 1.0].
-    pub fn adjust_slew(&mut self, axis1_rel_spd: f64, axis2_rel_spd: f64) {
+    pub fn adjust_slew(&mut self, axis1_rel_spd: f64, axis2_rel_spd: f64, rate_mapping: &RateMapping) {
         if !self.state.borrow().adjusting {
-            self.state.borrow_mut().adjusting = true;
+            let mut state = self.state.borrow_mut();
+            state.adjusting = true;
+            state.start_handover();
             log::info!("begin manual adjustment");
         }
 
         let adj_speed = self.state.borrow().adjustment_slew_speed;
+        self.state.borrow_mut().manual_spd =
+            (rate_mapping.speed(axis1_rel_spd, adj_speed), rate_mapping.speed(axis2_rel_spd, adj_speed));
+        let progress = self.state.borrow_mut().handover_progress();
+        self.slew_adjusting(progress);
+    }
+
+    /// Polar adjustment mode: `rotation_rel_spd` rotates the adjustment direction and `magnitude_rel_spd` changes
+    /// the adjustment angle (both between [-1.0; 1.0]). The mount keeps tracking the adjusted position.
+    pub fn adjust_polar(&mut self, rotation_rel_spd: f64, magnitude_rel_spd: f64, rate_mapping: &RateMapping) {
+        let mut state = self.state.borrow_mut();
+        if state.adjusting { return; }
 
+        let active = rotation_rel_spd != 0.0 || magnitude_rel_spd != 0.0;
+        if active && state.polar_spd.is_none() {
+            log::info!("begin polar adjustment");
+        } else if !active && state.polar_spd.is_some() {
+            if let Some(adjustment) = &state.adjustment {
+                log::info!(
+                    "using new adjustment: rel_dir = {:.01}°, angle = {:.02}°",
+                    as_deg(adjustment.rel_dir),
+                    as_deg(adjustment.angle)
+                );
+            }
+            state.stats.num_adjustments += 1;
+        }
+
+        let adj_speed = state.adjustment_slew_speed;
+        state.polar_spd = if active {
+            Some((
+                rate_mapping.speed(rotation_rel_spd, deg_per_s(POLAR_ROTATION_SPD_DEG_PER_S)),
+                rate_mapping.speed(magnitude_rel_spd, adj_speed)
+            ))
+        } else {
+            None
+        };
+    }
+
+    /// Changes the adjustment by one timer interval at the rates requested with `adjust_polar`.
+    fn update_polar_adjustment(&self) {
+        let mut state = self.state.borrow_mut();
+        let Some((rotation_spd, magnitude_spd)) = state.polar_spd else { return; };
+        // adjustment direction is relative to target's motion
+        if !self.target.borrow().as_ref().is_some_and(|t| t.v_tangential.magnitude() >= 1.0e-12) { return; }
+
+        let dt = state.timer_interval.as_secs_f64();
+        let adjustment = state.adjustment.unwrap_or(Adjustment{ rel_dir: deg(0.0), angle: deg(0.0) });
+        state.adjustment = Some(polar_step(
+            adjustment,
+            deg(as_deg_per_s(rotation_spd) * dt),
+            deg(as_deg_per_s(magnitude_spd) * dt)
+        ));
+    }
+
+    /// Commands the target's speed plus the manual adjustment speed (blended if `handover` is in progress).
+    fn slew_adjusting(&self, handover: Option<f64>) {
         let t = self.target.borrow();
         if let Some(target) = t.as_ref() {
-            let new_axis1_spd = target.az_spd + axis1_rel_spd * adj_speed;
-            let new_axis2_spd = target.alt_spd + axis2_rel_spd * adj_speed;
-            if let Err(e) = self.mount.borrow_mut().as_mut().unwrap().slew(new_axis1_spd, new_axis2_spd) {
-                log::error!("error when slewing: {}", e);
+            let (manual_spd, trim) = (self.state.borrow().manual_spd, self.state.borrow().trim);
+            let speed = (target.az_spd + manual_spd.0 + trim.0, target.alt_spd + manual_spd.1 + trim.1);
+            for mount in self.routed_mounts() {
+                let mount_idx = if Rc::ptr_eq(&mount, &self.mount) { 0 } else { 1 };
+                let mut mount = mount.borrow_mut();
+                let mount = mount.as_mut().unwrap();
+                let mut state = self.state.borrow_mut();
+                let mut blended = |axis, speed| {
+                    state.handover_speed(mount_idx, axis, mount.commanded_speed(axis), speed, handover)
+                };
+                let (axis1_spd, axis2_spd) = (blended(Axis::Primary, speed.0), blended(Axis::Secondary, speed.1));
+                drop(state);
+                if let Err(e) = mount.slew(axis1_spd, axis2_spd) {
+                    log::error!("error when slewing: {}", e);
+                }
             }
         } else {
             log::error!("no target");
@@ -244,8 +792,12 @@ impl Tracking {
             return;
         }
         let target = target.as_ref().unwrap();
-        let target_pos = data::spherical_to_unit(target.azimuth, target.altitude);
-        let (mount_az, mount_alt) = match self.mount.borrow_mut().as_mut().unwrap().position() {
+        let mount = self.mount_routing.get().selected(&self.mount, &self.mount2);
+        let pos = match mount.borrow_mut().as_mut() {
+            Some(mount) => mount.position(),
+            None => Err("mount not connected".into())
+        };
+        let (mount_az, mount_alt) = match pos {
             Ok(pos) => pos,
             Err(e) => {
                 log::warn!("failed to get mount position: {}", e);
@@ -254,47 +806,196 @@ impl Tracking {
         };
         let adjusted_pos = data::spherical_to_unit(mount_az, mount_alt);
 
-        // To be precise, before calculating the offset and its angle to `v_tangential` we should project
-        // the `adjusted_pos` vector onto the plane tangent at `target_pos`; but since the angles involved are small,
-        // there will not be much difference.
-        let offset = adjusted_pos - target_pos;
-
-        let is_obtuse = target.v_tangential.dot(offset) < 0.0;
+        let adjustment = calc_adjustment(target, adjusted_pos);
+        if let Some(adjustment) = &adjustment {
+            log::info!(
+                "using new adjustment: rel_dir = {:.01}°, angle = {:.02}°",
+                as_deg(adjustment.rel_dir),
+                as_deg(adjustment.angle)
+            );
+        }
 
-        let rotation = Rad(
-            (target.v_tangential.cross(offset).magnitude() / (offset.magnitude() * target.v_tangential.magnitude())
-        ).asin()); // rotation angle of `offset` relative to `v_tangential`
+        let mut state = self.state.borrow_mut();
+        if adjustment.is_some() { state.stats.num_adjustments += 1; }
+        state.adjustment = adjustment;
+        state.adjusting = false;
+        state.start_handover();
+    }
 
-        let rotation = if is_obtuse { Rad::from(Deg(180.0)) - rotation } else { rotation };
-        let is_rotation_cw = target.v_tangential.cross(offset).dot(target_pos.to_vec()) < 0.0;
-        let rotation = if is_rotation_cw { -rotation } else { rotation };
+    /// Shifts the tracked position by the specified angles (measured on the sky; positive: towards increasing
+    /// azimuth and altitude) and keeps it as the current adjustment.
+    #[must_use]
+    pub fn apply_correction(&mut self, horizontal: f64::Angle, vertical: f64::Angle) -> Result<(), Box<dyn Error>> {
+        if !self.is_active() { return Err("not tracking".into()); }
+        if self.state.borrow().adjusting { return Err("manual adjustment in progress".into()); }
 
-        // approximate, since we use chord instead of arc length
-        let angular_offset = f64::Angle::new::<angle::radian>(offset.magnitude());
+        let t = self.target.borrow();
+        let target = t.as_ref().ok_or::<Box<dyn Error>>("no target".into())?;
 
-        let adjustment = Adjustment{
-            rel_dir: deg(Deg::from(rotation).0),
-            angle: angular_offset
+        let (azimuth, altitude) = if let Some(adj) = self.state.borrow().adjustment.as_ref() {
+            get_adjusted_pos(target.azimuth, target.altitude, target.v_tangential, adj)
+        } else {
+            (target.azimuth, target.altitude)
         };
-        log::info!(
-            "using new adjustment: rel_dir = {:.01}°, angle = {:.02}°",
-            as_deg(adjustment.rel_dir),
-            as_deg(adjustment.angle)
+        let cos_alt = altitude.get::<angle::radian>().cos().max(MIN_COS_ALT_FOR_CORRECTION);
+        let new_pos = data::spherical_to_unit(
+            azimuth + horizontal / cos_alt,
+            (altitude + vertical).min(deg(90.0)).max(deg(-90.0))
         );
 
-        let mut state = self.state.borrow_mut();
-        state.adjustment = Some(adjustment);
-        state.adjusting = false;
+        let adjustment = calc_adjustment(target, new_pos);
+        if let Some(adjustment) = &adjustment {
+            log::debug!(
+                "corrected adjustment: rel_dir = {:.01}°, angle = {:.03}°",
+                as_deg(adjustment.rel_dir),
+                as_deg(adjustment.angle)
+            );
+        }
+        self.state.borrow_mut().adjustment = adjustment;
+
+        Ok(())
     }
 
     pub fn cancel_adjustment(&mut self) {
         let mut state = self.state.borrow_mut();
         state.adjusting = false;
         state.adjustment = None;
+        state.start_handover();
         log::info!("cancel manual adjustment");
     }
 }
 
+/// Result of a single tracking control step.
+#[derive(Copy, Clone, Debug)]
+pub struct ControlStep {
+    /// Axis speeds to command.
+    pub speed: (AngSpeed, AngSpeed),
+    /// Position error (tracked position minus mount position) of each axis.
+    pub error: (f64::Angle, f64::Angle)
+}
+
+/// Tracking control law: follows the target's speed and closes the position error proportionally,
+/// limiting axis speeds to `max_spd`.
+pub fn control_step(
+    mount_pos: (f64::Angle, f64::Angle),
+    tracked_pos: (f64::Angle, f64::Angle),
+    target_spd: (AngSpeed, AngSpeed),
+    max_spd: AngSpeed
+) -> ControlStep {
+    let error = position_error(mount_pos, tracked_pos);
+    let axis_speed = |target_spd: AngSpeed, error: f64::Angle| {
+        (target_spd + deg_per_s(as_deg(error) * MATCH_POS_SPD_DEG_PER_S)).max(-max_spd).min(max_spd)
+    };
+
+    ControlStep{ speed: (axis_speed(target_spd.0, error.0), axis_speed(target_spd.1, error.1)), error }
+}
+
+/// Returns `adjustment` with direction rotated by `rotation` (kept within (-180°, 180°]) and angle changed
+/// by `change` (not below zero).
+fn polar_step(adjustment: Adjustment, rotation: f64::Angle, change: f64::Angle) -> Adjustment {
+    let rel_dir = -(180.0 - as_deg(adjustment.rel_dir + rotation)).rem_euclid(360.0) + 180.0;
+    Adjustment{ rel_dir: deg(rel_dir), angle: (adjustment.angle + change).max(deg(0.0)) }
+}
+
+/// Returns speed changing linearly from `from` (`progress` = 0.0) to `to` (`progress` = 1.0).
+fn blend_speed(from: AngSpeed, to: AngSpeed, progress: f64) -> AngSpeed {
+    from + (to - from) * progress.clamp(0.0, 1.0)
+}
+
+/// Returns position error (tracked position minus mount position) of each axis.
+pub fn position_error(
+    mount_pos: (f64::Angle, f64::Angle),
+    tracked_pos: (f64::Angle, f64::Angle)
+) -> (f64::Angle, f64::Angle) {
+    (angle_diff(mount_pos.0, tracked_pos.0), angle_diff(mount_pos.1, tracked_pos.1))
+}
+
+/// Quantizes axis speed to the mount's rate resolution. The quantization error is carried over to subsequent steps
+/// (error diffusion), so that on average the requested speed is achieved.
+#[derive(Copy, Clone, Default)]
+struct RateQuantizer {
+    /// Accumulated difference between requested and quantized speed (°/s).
+    residual: f64
+}
+
+impl RateQuantizer {
+    fn quantize(&mut self, speed: AngSpeed, resolution: Option<AngSpeed>) -> AngSpeed {
+        let resolution = match resolution {
+            Some(r) if as_deg_per_s(r) > 0.0 => as_deg_per_s(r),
+            _ => return speed
+        };
+        let wanted = as_deg_per_s(speed) + self.residual;
+        let quantized = (wanted / resolution).round() * resolution;
+        self.residual = (wanted - quantized).max(-resolution).min(resolution);
+
+        deg_per_s(quantized)
+    }
+}
+
+/// Returns axis offsets (azimuth, altitude) along the search pattern after `elapsed` time of searching around
+/// a position at `altitude`, and their rates of change.
+fn search_axis_offsets(
+    params: &SearchParams,
+    elapsed: Duration,
+    altitude: f64::Angle
+) -> ((f64::Angle, f64::Angle), (AngSpeed, AngSpeed)) {
+    let cos_alt = altitude.get::<angle::radian>().cos().max(MIN_COS_ALT_FOR_CORRECTION);
+    let axes = |(horizontal, vertical): (f64, f64)| (horizontal / cos_alt, vertical);
+    let p0 = axes(search_pattern::offset(params, elapsed));
+    let p1 = axes(search_pattern::offset(params, elapsed + SEARCH_SPD_DT));
+    let dt = SEARCH_SPD_DT.as_secs_f64();
+
+    ((deg(p0.0), deg(p0.1)), (deg_per_s((p1.0 - p0.0) / dt), deg_per_s((p1.1 - p0.1) / dt)))
+}
+
+/// Returns the position to track: target's position extrapolated by `extrapolate_by` at its angular speeds
+/// (when coasting) and shifted by `adjustment`.
+fn tracked_position(
+    target: &data::Target,
+    extrapolate_by: Duration,
+    adjustment: Option<&Adjustment>
+) -> (f64::Angle, f64::Angle) {
+    let secs = extrapolate_by.as_secs_f64();
+    let azimuth = target.azimuth + deg(as_deg_per_s(target.az_spd) * secs);
+    let altitude = (target.altitude + deg(as_deg_per_s(target.alt_spd) * secs)).min(deg(90.0)).max(deg(-90.0));
+
+    match adjustment {
+        Some(adj) => get_adjusted_pos(azimuth, altitude, target.v_tangential, adj),
+        None => (azimuth, altitude)
+    }
+}
+
+/// Returns adjustment corresponding to offset of `adjusted_pos` from target's position
+/// (`None` if too small or target is not moving).
+fn calc_adjustment(target: &data::Target, adjusted_pos: Point3<f64>) -> Option<Adjustment> {
+    let target_pos = data::spherical_to_unit(target.azimuth, target.altitude);
+
+    // To be precise, before calculating the offset and its angle to `v_tangential` we should project
+    // the `adjusted_pos` vector onto the plane tangent at `target_pos`; but since the angles involved are small,
+    // there will not be much difference.
+    let offset = adjusted_pos - target_pos;
+
+    if offset.magnitude() < 1.0e-9 || target.v_tangential.magnitude() < 1.0e-12 { return None; }
+
+    let is_obtuse = target.v_tangential.dot(offset) < 0.0;
+
+    let rotation = Rad(
+        (target.v_tangential.cross(offset).magnitude() / (offset.magnitude() * target.v_tangential.magnitude())
+    ).min(1.0).asin()); // rotation angle of `offset` relative to `v_tangential`
+
+    let rotation = if is_obtuse { Rad::from(Deg(180.0)) - rotation } else { rotation };
+    let is_rotation_cw = target.v_tangential.cross(offset).dot(target_pos.to_vec()) < 0.0;
+    let rotation = if is_rotation_cw { -rotation } else { rotation };
+
+    // approximate, since we use chord instead of arc length
+    let angular_offset = f64::Angle::new::<angle::radian>(offset.magnitude());
+
+    Some(Adjustment{
+        rel_dir: deg(Deg::from(rotation).0),
+        angle: angular_offset
+    })
+}
+
 fn get_adjusted_pos(
     azimuth: f64::Angle,
     altitude: f64::Angle,
@@ -336,3 +1037,102 @@ impl Notify for Tracking {
         Poll::Pending
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mount::{Mount, MockMount};
+
+    const STEP_S: f64 = 0.5;
+
+    /// Tracks a target moving at constant angular speeds with `MockMount`; returns the final position error.
+    fn track(
+        mount: &mut MockMount,
+        mut target_pos: (f64::Angle, f64::Angle),
+        target_spd: (AngSpeed, AngSpeed),
+        num_steps: usize
+    ) -> (f64::Angle, f64::Angle) {
+        let max_spd = mount.max_slew_speed().unwrap();
+        let mut error = (deg(0.0), deg(0.0));
+        for _ in 0..num_steps {
+            let step = control_step(mount.position().unwrap(), target_pos, target_spd, max_spd);
+            mount.slew(step.speed.0, step.speed.1).unwrap();
+            mount.advance(STEP_S);
+            target_pos.0 += deg(as_deg_per_s(target_spd.0) * STEP_S);
+            target_pos.1 += deg(as_deg_per_s(target_spd.1) * STEP_S);
+            error = step.error;
+        }
+        error
+    }
+
+    #[test]
+    fn converges_to_moving_target() {
+        let mut mount = MockMount::new(deg(100.0), deg(20.0), Some(deg_per_s(5.0)));
+        let error = track(&mut mount, (deg(105.0), deg(17.0)), (deg_per_s(0.1), deg_per_s(0.05)), 100);
+        assert!(as_deg(error.0).abs() < 0.01);
+        assert!(as_deg(error.1).abs() < 0.01);
+        // steady state: mount follows the target's speed
+        assert!((as_deg_per_s(mount.speed().0) - 0.1).abs() < 0.01);
+    }
+
+    #[test]
+    fn takes_shorter_way_around_azimuth_wrap() {
+        let stationary = (deg_per_s(0.0), deg_per_s(0.0));
+        let step = control_step((deg(359.0), deg(10.0)), (deg(1.0), deg(10.0)), stationary, deg_per_s(5.0));
+        assert!((as_deg(step.error.0) - 2.0).abs() < 1.0e-9);
+        assert!(as_deg_per_s(step.speed.0) > 0.0);
+
+        let step = control_step((deg(1.0), deg(10.0)), (deg(359.0), deg(10.0)), stationary, deg_per_s(5.0));
+        assert!((as_deg(step.error.0) + 2.0).abs() < 1.0e-9);
+        assert!(as_deg_per_s(step.speed.0) < 0.0);
+
+        let mut mount = MockMount::new(deg(358.0), deg(30.0), Some(deg_per_s(5.0)));
+        let error = track(&mut mount, (deg(2.0), deg(30.0)), (deg_per_s(0.2), deg_per_s(0.0)), 100);
+        assert!(as_deg(error.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn quantized_speed_matches_on_average() {
+        let resolution = Some(deg_per_s(0.01));
+        let mut quantizer = RateQuantizer::default();
+        let num_steps = 1000;
+        let sum: f64 = (0..num_steps).map(|_| as_deg_per_s(quantizer.quantize(deg_per_s(0.1234), resolution))).sum();
+        assert!((sum / num_steps as f64 - 0.1234).abs() < 1.0e-5);
+
+        // speed being a multiple of resolution is not dithered
+        let mut quantizer = RateQuantizer::default();
+        let first = quantizer.quantize(deg_per_s(0.25), resolution);
+        assert!((0..10).all(|_| quantizer.quantize(deg_per_s(0.25), resolution) == first));
+
+        assert_eq!(0.1234, as_deg_per_s(RateQuantizer::default().quantize(deg_per_s(0.1234), None)));
+    }
+
+    #[test]
+    fn blends_speeds_linearly() {
+        let (from, to) = (deg_per_s(2.0), deg_per_s(-0.5));
+        assert_eq!(2.0, as_deg_per_s(blend_speed(from, to, 0.0)));
+        assert!((as_deg_per_s(blend_speed(from, to, 0.4)) - 1.0).abs() < 1.0e-9);
+        assert_eq!(-0.5, as_deg_per_s(blend_speed(from, to, 1.0)));
+        assert_eq!(-0.5, as_deg_per_s(blend_speed(from, to, 1.5)));
+    }
+
+    #[test]
+    fn changes_adjustment_in_polar_mode() {
+        let adjustment = Adjustment{ rel_dir: deg(170.0), angle: deg(0.1) };
+        let result = polar_step(adjustment, deg(20.0), deg(0.05));
+        assert!((as_deg(result.rel_dir) + 170.0).abs() < 1.0e-9);
+        assert!((as_deg(result.angle) - 0.15).abs() < 1.0e-9);
+
+        let result = polar_step(result, deg(-20.0), deg(-1.0));
+        assert!((as_deg(result.rel_dir) - 170.0).abs() < 1.0e-9);
+        assert_eq!(0.0, as_deg(result.angle));
+    }
+
+    #[test]
+    fn clamps_speed_to_max() {
+        let target_spd = (deg_per_s(1.0), deg_per_s(0.0));
+        let step = control_step((deg(0.0), deg(0.0)), (deg(90.0), deg(-60.0)), target_spd, deg_per_s(5.0));
+        assert_eq!(5.0, as_deg_per_s(step.speed.0));
+        assert_eq!(-5.0, as_deg_per_s(step.speed.1));
+    }
+}
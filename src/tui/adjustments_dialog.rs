@@ -0,0 +1,169 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    config::Configuration,
+    data,
+    data::as_deg,
+    data_receiver,
+    event_handling,
+    target_source::TargetSource,
+    tracking::TrackingController,
+    tui,
+    tui::{close_dialog, msg_box, names, TuiData},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, Resizable, Scrollable, View},
+    views::{
+        Button,
+        CircularFocus,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        Panel,
+        SelectView,
+        TextView
+    },
+    With
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    connection: data_receiver::Connection,
+    config: Weak<RefCell<Configuration>>,
+    target_source: Weak<RefCell<Option<Box<dyn TargetSource>>>>,
+    tracking: TrackingController
+) -> impl View {
+    let source_id = {
+        upgrade!(target_source);
+        event_handling::target_source_id(&target_source, &connection)
+    };
+
+    let mut list = SelectView::<usize>::new();
+    fill_list(&mut list, &config.upgrade().unwrap().borrow().saved_adjustments());
+
+    Dialog::around(LinearLayout::vertical()
+        .child(TextView::new(format!("Target source: {}", source_id.as_deref().unwrap_or("(none)"))))
+        .child(DummyView{}.min_height(1))
+        .child(Panel::new(
+            list.with_name(names::SAVED_ADJUSTMENTS_LIST).scrollable().fixed_height(10).min_width(50)
+        ).title("Saved adjustments").title_position(HAlign::Left))
+        .child(LinearLayout::horizontal()
+            .child(Button::new("Save current", cclone!([config, tracking, source_id], move |curs| {
+                upgrade!(config);
+                on_save_current(curs, &config, &tracking, source_id.clone());
+            })))
+            .child(DummyView{}.min_width(1))
+            .child(Button::new("Apply", cclone!([config, tracking], move |curs| {
+                upgrade!(config);
+                on_apply(curs, &config.borrow(), &tracking);
+            })))
+            .child(DummyView{}.min_width(1))
+            .child(Button::new("Delete", cclone!([config], move |curs| {
+                upgrade!(config);
+                on_delete(curs, &config);
+            })))
+        )
+    )
+    .button("Close", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Saved adjustments")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn fill_list(list: &mut SelectView<usize>, adjustments: &[data::SavedAdjustment]) {
+    list.clear();
+    for (idx, adj) in adjustments.iter().enumerate() {
+        list.add_item(
+            format!("{} — {} ({:.1}°, {:.3}°)", adj.name, adj.source, as_deg(adj.rel_dir), as_deg(adj.angle)),
+            idx
+        );
+    }
+}
+
+fn refresh_list(curs: &mut cursive::Cursive, config: &Configuration) {
+    let adjustments = config.saved_adjustments();
+    curs.call_on_name(names::SAVED_ADJUSTMENTS_LIST, |v: &mut SelectView<usize>| fill_list(v, &adjustments));
+}
+
+fn selected_idx(curs: &mut cursive::Cursive) -> Option<usize> {
+    curs.call_on_name(names::SAVED_ADJUSTMENTS_LIST, |v: &mut SelectView<usize>| v.selection().map(|idx| *idx))
+        .flatten()
+}
+
+fn on_save_current(
+    curs: &mut cursive::Cursive,
+    config: &Rc<RefCell<Configuration>>,
+    tracking: &TrackingController,
+    source_id: Option<String>
+) {
+    let source = match source_id {
+        Some(s) => s,
+        None => { msg_box(curs, "No target source.", "Error"); return; }
+    };
+    let (rel_dir, angle) = match tracking.adjustment() {
+        Some(adj) => adj,
+        None => { msg_box(curs, "No current tracking adjustment.", "Error"); return; }
+    };
+
+    tui::simple_dialog::show(
+        curs,
+        "Enter adjustment name",
+        "",
+        25,
+        Rc::new(cclone!([@weak config], move |curs: &mut cursive::Cursive, name: &str| {
+            upgrade!(config);
+            let mut adjustments = config.borrow().saved_adjustments();
+            adjustments.retain(|adj| adj.name != name || adj.source != source);
+            adjustments.push(data::SavedAdjustment{ name: name.into(), source: source.clone(), rel_dir, angle });
+            config.borrow_mut().set_saved_adjustments(&adjustments);
+            log::info!("saved adjustment \"{}\" for {}", name, source);
+            refresh_list(curs, &config.borrow());
+        }))
+    );
+}
+
+fn on_apply(curs: &mut cursive::Cursive, config: &Configuration, tracking: &TrackingController) {
+    let adjustments = config.saved_adjustments();
+    let adj = match selected_idx(curs).and_then(|idx| adjustments.get(idx)) {
+        Some(adj) => adj,
+        None => return
+    };
+    log::info!("applying saved adjustment \"{}\"", adj.name);
+    tracking.restore_adjustment(Some((adj.rel_dir, adj.angle)));
+}
+
+fn on_delete(curs: &mut cursive::Cursive, config: &Rc<RefCell<Configuration>>) {
+    let mut adjustments = config.borrow().saved_adjustments();
+    let idx = match selected_idx(curs) {
+        Some(idx) if idx < adjustments.len() => idx,
+        _ => return
+    };
+    adjustments.remove(idx);
+    config.borrow_mut().set_saved_adjustments(&adjustments);
+    refresh_list(curs, &config.borrow());
+}
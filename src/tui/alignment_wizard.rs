@@ -0,0 +1,332 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+//! Step-by-step setting of the reference position: mount connection, observer location, choice of a reference
+//! object and centering it.
+
+use crate::{
+    catalog,
+    cclone,
+    config::Configuration,
+    data,
+    data::{as_deg, ObserverLocation},
+    event_handling,
+    mount::{Mount, MountType, MountWrapper},
+    tracking::TrackingController,
+    tui,
+    tui::{close_dialog, get_edit_view_str, get_select_view_idx, msg_box, names, observer_dialog, TuiData},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Nameable, Resizable, Scrollable, View},
+    views::{Button, CircularFocus, Dialog, DummyView, LinearLayout, OnEventView, SelectView, TextView},
+    With
+};
+use pointing_utils::uom;
+use std::{cell::RefCell, rc::{Rc, Weak}};
+use uom::si::f64;
+
+/// Stars below this altitude are not offered as reference objects.
+const MIN_STAR_ALTITUDE_DEG: f64 = 10.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Step {
+    Mount,
+    Observer,
+    Object,
+    Centering
+}
+
+const STEPS: [Step; 4] = [Step::Mount, Step::Observer, Step::Object, Step::Centering];
+
+impl Step {
+    fn title(&self) -> &'static str {
+        match self {
+            Step::Mount => "Connect to mount",
+            Step::Observer => "Set observer location",
+            Step::Object => "Choose reference object",
+            Step::Centering => "Center the reference object"
+        }
+    }
+}
+
+enum RefObject {
+    /// Reference position preset (e.g., a landmark).
+    Landmark(data::RefPositionPreset),
+    Star(&'static catalog::Star)
+}
+
+impl RefObject {
+    fn name(&self) -> &str {
+        match self {
+            RefObject::Landmark(preset) => &preset.name,
+            RefObject::Star(star) => star.name
+        }
+    }
+
+    /// Returns the current azimuth and altitude.
+    fn az_alt(&self, observer: &ObserverLocation) -> (f64::Angle, f64::Angle) {
+        match self {
+            RefObject::Landmark(preset) => (preset.azimuth, preset.altitude),
+            RefObject::Star(star) => star.az_alt(observer, chrono::Utc::now())
+        }
+    }
+}
+
+struct Wizard {
+    step: usize,
+    /// Reference objects offered in the `Object` step.
+    objects: Vec<RefObject>,
+    object: Option<RefObject>,
+    tui: Weak<RefCell<Option<TuiData>>>,
+    mount: Weak<RefCell<Option<MountWrapper>>>,
+    config: Weak<RefCell<Configuration>>,
+    observer: Weak<RefCell<Option<ObserverLocation>>>,
+    tracking: TrackingController
+}
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    mount: Weak<RefCell<Option<MountWrapper>>>,
+    config: Weak<RefCell<Configuration>>,
+    observer: Weak<RefCell<Option<ObserverLocation>>>,
+    tracking: TrackingController
+) -> impl View {
+    let wizard = Rc::new(RefCell::new(Wizard{
+        step: 0,
+        objects: vec![],
+        object: None,
+        tui: tui.clone(),
+        mount,
+        config,
+        observer,
+        tracking
+    }));
+    let content = step_view(&wizard);
+
+    Dialog::around(LinearLayout::vertical()
+        .child(TextView::new(step_label(0)).with_name(names::WIZARD_STEP))
+        .child(DummyView{})
+        .child(LinearLayout::vertical().child(content).with_name(names::WIZARD_CONTENT))
+        .min_width(60)
+    )
+    .button("Back", cclone!([wizard], move |curs| {
+        let step = wizard.borrow().step;
+        if step > 0 { show_step(curs, &wizard, step - 1); }
+    }))
+    .button("Next", cclone!([wizard], move |curs| on_next(curs, &wizard)))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Alignment wizard")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn step_label(step: usize) -> String {
+    format!("Step {}/{}: {}", step + 1, STEPS.len(), STEPS[step].title())
+}
+
+fn show_step(curs: &mut cursive::Cursive, wizard: &Rc<RefCell<Wizard>>, step: usize) {
+    wizard.borrow_mut().step = step;
+    let content = step_view(wizard);
+    curs.call_on_name(names::WIZARD_STEP, |v: &mut TextView| v.set_content(step_label(step)));
+    curs.call_on_name(names::WIZARD_CONTENT, |v: &mut LinearLayout| {
+        v.clear();
+        v.add_child(content);
+    });
+}
+
+fn step_view(wizard: &Rc<RefCell<Wizard>>) -> LinearLayout {
+    let mut w = wizard.borrow_mut();
+    let mut view = LinearLayout::vertical();
+
+    match STEPS[w.step] {
+        Step::Mount => {
+            let mount = w.mount.upgrade().unwrap();
+            if let Some(mount) = mount.borrow().as_ref() {
+                view.add_child(TextView::new(
+                    format!("Connected to {}.\n\nPress \"Next\" to continue.", mount.get_info())
+                ));
+            } else {
+                let config = w.config.upgrade().unwrap();
+                let mount_types = MountType::all();
+                let mut types = SelectView::<usize>::new().popup();
+                for (idx, mount_type) in mount_types.iter().enumerate() {
+                    types.add_item(mount_type.backend().name, idx);
+                }
+                let selected = config.borrow().mount_type()
+                    .and_then(|t| mount_types.iter().position(|m| *m == t))
+                    .unwrap_or(0);
+                let types = types.selected(selected);
+                let param = config.borrow().mount_type()
+                    .and_then(|t| config.borrow().mount_connection_param(t))
+                    .unwrap_or_default();
+
+                view.add_child(TextView::new("Choose mount type and enter connection parameter (see \"M\" dialog):"));
+                view.add_child(DummyView{});
+                view.add_child(LinearLayout::horizontal()
+                    .child(types.with_name(names::WIZARD_MOUNT_TYPE))
+                    .child(DummyView{}.min_width(1))
+                    .child(tui::styled_edit_view()
+                        .content(param)
+                        .with_name(names::WIZARD_MOUNT_PARAM)
+                        .fixed_width(25)
+                    )
+                    .child(DummyView{}.min_width(1))
+                    .child(Button::new("Connect", cclone!([wizard], move |curs| on_connect(curs, &wizard))))
+                );
+            }
+        },
+
+        Step::Observer => {
+            let observer = *w.observer.upgrade().unwrap().borrow();
+            view.add_child(observer_dialog::panel(observer));
+            view.add_child(TextView::new("Enter or verify the location (or use GPS via the \"O\" dialog)."));
+        },
+
+        Step::Object => {
+            let observer = w.observer.upgrade().unwrap().borrow().unwrap();
+            let display = w.config.upgrade().unwrap().borrow().angle_display();
+
+            let mut objects: Vec<RefObject> = w.config.upgrade().unwrap().borrow().ref_pos_presets()
+                .into_iter().map(RefObject::Landmark).collect();
+            let mut stars: Vec<RefObject> = catalog::BRIGHT_STARS.iter()
+                .map(RefObject::Star)
+                .filter(|s| as_deg(s.az_alt(&observer).1) >= MIN_STAR_ALTITUDE_DEG)
+                .collect();
+            stars.sort_by(|a, b| b.az_alt(&observer).1.partial_cmp(&a.az_alt(&observer).1).unwrap());
+            objects.append(&mut stars);
+
+            let mut list = SelectView::<usize>::new();
+            for (idx, object) in objects.iter().enumerate() {
+                let (az, alt) = object.az_alt(&observer);
+                let kind = if let RefObject::Landmark(_) = object { "landmark" } else { "star" };
+                list.add_item(
+                    format!(
+                        "{:<20}{:<10}az. {:<12}alt. {}",
+                        object.name(), kind, display.azimuth(az, 1), display.altitude(alt, 1)
+                    ),
+                    idx
+                );
+            }
+            w.objects = objects;
+
+            if w.objects.is_empty() {
+                view.add_child(
+                    TextView::new("No reference objects available (define a preset in the \"R\" dialog).")
+                );
+            } else {
+                view.add_child(list.with_name(names::WIZARD_OBJECT_LIST).scrollable().max_height(10));
+            }
+        },
+
+        Step::Centering => {
+            let name = w.object.as_ref().map(|o| o.name().to_string()).unwrap_or_default();
+            view.add_child(TextView::new(format!(
+                "Slew the mount (using the controller) to center {} in the field of view.\n\n\
+                Press \"Next\" to store the reference position.",
+                name
+            )));
+        }
+    }
+
+    view
+}
+
+fn on_connect(curs: &mut cursive::Cursive, wizard: &Rc<RefCell<Wizard>>) {
+    let mount_type = MountType::all()[get_select_view_idx(curs, names::WIZARD_MOUNT_TYPE)];
+    let param = get_edit_view_str(curs, names::WIZARD_MOUNT_PARAM);
+
+    let result = {
+        let w = wizard.borrow();
+        let (mount, config) = (w.mount.upgrade().unwrap(), w.config.upgrade().unwrap());
+        event_handling::connect_to_mount(mount_type, &param, &mount, &config, w.tracking.clone(), false)
+    };
+    match result {
+        Ok(info) => {
+            tui!(wizard.borrow().tui.upgrade().unwrap()).text_content.mount_name.set_content(info);
+            let step = wizard.borrow().step;
+            show_step(curs, wizard, step);
+        },
+        Err(e) => msg_box(curs, &format!("Failed to connect to mount: {}.", e), "Error")
+    }
+}
+
+fn on_next(curs: &mut cursive::Cursive, wizard: &Rc<RefCell<Wizard>>) {
+    let step = wizard.borrow().step;
+
+    match STEPS[step] {
+        Step::Mount => if wizard.borrow().mount.upgrade().unwrap().borrow().is_none() {
+            msg_box(curs, "Not connected to a mount.", "Error");
+            return;
+        },
+
+        Step::Observer => match observer_dialog::read(curs) {
+            Ok(value) => {
+                let w = wizard.borrow();
+                *w.observer.upgrade().unwrap().borrow_mut() = Some(value);
+                w.config.upgrade().unwrap().borrow_mut().set_observer_location(&value);
+            },
+            Err(e) => { msg_box(curs, &format!("Invalid observer location: {}.", e), "Error"); return; }
+        },
+
+        Step::Object => {
+            if wizard.borrow().objects.is_empty() { return; }
+            let idx = get_select_view_idx(curs, names::WIZARD_OBJECT_LIST);
+            let mut w = wizard.borrow_mut();
+            let object = w.objects.swap_remove(idx);
+            log::info!("reference object: {}", object.name());
+            w.object = Some(object);
+        },
+
+        Step::Centering => {
+            on_finish(curs, wizard);
+            return;
+        }
+    }
+
+    show_step(curs, wizard, step + 1);
+}
+
+fn on_finish(curs: &mut cursive::Cursive, wizard: &Rc<RefCell<Wizard>>) {
+    let w = wizard.borrow();
+    let (object, observer) = match (w.object.as_ref(), *w.observer.upgrade().unwrap().borrow()) {
+        (Some(object), Some(observer)) => (object, observer),
+        _ => return
+    };
+    let (az, alt) = object.az_alt(&observer);
+
+    let mount = w.mount.upgrade().unwrap();
+    let result = match mount.borrow_mut().as_mut() {
+        Some(mount) => mount.set_reference_position(az, alt),
+        None => Err("not connected to a mount".into())
+    };
+    match result {
+        Ok(()) => {
+            log::info!(
+                "reference position set from {}: az. {:.3}°, alt. {:.3}°", object.name(), as_deg(az), as_deg(alt)
+            );
+            let tui = w.tui.upgrade().unwrap();
+            close_dialog(curs, &tui);
+        },
+        Err(e) => msg_box(curs, &format!("Failed to set ref. position:\n{}", e), "Error")
+    }
+}
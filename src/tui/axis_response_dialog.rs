@@ -0,0 +1,136 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{
+    cclone,
+    config::Configuration,
+    controller::AxisResponse,
+    tui,
+    tui::{close_dialog, get_edit_view_str, msg_box, names, TuiData},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, Resizable, View},
+    views::{
+        Checkbox,
+        CircularFocus,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        Panel,
+        TextContent,
+        TextView,
+    },
+    With
+};
+use std::{cell::RefCell, rc::Weak};
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    axes_response: Weak<RefCell<[AxisResponse; 2]>>,
+    config: Weak<RefCell<Configuration>>
+) -> impl View {
+    let current = *axes_response.upgrade().unwrap().borrow();
+    let (axis1_input, axis2_input) = {
+        let tui = tui.upgrade().unwrap();
+        let axis1_input = tui!(tui).text_content.axis1_input.clone();
+        let axis2_input = tui!(tui).text_content.axis2_input.clone();
+        (axis1_input, axis2_input)
+    };
+
+    Dialog::around(LinearLayout::vertical()
+        .child(axis_panel("Mount axis 1", 0, &current[0], axis1_input))
+        .child(axis_panel("Mount axis 2", 1, &current[1], axis2_input))
+        .child(LinearLayout::horizontal()
+            .child(Checkbox::new()
+                .with_checked(config.upgrade().unwrap().borrow().polar_adjustment())
+                .with_name(names::POLAR_ADJUSTMENT)
+            )
+            .child(TextView::new(" polar adjustment during tracking (axis 1: direction, axis 2: distance)"))
+        )
+    )
+    .button("OK", cclone!([tui, axes_response, config], move |curs| {
+        upgrade!(tui, axes_response, config);
+
+        match read_responses(curs) {
+            Ok(responses) => {
+                *axes_response.borrow_mut() = responses;
+                config.borrow_mut().set_mount_axes_response(&responses);
+                let polar = curs.call_on_name(names::POLAR_ADJUSTMENT, |v: &mut Checkbox| v.is_checked()).unwrap();
+                config.borrow_mut().set_polar_adjustment(polar);
+                close_dialog(curs, &tui);
+            },
+
+            Err(e) => msg_box(curs, &format!("Invalid value: {}.", e), "Error")
+        }
+    }))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Controller axes response")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn axis_panel(title: &str, axis_idx: usize, response: &AxisResponse, input: TextContent) -> Panel<LinearLayout> {
+    let edit = |name: &str, value: f64| tui::styled_edit_view()
+        .content(format!("{}", value))
+        .with_name(name)
+        .fixed_width(6);
+
+    Panel::new(LinearLayout::vertical()
+        .child(LinearLayout::horizontal()
+            .child(TextView::new("dead zone: "))
+            .child(edit(names::AXIS_DEAD_ZONE[axis_idx], response.dead_zone))
+            .child(DummyView{}.min_width(2))
+            .child(TextView::new("exponent: "))
+            .child(edit(names::AXIS_EXPONENT[axis_idx], response.exponent))
+            .child(DummyView{}.min_width(2))
+            .child(TextView::new("sensitivity: "))
+            .child(edit(names::AXIS_SENSITIVITY[axis_idx], response.sensitivity))
+        )
+        .child(LinearLayout::horizontal()
+            .child(TextView::new("input → output: "))
+            .child(TextView::new_with_content(input))
+        )
+    ).title(title).title_position(HAlign::Left)
+}
+
+fn read_responses(curs: &mut cursive::Cursive) -> Result<[AxisResponse; 2], String> {
+    let mut parse = |name: &str, min: f64, max: f64| -> Result<f64, String> {
+        let s = get_edit_view_str(curs, name);
+        match s.parse::<f64>() {
+            Ok(value) if value >= min && value <= max => Ok(value),
+            _ => Err(format!("{} (expected a number from [{}, {}])", s, min, max))
+        }
+    };
+
+    let mut result = [AxisResponse::default(); 2];
+    for (i, response) in result.iter_mut().enumerate() {
+        response.dead_zone = parse(names::AXIS_DEAD_ZONE[i], 0.0, 0.9)?;
+        response.exponent = parse(names::AXIS_EXPONENT[i], 0.1, 10.0)?;
+        response.sensitivity = parse(names::AXIS_SENSITIVITY[i], 0.0, 1.0)?;
+    }
+
+    Ok(result)
+}
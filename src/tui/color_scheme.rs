@@ -0,0 +1,123 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+//! Selectable TUI color schemes.
+
+use cursive::{theme, theme::{Color, PaletteColor, Theme}};
+use strum::IntoEnumIterator;
+use strum_macros as sm;
+
+/// Palette keys of colors used by custom views.
+const MOUNT_COLOR: &str = "mount";
+const TARGET_COLOR: &str = "target";
+const RIM_COLOR: &str = "rim";
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, sm::EnumIter, sm::EnumString, sm::IntoStaticStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum ColorScheme {
+    #[default]
+    Default,
+    /// Red on black, preserves dark adaptation.
+    Night,
+    HighContrast
+}
+
+impl ColorScheme {
+    pub fn name(&self) -> &'static str { self.into() }
+
+    /// Returns the next scheme (cyclically).
+    pub fn next(&self) -> ColorScheme {
+        let all: Vec<_> = ColorScheme::iter().collect();
+        let idx = all.iter().position(|s| s == self).unwrap();
+        all[(idx + 1) % all.len()]
+    }
+}
+
+/// Creates the main theme (dialogs use it with borders added; see `create_dialog_theme`).
+pub fn create_main_theme(scheme: ColorScheme) -> Theme {
+    let mut theme = Theme::default();
+    theme.shadow = false;
+    theme.borders = theme::BorderStyle::None;
+
+    let rgb = |r, g, b| Color::Rgb(r, g, b);
+    let p = &mut theme.palette;
+    match scheme {
+        ColorScheme::Default => {
+            p[PaletteColor::View] = rgb(60, 60, 60);
+            p[PaletteColor::Background] = rgb(30, 30, 30);
+            p[PaletteColor::TitlePrimary] = rgb(255, 255, 255);
+            p[PaletteColor::Primary] = rgb(180, 180, 180);
+            p[PaletteColor::TitleSecondary] = rgb(200, 200, 200);
+            p[PaletteColor::Shadow] = rgb(0, 0, 0);
+            p[PaletteColor::Secondary] = rgb(50, 50, 200);
+            p[PaletteColor::Tertiary] = rgb(230, 230, 230);
+            p.set_color(MOUNT_COLOR, rgb(255, 220, 80));
+            p.set_color(TARGET_COLOR, rgb(80, 200, 255));
+            p.set_color(RIM_COLOR, rgb(120, 120, 120));
+        },
+
+        ColorScheme::Night => {
+            p[PaletteColor::View] = rgb(25, 0, 0);
+            p[PaletteColor::Background] = rgb(0, 0, 0);
+            p[PaletteColor::TitlePrimary] = rgb(230, 30, 30);
+            p[PaletteColor::Primary] = rgb(170, 0, 0);
+            p[PaletteColor::TitleSecondary] = rgb(140, 0, 0);
+            p[PaletteColor::Shadow] = rgb(0, 0, 0);
+            p[PaletteColor::Secondary] = rgb(255, 60, 60);
+            p[PaletteColor::Tertiary] = rgb(60, 0, 0);
+            p[PaletteColor::Highlight] = rgb(120, 0, 0);
+            p[PaletteColor::HighlightInactive] = rgb(70, 0, 0);
+            p[PaletteColor::HighlightText] = rgb(255, 90, 90);
+            p.set_color(MOUNT_COLOR, rgb(255, 70, 70));
+            p.set_color(TARGET_COLOR, rgb(150, 0, 0));
+            p.set_color(RIM_COLOR, rgb(90, 0, 0));
+        },
+
+        ColorScheme::HighContrast => {
+            p[PaletteColor::View] = rgb(0, 0, 0);
+            p[PaletteColor::Background] = rgb(0, 0, 0);
+            p[PaletteColor::TitlePrimary] = rgb(255, 255, 255);
+            p[PaletteColor::Primary] = rgb(255, 255, 255);
+            p[PaletteColor::TitleSecondary] = rgb(255, 255, 0);
+            p[PaletteColor::Shadow] = rgb(0, 0, 0);
+            p[PaletteColor::Secondary] = rgb(0, 0, 0);
+            p[PaletteColor::Tertiary] = rgb(255, 255, 0);
+            p[PaletteColor::Highlight] = rgb(255, 255, 255);
+            p[PaletteColor::HighlightInactive] = rgb(128, 128, 128);
+            p[PaletteColor::HighlightText] = rgb(0, 0, 0);
+            p.set_color(MOUNT_COLOR, rgb(255, 255, 0));
+            p.set_color(TARGET_COLOR, rgb(0, 255, 255));
+            p.set_color(RIM_COLOR, rgb(255, 255, 255));
+        }
+    }
+
+    theme
+}
+
+fn custom_style(printer: &cursive::Printer, key: &str) -> theme::ColorStyle {
+    theme::ColorStyle::front(printer.theme.palette.custom(key).copied().unwrap_or(Color::TerminalDefault))
+}
+
+/// Style of mount position markers in custom views.
+pub fn mount_style(printer: &cursive::Printer) -> theme::ColorStyle { custom_style(printer, MOUNT_COLOR) }
+
+/// Style of target position markers in custom views.
+pub fn target_style(printer: &cursive::Printer) -> theme::ColorStyle { custom_style(printer, TARGET_COLOR) }
+
+/// Style of auxiliary lines in custom views.
+pub fn rim_style(printer: &cursive::Printer) -> theme::ColorStyle { custom_style(printer, RIM_COLOR) }
@@ -0,0 +1,146 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{data::{angle_diff, as_deg, deg, PositionHistory}, tui::color_scheme};
+use cursive::{Printer, Vec2, View};
+use std::{cell::RefCell, rc::Weak};
+
+/// Compass rose radius (in rows; horizontally twice as many columns are used).
+const RADIUS: usize = 6;
+const ROSE_WIDTH: usize = 4 * RADIUS + 1;
+const ROSE_HEIGHT: usize = 2 * RADIUS + 1;
+const BAR_OFFSET: usize = ROSE_WIDTH + 3;
+const BAR_LABEL_WIDTH: usize = 4;
+
+const MOUNT_CHAR: &str = "*";
+const TARGET_CHAR: &str = "+";
+const BOTH_CHAR: &str = "#";
+
+/// Mount and target azimuth shown on a compass rose, and altitude shown on a vertical bar.
+pub struct CompassView {
+    history: Weak<RefCell<PositionHistory>>
+}
+
+impl CompassView {
+    pub fn new(history: Weak<RefCell<PositionHistory>>) -> CompassView {
+        CompassView{ history }
+    }
+}
+
+impl View for CompassView {
+    fn draw(&self, printer: &Printer) {
+        let history = match self.history.upgrade() { Some(h) => h, None => return };
+        let latest = history.borrow().samples().back().copied().unwrap_or_default();
+        let mount = latest.mount.map(|(az, alt)| (as_deg(az).rem_euclid(360.0), as_deg(alt)));
+        let target = latest.target.map(|(az, alt)| (as_deg(az).rem_euclid(360.0), as_deg(alt)));
+
+        draw_rose(printer, mount.map(|p| p.0), target.map(|p| p.0));
+        draw_altitude_bar(&printer.offset((BAR_OFFSET, 0)), mount.map(|p| p.1), target.map(|p| p.1));
+
+        let summary = match (mount, target) {
+            (Some(m), Some(t)) => format!(
+                "to target: az. {:+.2}°  alt. {:+.2}°",
+                as_deg(angle_diff(deg(m.0), deg(t.0))),
+                t.1 - m.1
+            ),
+            (Some(_), None) => "no target".into(),
+            _ => "mount not connected".into()
+        };
+        printer.print((0, ROSE_HEIGHT), &summary);
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(BAR_OFFSET + BAR_LABEL_WIDTH + 2, ROSE_HEIGHT + 1)
+    }
+}
+
+/// Returns cell of the compass rose's rim corresponding to azimuth (clockwise from north).
+fn rim_cell(azimuth: f64, radius: f64) -> (usize, usize) {
+    let (sin, cos) = azimuth.to_radians().sin_cos();
+    let x = (2 * RADIUS) as f64 + 2.0 * radius * sin;
+    let y = RADIUS as f64 - radius * cos;
+    (x.round() as usize, y.round() as usize)
+}
+
+fn draw_rose(printer: &Printer, mount_az: Option<f64>, target_az: Option<f64>) {
+    let rim_style = color_scheme::rim_style(printer);
+    printer.with_color(rim_style, |p| {
+        for i in 0..36 {
+            p.print(rim_cell(i as f64 * 10.0, RADIUS as f64), "·");
+        }
+    });
+    for (azimuth, label) in [(0.0, "N"), (90.0, "E"), (180.0, "S"), (270.0, "W")] {
+        printer.print(rim_cell(azimuth, RADIUS as f64), label);
+    }
+    printer.print((2 * RADIUS, RADIUS), "o");
+
+    let mount_style = color_scheme::mount_style(printer);
+    let target_style = color_scheme::target_style(printer);
+    let marker_radius = RADIUS as f64 - 1.0;
+    let mount_cell = mount_az.map(|az| rim_cell(az, marker_radius));
+    let target_cell = target_az.map(|az| rim_cell(az, marker_radius));
+    if let Some(az) = mount_az {
+        // direction line from the center
+        printer.with_color(mount_style, |p| {
+            for r in 1..(RADIUS - 1) { p.print(rim_cell(az, r as f64), "."); }
+        });
+    }
+    match (mount_cell, target_cell) {
+        (Some(m), Some(t)) if m == t => printer.print(m, BOTH_CHAR),
+        (m, t) => {
+            if let Some(t) = t { printer.with_color(target_style, |p| p.print(t, TARGET_CHAR)); }
+            if let Some(m) = m { printer.with_color(mount_style, |p| p.print(m, MOUNT_CHAR)); }
+        }
+    }
+}
+
+/// Returns row of the altitude bar corresponding to altitude (0°-90°).
+fn bar_row(altitude: f64) -> usize {
+    ((90.0 - altitude.max(0.0).min(90.0)) / 90.0 * (ROSE_HEIGHT - 1) as f64).round() as usize
+}
+
+fn draw_altitude_bar(printer: &Printer, mount_alt: Option<f64>, target_alt: Option<f64>) {
+    for (altitude, label) in [(90.0, "90°"), (45.0, "45°"), (0.0, " 0°")] {
+        printer.print((0, bar_row(altitude)), label);
+    }
+    let bar_x = BAR_LABEL_WIDTH;
+    for y in 0..ROSE_HEIGHT { printer.print((bar_x, y), "│"); }
+
+    let mount_style = color_scheme::mount_style(printer);
+    let target_style = color_scheme::target_style(printer);
+    match (mount_alt.map(bar_row), target_alt.map(bar_row)) {
+        (Some(m), Some(t)) if m == t => printer.print((bar_x, m), BOTH_CHAR),
+        (m, t) => {
+            if let Some(t) = t { printer.with_color(target_style, |p| p.print((bar_x, t), TARGET_CHAR)); }
+            if let Some(m) = m { printer.with_color(mount_style, |p| p.print((bar_x, m), MOUNT_CHAR)); }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rim_cells_of_cardinal_directions() {
+        assert_eq!((2 * RADIUS, 0), rim_cell(0.0, RADIUS as f64));
+        assert_eq!((4 * RADIUS, RADIUS), rim_cell(90.0, RADIUS as f64));
+        assert_eq!((2 * RADIUS, 2 * RADIUS), rim_cell(180.0, RADIUS as f64));
+        assert_eq!((0, RADIUS), rim_cell(270.0, RADIUS as f64));
+    }
+}
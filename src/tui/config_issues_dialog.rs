@@ -0,0 +1,135 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    config::{ConfigIssue, Configuration},
+    tui,
+    tui::{close_dialog, get_edit_view_str, msg_box, names, set_edit_view_str, TuiData},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, Resizable, Scrollable, View},
+    views::{CircularFocus, Dialog, DummyView, LinearLayout, OnEventView, Panel, SelectView, TextContent, TextView},
+    With
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+
+/// Shows `issues` found by `Configuration::validate`, allowing to fix or reset the affected entries.
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    config: Weak<RefCell<Configuration>>,
+    issues: Vec<ConfigIssue>
+) -> impl View {
+    let details = TextContent::new("");
+    let issues = Rc::new(RefCell::new(issues));
+
+    let mut list = SelectView::<usize>::new()
+        .on_select(cclone!([issues, details], move |curs, idx: &usize| {
+            show_details(curs, &issues.borrow()[*idx], &details);
+        }));
+    fill_list(&mut list, &issues.borrow());
+    if let Some(issue) = issues.borrow().first() { details.set_content(issue.problem.clone()); }
+
+    Dialog::around(LinearLayout::vertical()
+        .child(TextView::new("The following configuration entries are invalid; defaults are used instead."))
+        .child(DummyView{}.min_height(1))
+        .child(Panel::new(
+            list.with_name(names::CONFIG_ISSUE_LIST).scrollable().fixed_height(8).min_width(60)
+        ).title("Entries").title_position(HAlign::Left))
+        .child(TextView::new_with_content(details))
+        .child(LinearLayout::horizontal()
+            .child(TextView::new("Value:"))
+            .child(DummyView{}.min_width(1))
+            .child(tui::styled_edit_view()
+                .content(issues.borrow().first().map(|i| i.value.clone()).unwrap_or_default())
+                .with_name(names::CONFIG_ISSUE_VALUE)
+                .min_width(50)
+            )
+        )
+        .child(DummyView{}.min_height(1))
+        .child(TextView::new("Changes are saved in the configuration file; most of them take effect after restart."))
+    )
+    .button("Fix", cclone!([tui, config, issues], move |curs| {
+        upgrade!(tui, config);
+        let Some(issue) = selected(curs, &issues.borrow()) else { return; };
+        let value = get_edit_view_str(curs, names::CONFIG_ISSUE_VALUE);
+        config.borrow_mut().fix_entry(&issue, &value);
+        log::info!("configuration entry [{}] {} changed to \"{}\"", issue.section, issue.key, value.trim());
+        on_changed(curs, &tui, &config, &issues);
+    }))
+    .button("Reset", cclone!([tui, config, issues], move |curs| {
+        upgrade!(tui, config);
+        let Some(issue) = selected(curs, &issues.borrow()) else { return; };
+        config.borrow_mut().reset_entry(&issue);
+        log::info!("configuration entry [{}] {} reset to default", issue.section, issue.key);
+        on_changed(curs, &tui, &config, &issues);
+    }))
+    .button("Reset all", cclone!([tui, config, issues], move |curs| {
+        upgrade!(tui, config);
+        for issue in issues.borrow().iter() { config.borrow_mut().reset_entry(issue); }
+        log::info!("all invalid configuration entries reset to defaults");
+        on_changed(curs, &tui, &config, &issues);
+    }))
+    .button("Close", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Configuration problems")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn fill_list(list: &mut SelectView<usize>, issues: &[ConfigIssue]) {
+    list.clear();
+    for (idx, issue) in issues.iter().enumerate() {
+        list.add_item(format!("[{}] {}", issue.section, issue.key), idx);
+    }
+}
+
+fn show_details(curs: &mut cursive::Cursive, issue: &ConfigIssue, details: &TextContent) {
+    details.set_content(issue.problem.clone());
+    set_edit_view_str(curs, names::CONFIG_ISSUE_VALUE, issue.value.clone());
+}
+
+fn selected(curs: &mut cursive::Cursive, issues: &[ConfigIssue]) -> Option<ConfigIssue> {
+    let idx = curs.call_on_name(names::CONFIG_ISSUE_LIST, |v: &mut SelectView<usize>| v.selection().map(|i| *i))??;
+    issues.get(idx).cloned()
+}
+
+/// Validates the configuration again and updates the list; closes the dialog once there are no more issues.
+fn on_changed(
+    curs: &mut cursive::Cursive,
+    tui: &Rc<RefCell<Option<TuiData>>>,
+    config: &Rc<RefCell<Configuration>>,
+    issues: &Rc<RefCell<Vec<ConfigIssue>>>
+) {
+    *issues.borrow_mut() = config.borrow().validate();
+    if issues.borrow().is_empty() {
+        close_dialog(curs, tui);
+        msg_box(curs, "No more configuration problems.", "Configuration");
+        return;
+    }
+    let on_select = curs.call_on_name(names::CONFIG_ISSUE_LIST, |v: &mut SelectView<usize>| {
+        fill_list(v, &issues.borrow());
+        v.set_selection(0)
+    });
+    if let Some(on_select) = on_select { on_select(curs); }
+}
@@ -0,0 +1,124 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{
+    cclone,
+    mount::{Mount, MountWrapper},
+    tui::{
+        close_dialog,
+        create_dialog_theme,
+        get_edit_view_str,
+        msg_box,
+        names,
+        set_edit_view_str,
+        TuiData,
+        WithShadow
+    },
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Nameable, Resizable, Scrollable},
+    View,
+    views::{Dialog, DummyView, LinearLayout, OnEventView, ScrollView, TextView, ThemedView},
+    With
+};
+use std::{cell::RefCell, rc::Weak};
+
+type MountRef = Weak<RefCell<Option<MountWrapper>>>;
+
+pub fn dialog(tui: Weak<RefCell<Option<TuiData>>>, mount: MountRef) -> impl View {
+    Dialog::around(LinearLayout::vertical()
+        .child(TextView::new(
+            "Commands are sent to the mount as typed, bypassing TPTool's checks (e.g., parking, altitude limit).\n\
+            Commands other than queries require confirmation."
+        ))
+        .child(DummyView{})
+        .child(TextView::new("")
+            .scrollable()
+            .with_name(names::CONSOLE_OUTPUT)
+            .min_height(12)
+            .max_height(16)
+            .min_width(90)
+        )
+        .child(DummyView{})
+        .child(crate::tui::styled_edit_view()
+            .on_submit(cclone!([mount], move |curs, command| on_send(curs, &mount, command)))
+            .with_name(names::CONSOLE_COMMAND)
+        )
+    )
+    .button("Send", cclone!([mount], move |curs| {
+        let command = get_edit_view_str(curs, names::CONSOLE_COMMAND);
+        on_send(curs, &mount, &command);
+    }))
+    .button("Close", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Mount command console")
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn on_send(curs: &mut cursive::Cursive, mount: &MountRef, command: &str) {
+    let command = command.trim().to_string();
+    if command.is_empty() { return; }
+
+    let is_query = match mount.upgrade().unwrap().borrow().as_ref() {
+        Some(m) => m.is_query_command(&command),
+        None => { msg_box(curs, "Not connected to a mount.", "Error"); return; }
+    };
+
+    if is_query {
+        send(curs, mount, &command);
+    } else {
+        let dt = create_dialog_theme(curs);
+        curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+            dt,
+            Dialog::text(format!(
+                "\"{}\" is not a known query and may move or reconfigure the mount.\nSend anyway?",
+                command
+            ))
+                .title("Confirm command")
+                .button("Send", cclone!([mount], move |curs| {
+                    curs.pop_layer();
+                    send(curs, &mount, &command);
+                }))
+                .dismiss_button("Cancel")
+        )));
+    }
+}
+
+fn send(curs: &mut cursive::Cursive, mount: &MountRef, command: &str) {
+    let result = match mount.upgrade().unwrap().borrow_mut().as_mut() {
+        Some(m) => m.raw_command(command),
+        None => Err("not connected to a mount".into())
+    };
+
+    let reply = match result {
+        Ok(reply) => { log::info!("console: sent \"{}\", reply: \"{}\"", command, reply); reply },
+        Err(e) => { log::error!("console: command \"{}\" failed: {}", command, e); format!("(error: {})", e) }
+    };
+
+    curs.call_on_name(names::CONSOLE_OUTPUT, |v: &mut ScrollView<TextView>| {
+        let text = v.get_inner_mut();
+        if !text.get_content().source().is_empty() { text.append("\n"); }
+        text.append(format!("> {}\n< {}", command, reply));
+        v.scroll_to_bottom();
+    });
+    set_edit_view_str(curs, names::CONSOLE_COMMAND, "");
+}
@@ -0,0 +1,288 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{
+    cclone,
+    config::Configuration,
+    controller::{ActionAssignments, AxisResponse, ConnectedController, TargetAction},
+    event_handling,
+    tui,
+    tui::{close_dialog, create_dialog_theme, get_edit_view_str, msg_box, names, TuiData, WithShadow},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, Resizable, View},
+    views::{
+        Checkbox,
+        CircularFocus,
+        Dialog,
+        LinearLayout,
+        OnEventView,
+        Panel,
+        SelectView,
+        TextView,
+        ThemedView,
+    },
+    With
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+use strum::IntoEnumIterator;
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    ctrl_actions: Weak<RefCell<ActionAssignments>>,
+    axes_response: Weak<RefCell<[AxisResponse; 2]>>,
+    capture: Weak<RefCell<Option<TargetAction>>>,
+    config: Weak<RefCell<Configuration>>,
+    connected: Weak<RefCell<Vec<ConnectedController>>>
+) -> impl View {
+    let profile = profile_label(&config.upgrade().unwrap().borrow().controller_profile());
+
+    let mut actions = SelectView::<TargetAction>::new();
+    {
+        let ctrl_actions = ctrl_actions.upgrade().unwrap();
+        for target_action in TargetAction::iter() {
+            actions.add_item(item_label(target_action, &ctrl_actions.borrow()), target_action);
+        }
+    }
+    actions.set_on_submit(cclone!([capture], move |curs, target_action: &TargetAction| {
+        upgrade!(capture);
+        start_capture(curs, &capture, *target_action);
+    }));
+
+    let close = cclone!([tui, capture], move |curs: &mut cursive::Cursive| {
+        upgrade!(tui, capture);
+        *capture.borrow_mut() = None;
+        close_dialog(curs, &tui);
+    });
+
+    Dialog::around(LinearLayout::vertical()
+        .child(LinearLayout::horizontal()
+            .child(TextView::new("Profile: "))
+            .child(TextView::new(profile).with_name(names::CTRL_PROFILE))
+        )
+        .child(Panel::new(actions.with_name(names::CTRL_ACTION_LIST))
+            .title("Actions")
+            .title_position(HAlign::Left)
+        )
+        .child(TextView::new("Select an action and press Enter or \"Assign\".")
+            .with_name(names::CTRL_CAPTURE_STATUS)
+            .min_width(60)
+        )
+    )
+    .button("Assign", cclone!([capture], move |curs| {
+        upgrade!(capture);
+        if let Some(target_action) = selected_action(curs) {
+            start_capture(curs, &capture, target_action);
+        }
+    }))
+    .button("Clear", cclone!([ctrl_actions, capture, config], move |curs| {
+        upgrade!(ctrl_actions, capture, config);
+        *capture.borrow_mut() = None;
+        if let Some(target_action) = selected_action(curs) {
+            ctrl_actions.borrow_mut().set(target_action, None);
+            config.borrow_mut().save_controller_actions(&ctrl_actions.borrow());
+            on_assignment_changed(curs, target_action, &ctrl_actions.borrow());
+        }
+    }))
+    .button("Profile...", cclone!([ctrl_actions, axes_response, capture, config], move |curs| {
+        upgrade!(capture);
+        *capture.borrow_mut() = None;
+        on_choose_profile(curs, ctrl_actions.clone(), axes_response.clone(), config.clone());
+    }))
+    .button("Controllers...", cclone!([capture, config, connected], move |curs| {
+        upgrade!(capture);
+        *capture.borrow_mut() = None;
+        on_choose_controllers(curs, connected.clone(), config.clone());
+    }))
+    .button("Close", close.clone())
+    .title("Controller actions")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), close)
+}
+
+/// Updates the dialog after an assignment has been changed (also called by the controller event handler
+/// once a pending capture completes).
+pub fn on_assignment_changed(curs: &mut cursive::Cursive, target_action: TargetAction, ctrl_actions: &ActionAssignments) {
+    update_item_label(curs, target_action, ctrl_actions);
+    curs.call_on_name(names::CTRL_CAPTURE_STATUS, |v: &mut TextView| {
+        v.set_content(format!("{}: {}", target_action, assignment_str(target_action, ctrl_actions)));
+    });
+}
+
+fn start_capture(curs: &mut cursive::Cursive, capture: &Rc<RefCell<Option<TargetAction>>>, target_action: TargetAction) {
+    *capture.borrow_mut() = Some(target_action);
+    let input = match target_action {
+        TargetAction::MountAxis1 | TargetAction::MountAxis2 => "Move a controller axis",
+        _ => "Press and release a controller button"
+    };
+    curs.call_on_name(names::CTRL_CAPTURE_STATUS, |v: &mut TextView| {
+        v.set_content(format!(
+            "{} to assign to \"{}\" (hold another button first for a chord)...", input, target_action
+        ));
+    });
+}
+
+fn selected_action(curs: &mut cursive::Cursive) -> Option<TargetAction> {
+    curs.call_on_name(names::CTRL_ACTION_LIST, |v: &mut SelectView<TargetAction>| v.selection().map(|a| *a)).unwrap()
+}
+
+fn assignment_str(target_action: TargetAction, ctrl_actions: &ActionAssignments) -> String {
+    match ctrl_actions.get(target_action) {
+        Some(src_action) => if src_action.ctrl_name.is_empty() {
+            src_action.serialize()
+        } else {
+            format!("{} ({})", src_action.serialize(), src_action.ctrl_name)
+        },
+
+        None => "(none)".into()
+    }
+}
+
+fn item_label(target_action: TargetAction, ctrl_actions: &ActionAssignments) -> String {
+    format!("{:<26}{}", target_action.to_string(), assignment_str(target_action, ctrl_actions))
+}
+
+fn update_item_label(curs: &mut cursive::Cursive, target_action: TargetAction, ctrl_actions: &ActionAssignments) {
+    curs.call_on_name(names::CTRL_ACTION_LIST, |v: &mut SelectView<TargetAction>| {
+        let index = TargetAction::iter().position(|a| a == target_action).unwrap();
+        if let Some((label, _)) = v.get_item_mut(index) {
+            *label = item_label(target_action, ctrl_actions).into();
+        }
+    });
+}
+
+fn profile_label(profile: &str) -> String {
+    if profile.is_empty() { "(default)".into() } else { profile.into() }
+}
+
+fn on_choose_profile(
+    curs: &mut cursive::Cursive,
+    ctrl_actions: Weak<RefCell<ActionAssignments>>,
+    axes_response: Weak<RefCell<[AxisResponse; 2]>>,
+    config: Weak<RefCell<Configuration>>
+) {
+    let profiles = {
+        let mut profiles = SelectView::<String>::new();
+        upgrade!(config);
+        let current = config.borrow().controller_profile();
+        let all_profiles = config.borrow().controller_profiles();
+        let current_idx = all_profiles.iter().position(|p| *p == current).unwrap_or(0);
+        for profile in all_profiles {
+            profiles.add_item(profile_label(&profile), profile);
+        }
+        profiles.selected(current_idx)
+    };
+
+    let dt = create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dt,
+        Dialog::around(LinearLayout::vertical()
+            .child(Panel::new(profiles.with_name(names::CTRL_PROFILE_LIST)).title("Profiles").title_position(HAlign::Left))
+            .child(LinearLayout::horizontal()
+                .child(TextView::new("new profile: "))
+                .child(tui::styled_edit_view().with_name(names::CTRL_NEW_PROFILE).fixed_width(20))
+            )
+        )
+        .title("Controller profile")
+        .button("Select", cclone!([ctrl_actions, axes_response, config], move |curs| {
+            upgrade!(ctrl_actions, axes_response, config);
+            let profile = curs.call_on_name(
+                names::CTRL_PROFILE_LIST, |v: &mut SelectView<String>| v.selection()
+            ).unwrap();
+            if let Some(profile) = profile {
+                activate_profile(curs, &profile, &ctrl_actions, &axes_response, &config);
+            }
+        }))
+        .button("Create", cclone!([ctrl_actions, axes_response, config], move |curs| {
+            upgrade!(ctrl_actions, axes_response, config);
+            let profile = get_edit_view_str(curs, names::CTRL_NEW_PROFILE).trim().to_string();
+            if profile.is_empty() || profile.contains(|c| c == '[' || c == ']') {
+                msg_box(curs, "Invalid profile name.", "Error");
+            } else if config.borrow().controller_profiles().contains(&profile) {
+                msg_box(curs, &format!("Profile \"{}\" already exists.", profile), "Error");
+            } else {
+                log::info!("creating controller profile \"{}\"", profile);
+                config.borrow_mut().add_controller_profile(&profile);
+                activate_profile(curs, &profile, &ctrl_actions, &axes_response, &config);
+            }
+        }))
+        .dismiss_button("Cancel")
+        .wrap_with(OnEventView::new)
+        .on_event(event::Event::Key(event::Key::Esc), |curs| { curs.pop_layer(); })
+    )));
+}
+
+/// Shows connected controllers and allows enabling/disabling them.
+fn on_choose_controllers(
+    curs: &mut cursive::Cursive,
+    connected: Weak<RefCell<Vec<ConnectedController>>>,
+    config: Weak<RefCell<Configuration>>
+) {
+    let mut list = LinearLayout::vertical();
+    {
+        let controllers = connected.upgrade().unwrap();
+        if controllers.borrow().is_empty() {
+            list.add_child(TextView::new("(no controllers connected)"));
+        }
+        for controller in controllers.borrow().iter() {
+            let id = controller.id;
+            list.add_child(LinearLayout::horizontal()
+                .child(Checkbox::new()
+                    .with_checked(controller.enabled)
+                    .on_change(cclone!([connected, config], move |_, enabled| {
+                        upgrade!(connected, config);
+                        log::info!("controller [{:016X}] {}", id, if enabled { "enabled" } else { "disabled" });
+                        for c in connected.borrow_mut().iter_mut().filter(|c| c.id == id) { c.enabled = enabled; }
+                        config.borrow_mut().set_controller_enabled(id, enabled);
+                    }))
+                )
+                .child(TextView::new(format!(" [{:016X}] {}", id, controller.name)))
+            );
+        }
+    }
+
+    let dt = create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dt,
+        Dialog::around(Panel::new(list).title("Enabled").title_position(HAlign::Left))
+            .title("Connected controllers")
+            .dismiss_button("Close")
+            .wrap_with(OnEventView::new)
+            .on_event(event::Event::Key(event::Key::Esc), |curs| { curs.pop_layer(); })
+    )));
+}
+
+fn activate_profile(
+    curs: &mut cursive::Cursive,
+    profile: &str,
+    ctrl_actions: &Rc<RefCell<ActionAssignments>>,
+    axes_response: &Rc<RefCell<[AxisResponse; 2]>>,
+    config: &Rc<RefCell<Configuration>>
+) {
+    event_handling::on_controller_profile_selected(profile, config, ctrl_actions, axes_response);
+    for target_action in TargetAction::iter() {
+        update_item_label(curs, target_action, &ctrl_actions.borrow());
+    }
+    curs.call_on_name(names::CTRL_PROFILE, |v: &mut TextView| v.set_content(profile_label(profile)));
+    curs.pop_layer();
+}
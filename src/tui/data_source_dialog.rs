@@ -19,55 +19,72 @@
 use crate::{
     cclone,
     config::Configuration,
+    data::{as_deg, deg},
     data_receiver,
+    discovery,
+    target_source::{SimulatedPath, SimulatedTarget, SimulatedTargetParams, TargetSource},
     tui,
     tui::{
         close_dialog,
+        create_dialog_theme,
         get_edit_view_str,
         msg_box,
         names,
-        TuiData
+        TuiData,
+        WithShadow
     },
     upgrade
 };
 use cursive::{
+    align::HAlign,
     event,
     view::{Nameable, Resizable, View},
     views::{
+        Button,
         CircularFocus,
         Dialog,
         DummyView,
         LinearLayout,
         OnEventView,
+        Panel,
+        RadioGroup,
         TextView,
+        ThemedView
     },
     With
 };
-use std::{cell::RefCell, rc::{Rc, Weak}};
+use pointing_utils::uom;
+use std::{cell::RefCell, error::Error, rc::{Rc, Weak}, time::Duration};
+use strum::IntoEnumIterator;
+use uom::si::{f64, length, velocity};
 
 pub fn dialog(
     tui: Weak<RefCell<Option<TuiData>>>,
     connection: data_receiver::Connection,
-    config: Weak<RefCell<Configuration>>
+    config: Weak<RefCell<Configuration>>,
+    target_source: Weak<RefCell<Option<Box<dyn TargetSource>>>>
 ) -> impl View {
-    Dialog::around(
-        LinearLayout::horizontal()
+    Dialog::around(LinearLayout::vertical()
+        .child(LinearLayout::horizontal()
             .child(TextView::new("Server address and port:"))
             .child(DummyView{}.min_width(1))
             .child(tui::styled_edit_view()
                 .content(config.upgrade().unwrap().borrow().data_source_addr().unwrap_or("".into()))
-                .on_submit(cclone!([tui, connection, config], move |curs, s| {
-                    upgrade!(tui, config);
-                    on_connect_to_data_source(curs, &tui, connection.clone(), &config, s);
-                }))
+                .on_submit(cclone!([connection], move |curs, s| on_connect_to_data_source(curs, connection.clone(), s)))
                 .with_name(names::SERVER_ADDR)
                 .fixed_width(20)
+            )
+            .child(DummyView{}.min_width(1))
+            .child(Button::new("Discover", |curs| {
+                tui::discovery_dialog::show(curs, discovery::DATA_SOURCE_SERVICE, names::SERVER_ADDR);
+            }))
         )
+        .child(DummyView{})
+        .child(simulated_target_panel(tui.clone(), connection.clone(), config.clone(), target_source.clone()))
     )
-    .button("OK", cclone!([tui, connection, config], move |curs| {
-        upgrade!(tui, config);
+    .button("OK", cclone!([connection], move |curs| {
         let server_address = get_edit_view_str(curs, names::SERVER_ADDR);
-        on_connect_to_data_source(curs, &tui, connection.clone(), &config, &server_address);
+        on_connect_to_data_source(curs, connection.clone(), &server_address);
     }))
     .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
     .title("Connect to data source")
@@ -79,23 +96,161 @@ pub fn dialog(
     ))
 }
 
-fn on_connect_to_data_source(
+fn on_connect_to_data_source(curs: &mut cursive::Cursive, connection: data_receiver::Connection, server_addr: &str) {
+    // the result is handled by `on_connection_finished`
+    connection.start_connecting(server_addr);
+
+    let dt = create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dt,
+        Dialog::around(
+            TextView::new(connecting_text(server_addr, Duration::ZERO)).with_name(names::DATA_SOURCE_CONNECTING)
+        )
+            .title("Connecting")
+            .button("Cancel", cclone!([connection], move |curs| {
+                connection.cancel_connecting();
+                curs.pop_layer();
+            }))
+            .wrap_with(OnEventView::new)
+            .on_event(event::Event::Key(event::Key::Esc), move |curs| {
+                connection.cancel_connecting();
+                curs.pop_layer();
+            })
+    )));
+}
+
+fn connecting_text(server_addr: &str, elapsed: Duration) -> String {
+    const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+    let spinner = SPINNER[(elapsed.as_millis() / 250) as usize % SPINNER.len()];
+    format!("{} Connecting to {}... ({} s)", spinner, server_addr, elapsed.as_secs())
+}
+
+/// Returns true if the progress indicator of a connection attempt is shown.
+pub fn is_showing_progress(curs: &mut cursive::Cursive) -> bool {
+    curs.find_name::<TextView>(names::DATA_SOURCE_CONNECTING).is_some()
+}
+
+/// Updates the progress indicator of a connection attempt.
+pub fn on_connecting(curs: &mut cursive::Cursive, server_addr: &str, elapsed: Duration) {
+    curs.call_on_name(names::DATA_SOURCE_CONNECTING, |v: &mut TextView| {
+        v.set_content(connecting_text(server_addr, elapsed));
+    });
+}
+
+/// Closes the progress indicator and, on success, the data source dialog.
+pub fn on_connection_finished(
     curs: &mut cursive::Cursive,
     tui: &Rc<RefCell<Option<TuiData>>>,
-    connection: data_receiver::Connection,
-    config: &Rc<RefCell<Configuration>>,
-    server_addr: &str
+    server_addr: &str,
+    result: Result<(), Box<dyn Error>>
 ) {
-    match connection.connect(server_addr) {
+    if !is_showing_progress(curs) { return; }
+    curs.pop_layer(); // the progress indicator
+
+    match result {
         Ok(()) => {
-            log::info!("connected to data source {}", server_addr);
-            config.borrow_mut().set_data_source_addr(server_addr);
+            tui!(tui).text_content.target_source.set_content(server_addr);
             close_dialog(curs, tui);
         },
 
-        Err(e) => {
-            log::error!("error connecting to data source \"{}\": {}", server_addr, e);
-            msg_box(curs, &format!("Failed to connect to \"{}\":\n{}.", server_addr, e), "Error");
-        }
+        Err(e) => msg_box(curs, &format!("Failed to connect to \"{}\":\n{}.", server_addr, e), "Error")
     }
 }
+
+fn simulated_target_panel(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    connection: data_receiver::Connection,
+    config: Weak<RefCell<Configuration>>,
+    target_source: Weak<RefCell<Option<Box<dyn TargetSource>>>>
+) -> impl View {
+    let params = config.upgrade().unwrap().borrow().simulated_target_params();
+
+    let mut rb_path = RadioGroup::<SimulatedPath>::new();
+    let mut paths = LinearLayout::horizontal().child(TextView::new("Path: "));
+    for path in SimulatedPath::iter() {
+        let button = rb_path.button(path, path.to_string());
+        paths.add_child(if path == params.path { button.selected() } else { button });
+        paths.add_child(DummyView{}.min_width(1));
+    }
+
+    let edit = |label: &str, value: String, name: &str, unit: &str| {
+        LinearLayout::horizontal()
+            .child(TextView::new(label).min_width(10))
+            .child(tui::styled_edit_view().content(value).with_name(name).fixed_width(10))
+            .child(TextView::new(unit))
+    };
+
+    Panel::new(LinearLayout::vertical()
+        .child(paths)
+        .child(edit(
+            "speed:",
+            format!("{:.0}", params.speed.get::<velocity::kilometer_per_hour>()),
+            names::SIM_TARGET_SPEED,
+            " km/h"
+        ))
+        .child(edit(
+            "altitude:",
+            format!("{:.0}", params.altitude.get::<length::meter>()),
+            names::SIM_TARGET_ALTITUDE,
+            " m"
+        ))
+        .child(edit("course:", format!("{:.1}", as_deg(params.course)), names::SIM_TARGET_COURSE, "°"))
+        .child(edit(
+            "distance:",
+            format!("{:.0}", params.distance.get::<length::meter>()),
+            names::SIM_TARGET_DISTANCE,
+            " m (closest approach or radius)"
+        ))
+        .child(Button::new("Start simulation", move |curs| {
+            upgrade!(tui, config, target_source);
+            on_start_simulation(curs, &tui, &connection, &config, &target_source, *rb_path.selection());
+        }))
+    )
+    .title("Simulated target (practice)")
+    .title_position(HAlign::Left)
+}
+
+fn read_simulated_target_params(
+    curs: &mut cursive::Cursive,
+    path: SimulatedPath
+) -> Result<SimulatedTargetParams, String> {
+    let parse = |name: &str| {
+        let s = get_edit_view_str(curs, name);
+        s.parse::<f64>().map_err(|_| format!("invalid value: {}", s))
+    };
+
+    Ok(SimulatedTargetParams{
+        path,
+        speed: f64::Velocity::new::<velocity::kilometer_per_hour>(parse(names::SIM_TARGET_SPEED)?),
+        altitude: f64::Length::new::<length::meter>(parse(names::SIM_TARGET_ALTITUDE)?),
+        course: deg(parse(names::SIM_TARGET_COURSE)?),
+        distance: f64::Length::new::<length::meter>(parse(names::SIM_TARGET_DISTANCE)?)
+    })
+}
+
+fn on_start_simulation(
+    curs: &mut cursive::Cursive,
+    tui: &Rc<RefCell<Option<TuiData>>>,
+    connection: &data_receiver::Connection,
+    config: &Rc<RefCell<Configuration>>,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    path: SimulatedPath
+) {
+    if config.borrow().observer_location().is_none() {
+        msg_box(curs, "Observer location not set (press O to set it).", "Error");
+        return;
+    }
+
+    let source = match read_simulated_target_params(curs, path)
+        .and_then(|params| SimulatedTarget::new(params).map(|s| (params, s)).map_err(|e| e.to_string()))
+    {
+        Ok((params, source)) => { config.borrow_mut().set_simulated_target_params(&params); source },
+        Err(e) => { msg_box(curs, &format!("Invalid simulated target parameters: {}.", e), "Error"); return; }
+    };
+
+    log::info!("tracking {}", source.name());
+    connection.disconnect();
+    tui!(tui).text_content.target_source.set_content(source.name());
+    *target_source.borrow_mut() = Some(Box::new(source));
+    close_dialog(curs, tui);
+}
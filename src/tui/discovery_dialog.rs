@@ -0,0 +1,60 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{discovery, tui, tui::{msg_box, set_edit_view_str, WithShadow}};
+use cursive::{
+    event,
+    view::{Resizable, Scrollable},
+    views::{Dialog, OnEventView, SelectView, ThemedView},
+    With
+};
+
+/// Browses the network for `service` and lets the user choose one of the found instances; its address is put
+/// in the edit view `edit_view_name`.
+pub fn show(curs: &mut cursive::Cursive, service: &str, edit_view_name: &'static str) {
+    let services = match discovery::browse(service, discovery::DEFAULT_BROWSE_DURATION) {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("service discovery failed: {}", e);
+            msg_box(curs, &format!("Service discovery failed:\n{}.", e), "Error");
+            return;
+        }
+    };
+
+    if services.is_empty() {
+        msg_box(curs, "No services found.", "Discovery");
+        return;
+    }
+
+    let mut list = SelectView::<String>::new().on_submit(move |curs, address: &String| {
+        set_edit_view_str(curs, edit_view_name, address);
+        curs.pop_layer();
+    });
+    for s in &services {
+        list.add_item(s.to_string(), s.address.to_string());
+    }
+
+    let dialog_theme = tui::create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dialog_theme,
+        Dialog::around(list.scrollable().max_height(10).min_width(40))
+            .title("Discovered services")
+            .dismiss_button("Cancel")
+            .wrap_with(OnEventView::new)
+            .on_event(event::Event::Key(event::Key::Esc), |curs| { curs.pop_layer(); })
+    )));
+}
@@ -0,0 +1,90 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{
+    cclone,
+    config::Configuration,
+    data_receiver,
+    target_source,
+    target_source::{Body, TargetSource},
+    tui,
+    tui::{close_dialog, msg_box, names, TuiData},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, View},
+    views::{
+        CircularFocus,
+        Dialog,
+        OnEventView,
+        Panel,
+        SelectView,
+    },
+    With
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+use strum::IntoEnumIterator;
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    connection: data_receiver::Connection,
+    config: Weak<RefCell<Configuration>>,
+    target_source: Weak<RefCell<Option<Box<dyn TargetSource>>>>
+) -> impl View {
+    let mut bodies = SelectView::<Body>::new();
+    for body in Body::iter() {
+        bodies.add_item(body.to_string(), body);
+    }
+
+    Dialog::around(Panel::new(bodies.with_name(names::SOLAR_SYSTEM_BODY)).title("Body").title_position(HAlign::Left))
+    .button("OK", cclone!([tui, connection, config, target_source], move |curs| {
+        upgrade!(tui, config, target_source);
+        on_select_body(curs, &tui, &connection, &config, &target_source);
+    }))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Track Sun, Moon or planet")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn on_select_body(
+    curs: &mut cursive::Cursive,
+    tui: &Rc<RefCell<Option<TuiData>>>,
+    connection: &data_receiver::Connection,
+    config: &Rc<RefCell<Configuration>>,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>
+) {
+    if config.borrow().observer_location().is_none() {
+        msg_box(curs, "Observer location not set (press O to set it).", "Error");
+        return;
+    }
+
+    let body = *curs.call_on_name(names::SOLAR_SYSTEM_BODY, |v: &mut SelectView<Body>| v.selection().unwrap()).unwrap();
+    log::info!("tracking {}", body);
+
+    connection.disconnect();
+    tui!(tui).text_content.target_source.set_content(body.to_string());
+    *target_source.borrow_mut() = Some(Box::new(target_source::SolarSystemBody::new(body)));
+    close_dialog(curs, tui);
+}
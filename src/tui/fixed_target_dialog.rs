@@ -0,0 +1,123 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    config::Configuration,
+    data::{as_deg, deg},
+    data_receiver,
+    mount::{Mount, MountWrapper},
+    target_source,
+    target_source::TargetSource,
+    tui,
+    tui::{close_dialog, get_edit_view_str, msg_box, names, set_edit_view_str, TuiData},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Nameable, Resizable, View},
+    views::{
+        Button,
+        CircularFocus,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        TextView,
+    },
+    With
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    mount: Weak<RefCell<Option<MountWrapper>>>,
+    connection: data_receiver::Connection,
+    config: Weak<RefCell<Configuration>>,
+    target_source: Weak<RefCell<Option<Box<dyn TargetSource>>>>
+) -> impl View {
+    let edit = |label: &str, name: &str| LinearLayout::horizontal()
+        .child(TextView::new(label))
+        .child(tui::styled_edit_view().with_name(name).fixed_width(10))
+        .child(TextView::new("°"));
+
+    Dialog::around(LinearLayout::vertical()
+        .child(TextView::new("Track a stationary point (e.g., to hold the mount on a terrestrial object)."))
+        .child(DummyView{})
+        .child(edit("azimuth:  ", names::FIXED_TARGET_AZ))
+        .child(edit("altitude: ", names::FIXED_TARGET_ALT))
+        .child(DummyView{})
+        .child(Button::new("Use current mount position", cclone!([mount], move |curs| {
+            upgrade!(mount);
+            let pos = match mount.borrow_mut().as_mut() {
+                Some(mount) => mount.position(),
+                None => Err("mount not connected".into())
+            };
+            match pos {
+                Ok((azimuth, altitude)) => {
+                    set_edit_view_str(curs, names::FIXED_TARGET_AZ, format!("{:.4}", as_deg(azimuth)));
+                    set_edit_view_str(curs, names::FIXED_TARGET_ALT, format!("{:.4}", as_deg(altitude)));
+                },
+                Err(e) => msg_box(curs, &format!("Failed to get mount position: {}.", e), "Error")
+            }
+        })))
+    )
+    .button("OK", cclone!([tui, connection, config, target_source], move |curs| {
+        upgrade!(tui, config, target_source);
+        on_ok(curs, &tui, &connection, &config, &target_source);
+    }))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Fixed target")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn on_ok(
+    curs: &mut cursive::Cursive,
+    tui: &Rc<RefCell<Option<TuiData>>>,
+    connection: &data_receiver::Connection,
+    config: &Rc<RefCell<Configuration>>,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>
+) {
+    if config.borrow().observer_location().is_none() {
+        msg_box(curs, "Observer location not set (press O to set it).", "Error");
+        return;
+    }
+
+    let azimuth_str = get_edit_view_str(curs, names::FIXED_TARGET_AZ);
+    let altitude_str = get_edit_view_str(curs, names::FIXED_TARGET_ALT);
+    let azimuth = match azimuth_str.parse::<f64>() {
+        Ok(value) => value,
+        Err(_) => { msg_box(curs, &format!("Invalid azimuth: {}.", azimuth_str), "Error"); return; }
+    };
+    let altitude = match altitude_str.parse::<f64>() {
+        Ok(value) if (-90.0..=90.0).contains(&value) => value,
+        _ => { msg_box(curs, &format!("Invalid altitude: {}.", altitude_str), "Error"); return; }
+    };
+
+    let name = format!("fixed az. {:.2}°, alt. {:.2}°", azimuth, altitude);
+    log::info!("tracking {}", name);
+    connection.disconnect();
+    tui!(tui).text_content.target_source.set_content(name.clone());
+    *target_source.borrow_mut() =
+        Some(Box::new(target_source::FixedDirection::new(&name, deg(azimuth), deg(altitude))));
+    close_dialog(curs, tui);
+}
@@ -0,0 +1,53 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    latency::LatencyStats,
+    tui::{close_dialog, TuiData},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Resizable, View},
+    views::{CircularFocus, Dialog, OnEventView, TextContent, TextView},
+    With
+};
+use std::{cell::RefCell, rc::Weak};
+
+pub fn dialog(tui: Weak<RefCell<Option<TuiData>>>, latency: Weak<RefCell<LatencyStats>>) -> impl View {
+    let summary = TextContent::new(latency.upgrade().unwrap().borrow().summary());
+
+    Dialog::around(TextView::new_with_content(summary.clone()).min_width(55))
+        .button("Refresh", cclone!([latency, summary], move |_| {
+            upgrade!(latency);
+            summary.set_content(latency.borrow().summary());
+        }))
+        .button("Reset", cclone!([latency, summary], move |_| {
+            upgrade!(latency);
+            *latency.borrow_mut() = Default::default();
+            summary.set_content(latency.borrow().summary());
+        }))
+        .button("Close", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+        .title("Latency")
+        .wrap_with(CircularFocus::new)
+        .wrap_tab()
+        .wrap_with(OnEventView::new)
+        .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+            move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+        ))
+}
@@ -0,0 +1,78 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{cclone, config::Configuration, log_buffer, tui::{close_dialog, names, TuiData}, upgrade};
+use cursive::{
+    event,
+    view::{Nameable, Resizable, Scrollable},
+    View,
+    views::{Dialog, DummyView, LinearLayout, OnEventView, ScrollView, TextContent, TextView},
+    With
+};
+use std::{cell::RefCell, rc::Weak};
+
+pub fn dialog(tui: Weak<RefCell<Option<TuiData>>>, config: Weak<RefCell<Configuration>>) -> impl View {
+    let mut log_view = TextView::new(log_text()).scrollable();
+    log_view.scroll_to_bottom();
+
+    let level_text = TextContent::new(level_descr(log::max_level()));
+
+    Dialog::around(LinearLayout::vertical()
+        .child(log_view.with_name(names::LOG_VIEW).max_height(16).min_width(90))
+        .child(DummyView{})
+        .child(TextView::new_with_content(level_text.clone()))
+    )
+    .button("Log level", cclone!([config], move |_| {
+        upgrade!(config);
+        let level = next_level(log::max_level());
+        log::set_max_level(level);
+        log::info!("log level changed to {}", level);
+        config.borrow_mut().set_log_level(level);
+        level_text.set_content(level_descr(level));
+    }))
+    .button("Refresh", |curs| {
+        curs.call_on_name(names::LOG_VIEW, |v: &mut ScrollView<TextView>| {
+            v.get_inner_mut().set_content(log_text());
+            v.scroll_to_bottom();
+        });
+    })
+    .button("Close", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Recent warnings and errors")
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn level_descr(level: log::LevelFilter) -> String {
+    format!("Log file level: {}", level.to_string().to_lowercase())
+}
+
+/// Cycles between info, debug and trace log levels.
+fn next_level(level: log::LevelFilter) -> log::LevelFilter {
+    match level {
+        log::LevelFilter::Debug => log::LevelFilter::Trace,
+        log::LevelFilter::Trace => log::LevelFilter::Info,
+        _ => log::LevelFilter::Debug
+    }
+}
+
+fn log_text() -> String {
+    let entries = log_buffer::entries();
+    if entries.is_empty() { "(none)".into() } else { entries.join("\n") }
+}
@@ -17,19 +17,55 @@
 //
 
 mod about_dialog;
-mod data_source_dialog;
+pub mod color_scheme;
+mod adjustments_dialog;
+mod alignment_wizard;
+mod axis_response_dialog;
+mod compass_dialog;
+mod compass_view;
+mod config_issues_dialog;
+mod console_dialog;
+pub mod controller_dialog;
+pub mod data_source_dialog;
+mod discovery_dialog;
+mod ephemeris_dialog;
+mod fixed_target_dialog;
+mod latency_dialog;
+mod log_dialog;
+mod mount_details_dialog;
 mod mount_dialog;
+pub mod nudge_dialog;
+mod observer_dialog;
+mod profile_dialog;
+pub mod rate_calibration_dialog;
 mod ref_pos_dialog;
+mod satellite_dialog;
 mod shadow_view;
 mod simple_dialog;
+mod sites_dialog;
+mod sky_map_dialog;
+mod sky_map_view;
+mod slew_speed_dialog;
+mod stats_dialog;
+mod target_queue_dialog;
+mod trend_dialog;
+mod trend_view;
 mod zero_pos_dialog;
 
 use crate::{
     cclone,
+    config,
+    controller::TargetAction,
     data,
     data::ProgramState,
     event_handling,
-    event_handling::SLEW_SPEED_CHANGE_FACTOR
+    event_handling::SLEW_SPEED_CHANGE_FACTOR,
+    mount::Axis,
+    shutdown,
+    sun_avoidance,
+    sun_avoidance::SunAvoidance,
+    tracking::TrackingController,
+    upgrade
 };
 use cursive::{
     align::HAlign,
@@ -37,18 +73,22 @@ use cursive::{
     reexports::enumset,
     Rect,
     theme,
-    theme::Theme,
     Vec2,
     View,
-    view::{Offset, Position, Resizable},
+    direction::Orientation,
+    view::{Finder, Nameable, Offset, Position, Resizable},
     views::{
         Dialog,
         DummyView,
         EditView,
         FixedLayout,
+        HideableView,
+        Layer,
         LinearLayout,
         OnLayoutView,
+        PaddedView,
         Panel,
+        ResizedView,
         SelectView,
         TextContent,
         TextView,
@@ -64,17 +104,71 @@ use uom::si::f64;
 /// Unique Cursive view names.
 mod names {
     pub const SERVER_ADDR: &str = "server_addr";
+    pub const MOUNT_DETAILS: &str = "mount_details";
+    pub const DATA_SOURCE_CONNECTING: &str = "data_source_connecting";
     pub const MOUNT_CONNECTION: &str = "mount_connection";
     pub const REF_POS_AZ: &str = "ref_pos_azimuth";
     pub const REF_POS_ALT: &str = "ref_pos_altitude";
     pub const REF_POS_SEL_PRESET: &str = "ref_pos_selected_preset";
+    pub const REF_POS_MAGNETIC: &str = "ref_pos_magnetic";
     pub const SIMPLE_DIALOG_TEXT: &str = "simple_dialog_text";
     pub const OBS_LAT: &str = "observer_latitude";
     pub const OBS_LON: &str = "observer_longitude";
     pub const OBS_ELEVATION: &str = "observer_elevation";
+    pub const OBS_MAGNETIC_DECLINATION: &str = "observer_magnetic_declination";
     pub const TARGET_LAT: &str = "target_latitude";
     pub const TARGET_LON: &str = "target_longitude";
     pub const TARGET_ELEVATION: &str = "target_elevation";
+    pub const TLE_FILE: &str = "tle_file";
+    pub const SATELLITE_LIST: &str = "satellite_list";
+    pub const SATELLITE_OPTICAL: &str = "satellite_optical";
+    pub const SOLAR_SYSTEM_BODY: &str = "solar_system_body";
+    pub const FIXED_TARGET_AZ: &str = "fixed_target_azimuth";
+    pub const FIXED_TARGET_ALT: &str = "fixed_target_altitude";
+    pub const GPS_DEVICE: &str = "gps_device";
+    pub const CTRL_ACTION_LIST: &str = "ctrl_action_list";
+    pub const CTRL_CAPTURE_STATUS: &str = "ctrl_capture_status";
+    pub const CTRL_PROFILE: &str = "ctrl_profile";
+    pub const LOG_VIEW: &str = "log_view";
+    pub const CTRL_PROFILE_LIST: &str = "ctrl_profile_list";
+    pub const CTRL_NEW_PROFILE: &str = "ctrl_new_profile";
+    pub const AXIS_DEAD_ZONE: [&str; 2] = ["axis1_dead_zone", "axis2_dead_zone"];
+    pub const AXIS_EXPONENT: [&str; 2] = ["axis1_exponent", "axis2_exponent"];
+    pub const AXIS_SENSITIVITY: [&str; 2] = ["axis1_sensitivity", "axis2_sensitivity"];
+    pub const POLAR_ADJUSTMENT: &str = "polar_adjustment";
+    pub const SLEW_SPEED_VALUE: &str = "slew_speed_value";
+    pub const SLEW_SPEED_PRESET_LIST: &str = "slew_speed_preset_list";
+    pub const TARGET_QUEUE_FILE: &str = "target_queue_file";
+    pub const TARGET_QUEUE_LIST: &str = "target_queue_list";
+    pub const SAVED_ADJUSTMENTS_LIST: &str = "saved_adjustments_list";
+    pub const PROFILE_LIST: &str = "profile_list";
+    pub const REF_POS_MANAGE_LIST: &str = "ref_pos_manage_list";
+    pub const LANDMARK_LIST: &str = "landmark_list";
+    pub const SITE_LIST: &str = "site_list";
+    pub const SERIAL_PORT_LIST: &str = "serial_port_list";
+    pub const SIM_TARGET_SPEED: &str = "sim_target_speed";
+    pub const SIM_TARGET_ALTITUDE: &str = "sim_target_altitude";
+    pub const SIM_TARGET_COURSE: &str = "sim_target_course";
+    pub const SIM_TARGET_DISTANCE: &str = "sim_target_distance";
+    pub const WIZARD_STEP: &str = "wizard_step";
+    pub const WIZARD_CONTENT: &str = "wizard_content";
+    pub const WIZARD_MOUNT_TYPE: &str = "wizard_mount_type";
+    pub const WIZARD_MOUNT_PARAM: &str = "wizard_mount_param";
+    pub const WIZARD_OBJECT_LIST: &str = "wizard_object_list";
+    pub const MAIN_ROW_TOP: &str = "main_row_top";
+    pub const MAIN_ROW_BOTTOM: &str = "main_row_bottom";
+    pub const CONTROLLER_PANEL: &str = "controller_panel";
+    pub const PUSH_TO_PANEL: &str = "push_to_panel";
+    pub const RATE_CALIB_SPEED: &str = "rate_calib_speed";
+    pub const RATE_CALIB_DURATION: &str = "rate_calib_duration";
+    pub const RATE_CALIB_STATUS: &str = "rate_calib_status";
+    pub const NUDGE_OFFSET: &str = "nudge_offset";
+    pub const NUDGE_STATUS: &str = "nudge_status";
+    pub const CONFIG_ISSUE_LIST: &str = "config_issue_list";
+    pub const CONFIG_ISSUE_VALUE: &str = "config_issue_value";
+    pub const SKY_MAP: &str = "sky_map";
+    pub const CONSOLE_OUTPUT: &str = "console_output";
+    pub const CONSOLE_COMMAND: &str = "console_command";
 }
 
 #[macro_export]
@@ -110,17 +204,73 @@ pub struct TuiData {
 pub struct Texts {
     pub controller_name: TextContent,
     pub controller_event: TextContent,
+    /// Raw and processed value of controller axis assigned to mount axis 1.
+    pub axis1_input: TextContent,
+    /// Raw and processed value of controller axis assigned to mount axis 2.
+    pub axis2_input: TextContent,
+    pub target_source: TextContent,
+    /// Connection status of network data source.
+    pub target_source_status: TextContent,
+    /// Identifier and latency of target data (if provided by the data source).
+    pub target_id: TextContent,
+    /// Indication of stale target data.
+    pub target_stale: TextContent,
+    /// Indication of raised target alarms.
+    pub target_alarm: TextContent,
     pub target_dist: TextContent,
     pub target_spd: TextContent,
     pub target_az: TextContent,
     pub target_alt: TextContent,
+    /// Right ascension of the target (of date).
+    pub target_ra: TextContent,
+    /// Declination of the target (of date).
+    pub target_dec: TextContent,
     pub mount_name: TextContent,
+    /// Unfiltered speed of mount axes.
+    pub mount_raw_spd: TextContent,
+    /// Position of the secondary mount.
+    pub mount2_info: TextContent,
+    /// Mount(s) controlled by slewing and tracking.
+    pub mount_routing: TextContent,
     pub mount_az: TextContent,
     pub mount_alt: TextContent,
+    /// Hour angle of the mount's pointing direction (requires observer location).
+    pub mount_ha: TextContent,
+    /// Declination (of date) of the mount's pointing direction (requires observer location).
+    pub mount_dec: TextContent,
     pub mount_total_az_travel: TextContent,
     pub mount_total_alt_travel: TextContent,
+    /// Remaining travel of mount axes before the max travel is exceeded.
+    pub mount_travel_budget: TextContent,
+    /// Lifetime axis travel of the mount.
+    pub mount_odometer: TextContent,
+    /// Accumulated axis drift (if monitored).
+    pub mount_drift: TextContent,
+    /// Condition reported by the mount driver (e.g., reached altitude limit).
+    pub mount_warning: TextContent,
     pub tracking_state: TextContent,
+    /// Axis speeds calculated by tracking.
+    pub tracking_speeds: TextContent,
+    /// Whether the target is inside the camera field of view.
+    pub tracking_fov: TextContent,
     pub slew_speed: TextContent,
+    /// Max. speed of manual adjustments during tracking.
+    pub adjustment_speed: TextContent,
+    /// Marks the speed (slew or adjustment) currently changed by PageUp/PageDown.
+    pub slew_speed_marker: TextContent,
+    pub adjustment_speed_marker: TextContent,
+    /// Offsets added to the tracking speeds of both axes.
+    pub tracking_trim: TextContent,
+    /// Offset of the system clock relative to NTP or GPS time.
+    pub clock_offset: TextContent,
+    /// Direction and angle to push the mount in azimuth (push-to mode).
+    pub push_to_az: TextContent,
+    /// Direction and angle to push the mount in altitude (push-to mode).
+    pub push_to_alt: TextContent,
+    /// Angular distance between mount and target (push-to mode).
+    pub push_to_distance: TextContent,
+    /// Connection indicators and current time (top line of the screen).
+    pub status_bar: TextContent
 }
 
 struct CommandBarBuilder {
@@ -134,8 +284,8 @@ impl CommandBarBuilder {
             highlight: theme::Style{
                 effects: enumset::EnumSet::from(theme::Effect::Simple),
                 color: theme::ColorStyle{
-                    front: theme::ColorType::Color(theme::Color::Rgb(0, 0, 0)),
-                    back: theme::ColorType::Color(theme::Color::Rgb(200, 200, 200)),
+                    front: theme::ColorType::Palette(theme::PaletteColor::Shadow),
+                    back: theme::ColorType::Palette(theme::PaletteColor::TitleSecondary),
                 }
             },
             contents: cursive::utils::span::SpannedString::new(),
@@ -204,8 +354,8 @@ impl Notify for Refresher {
 // once it lands in cursive >0.20.0
 pub fn styled_edit_view() -> EditView {
     EditView::new().style(theme::ColorStyle{
-        front: theme::ColorType::Color(theme::Color::Rgb(50, 50, 200)),
-        back: theme::ColorType::Color(theme::Color::Rgb(230, 230, 230))
+        front: theme::ColorType::Palette(theme::PaletteColor::Secondary),
+        back: theme::ColorType::Palette(theme::PaletteColor::Tertiary)
     })
 }
 
@@ -224,52 +374,299 @@ pub fn get_select_view_idx(curs: &mut cursive::Cursive, name: &str) -> usize {
 pub fn init(state: &mut ProgramState) {
     let curs = &mut state.cursive_stepper.curs;
 
-	curs.add_global_callback('q', |c| { c.quit(); });
+    curs.add_global_callback('q', cclone!([
+            @weak (state.mount_spd) as mount_spd,
+            (state.tracking.controller()) as tracking
+        ], move |curs| {
+            upgrade!(mount_spd);
+            // the shutdown sequence (see `shutdown::shutdown`) runs after the event loop finishes
+            if shutdown::mount_moving(&mount_spd.borrow(), &tracking) {
+                confirm_quit(curs);
+            } else {
+                curs.quit();
+            }
+        }
+    ));
+
+    curs.add_global_callback('s', cclone!([
+            @weak (state.mount) as mount,
+            @weak (state.mount2) as mount2,
+            (state.tracking.controller()) as tracking
+        ], move |_| {
+            upgrade!(mount, mount2);
+            event_handling::on_stop_mount(&[&mount, &mount2], &tracking);
+        }
+    ));
+
+    curs.add_global_callback('k', cclone!([
+            @weak (state.mount_routing) as mount_routing,
+            @weak (state.tui) as tui
+        ], move |_| {
+            upgrade!(mount_routing, tui);
+            let routing = mount_routing.get().next();
+            mount_routing.set(routing);
+            log::info!("controlling mount(s): {}", routing);
+            tui!(tui).text_content.mount_routing.set_content(routing.to_string());
+        }
+    ));
+
+    curs.add_global_callback('h', cclone!([
+            @weak (state.mount) as mount,
+            @weak (state.mount2) as mount2,
+            @weak (state.mount_routing) as mount_routing,
+            @weak (state.tui) as tui,
+            (state.tracking.controller()) as tracking
+        ], move |curs| {
+            upgrade!(mount, mount2, mount_routing, tui);
+            if let Err(e) = event_handling::on_toggle_park(&mount, &mount2, mount_routing.get(), &tracking, &tui) {
+                msg_box(curs, &format!("Failed to park/unpark mount:\n{}.", e), "Error");
+            }
+        }
+    ));
+
+    curs.add_global_callback('t', cclone!([
+        (state.tracking.controller()) as tracking,
+        @weak (state.target) as target,
+        @weak (state.observer) as observer,
+        @weak (state.sun_avoidance) as sun_avoidance
+    ], move |curs| {
+        upgrade!(target, observer, sun_avoidance);
+        event_handling::on_toggle_tracking(curs, &tracking, &target, &observer, &sun_avoidance);
+    }));
 
-    curs.add_global_callback('s', cclone!([@weak (state.mount) as mount, (state.tracking.controller()) as tracking],
+    curs.add_global_callback(event::Event::Key(event::Key::F6), cclone!([(state.tracking.controller()) as tracking],
         move |_| {
-            let mount = mount.upgrade().unwrap();
-            event_handling::on_stop_mount(&mount, &tracking);
+            event_handling::on_toggle_pause(&tracking);
         }
     ));
 
-    curs.add_global_callback('t', cclone!([(state.tracking.controller()) as tracking], move |_| {
-        event_handling::on_toggle_tracking(&tracking);
+    curs.add_global_callback('y', cclone!([(state.tracking.controller()) as tracking], move |_| {
+        tracking.set_dry_run(!tracking.is_dry_run());
     }));
 
     curs.add_global_callback('d', cclone!([
         @weak (state.tui) as tui,
         (state.data_receiver.connection()) as connection,
-        @weak (state.config) as config
+        @weak (state.config) as config,
+        @weak (state.target_source) as target_source
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                data_source_dialog::dialog, curs, tui, connection.clone(), config.clone(), target_source.clone()
+            );
+        }
+    ));
+
+    curs.add_global_callback('b', cclone!([
+        @weak (state.tui) as tui,
+        (state.data_receiver.connection()) as connection,
+        @weak (state.config) as config,
+        @weak (state.target_source) as target_source
         ], move |curs| {
-            show_dlg_on_global_callback!(data_source_dialog::dialog, curs, tui, connection.clone(), config.clone());
+            show_dlg_on_global_callback!(
+                ephemeris_dialog::dialog, curs, tui, connection.clone(), config.clone(), target_source.clone()
+            );
         }
     ));
 
+    curs.add_global_callback('e', cclone!([
+        @weak (state.tui) as tui,
+        (state.data_receiver.connection()) as connection,
+        @weak (state.config) as config,
+        @weak (state.target_source) as target_source
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                satellite_dialog::dialog, curs, tui, connection.clone(), config.clone(), target_source.clone()
+            );
+        }
+    ));
+
+    curs.add_global_callback('u', cclone!([
+        @weak (state.tui) as tui,
+        (state.data_receiver.connection()) as connection,
+        @weak (state.config) as config,
+        @weak (state.target_source) as target_source,
+        @weak (state.target_queue) as target_queue
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                target_queue_dialog::dialog,
+                curs,
+                tui,
+                connection.clone(),
+                config.clone(),
+                target_source.clone(),
+                target_queue.clone()
+            );
+        }
+    ));
+
+    for (key, forward) in [(']', true), ('[', false)] {
+        curs.add_global_callback(key, cclone!([
+            @weak (state.tui) as tui,
+            (state.data_receiver.connection()) as connection,
+            @weak (state.target_source) as target_source,
+            @weak (state.target_queue) as target_queue
+            ], move |_| {
+                upgrade!(tui, target_source, target_queue);
+                if tui!(tui).showing_dialog { return; }
+                event_handling::on_queue_step(forward, &target_queue, &connection, &target_source, &tui);
+            }
+        ));
+    }
+
     curs.add_global_callback('m', cclone!([
         @weak (state.tui) as tui,
         @weak (state.mount) as mount,
+        @weak (state.mount2) as mount2,
+        @weak (state.config) as config,
+        (state.tracking.controller()) as tracking
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                mount_dialog::dialog, curs, tui, mount.clone(), mount2.clone(), config.clone(), tracking.clone()
+            );
+        }
+    ));
+
+    curs.add_global_callback('w', cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.mount) as mount,
+        @weak (state.config) as config,
+        @weak (state.observer) as observer,
+        (state.tracking.controller()) as tracking
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                alignment_wizard::dialog, curs, tui, mount.clone(), config.clone(), observer.clone(), tracking.clone()
+            );
+        }
+    ));
+
+    curs.add_global_callback('f', cclone!([
+        @weak (state.tui) as tui,
+        (state.data_receiver.connection()) as connection,
         @weak (state.config) as config,
+        @weak (state.target_source) as target_source,
         (state.tracking.controller()) as tracking
         ], move |curs| {
-            show_dlg_on_global_callback!(mount_dialog::dialog, curs, tui, mount.clone(), config.clone(), tracking.clone());
+            show_dlg_on_global_callback!(
+                adjustments_dialog::dialog,
+                curs,
+                tui,
+                connection.clone(),
+                config.clone(),
+                target_source.clone(),
+                tracking.clone()
+            );
+        }
+    ));
+
+    curs.add_global_callback('n', cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.mount) as mount,
+        @weak (state.mount2) as mount2
+        ], move |curs| {
+            show_dlg_on_global_callback!(mount_details_dialog::dialog, curs, tui, mount.clone(), mount2.clone());
         }
     ));
 
     curs.add_global_callback('r', cclone!([
         @weak (state.tui) as tui,
         @weak (state.mount) as mount,
-        @weak (state.config) as config
+        @weak (state.mount2) as mount2,
+        @weak (state.mount_routing) as mount_routing,
+        @weak (state.config) as config,
+        @weak (state.observer) as observer
         ], move |curs| {
+            let mount = mount_routing.upgrade().unwrap().get().selected(&mount, &mount2).clone();
             if mount.upgrade().unwrap().borrow().is_none() {
                 msg_box(curs, "Not connected to a mount.", "Error");
             } else {
-                show_dlg_on_global_callback!(ref_pos_dialog::dialog, curs, tui.clone(), mount.clone(), config.clone());
+                show_dlg_on_global_callback!(
+                    ref_pos_dialog::dialog, curs, tui.clone(), mount.clone(), config.clone(), observer.clone()
+                );
             }
         }
     ));
 
-    curs.add_global_callback('z', cclone!([@weak (state.tui) as tui, @weak (state.mount) as mount], move |curs| {
+    curs.add_global_callback('x', cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.mount) as mount,
+        @weak (state.mount2) as mount2,
+        @weak (state.mount_routing) as mount_routing
+        ], move |curs| {
+            let mount = mount_routing.upgrade().unwrap().get().selected(&mount, &mount2).clone();
+            if mount.upgrade().unwrap().borrow().is_none() {
+                msg_box(curs, "Not connected to a mount.", "Error");
+            } else {
+                show_dlg_on_global_callback!(console_dialog::dialog, curs, tui.clone(), mount.clone());
+            }
+        }
+    ));
+
+    curs.add_global_callback('o', cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.observer) as observer,
+        @weak (state.config) as config,
+        @weak (state.gps) as gps
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                observer_dialog::dialog, curs, tui.clone(), observer.clone(), config.clone(), gps.clone()
+            );
+        }
+    ));
+
+    curs.add_global_callback(event::Event::Key(event::Key::F2), cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.config) as config,
+        @weak (state.observer) as observer,
+        @weak (state.ctrl_actions) as ctrl_actions,
+        @weak (state.axes_response) as axes_response
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                profile_dialog::dialog,
+                curs,
+                tui.clone(),
+                config.clone(),
+                observer.clone(),
+                ctrl_actions.clone(),
+                axes_response.clone()
+            );
+        }
+    ));
+
+    curs.add_global_callback(event::Event::Key(event::Key::F3), cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.mount) as mount,
+        @weak (state.rate_calibration) as rate_calibration,
+        @weak (state.config) as config,
+        (state.tracking.controller()) as tracking
+    ], move |curs| {
+        if mount.upgrade().unwrap().borrow().is_none() {
+            msg_box(curs, "Not connected to a mount.", "Error");
+        } else {
+            show_dlg_on_global_callback!(
+                rate_calibration_dialog::dialog,
+                curs,
+                tui.clone(),
+                mount.clone(),
+                rate_calibration.clone(),
+                config.clone(),
+                tracking.clone()
+            );
+        }
+    }));
+
+    curs.add_global_callback('p', cclone!([@weak (state.tui) as tui, (state.tracking.controller()) as tracking],
+        move |curs| {
+            show_dlg_on_global_callback!(stats_dialog::dialog, curs, tui.clone(), tracking.clone());
+        }
+    ));
+
+    curs.add_global_callback('z', cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.mount) as mount,
+        @weak (state.mount2) as mount2,
+        @weak (state.mount_routing) as mount_routing
+    ], move |curs| {
+        let mount = mount_routing.upgrade().unwrap().get().selected(&mount, &mount2).clone();
         if mount.upgrade().unwrap().borrow().is_none() {
             msg_box(curs, "Not connected to a mount.", "Error");
         } else {
@@ -277,9 +674,179 @@ pub fn init(state: &mut ProgramState) {
         }
     }));
 
+    curs.add_global_callback(event::Event::Key(event::Key::F5), cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.mount) as mount,
+        @weak (state.mount2) as mount2,
+        @weak (state.mount_routing) as mount_routing,
+        (state.tracking.controller()) as tracking
+    ], move |curs| {
+        let mount = mount_routing.upgrade().unwrap().get().selected(&mount, &mount2).clone();
+        if mount.upgrade().unwrap().borrow().is_none() {
+            msg_box(curs, "Not connected to a mount.", "Error");
+        } else {
+            show_dlg_on_global_callback!(nudge_dialog::dialog, curs, tui.clone(), mount.clone(), tracking.clone());
+        }
+    }));
+
+    curs.add_global_callback(event::Event::Key(event::Key::F7), cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.mount) as mount,
+        @weak (state.mount2) as mount2,
+        @weak (state.mount_routing) as mount_routing,
+        (state.data_receiver.connection()) as connection,
+        @weak (state.config) as config,
+        @weak (state.target_source) as target_source
+    ], move |curs| {
+        let mount = mount_routing.upgrade().unwrap().get().selected(&mount, &mount2).clone();
+        show_dlg_on_global_callback!(
+            fixed_target_dialog::dialog,
+            curs,
+            tui.clone(),
+            mount,
+            connection.clone(),
+            config.clone(),
+            target_source.clone()
+        );
+    }));
+
+    curs.add_global_callback(event::Event::Key(event::Key::F8), cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.latency) as latency
+    ], move |curs| {
+        show_dlg_on_global_callback!(latency_dialog::dialog, curs, tui.clone(), latency.clone());
+    }));
+
+    curs.add_global_callback('j', cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.axes_response) as axes_response,
+        @weak (state.config) as config
+        ], move |curs| {
+            show_dlg_on_global_callback!(axis_response_dialog::dialog, curs, tui.clone(), axes_response.clone(), config.clone());
+        }
+    ));
+
+    curs.add_global_callback('c', cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.ctrl_actions) as ctrl_actions,
+        @weak (state.axes_response) as axes_response,
+        @weak (state.ctrl_action_capture) as capture,
+        @weak (state.config) as config,
+        @weak (state.connected_controllers) as connected
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                controller_dialog::dialog,
+                curs,
+                tui.clone(),
+                ctrl_actions.clone(),
+                axes_response.clone(),
+                capture.clone(),
+                config.clone(),
+                connected.clone()
+            );
+        }
+    ));
+
+    for (key, action) in [
+        (event::Key::Right, TargetAction::MountAxis1Pos),
+        (event::Key::Left, TargetAction::MountAxis1Neg),
+        (event::Key::Up, TargetAction::MountAxis2Pos),
+        (event::Key::Down, TargetAction::MountAxis2Neg)
+    ] {
+        curs.add_global_callback(event::Event::Key(key), cclone!([
+            @weak (state.tui) as tui,
+            @weak (state.keyboard_slewing) as keyboard_slewing
+            ], move |_| {
+                upgrade!(tui, keyboard_slewing);
+                if tui!(tui).showing_dialog { return; }
+                keyboard_slewing.borrow_mut().on_key(action);
+            }
+        ));
+    }
+
+    for (key, axis, positive) in [
+        (event::Key::Right, Axis::Primary, true),
+        (event::Key::Left, Axis::Primary, false),
+        (event::Key::Up, Axis::Secondary, true),
+        (event::Key::Down, Axis::Secondary, false)
+    ] {
+        curs.add_global_callback(event::Event::Shift(key), cclone!([
+            @weak (state.tui) as tui,
+            @weak (state.config) as config,
+            (state.tracking.controller()) as tracking
+            ], move |_| {
+                upgrade!(tui, config);
+                if tui!(tui).showing_dialog { return; }
+                event_handling::on_trim(&tracking, &config.borrow(), axis, positive);
+            }
+        ));
+    }
+
+    curs.add_global_callback('0', cclone!([@weak (state.tui) as tui, (state.tracking.controller()) as tracking],
+        move |_| {
+            upgrade!(tui);
+            if tui!(tui).showing_dialog { return; }
+            tracking.reset_trim();
+        }
+    ));
+
+    curs.add_global_callback('g', cclone!([@weak (state.tui) as tui, @weak (state.pos_history) as pos_history],
+        move |curs| {
+            show_dlg_on_global_callback!(trend_dialog::dialog, curs, tui.clone(), pos_history.clone());
+        }
+    ));
+
+    curs.add_global_callback('i', cclone!([@weak (state.tui) as tui, @weak (state.pos_history) as pos_history],
+        move |curs| {
+            show_dlg_on_global_callback!(compass_dialog::dialog, curs, tui.clone(), pos_history.clone());
+        }
+    ));
+
+    curs.add_global_callback(event::Event::Key(event::Key::F9), cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.pos_history) as pos_history,
+        (state.data_receiver.connection()) as connection,
+        @weak (state.config) as config,
+        @weak (state.target_source) as target_source,
+        @weak (state.target_queue) as target_queue
+    ], move |curs| {
+        show_dlg_on_global_callback!(
+            sky_map_dialog::dialog,
+            curs,
+            tui.clone(),
+            pos_history.clone(),
+            connection.clone(),
+            config.clone(),
+            target_source.clone(),
+            target_queue.clone()
+        );
+    }));
+
+    curs.add_global_callback(event::Event::Key(event::Key::F10), cclone!([
+            @weak (state.mount) as mount,
+            @weak (state.mount2) as mount2,
+            @weak (state.mount_routing) as mount_routing,
+            @weak (state.config) as config,
+            @weak (state.tui) as tui
+        ], move |curs| {
+            upgrade!(mount, mount2, mount_routing, config, tui);
+            if let Err(e) = event_handling::on_toggle_push_to(&mount, &mount2, mount_routing.get(), &config, &tui) {
+                msg_box(curs, &format!("Failed to toggle push-to mode:\n{}.", e), "Error");
+            }
+        }
+    ));
+
+    curs.add_global_callback('l', cclone!([@weak (state.tui) as tui, @weak (state.config) as config],
+        move |curs| {
+            show_dlg_on_global_callback!(log_dialog::dialog, curs, tui.clone(), config.clone());
+        }
+    ));
+
     curs.add_global_callback(event::Event::Key(event::Key::PageUp), cclone!([
             @weak (state.slew_speed) as slew_speed,
             @weak (state.tui) as tui,
+            @weak (state.config) as config,
+            @weak (state.mount) as mount,
             (state.tracking.controller()) as tracking,
             (state.refresher.request()) as refresh_req
         ], move |_| {
@@ -287,6 +854,8 @@ pub fn init(state: &mut ProgramState) {
                 SLEW_SPEED_CHANGE_FACTOR,
                 slew_speed.clone(),
                 tui.clone(),
+                config.clone(),
+                mount.clone(),
                 &tracking,
                 refresh_req.clone()
             );
@@ -296,6 +865,8 @@ pub fn init(state: &mut ProgramState) {
     curs.add_global_callback(event::Event::Key(event::Key::PageDown), cclone!([
             @weak (state.slew_speed) as slew_speed,
             @weak (state.tui) as tui,
+            @weak (state.config) as config,
+            @weak (state.mount) as mount,
             (state.tracking.controller()) as tracking,
             (state.refresher.request()) as refresh_req
         ], move |_| {
@@ -303,20 +874,67 @@ pub fn init(state: &mut ProgramState) {
                 1.0 / SLEW_SPEED_CHANGE_FACTOR,
                 slew_speed.clone(),
                 tui.clone(),
+                config.clone(),
+                mount.clone(),
                 &tracking,
                 refresh_req.clone()
             );
         }
     ));
 
+    for preset_idx in 0..9 {
+        curs.add_global_callback(char::from_digit(preset_idx as u32 + 1, 10).unwrap(), cclone!([
+                @weak (state.slew_speed) as slew_speed,
+                @weak (state.tui) as tui,
+                @weak (state.config) as config,
+                @weak (state.mount) as mount,
+                (state.refresher.request()) as refresh_req
+            ], move |_| {
+                upgrade!(slew_speed, tui, config, mount);
+                if tui!(tui).showing_dialog { return; }
+                let preset = config.borrow().slew_speed_presets().get(preset_idx).copied();
+                if let Some(preset) = preset {
+                    event_handling::set_slew_speed(preset, &slew_speed, &tui, &config, &mount);
+                    refresh_req.upgrade().unwrap().borrow_mut().refresh();
+                }
+            }
+        ));
+    }
+
+    curs.add_global_callback('v', cclone!([
+        @weak (state.tui) as tui,
+        @weak (state.slew_speed) as slew_speed,
+        @weak (state.config) as config,
+        @weak (state.mount) as mount
+        ], move |curs| {
+            show_dlg_on_global_callback!(
+                slew_speed_dialog::dialog, curs, tui.clone(), slew_speed.clone(), config.clone(), mount.clone()
+            );
+        }
+    ));
+
     curs.add_global_callback('a', cclone!([@weak (state.tui) as tui], move |curs| {
         show_dlg_on_global_callback!(about_dialog::dialog, curs, tui.clone(),);
     }));
 
-    let main_theme = create_main_theme(curs.current_theme());
-    curs.set_theme(main_theme);
+    curs.add_global_callback(event::Event::Key(event::Key::F4), cclone!([@weak (state.config) as config],
+        move |curs| {
+            upgrade!(config);
+            let scheme = config.borrow().color_scheme().next();
+            config.borrow_mut().set_color_scheme(scheme);
+            curs.set_theme(color_scheme::create_main_theme(scheme));
+            log::info!("color scheme: {}", scheme.name());
+        }
+    ));
 
-    let text_content = init_views(curs, *state.slew_speed.borrow());
+    curs.set_theme(color_scheme::create_main_theme(state.config.borrow().color_scheme()));
+
+    let text_content = init_views(
+        curs,
+        *state.slew_speed.borrow(),
+        state.tracking.controller().adjustment_slew_speed(),
+        &state.angle_display
+    );
     init_command_bar(curs);
 
     *state.tui.borrow_mut() = Some(TuiData{
@@ -335,16 +953,45 @@ fn init_command_bar(curs: &mut cursive::Cursive) {
                 CommandBarBuilder::new()
                     .command("T", "Toggle tracking")
                     .command("S", "Stop slewing")
+                    .command("H", "Park/unpark")
+                    .command("Y", "Dry run")
                     .command("D", "Data source")
+                    .command("E", "Satellite")
+                    .command("B", "Sun/Moon/planet")
+                    .command("U", "Target queue")
                     .command("M", "Mount")
+                    .command("N", "Mount details")
+                    .command("X", "Mount console")
+                    .command("K", "Controlled mount")
                     .command("R", "Ref. position")
+                    .command("W", "Align wizard")
+                    .command("F", "Saved adjustments")
+                    .command("O", "Observer")
+                    .command("F2", "Profile")
+                    .command("F3", "Rate calibration")
+                    .command("F4", "Colors")
+                    .command("F5", "Move by offset")
+                    .command("F6", "Pause tracking")
+                    .command("F7", "Fixed target")
+                    .command("F8", "Latency")
+                    .command("F9", "Sky map")
+                    .command("F10", "Push-to")
                     .command("Z", "Zero position")
+                    .command("J", "Joystick axes")
+                    .command("C", "Controller")
+                    .command("G", "Trend")
+                    .command("I", "Compass")
+                    .command("L", "Log")
+                    .command("P", "Statistics")
+                    .command("V", "Slew speed")
+                    .command("Shift+arrows", "Rate trim")
+                    .command("0", "Reset trim")
                     .command("Q", "Quit")
                     .command("A", "About")
                     .build()
             ),
             |layout, size| {
-                let rect = Rect::from_size((0, size.y - 2), (size.x, 2));
+                let rect = Rect::from_size((0, size.y - COMMAND_BAR_HEIGHT), (size.x, COMMAND_BAR_HEIGHT));
                 layout.set_child_position(0, rect);
                 layout.layout(size);
             },
@@ -353,36 +1000,72 @@ fn init_command_bar(curs: &mut cursive::Cursive) {
     );
 }
 
-fn init_views(curs: &mut cursive::Cursive, slew_speed: f64::AngularVelocity) -> Texts {
+/// Terminal width from which panels are shown side by side (otherwise they are stacked).
+const WIDE_LAYOUT_MIN_WIDTH: usize = 90;
+const LEFT_PANEL_MIN_WIDTH: usize = 44;
+/// Terminal height below which the Controller panel is hidden (in wide and narrow layout).
+const CONTROLLER_PANEL_MIN_HEIGHT: (usize, usize) = (18, 32);
+const COMMAND_BAR_HEIGHT: usize = 2;
+
+type ControllerPanel = HideableView<ResizedView<Layer<Panel<LinearLayout>>>>;
+type PushToPanel = HideableView<ResizedView<Layer<Panel<LinearLayout>>>>;
+
+/// Shows or hides the Push-to panel (guidance for moving the mount by hand).
+pub fn set_push_to_panel_visible(curs: &mut cursive::Cursive, visible: bool) {
+    curs.call_on_name(names::PUSH_TO_PANEL, |panel: &mut PushToPanel| {
+        if panel.is_visible() != visible { panel.set_visible(visible); }
+    });
+}
+
+fn init_views(
+    curs: &mut cursive::Cursive,
+    slew_speed: f64::AngularVelocity,
+    adjustment_speed: f64::AngularVelocity,
+    angle_display: &data::AngleDisplay
+) -> Texts {
     // ---------------------------------
     // Status
     //
     let tracking_state = TextContent::new("disabled");
-    let slew_speed = TextContent::new(format!("{:.2}°/s", data::as_deg_per_s(slew_speed)));
-    curs.screen_mut().add_layer_at(
-        Position::new(Offset::Absolute(1), Offset::Absolute(8)),
-        Panel::new(LinearLayout::vertical()
-            .child(label_and_content("Tracking: ", tracking_state.clone()))
-            .child(label_and_content("Slew speed: ", slew_speed.clone()))
+    let tracking_speeds = TextContent::new("");
+    let tracking_fov = TextContent::new("");
+    let mount_routing = TextContent::new(crate::mount::MountRouting::default().to_string());
+    let slew_speed = TextContent::new(angle_display.speed(slew_speed, 2));
+    let adjustment_speed = TextContent::new(angle_display.speed(adjustment_speed, 3));
+    let slew_speed_marker = TextContent::new(SPEED_KEYS_MARKER);
+    let adjustment_speed_marker = TextContent::new("");
+    let tracking_trim = TextContent::new("none");
+    let clock_offset = TextContent::new("(unknown)");
+    let status_panel = Panel::new(LinearLayout::vertical()
+        .child(label_and_content("Tracking: ", tracking_state.clone()))
+        .child(label_and_content("Axis speeds: ", tracking_speeds.clone()))
+        .child(label_and_content("Camera FOV: ", tracking_fov.clone()))
+        .child(label_and_content("Slew speed: ", slew_speed.clone())
+            .child(TextView::new_with_content(slew_speed_marker.clone()))
         )
-        .title("Status")
-        .title_position(HAlign::Left)
-    );
+        .child(label_and_content("Adj. speed: ", adjustment_speed.clone())
+            .child(TextView::new_with_content(adjustment_speed_marker.clone()))
+        )
+        .child(label_and_content("Rate trim: ", tracking_trim.clone()))
+        .child(label_and_content("Controlled mount: ", mount_routing.clone()))
+        .child(label_and_content("Clock offset: ", clock_offset.clone()))
+    )
+    .title("Status")
+    .title_position(HAlign::Left);
 
     // ---------------------------------
     // Controller
     //
     let controller_name = TextContent::new("(disconnected)");
     let controller_event = TextContent::new("");
-    curs.screen_mut().add_layer_at(
-        Position::new(Offset::Absolute(45), Offset::Absolute(8)),
-        Panel::new(LinearLayout::vertical()
-            .child(TextView::new_with_content(controller_name.clone()))
-            .child(TextView::new_with_content(controller_event.clone()))
-        )
-        .title("Controller")
-        .title_position(HAlign::Left)
-    );
+    let axis1_input = TextContent::new("");
+    let axis2_input = TextContent::new("");
+    let controller_panel = Panel::new(LinearLayout::vertical()
+        .child(TextView::new_with_content(controller_name.clone()))
+        .child(TextView::new_with_content(controller_event.clone()))
+    )
+    .title("Controller")
+    .title_position(HAlign::Left);
 
     // ---------------------------------
     // Mount
@@ -390,70 +1073,194 @@ fn init_views(curs: &mut cursive::Cursive, slew_speed: f64::AngularVelocity) ->
     let mount_name = TextContent::new("(disconnected)");
     let mount_az = TextContent::new("");
     let mount_alt = TextContent::new("");
+    let mount_ha = TextContent::new("");
+    let mount_dec = TextContent::new("");
     let mount_total_az_travel = TextContent::new("");
     let mount_total_alt_travel = TextContent::new("");
-    curs.screen_mut().add_layer_at(
-        Position::new(Offset::Absolute(45), Offset::Absolute(1)),
-        Panel::new(LinearLayout::vertical()
-            .child(TextView::new_with_content(mount_name.clone()))
-            .child(
-                LinearLayout::horizontal()
-                    .child(label_and_content("az. ", mount_az.clone()))
-                    .child(DummyView{}.min_width(2))
-                    .child(label_and_content("alt. ", mount_alt.clone()))
-            )
-            .child(
-                LinearLayout::horizontal()
-                    .child(TextView::new("total travel: "))
-                    .child(label_and_content("az. ", mount_total_az_travel.clone()))
-                    .child(DummyView{}.min_width(1))
-                    .child(label_and_content("alt. ", mount_total_alt_travel.clone()))
-            )
+    let mount_travel_budget = TextContent::new("");
+    let mount_odometer = TextContent::new("");
+    let mount2_info = TextContent::new("");
+    let mount_raw_spd = TextContent::new("");
+    let mount_drift = TextContent::new("");
+    let mount_warning = TextContent::new("");
+    let mount_panel = Panel::new(LinearLayout::vertical()
+        .child(TextView::new_with_content(mount_name.clone()))
+        .child(
+            LinearLayout::horizontal()
+                .child(label_and_content("az. ", mount_az.clone()))
+                .child(DummyView{}.min_width(2))
+                .child(label_and_content("alt. ", mount_alt.clone()))
         )
-        .title("Mount")
-        .title_position(HAlign::Left)
-    );
+        .child(
+            LinearLayout::horizontal()
+                .child(label_and_content("HA ", mount_ha.clone()))
+                .child(DummyView{}.min_width(2))
+                .child(label_and_content("Dec ", mount_dec.clone()))
+        )
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new("total travel: "))
+                .child(label_and_content("az. ", mount_total_az_travel.clone()))
+                .child(DummyView{}.min_width(1))
+                .child(label_and_content("alt. ", mount_total_alt_travel.clone()))
+        )
+        .child(label_and_content("travel left: ", mount_travel_budget.clone()))
+        .child(label_and_content("odometer: ", mount_odometer.clone()))
+        .child(LinearLayout::horizontal()
+            .child(label_and_content("raw spd.: ", mount_raw_spd.clone()))
+            .child(DummyView{}.min_width(2))
+            .child(TextView::new_with_content(mount_drift.clone()))
+        )
+        .child(TextView::new_with_content(mount_warning.clone()))
+        .child(TextView::new_with_content(mount2_info.clone()))
+    )
+    .title("Mount")
+    .title_position(HAlign::Left);
 
     // ---------------------------------
     // Target
     //
+    let target_source = TextContent::new("(none)");
+    let target_source_status = TextContent::new("");
+    let target_id = TextContent::new("");
+    let target_stale = TextContent::new("");
+    let target_alarm = TextContent::new("");
     let target_dist = TextContent::new("");
     let target_spd = TextContent::new("");
     let target_az = TextContent::new("");
     let target_alt = TextContent::new("");
-    curs.screen_mut().add_layer_at(
-        Position::new(Offset::Absolute(1), Offset::Absolute(1)),
-        Panel::new(LinearLayout::vertical()
-            .child(
-                LinearLayout::horizontal()
-                    .child(label_and_content("dist. ", target_dist.clone()))
-                    .child(DummyView{}.min_width(1))
-                    .child(label_and_content("spd. ", target_spd.clone()))
-            )
-            .child(label_and_content("az. ", target_az.clone()))
-            .child(label_and_content("alt. ", target_alt.clone()))
+    let target_ra = TextContent::new("");
+    let target_dec = TextContent::new("");
+    let target_panel = Panel::new(LinearLayout::vertical()
+        .child(label_and_content("src. ", target_source.clone()))
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new_with_content(target_source_status.clone()))
+                .child(DummyView{}.min_width(1))
+                .child(TextView::new_with_content(target_id.clone()))
+                .child(DummyView{}.min_width(1))
+                .child(TextView::new_with_content(target_stale.clone()))
+                .child(DummyView{}.min_width(1))
+                .child(TextView::new_with_content(target_alarm.clone()))
         )
-        .title("Target")
-        .title_position(HAlign::Left)
+        .child(
+            LinearLayout::horizontal()
+                .child(label_and_content("dist. ", target_dist.clone()))
+                .child(DummyView{}.min_width(1))
+                .child(label_and_content("spd. ", target_spd.clone()))
+        )
+        .child(
+            LinearLayout::horizontal()
+                .child(label_and_content("az. ", target_az.clone()).min_width(22))
+                .child(label_and_content("RA ", target_ra.clone()))
+        )
+        .child(
+            LinearLayout::horizontal()
+                .child(label_and_content("alt. ", target_alt.clone()).min_width(22))
+                .child(label_and_content("Dec ", target_dec.clone()))
+        )
+    )
+    .title("Target")
+    .title_position(HAlign::Left);
+
+    // ---------------------------------
+    // Push-to
+    //
+    let push_to_az = TextContent::new("");
+    let push_to_alt = TextContent::new("");
+    let push_to_distance = TextContent::new("(no target)");
+    let push_to_panel = Panel::new(LinearLayout::horizontal()
+        .child(label_and_content("az. ", push_to_az.clone()).min_width(16))
+        .child(label_and_content("alt. ", push_to_alt.clone()).min_width(16))
+        .child(label_and_content("distance: ", push_to_distance.clone()))
+    )
+    .title("Push-to")
+    .title_position(HAlign::Left);
+
+    let main_view = OnLayoutView::new(
+        LinearLayout::vertical()
+            .child(LinearLayout::horizontal()
+                .child(Layer::new(target_panel).min_width(LEFT_PANEL_MIN_WIDTH))
+                .child(Layer::new(mount_panel).full_width())
+                .with_name(names::MAIN_ROW_TOP)
+            )
+            .child(LinearLayout::horizontal()
+                .child(Layer::new(status_panel).min_width(LEFT_PANEL_MIN_WIDTH))
+                .child(HideableView::new(Layer::new(controller_panel).full_width()).with_name(names::CONTROLLER_PANEL))
+                .with_name(names::MAIN_ROW_BOTTOM)
+            )
+            .child(HideableView::new(Layer::new(push_to_panel).full_width()).hidden().with_name(names::PUSH_TO_PANEL)),
+        |layout, size| {
+            // panels are shown side by side on wide terminals and stacked on narrow ones
+            let wide = size.x >= WIDE_LAYOUT_MIN_WIDTH;
+            let orientation = if wide { Orientation::Horizontal } else { Orientation::Vertical };
+            for row in [names::MAIN_ROW_TOP, names::MAIN_ROW_BOTTOM] {
+                layout.call_on_name(row, |row: &mut LinearLayout| row.set_orientation(orientation));
+            }
+            let min_height = if wide { CONTROLLER_PANEL_MIN_HEIGHT.0 } else { CONTROLLER_PANEL_MIN_HEIGHT.1 };
+            layout.call_on_name(names::CONTROLLER_PANEL, |panel: &mut ControllerPanel| {
+                panel.set_visible(size.y >= min_height)
+            });
+            layout.layout(size);
+        }
+    );
+    let status_bar = TextContent::new("");
+    curs.screen_mut().add_transparent_layer(
+        LinearLayout::vertical()
+            .child(TextView::new_with_content(status_bar.clone()).no_wrap())
+            .child(PaddedView::lrtb(1, 1, 0, COMMAND_BAR_HEIGHT, main_view))
+            .full_screen()
     );
 
     Texts{
         controller_name,
         controller_event,
+        axis1_input,
+        axis2_input,
+        target_source,
+        target_source_status,
+        target_id,
+        target_stale,
+        target_alarm,
         target_dist,
         target_spd,
         target_az,
         target_alt,
+        target_ra,
+        target_dec,
         mount_name,
+        mount2_info,
+        mount_raw_spd,
+        mount_routing,
         mount_az,
         mount_alt,
+        mount_ha,
+        mount_dec,
         mount_total_az_travel,
         mount_total_alt_travel,
+        mount_travel_budget,
+        mount_odometer,
+        mount_drift,
+        mount_warning,
         tracking_state,
-        slew_speed
+        tracking_speeds,
+        tracking_fov,
+        slew_speed,
+        adjustment_speed,
+        slew_speed_marker,
+        adjustment_speed_marker,
+        tracking_trim,
+        clock_offset,
+        push_to_az,
+        push_to_alt,
+        push_to_distance,
+        status_bar
     }
 }
 
+/// Shown next to the speed changed by PageUp/PageDown.
+pub const SPEED_KEYS_MARKER: &str = "  [PgUp/PgDn]";
+
 fn label_and_content(label: &str, content: TextContent) -> LinearLayout {
     LinearLayout::horizontal()
         .child(TextView::new(label))
@@ -461,26 +1268,13 @@ fn label_and_content(label: &str, content: TextContent) -> LinearLayout {
             .style(theme::Style{
                 effects: enumset::EnumSet::from(theme::Effect::Simple),
                 color: theme::ColorStyle{
-                    front: theme::ColorType::Color(theme::Color::Rgb(255, 255, 255)),
+                    front: theme::ColorType::Palette(theme::PaletteColor::TitlePrimary),
                     back: theme::ColorType::InheritParent
                 }
             })
         )
 }
 
-fn create_main_theme(base: &Theme) -> Theme {
-    let mut theme = base.clone();
-
-    theme.shadow = false;
-    theme.borders = theme::BorderStyle::None;
-    theme.palette[theme::PaletteColor::View] = theme::Color::Rgb(60, 60, 60);
-    theme.palette[theme::PaletteColor::Background] = theme::Color::Rgb(30, 30, 30);
-    theme.palette[theme::PaletteColor::TitlePrimary] = theme::Color::Rgb(255, 255, 255);
-    theme.palette[theme::PaletteColor::Primary] = theme::Color::Rgb(180, 180, 180);
-
-    theme
-}
-
 /// Simplifies passing weak references to closures. Instead of:
 ///
 ///   let r1 = Rc::new(1);
@@ -558,6 +1352,50 @@ pub fn msg_box(curs: &mut cursive::Cursive, text: &str, title: &str) {
     )));
 }
 
+fn confirm_quit(curs: &mut cursive::Cursive) {
+    let dt = create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dt,
+        Dialog::text("The mount is still moving; it will be stopped before quitting.\nQuit anyway?")
+            .title("Quit")
+            .button("Quit", |curs| curs.quit())
+            .dismiss_button("Cancel")
+    )));
+}
+
+/// Asks whether to start tracking a target within the exclusion zone around the Sun.
+pub fn confirm_sun_override(
+    curs: &mut cursive::Cursive,
+    sun_dist: f64::Angle,
+    tracking: TrackingController,
+    sun_avoidance: Weak<RefCell<SunAvoidance>>
+) {
+    let dt = create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dt,
+        Dialog::text(format!(
+            "The target is {:.1}° from the Sun.\nTrack anyway? The Sun exclusion zone will be disabled for {} min.",
+            data::as_deg(sun_dist),
+            sun_avoidance::OVERRIDE_DURATION.as_secs() / 60
+        ))
+            .title("Sun avoidance")
+            .button("Track", move |curs| {
+                curs.pop_layer();
+                upgrade!(sun_avoidance);
+                sun_avoidance.borrow_mut().set_override();
+                tracking.start();
+            })
+            .dismiss_button("Cancel")
+    )));
+}
+
+/// Shows the dialog with problems found in the configuration.
+pub fn show_config_issues(state: &mut ProgramState, issues: Vec<config::ConfigIssue>) {
+    let curs = &mut state.cursive_stepper.curs;
+    let tui = Rc::downgrade(&state.tui);
+    show_dlg_on_global_callback!(config_issues_dialog::dialog, curs, tui, Rc::downgrade(&state.config), issues);
+}
+
 fn create_dialog_theme(curs: &cursive::Cursive) -> theme::Theme {
     let mut theme = curs.current_theme().clone();
     theme.borders = theme::BorderStyle::Simple;
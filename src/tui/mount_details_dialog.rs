@@ -0,0 +1,77 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{mount::{Mount, MountWrapper}, tui::{close_dialog, names, TuiData}, upgrade};
+use cursive::{
+    event,
+    view::Nameable,
+    View,
+    views::{Dialog, LinearLayout, OnEventView, TextView},
+    With
+};
+use std::{cell::RefCell, rc::Weak};
+
+fn describe(mount: Option<&MountWrapper>) -> String {
+    match mount {
+        None => "(not connected)".into(),
+        Some(mount) => {
+            let mut result = mount.get_info();
+            let details = mount.details();
+            let width = details.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+            for (name, value) in &details {
+                result += &format!("\n  {:width$}  {}", name, value, width = width);
+            }
+            let diagnostics = mount.diagnostics();
+            if !diagnostics.is_empty() {
+                result += "\n  Diagnostics:";
+                let width = diagnostics.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+                for (name, value) in &diagnostics {
+                    result += &format!("\n    {:width$}  {}", name, value, width = width);
+                }
+            }
+            result
+        }
+    }
+}
+
+fn describe_all(mount: &Weak<RefCell<Option<MountWrapper>>>, mount2: &Weak<RefCell<Option<MountWrapper>>>) -> String {
+    let mut text = format!("Main mount: {}", describe(mount.upgrade().unwrap().borrow().as_ref()));
+    if let Some(mount2) = mount2.upgrade().unwrap().borrow().as_ref() {
+        text += &format!("\n\nSecondary mount: {}", describe(Some(mount2)));
+    }
+    text
+}
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    mount: Weak<RefCell<Option<MountWrapper>>>,
+    mount2: Weak<RefCell<Option<MountWrapper>>>
+) -> impl View {
+    Dialog::around(LinearLayout::vertical()
+        .child(TextView::new(describe_all(&mount, &mount2)).with_name(names::MOUNT_DETAILS))
+    )
+        .button("Refresh", move |curs| {
+            let text = describe_all(&mount, &mount2);
+            curs.call_on_name(names::MOUNT_DETAILS, |v: &mut TextView| v.set_content(text));
+        })
+        .button("OK", crate::cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+        .title("Mount details")
+        .wrap_with(OnEventView::new)
+        .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+            move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+        ))
+}
@@ -19,23 +19,30 @@
 use crate::{
     cclone,
     config::Configuration,
+    discovery,
+    event_handling,
     mount,
+    mount::MountType,
     tracking::TrackingController,
     tui,
     tui::{close_dialog, get_edit_view_str, msg_box, names, set_edit_view_str, TuiData},
     upgrade
 };
 use cursive::{
+    align::HAlign,
     event,
-    view::{Nameable, Resizable, View},
+    view::{Nameable, Resizable, Scrollable, View},
     views::{
+        Button,
         CircularFocus,
         Dialog,
         DummyView,
         EditView,
         LinearLayout,
         OnEventView,
+        Panel,
         RadioGroup,
+        SelectView,
         TextContent,
         TextView,
     },
@@ -43,62 +50,109 @@ use cursive::{
 };
 use std::{cell::RefCell, rc::{Rc, Weak}};
 
-#[derive(Copy, Clone)]
-enum MountType {
-    Simulator,
-    Ioptron
-}
+/// Returns available serial ports as (description, port name).
+fn serial_ports() -> Vec<(String, String)> {
+    match serialport::available_ports() {
+        Ok(ports) => ports.into_iter().map(|port| {
+            let descr = match &port.port_type {
+                serialport::SerialPortType::UsbPort(info) => match (&info.manufacturer, &info.product) {
+                    (_, Some(product)) => format!("{} ({})", port.port_name, product),
+                    (Some(manufacturer), None) => format!("{} ({})", port.port_name, manufacturer),
+                    (None, None) => format!("{} (USB)", port.port_name)
+                },
+                serialport::SerialPortType::BluetoothPort => format!("{} (Bluetooth)", port.port_name),
+                _ => port.port_name.clone()
+            };
+            (descr, port.port_name)
+        }).collect(),
 
-impl MountType {
-    fn connection_param_descr(&self) -> &'static str {
-        match self {
-            MountType::Simulator => "address and port:",
-            MountType::Ioptron => "Serial device (e.g., \"/dev/ttyUSB0\" on Linux\nor \"COM3\" on Windows):",
+        Err(e) => {
+            log::warn!("failed to enumerate serial ports: {}", e);
+            vec![]
         }
     }
 }
 
+fn fill_serial_port_list(curs: &mut cursive::Cursive) {
+    curs.call_on_name(names::SERIAL_PORT_LIST, |v: &mut SelectView<String>| {
+        v.clear();
+        v.add_all(serial_ports());
+    });
+}
+
 pub fn dialog(
     tui: Weak<RefCell<Option<TuiData>>>,
     mount: Weak<RefCell<Option<mount::MountWrapper>>>,
+    mount2: Weak<RefCell<Option<mount::MountWrapper>>>,
     config: Weak<RefCell<Configuration>>,
     tracking: TrackingController
 ) -> impl View {
-    let param_descr_content = TextContent::new(MountType::Simulator.connection_param_descr());
+    let mount_types = MountType::all();
+    let param_descr_content = TextContent::new(mount_types[0].backend().param_descr);
     let param_descr = TextView::new_with_content(param_descr_content.clone());
 
     let mut rb_group = RadioGroup::new()
         .on_change(cclone!([config], move |curs, mount_type: &MountType| {
             upgrade!(config);
-            param_descr_content.set_content(mount_type.connection_param_descr());
-            let prev_value = match mount_type {
-                MountType::Simulator => config.borrow().mount_simulator_addr(),
-                MountType::Ioptron => config.borrow().mount_ioptron_device()
-            }.unwrap_or("".into());
+            param_descr_content.set_content(mount_type.backend().param_descr);
+            let prev_value = config.borrow().mount_connection_param(*mount_type).unwrap_or("".into());
             set_edit_view_str(curs, names::MOUNT_CONNECTION, prev_value);
         }));
     let rb_group2 = rb_group.clone();
 
+    let mut rb_secondary = RadioGroup::<bool>::new();
+    let rb_secondary2 = rb_secondary.clone();
+
+    let mut mount_type_buttons = LinearLayout::vertical();
+    for (idx, mount_type) in mount_types.iter().enumerate() {
+        let button = rb_group.button(*mount_type, mount_type.backend().name);
+        mount_type_buttons.add_child(if idx == 0 { button.selected() } else { button });
+    }
+
     Dialog::around(
         LinearLayout::vertical()
-            .child(rb_group.button(MountType::Simulator, "Simulator").selected())
-            .child(rb_group.button(MountType::Ioptron, "iOptron"))
+            .child(mount_type_buttons)
+            .child(DummyView{})
+            .child(TextView::new("Connect as:"))
+            .child(rb_secondary.button(false, "main mount").selected())
+            .child(rb_secondary.button(true, "secondary mount"))
             .child(DummyView{})
             .child(param_descr)
             .child(tui::styled_edit_view()
-                .content(config.upgrade().unwrap().borrow().mount_simulator_addr().unwrap_or("".into()))
-                .on_submit(cclone!([tui, mount, config, tracking], move |curs, s| {
-                    upgrade!(tui, mount, config);
-                    on_connect_to_mount(curs, &tui, &mount, &config, *rb_group.selection(), s, tracking.clone());
+                .content(config.upgrade().unwrap().borrow().mount_connection_param(mount_types[0]).unwrap_or("".into()))
+                .on_submit(cclone!([tui, mount, mount2, config, tracking], move |curs, s| {
+                    upgrade!(tui, mount, mount2, config);
+                    let secondary = *rb_secondary.selection();
+                    let mount = if secondary { &mount2 } else { &mount };
+                    on_connect_to_mount(
+                        curs, &tui, mount, &config, *rb_group.selection(), s, tracking.clone(), secondary
+                    );
                 }))
                 .with_name(names::MOUNT_CONNECTION)
                 .fixed_width(20)
             )
+            .child(Button::new("Discover simulators", |curs| {
+                tui::discovery_dialog::show(curs, discovery::MOUNT_SIMULATOR_SERVICE, names::MOUNT_CONNECTION);
+            }))
+            .child(DummyView{})
+            .child(Panel::new(
+                SelectView::<String>::new()
+                    .with_all(serial_ports())
+                    .on_submit(|curs, port: &String| set_edit_view_str(curs, names::MOUNT_CONNECTION, port))
+                    .with_name(names::SERIAL_PORT_LIST)
+                    .scrollable()
+                    .fixed_height(4)
+            ).title("Serial ports").title_position(HAlign::Left))
+            .child(Button::new("Refresh", fill_serial_port_list))
     )
-    .button("OK", cclone!([tui, mount, config, tracking], move |curs| {
-        upgrade!(tui, mount, config);
+    .button("OK", cclone!([tui, mount, mount2, config, tracking], move |curs| {
+        upgrade!(tui, mount, mount2, config);
         let connection_param = get_edit_view_str(curs, names::MOUNT_CONNECTION);
-        on_connect_to_mount(curs, &tui, &mount, &config, *rb_group2.selection(), &connection_param, tracking.clone());
+        let secondary = *rb_secondary2.selection();
+        let mount = if secondary { &mount2 } else { &mount };
+        on_connect_to_mount(
+            curs, &tui, mount, &config, *rb_group2.selection(), &connection_param, tracking.clone(), secondary
+        );
     }))
     .button("Cancel",crate::cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
     .title("Connect to mount")
@@ -117,34 +171,14 @@ fn on_connect_to_mount(
     config: &Rc<RefCell<Configuration>>,
     mount_type: MountType,
     connection_param: &str,
-    tracking: TrackingController
+    tracking: TrackingController,
+    secondary: bool
 ) {
-    let result = match mount_type {
-        MountType::Simulator => mount::Simulator::new(connection_param),
-        MountType::Ioptron => mount::Ioptron::new(connection_param)
-    };
-
-    match result {
-        Ok(m) => {
-            log::info!("connected to {}", m.get_info());
-            tui!(tui).text_content.mount_name.set_content(m.get_info());
-            let mut wrapper = mount::MountWrapper::new(m);
-            wrapper.set_on_max_travel_exceeded(Box::new(cclone!(
-                [tracking],
-                move |mount, axis1, axis2| crate::event_handling::on_max_travel_exceeded(
-                    mount, axis1, axis2, tracking.clone()
-                )
-            )));
-            *mount.borrow_mut() = Some(wrapper);
-            match mount_type {
-                MountType::Simulator => config.borrow_mut().set_mount_simulator_addr(connection_param),
-                MountType::Ioptron => config.borrow_mut().set_mount_ioptron_device(connection_param)
-            }
+    match event_handling::connect_to_mount(mount_type, connection_param, mount, config, tracking, secondary) {
+        Ok(info) => {
+            if !secondary { tui!(tui).text_content.mount_name.set_content(info); }
             close_dialog(curs, tui);
         },
-        Err(e) => {
-            log::error!("error connecting to mount at \"{}\": {}", connection_param, e);
-            msg_box(curs, &format!("Failed to connect to mount: {}.", e), "Error");
-        }
+        Err(e) => msg_box(curs, &format!("Failed to connect to mount: {}.", e), "Error")
     }
 }
@@ -0,0 +1,117 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    data::deg,
+    mount::{Axis, Mount, MountWrapper},
+    tracking::TrackingController,
+    tui,
+    tui::{close_dialog, get_edit_view_str, msg_box, names, TuiData},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Nameable, Resizable, View},
+    views::{
+        CircularFocus,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        RadioGroup,
+        TextView,
+    },
+    With
+};
+use std::{cell::RefCell, rc::Weak};
+
+const MAX_OFFSET_DEG: f64 = 90.0;
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    mount: Weak<RefCell<Option<MountWrapper>>>,
+    tracking: TrackingController
+) -> impl View {
+    let mut rb_axis = RadioGroup::<Axis>::new();
+
+    Dialog::around(LinearLayout::vertical()
+        .child(LinearLayout::horizontal()
+            .child(rb_axis.button(Axis::Primary, "Axis 1").selected())
+            .child(DummyView{}.min_width(2))
+            .child(rb_axis.button(Axis::Secondary, "Axis 2"))
+        )
+        .child(LinearLayout::horizontal()
+            .child(TextView::new("Offset (°): "))
+            .child(tui::styled_edit_view().content("0.5").with_name(names::NUDGE_OFFSET).fixed_width(8))
+        )
+        .child(DummyView{})
+        .child(TextView::new("Press Move to move the selected axis by the offset.").with_name(names::NUDGE_STATUS))
+    )
+    .button("Move", cclone!([mount], move |curs| {
+        upgrade!(mount);
+        if tracking.is_active() {
+            msg_box(curs, "Stop tracking first.", "Error");
+            return;
+        }
+        let offset = match read_offset(curs) {
+            Ok(offset) => offset,
+            Err(e) => { msg_box(curs, &format!("Invalid value: {}.", e), "Error"); return; }
+        };
+        let axis = *rb_axis.selection();
+        let mut mount = mount.borrow_mut();
+        let Some(mount) = mount.as_mut() else { msg_box(curs, "Not connected to a mount.", "Error"); return; };
+        match mount.start_nudge(axis, deg(offset)) {
+            Ok(()) => on_progress(curs, &format!("Moving axis {} by {:+.3}°...", axis, offset)),
+            Err(e) => msg_box(curs, &format!("Failed to start moving: {}.", e), "Error")
+        }
+    }))
+    .button("Stop", cclone!([mount], move |curs| {
+        upgrade!(mount);
+        if let Some(mount) = mount.borrow_mut().as_mut() {
+            if mount.is_nudging() {
+                if let Err(e) = mount.stop() { log::error!("error stopping mount: {}", e); }
+                on_progress(curs, "Stopped.");
+            }
+        }
+    }))
+    .button("Close", cclone!([tui], move |curs| {
+        upgrade!(tui);
+        close_dialog(curs, &tui);
+    }))
+    .title("Move by offset")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), cclone!([tui], move |curs| {
+        upgrade!(tui);
+        close_dialog(curs, &tui);
+    }))
+}
+
+/// Updates the move status (if the dialog is shown).
+pub fn on_progress(curs: &mut cursive::Cursive, description: &str) {
+    curs.call_on_name(names::NUDGE_STATUS, |v: &mut TextView| v.set_content(description));
+}
+
+fn read_offset(curs: &mut cursive::Cursive) -> Result<f64, String> {
+    let offset_str = get_edit_view_str(curs, names::NUDGE_OFFSET);
+    match offset_str.parse::<f64>() {
+        Ok(value) if value != 0.0 && value.abs() <= MAX_OFFSET_DEG => Ok(value),
+        _ => Err(format!("{} (expected a non-zero offset from [-{1}, {1}])", offset_str, MAX_OFFSET_DEG))
+    }
+}
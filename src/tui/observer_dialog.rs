@@ -0,0 +1,228 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{
+    cclone,
+    clock,
+    config::Configuration,
+    data,
+    data::{as_deg, deg},
+    gps::GpsReceiver,
+    magnetic::MagneticModel,
+    tui,
+    tui::{close_dialog, get_edit_view_str, msg_box, names, set_edit_view_str, TuiData},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, Resizable, View},
+    views::{Button, CircularFocus, Dialog, DummyView, LinearLayout, OnEventView, PaddedView, Panel, TextContent, TextView},
+    With
+};
+use pointing_utils::{cgmath::InnerSpace, uom};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+use uom::si::{f64, length};
+
+/// Entered location farther than this from the current site's location is no longer considered the site.
+const SAME_SITE_MAX_DIST_M: f64 = 1.0;
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    observer: Weak<RefCell<Option<data::ObserverLocation>>>,
+    config: Weak<RefCell<Configuration>>,
+    gps: Weak<RefCell<Option<GpsReceiver>>>
+) -> impl View {
+    let gps_status = TextContent::new(match gps.upgrade().unwrap().borrow().as_ref() {
+        Some(receiver) => format!("connected to {}", receiver.description()),
+        None => "not connected".into()
+    });
+
+    Dialog::around(LinearLayout::vertical()
+        .child(panel(*observer.upgrade().unwrap().borrow()))
+        .child(LinearLayout::horizontal()
+            .child(TextView::new(format!(
+                "Site: {}  ",
+                config.upgrade().unwrap().borrow().current_site().map_or("(none)".to_string(), |site| site.name)
+            )))
+            .child(Button::new("Sites", cclone!([tui, observer, config], move |curs| {
+                tui::sites_dialog::show(curs, tui.clone(), observer.clone(), config.clone());
+            })))
+        )
+        .child(LinearLayout::horizontal()
+            .child(TextView::new("Magnetic declination: "))
+            .child(tui::styled_edit_view()
+                .content(format!("{:.1}", as_deg(config.upgrade().unwrap().borrow().magnetic_declination())))
+                .with_name(names::OBS_MAGNETIC_DECLINATION)
+                .fixed_width(7)
+            )
+            .child(TextView::new("° (east: +)  "))
+            .child(Button::new("Compute (WMM)", cclone!([config], move |curs| {
+                upgrade!(config);
+                on_compute_declination(curs, &config);
+            })))
+        )
+        .child(Panel::new(LinearLayout::vertical()
+            .child(TextView::new("Serial device or gpsd address (e.g., \"gpsd:localhost:2947\"):"))
+            .child(LinearLayout::horizontal()
+                .child(tui::styled_edit_view()
+                    .content(config.upgrade().unwrap().borrow().gps_device().unwrap_or("".into()))
+                    .with_name(names::GPS_DEVICE)
+                    .fixed_width(30)
+                )
+                .child(DummyView{}.min_width(1))
+                .child(Button::new("Connect", cclone!([gps, config, gps_status], move |curs| {
+                    upgrade!(gps, config);
+                    on_connect_to_gps(curs, &gps, &config, &gps_status);
+                })))
+                .child(Button::new("Disconnect", cclone!([gps, config, gps_status], move |_| {
+                    upgrade!(gps, config);
+                    *gps.borrow_mut() = None;
+                    config.borrow_mut().set_gps_device("");
+                    gps_status.set_content("not connected");
+                })))
+            )
+            .child(TextView::new_with_content(gps_status.clone()))
+        ).title("GPS").title_position(HAlign::Left))
+    )
+    .button("OK", cclone!([tui, observer, config], move |curs| {
+        upgrade!(tui, observer, config);
+        let declination = match get_edit_view_str(curs, names::OBS_MAGNETIC_DECLINATION).parse::<f64>() {
+            Ok(value) if (-180.0..=180.0).contains(&value) => deg(value),
+            _ => { msg_box(curs, "Invalid magnetic declination.", "Error"); return; }
+        };
+        match read(curs) {
+            Ok(value) => {
+                log::info!(
+                    "observer location: lat. {:.6}°, lon. {:.6}°, elev. {:.1} m",
+                    as_deg(value.lat), as_deg(value.lon), value.elevation.get::<length::meter>()
+                );
+                *observer.borrow_mut() = Some(value);
+                let site = config.borrow().current_site();
+                if site.is_some_and(|site| (site.location.ecef() - value.ecef()).magnitude() > SAME_SITE_MAX_DIST_M) {
+                    config.borrow_mut().clear_current_site();
+                }
+                config.borrow_mut().set_observer_location(&value);
+                config.borrow_mut().set_magnetic_declination(declination);
+                close_dialog(curs, &tui);
+            },
+
+            Err(e) => msg_box(curs, &format!("Invalid observer location: {}.", e), "Error")
+        }
+    }))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Observer location")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn on_connect_to_gps(
+    curs: &mut cursive::Cursive,
+    gps: &Rc<RefCell<Option<GpsReceiver>>>,
+    config: &Rc<RefCell<Configuration>>,
+    gps_status: &TextContent
+) {
+    let device = get_edit_view_str(curs, names::GPS_DEVICE);
+    match GpsReceiver::new(&device) {
+        Ok(receiver) => {
+            log::info!("connected to GPS {}", device);
+            gps_status.set_content(format!("connected to {}", receiver.description()));
+            *gps.borrow_mut() = Some(receiver);
+            config.borrow_mut().set_gps_device(&device);
+        },
+
+        Err(e) => {
+            log::error!("error connecting to GPS \"{}\": {}", device, e);
+            msg_box(curs, &format!("Failed to connect to GPS:\n{}.", e), "Error");
+        }
+    }
+}
+
+fn on_compute_declination(curs: &mut cursive::Cursive, config: &Rc<RefCell<Configuration>>) {
+    let location = match read(curs) {
+        Ok(location) => location,
+        Err(e) => { msg_box(curs, &format!("Invalid observer location: {}.", e), "Error"); return; }
+    };
+    let Some(wmm_file) = config.borrow().wmm_file() else {
+        msg_box(
+            curs,
+            "Set the World Magnetic Model coefficient file (\"WmmFile\") in the configuration file first.",
+            "Error"
+        );
+        return;
+    };
+    match MagneticModel::load(&wmm_file) {
+        Ok(model) => {
+            let declination = model.declination(&location, clock::utc_now());
+            log::info!("computed magnetic declination: {:.2}°", as_deg(declination));
+            set_edit_view_str(curs, names::OBS_MAGNETIC_DECLINATION, format!("{:.1}", as_deg(declination)));
+        },
+
+        Err(e) => {
+            log::error!("error loading WMM file \"{}\": {}", wmm_file.to_string_lossy(), e);
+            msg_box(curs, &format!("Failed to load WMM file:\n{}.", e), "Error");
+        }
+    }
+}
+
+/// Creates a panel with edit views for observer's latitude, longitude and elevation.
+pub fn panel(observer: Option<data::ObserverLocation>) -> Panel<PaddedView<LinearLayout>> {
+    let value = |f: &dyn Fn(&data::ObserverLocation) -> String| observer.as_ref().map(f).unwrap_or("".into());
+
+    Panel::new(PaddedView::lrtb(0, 0, 1, 1,
+        LinearLayout::horizontal()
+            .child(TextView::new("lat.: "))
+            .child(tui::styled_edit_view()
+                .content(value(&|o| format!("{:.6}", as_deg(o.lat))))
+                .with_name(names::OBS_LAT)
+                .fixed_width(10)
+            )
+            .child(TextView::new("°"))
+            .child(DummyView{}.min_width(1))
+            .child(TextView::new("lon.: "))
+            .child(tui::styled_edit_view()
+                .content(value(&|o| format!("{:.6}", as_deg(o.lon))))
+                .with_name(names::OBS_LON)
+                .fixed_width(10)
+            )
+            .child(TextView::new("°"))
+            .child(DummyView{}.min_width(1))
+            .child(TextView::new("elev.: "))
+            .child(tui::styled_edit_view()
+                .content(value(&|o| format!("{:.1}", o.elevation.get::<length::meter>())))
+                .with_name(names::OBS_ELEVATION)
+                .fixed_width(10)
+            )
+            .child(TextView::new(" m"))
+    )).title("Observer").title_position(HAlign::Left)
+}
+
+/// Reads observer location from the edit views created by `panel`.
+pub fn read(curs: &mut cursive::Cursive) -> Result<data::ObserverLocation, String> {
+    let parse = |s: Rc<String>| (*s).parse::<f64>().map_err(|_| format!("invalid value: {}", *s));
+
+    Ok(data::ObserverLocation{
+        lat: deg(parse(get_edit_view_str(curs, names::OBS_LAT))?),
+        lon: deg(parse(get_edit_view_str(curs, names::OBS_LON))?),
+        elevation: f64::Length::new::<length::meter>(parse(get_edit_view_str(curs, names::OBS_ELEVATION))?)
+    })
+}
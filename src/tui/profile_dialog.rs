@@ -0,0 +1,144 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    config,
+    config::Configuration,
+    controller,
+    data,
+    tui,
+    tui::{close_dialog, msg_box, names, TuiData},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, Resizable, Scrollable, View},
+    views::{Button, CircularFocus, Dialog, LinearLayout, OnEventView, Panel, SelectView, TextView},
+    With
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+
+const DEFAULT_PROFILE_LABEL: &str = "(default)";
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    config: Weak<RefCell<Configuration>>,
+    observer: Weak<RefCell<Option<data::ObserverLocation>>>,
+    ctrl_actions: Weak<RefCell<controller::ActionAssignments>>,
+    axes_response: Weak<RefCell<[controller::AxisResponse; 2]>>
+) -> impl View {
+    let current = {
+        upgrade!(config);
+        let config = config.borrow();
+        match config.profile() {
+            Some(profile) => profile,
+            None => format!("{} ({})", DEFAULT_PROFILE_LABEL, config.file_path().to_string_lossy())
+        }
+    };
+
+    let mut list = SelectView::<Option<String>>::new();
+    fill_list(&mut list);
+
+    Dialog::around(LinearLayout::vertical()
+        .child(TextView::new(format!("Current: {}", current)))
+        .child(Panel::new(
+            list.with_name(names::PROFILE_LIST).scrollable().fixed_height(8).min_width(30)
+        ).title("Profiles").title_position(HAlign::Left))
+        .child(Button::new("New (copy of current)...", cclone!([config], move |curs| {
+            upgrade!(config);
+            on_new_profile(curs, &config);
+        })))
+    )
+    .button("Switch", cclone!([tui, config, observer, ctrl_actions, axes_response], move |curs| {
+        upgrade!(tui, config, observer, ctrl_actions, axes_response);
+        let profile = match curs.call_on_name(names::PROFILE_LIST, |v: &mut SelectView<Option<String>>| {
+            v.selection().map(|p| (*p).clone())
+        }).flatten() {
+            Some(p) => p,
+            None => return
+        };
+        switch_profile(profile.as_deref(), &config, &observer, &ctrl_actions, &axes_response);
+        close_dialog(curs, &tui);
+        msg_box(
+            curs,
+            "Profile switched. Some settings (e.g., network inputs, angle format) take effect after restart.",
+            "Profile"
+        );
+    }))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Configuration profile")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn fill_list(list: &mut SelectView<Option<String>>) {
+    list.clear();
+    list.add_item(DEFAULT_PROFILE_LABEL, None);
+    for profile in config::profiles() {
+        list.add_item(profile.clone(), Some(profile));
+    }
+}
+
+fn on_new_profile(curs: &mut cursive::Cursive, config: &Rc<RefCell<Configuration>>) {
+    tui::simple_dialog::show(
+        curs,
+        "Enter profile name",
+        "",
+        20,
+        Rc::new(cclone!([@weak config], move |curs: &mut cursive::Cursive, name: &str| {
+            upgrade!(config);
+            let name = name.trim();
+            if name.is_empty() || name.contains(|c: char| std::path::is_separator(c)) {
+                msg_box(curs, "Invalid profile name.", "Error");
+                return;
+            }
+            match config.borrow().copy_to_profile(name) {
+                Ok(()) => log::info!("created configuration profile \"{}\"", name),
+                Err(e) => {
+                    log::error!("failed to create configuration profile \"{}\": {}", name, e);
+                    msg_box(curs, &format!("Failed to create profile:\n{}.", e), "Error");
+                }
+            }
+            curs.call_on_name(names::PROFILE_LIST, |v: &mut SelectView<Option<String>>| fill_list(v));
+        }))
+    );
+}
+
+/// Makes `profile` the active configuration and reloads settings which can be changed at runtime.
+fn switch_profile(
+    profile: Option<&str>,
+    config: &Rc<RefCell<Configuration>>,
+    observer: &Rc<RefCell<Option<data::ObserverLocation>>>,
+    ctrl_actions: &Rc<RefCell<controller::ActionAssignments>>,
+    axes_response: &Rc<RefCell<[controller::AxisResponse; 2]>>
+) {
+    // store first, in case the same profile is loaded again
+    if let Err(e) = config.borrow().store() { log::error!("error saving configuration: {}", e); }
+    *config.borrow_mut() = Configuration::from_profile(profile);
+    let config = config.borrow();
+    log::info!("switched to configuration file {}", config.file_path().to_string_lossy());
+    log::set_max_level(config.log_level());
+    *observer.borrow_mut() = config.observer_location();
+    *ctrl_actions.borrow_mut() = config.controller_actions();
+    *axes_response.borrow_mut() = config.mount_axes_response();
+}
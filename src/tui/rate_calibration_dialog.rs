@@ -0,0 +1,155 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    config::Configuration,
+    data::deg_per_s,
+    mount::{Axis, MountWrapper, RateCalibration},
+    tracking::TrackingController,
+    tui,
+    tui::{close_dialog, get_edit_view_str, msg_box, names, TuiData},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Nameable, Resizable, View},
+    views::{
+        CircularFocus,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        RadioGroup,
+        TextView,
+    },
+    With
+};
+use std::{cell::RefCell, rc::Weak, time::Duration};
+
+const DEFAULT_SPEED_DEG_PER_S: f64 = 1.0;
+const DEFAULT_DURATION_S: f64 = 20.0;
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    mount: Weak<RefCell<Option<MountWrapper>>>,
+    calibration: Weak<RefCell<Option<RateCalibration>>>,
+    config: Weak<RefCell<Configuration>>,
+    tracking: TrackingController
+) -> impl View {
+    let scale = mount.upgrade().unwrap().borrow().as_ref().unwrap().rate_scale();
+
+    let mut rb_axis = RadioGroup::<Axis>::new();
+    let edit = |name: &str, value: f64| tui::styled_edit_view()
+        .content(format!("{}", value))
+        .with_name(name)
+        .fixed_width(6);
+
+    Dialog::around(LinearLayout::vertical()
+        .child(TextView::new(format!("Current rate scale: axis 1: {:.4}, axis 2: {:.4}", scale.0, scale.1)))
+        .child(DummyView{})
+        .child(LinearLayout::horizontal()
+            .child(rb_axis.button(Axis::Primary, "Axis 1").selected())
+            .child(DummyView{}.min_width(2))
+            .child(rb_axis.button(Axis::Secondary, "Axis 2"))
+        )
+        .child(LinearLayout::horizontal()
+            .child(TextView::new("Speed (°/s): "))
+            .child(edit(names::RATE_CALIB_SPEED, DEFAULT_SPEED_DEG_PER_S))
+            .child(DummyView{}.min_width(2))
+            .child(TextView::new("Duration (s): "))
+            .child(edit(names::RATE_CALIB_DURATION, DEFAULT_DURATION_S))
+        )
+        .child(DummyView{})
+        .child(TextView::new("Press Start to slew the selected axis and measure its actual speed.")
+            .with_name(names::RATE_CALIB_STATUS))
+    )
+    .button("Start", cclone!([mount, calibration], move |curs| {
+        upgrade!(mount, calibration);
+        if tracking.is_active() {
+            msg_box(curs, "Stop tracking first.", "Error");
+            return;
+        }
+        let (speed, duration) = match read_params(curs) {
+            Ok(params) => params,
+            Err(e) => { msg_box(curs, &format!("Invalid value: {}.", e), "Error"); return; }
+        };
+        let mut mount = mount.borrow_mut();
+        let mount = mount.as_mut().unwrap();
+        if let Some(prev) = calibration.borrow_mut().as_mut() { prev.abort(Some(mount)); }
+        match RateCalibration::start(mount, *rb_axis.selection(), deg_per_s(speed), duration) {
+            Ok(c) => {
+                on_progress(curs, &c.description());
+                *calibration.borrow_mut() = Some(c);
+            },
+            Err(e) => msg_box(curs, &format!("Failed to start slewing: {}.", e), "Error")
+        }
+    }))
+    .button("Apply", cclone!([mount, calibration, config], move |curs| {
+        upgrade!(mount, calibration, config);
+        let result = calibration.borrow().as_ref().and_then(|c| c.result().map(|r| (c.axis(), r.clone())));
+        let (axis, scale) = match result {
+            Some((axis, Ok(scale))) => (axis, scale),
+            _ => { msg_box(curs, "No successful measurement to apply.", "Error"); return; }
+        };
+        let mut mount = mount.borrow_mut();
+        let mount = mount.as_mut().unwrap();
+        let mut value = mount.rate_scale();
+        match axis { Axis::Primary => value.0 = scale, Axis::Secondary => value.1 = scale }
+        mount.set_rate_scale(value);
+        config.borrow_mut().set_mount_rate_scale(value);
+        on_progress(curs, &format!("Applied rate scale {:.4} to axis {}.", scale, axis));
+    }))
+    .button("Close", cclone!([tui, mount, calibration], move |curs| {
+        upgrade!(tui, mount, calibration);
+        abort(&mount, &calibration);
+        close_dialog(curs, &tui);
+    }))
+    .title("Axis rate calibration")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), cclone!([tui, mount, calibration], move |curs| {
+        upgrade!(tui, mount, calibration);
+        abort(&mount, &calibration);
+        close_dialog(curs, &tui);
+    }))
+}
+
+/// Updates the measurement status (if the dialog is shown).
+pub fn on_progress(curs: &mut cursive::Cursive, description: &str) {
+    curs.call_on_name(names::RATE_CALIB_STATUS, |v: &mut TextView| v.set_content(description));
+}
+
+fn abort(mount: &RefCell<Option<MountWrapper>>, calibration: &RefCell<Option<RateCalibration>>) {
+    if let Some(mut c) = calibration.borrow_mut().take() { c.abort(mount.borrow_mut().as_mut()); }
+}
+
+fn read_params(curs: &mut cursive::Cursive) -> Result<(f64, Duration), String> {
+    let speed_str = get_edit_view_str(curs, names::RATE_CALIB_SPEED);
+    let speed = match speed_str.parse::<f64>() {
+        Ok(value) if value != 0.0 && value.abs() <= 10.0 => value,
+        _ => return Err(format!("{} (expected a non-zero speed from [-10, 10])", speed_str))
+    };
+    let duration_str = get_edit_view_str(curs, names::RATE_CALIB_DURATION);
+    let duration = match duration_str.parse::<f64>() {
+        Ok(value) if (1.0..=600.0).contains(&value) => value,
+        _ => return Err(format!("{} (expected a duration from [1, 600])", duration_str))
+    };
+
+    Ok((speed, Duration::from_secs_f64(duration)))
+}
@@ -16,7 +16,6 @@
 // along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
 //
 
-use cgmath::Deg;
 use crate::{
     cclone,
     config::Configuration,
@@ -40,9 +39,10 @@ use crate::{
 use cursive::{
     align::HAlign,
     event,
-    view::{Nameable, Resizable, View},
+    view::{Nameable, Resizable, Scrollable, View},
     views::{
         Button,
+        Checkbox,
         CircularFocus,
         Dialog,
         DummyView,
@@ -58,16 +58,20 @@ use cursive::{
     },
     With
 };
-use pointing_utils::{cgmath, GeoPos, LatLon, uom};
+use pointing_utils::uom;
 use std::{cell::RefCell, rc::{Rc, Weak}};
 use uom::si::{f64, length};
 
 pub fn dialog(
     tui: Weak<RefCell<Option<TuiData>>>,
     mount: Weak<RefCell<Option<mount::MountWrapper>>>,
-    config: Weak<RefCell<Configuration>>
+    config: Weak<RefCell<Configuration>>,
+    observer: Weak<RefCell<Option<data::ObserverLocation>>>
 ) -> impl View {
     let preset_name = TextContent::new("(none)");
+    let sightings = Rc::new(RefCell::new(Vec::<mount::RefSighting>::new()));
+    let sightings_info = TextContent::new(sightings_summary(&[]));
+    let declination = config.upgrade().unwrap().borrow().magnetic_declination();
 
     Dialog::around(LinearLayout::vertical()
         .child(
@@ -81,14 +85,24 @@ pub fn dialog(
                     move |curs| on_load_preset(curs, preset_name.clone(), config.clone())
                 )))
                 .child(Button::new("Store", cclone!([config, preset_name], move |curs| {
-                    on_store_preset(curs, preset_name.clone(), config.clone());
+                    on_store_preset(curs, preset_name.clone(), config.clone(), declination);
+                })))
+                .child(Button::new("Manage", cclone!([config], move |curs| {
+                    on_manage_presets(curs, config.clone());
                 })))
         )
         .child(DummyView{}.min_height(1))
         .child(Button::new(
             "Calc. from lat., lon. of observer and target",
-            |curs| on_calc_from_observer_and_target_pos(curs)
+            cclone!([observer, config], move |curs| {
+                upgrade!(observer);
+                on_calc_from_observer_and_target_pos(curs, *observer.borrow(), config.clone());
+            })
         ))
+        .child(Button::new("Calc. from landmark", cclone!([observer, config], move |curs| {
+            upgrade!(observer);
+            on_choose_landmark(curs, *observer.borrow(), config.clone());
+        })))
         .child(DummyView{}.min_height(1))
         .child(
             LinearLayout::horizontal()
@@ -108,30 +122,55 @@ pub fn dialog(
                 )
                 .child(TextView::new("°"))
         )
+        .child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().with_name(names::REF_POS_MAGNETIC))
+                .child(TextView::new(format!(
+                    " azimuth is a magnetic bearing (declination {:+.1}°)", as_deg(declination)
+                )))
+        )
+        .child(DummyView{}.min_height(1))
+        .child(TextView::new(
+            "To improve accuracy, center the landmark(s) several times and press \"Add sighting\" each time;\n\
+            the ref. position is then fitted to all sightings."
+        ))
+        .child(
+            LinearLayout::horizontal()
+                .child(Button::new("Add sighting", cclone!([mount, sightings, sightings_info], move |curs| {
+                    upgrade!(mount);
+                    on_add_sighting(curs, &mount, &sightings, &sightings_info, declination);
+                })))
+                .child(DummyView{}.min_width(1))
+                .child(Button::new("Clear sightings", cclone!([sightings, sightings_info], move |_| {
+                    sightings.borrow_mut().clear();
+                    sightings_info.set_content(sightings_summary(&[]));
+                })))
+        )
+        .child(TextView::new_with_content(sightings_info.clone()))
     )
-    .button("OK", cclone!([tui, mount], move |curs| {
+    .button("OK", cclone!([tui, mount, sightings], move |curs| {
         upgrade!(tui, mount);
 
-        let ref_az = get_edit_view_str(curs, names::REF_POS_AZ);
-        let ref_alt = get_edit_view_str(curs, names::REF_POS_ALT);
-
-        let ref_az = (*ref_az).parse::<f64>();
-        let ref_alt = (*ref_alt).parse::<f64>();
+        if !sightings.borrow().is_empty() {
+            close_dialog(curs, &tui);
+            let fit = mount.borrow_mut().as_mut().unwrap().set_reference_sightings(&sightings.borrow());
+            log::info!(
+                "ref. position fitted to {} sightings; RMS residual: {:.3}°, {:.3}°",
+                sightings.borrow().len(), as_deg(fit.rms_residual.0), as_deg(fit.rms_residual.1)
+            );
+            return;
+        }
 
-        let err: Option<_> = match (ref_az, ref_alt) {
-            (Ok(ref_az), Ok(ref_alt)) => {
+        match read_az_alt(curs, declination) {
+            Ok((ref_az, ref_alt)) => {
                 close_dialog(curs, &tui);
                 if let Err(e) = mount.borrow_mut().as_mut().unwrap().set_reference_position(deg(ref_az), deg(ref_alt)) {
                     msg_box(curs, &format!("Failed to set ref. position:\n{}", e), "Error");
                 }
-                None
             },
 
-            (Err(e), _) => Some(e),
-            (Ok(_), Err(e)) => Some(e)
-        };
-
-        if let Some(err) = err { msg_box(curs, &format!("Invalid value: {}.", err), "Error"); }
+            Err(e) => msg_box(curs, &format!("Invalid value: {}.", e), "Error")
+        }
     }))
     .button("Cancel", crate::cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
     .title("Set current reference position")
@@ -143,6 +182,52 @@ pub fn dialog(
     ))
 }
 
+fn on_add_sighting(
+    curs: &mut cursive::Cursive,
+    mount: &RefCell<Option<mount::MountWrapper>>,
+    sightings: &RefCell<Vec<mount::RefSighting>>,
+    sightings_info: &TextContent,
+    declination: f64::Angle
+) {
+    let Ok((az, alt)) = read_az_alt(curs, declination) else {
+        msg_box(curs, "Invalid azimuth or altitude value.", "Error");
+        return;
+    };
+
+    match mount.borrow_mut().as_mut().unwrap().capture_sighting(deg(az), deg(alt)) {
+        Ok(sighting) => {
+            sightings.borrow_mut().push(sighting);
+            sightings_info.set_content(sightings_summary(&sightings.borrow()));
+        },
+        Err(e) => msg_box(curs, &format!("Failed to get mount position:\n{}", e), "Error")
+    }
+}
+
+/// Reads the entered azimuth and altitude (degrees); a magnetic bearing is converted to true azimuth.
+fn read_az_alt(curs: &mut cursive::Cursive, declination: f64::Angle) -> Result<(f64, f64), std::num::ParseFloatError> {
+    let az = get_edit_view_str(curs, names::REF_POS_AZ).parse::<f64>()?;
+    let alt = get_edit_view_str(curs, names::REF_POS_ALT).parse::<f64>()?;
+    let magnetic = curs.call_on_name(names::REF_POS_MAGNETIC, |v: &mut Checkbox| v.is_checked()).unwrap_or(false);
+
+    Ok((if magnetic { (az + as_deg(declination)).rem_euclid(360.0) } else { az }, alt))
+}
+
+/// Fills in true azimuth and altitude.
+fn set_az_alt(curs: &mut cursive::Cursive, az: f64::Angle, alt: f64::Angle, precision: usize) {
+    set_edit_view_str(curs, names::REF_POS_AZ, format!("{:.*}", precision, as_deg(az)));
+    set_edit_view_str(curs, names::REF_POS_ALT, format!("{:.*}", precision, as_deg(alt)));
+    curs.call_on_name(names::REF_POS_MAGNETIC, |v: &mut Checkbox| { v.uncheck(); });
+}
+
+fn sightings_summary(sightings: &[mount::RefSighting]) -> String {
+    if sightings.is_empty() { return "sightings: 0".into(); }
+    let fit = mount::fit_offsets(sightings);
+    format!(
+        "sightings: {}  (RMS residual: az. {:.3}°, alt. {:.3}°)",
+        sightings.len(), as_deg(fit.rms_residual.0), as_deg(fit.rms_residual.1)
+    )
+}
+
 fn on_preset_chosen(
     curs: &mut cursive::Cursive,
     preset_name: &TextContent,
@@ -151,8 +236,7 @@ fn on_preset_chosen(
 ) {
     upgrade!(config);
     let preset = &config.borrow().ref_pos_presets()[preset_idx];
-    set_edit_view_str(curs, names::REF_POS_AZ, format!("{:.3}", as_deg(preset.azimuth)));
-    set_edit_view_str(curs, names::REF_POS_ALT, format!("{:.3}", as_deg(preset.altitude)));
+    set_az_alt(curs, preset.azimuth, preset.altitude, 3);
     preset_name.set_content(preset.name.clone());
     curs.pop_layer();
 }
@@ -184,11 +268,13 @@ fn on_load_preset(curs: &mut cursive::Cursive, preset_name: TextContent, config:
     )));
 }
 
-fn on_store_preset(curs: &mut cursive::Cursive, preset_name: TextContent, config: Weak<RefCell<Configuration>>) {
-    let az = get_edit_view_str(curs, names::REF_POS_AZ).parse::<f64>();
-    let alt = get_edit_view_str(curs, names::REF_POS_ALT).parse::<f64>();
-
-    if let (Ok(az), Ok(alt)) = (az, alt) {
+fn on_store_preset(
+    curs: &mut cursive::Cursive,
+    preset_name: TextContent,
+    config: Weak<RefCell<Configuration>>,
+    declination: f64::Angle
+) {
+    if let Ok((az, alt)) = read_az_alt(curs, declination) {
         tui::simple_dialog::show(
             curs,
             "Enter preset name",
@@ -207,34 +293,244 @@ fn on_store_preset(curs: &mut cursive::Cursive, preset_name: TextContent, config
     }
 }
 
-fn on_calc_from_observer_and_target_pos(curs: &mut cursive::Cursive) {
+fn fill_preset_list(list: &mut SelectView<usize>, presets: &[data::RefPositionPreset]) {
+    list.clear();
+    for (idx, preset) in presets.iter().enumerate() {
+        list.add_item(
+            format!("{}  ({:.3}°, {:.3}°)", preset.name, as_deg(preset.azimuth), as_deg(preset.altitude)),
+            idx
+        );
+    }
+}
+
+fn refresh_preset_list(curs: &mut cursive::Cursive, config: &Configuration) {
+    let presets = config.ref_pos_presets();
+    curs.call_on_name(names::REF_POS_MANAGE_LIST, |v: &mut SelectView<usize>| fill_preset_list(v, &presets));
+}
+
+fn selected_preset(curs: &mut cursive::Cursive) -> Option<usize> {
+    curs.call_on_name(names::REF_POS_MANAGE_LIST, |v: &mut SelectView<usize>| v.selection().map(|idx| *idx))
+        .flatten()
+}
+
+fn on_manage_presets(curs: &mut cursive::Cursive, config: Weak<RefCell<Configuration>>) {
+    let mut list = SelectView::<usize>::new();
+    fill_preset_list(&mut list, &config.upgrade().unwrap().borrow().ref_pos_presets());
+
     let dt = create_dialog_theme(curs);
     curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
         dt,
         Dialog::around(LinearLayout::vertical()
-            .child(Panel::new(PaddedView::lrtb(0, 0, 1, 1,
-                LinearLayout::horizontal()
-                    .child(TextView::new("lat.: "))
-                    .child(tui::styled_edit_view()
-                        .with_name(names::OBS_LAT)
-                        .fixed_width(10)
-                    )
-                    .child(TextView::new("°"))
-                    .child(DummyView{}.min_width(1))
-                    .child(TextView::new("lon.: "))
-                    .child(tui::styled_edit_view()
-                        .with_name(names::OBS_LON)
-                        .fixed_width(10)
-                        )
-                    .child(TextView::new("°"))
-                    .child(DummyView{}.min_width(1))
-                    .child(TextView::new("elev.: "))
-                    .child(tui::styled_edit_view()
-                        .with_name(names::OBS_ELEVATION)
-                        .fixed_width(10)
-                    )
-                    .child(TextView::new(" m"))
-            )).title("Observer").title_position(HAlign::Left))
+            .child(list.with_name(names::REF_POS_MANAGE_LIST).scrollable().fixed_height(10).min_width(40))
+            .child(DummyView{}.min_height(1))
+            .child(LinearLayout::horizontal()
+                .child(Button::new("Rename", cclone!([config], move |curs| on_rename_preset(curs, config.clone()))))
+                .child(Button::new("Delete", cclone!([config], move |curs| {
+                    upgrade!(config);
+                    if let Some(idx) = selected_preset(curs) {
+                        let mut presets = config.borrow().ref_pos_presets();
+                        if idx < presets.len() {
+                            presets.remove(idx);
+                            config.borrow_mut().set_ref_pos_presets(&presets);
+                        }
+                    }
+                    refresh_preset_list(curs, &config.borrow());
+                })))
+                .child(Button::new("Import", cclone!([config], move |curs| on_import_presets(curs, config.clone()))))
+                .child(Button::new("Export", cclone!([config], move |curs| on_export_presets(curs, config.clone()))))
+            )
+        )
+        .title("Reference position presets")
+        .dismiss_button("Close")
+        .wrap_with(OnEventView::new)
+        .on_event(event::Event::Key(event::Key::Esc), |curs| { curs.pop_layer(); })
+    )));
+}
+
+fn on_rename_preset(curs: &mut cursive::Cursive, config: Weak<RefCell<Configuration>>) {
+    let idx = match selected_preset(curs) {
+        Some(idx) => idx,
+        None => return
+    };
+
+    tui::simple_dialog::show(
+        curs,
+        "Enter new preset name",
+        "",
+        25,
+        Rc::new(cclone!([config], move |curs: &mut cursive::Cursive, name: &str| {
+            upgrade!(config);
+            let mut presets = config.borrow().ref_pos_presets();
+            if let Some(preset) = presets.get_mut(idx) {
+                preset.name = name.into();
+                config.borrow_mut().set_ref_pos_presets(&presets);
+            }
+            refresh_preset_list(curs, &config.borrow());
+        }))
+    );
+}
+
+fn on_import_presets(curs: &mut cursive::Cursive, config: Weak<RefCell<Configuration>>) {
+    tui::simple_dialog::show(
+        curs,
+        "Import presets (CSV)",
+        "File: ",
+        40,
+        Rc::new(cclone!([config], move |curs: &mut cursive::Cursive, path: &str| {
+            upgrade!(config);
+            match || -> Result<_, Box<dyn std::error::Error>> {
+                data::ref_pos_presets_from_csv(&std::fs::read_to_string(path)?)
+            }() {
+                Ok(imported) => {
+                    log::info!("imported {} ref. position preset(s) from \"{}\"", imported.len(), path);
+                    let mut presets = config.borrow().ref_pos_presets();
+                    presets.extend(imported);
+                    config.borrow_mut().set_ref_pos_presets(&presets);
+                    refresh_preset_list(curs, &config.borrow());
+                },
+
+                Err(e) => {
+                    log::error!("error importing ref. position presets from \"{}\": {}", path, e);
+                    msg_box(curs, &format!("Failed to import presets:\n{}.", e), "Error");
+                }
+            }
+        }))
+    );
+}
+
+fn on_export_presets(curs: &mut cursive::Cursive, config: Weak<RefCell<Configuration>>) {
+    tui::simple_dialog::show(
+        curs,
+        "Export presets (CSV)",
+        "File: ",
+        40,
+        Rc::new(cclone!([config], move |curs: &mut cursive::Cursive, path: &str| {
+            upgrade!(config);
+            let presets = config.borrow().ref_pos_presets();
+            match std::fs::write(path, data::ref_pos_presets_to_csv(&presets)) {
+                Ok(()) => log::info!("exported {} ref. position preset(s) to \"{}\"", presets.len(), path),
+                Err(e) => {
+                    log::error!("error exporting ref. position presets to \"{}\": {}", path, e);
+                    msg_box(curs, &format!("Failed to export presets:\n{}.", e), "Error");
+                }
+            }
+        }))
+    );
+}
+
+fn fill_landmark_list(list: &mut SelectView<usize>, landmarks: &[data::Landmark], observer: &data::ObserverLocation) {
+    list.clear();
+    for (idx, landmark) in landmarks.iter().enumerate() {
+        let (az, alt) = landmark.az_alt(observer);
+        list.add_item(format!("{}  (az. {:.3}°, alt. {:.3}°)", landmark.name, as_deg(az), as_deg(alt)), idx);
+    }
+}
+
+fn on_landmark_chosen(
+    curs: &mut cursive::Cursive,
+    idx: usize,
+    observer: &data::ObserverLocation,
+    config: &Weak<RefCell<Configuration>>
+) {
+    upgrade!(config);
+    let Some(landmark) = config.borrow().landmarks().get(idx).cloned() else { return; };
+    let (az, alt) = landmark.az_alt(observer);
+    set_az_alt(curs, az, alt, 4);
+    curs.pop_layer();
+}
+
+fn on_choose_landmark(
+    curs: &mut cursive::Cursive,
+    observer: Option<data::ObserverLocation>,
+    config: Weak<RefCell<Configuration>>
+) {
+    let Some(observer) = observer else {
+        msg_box(curs, "Observer location not set.", "Error");
+        return;
+    };
+    let mut list = SelectView::<usize>::new().on_submit(
+        cclone!([config], move |curs, idx| on_landmark_chosen(curs, *idx, &observer, &config))
+    );
+    fill_landmark_list(&mut list, &config.upgrade().unwrap().borrow().landmarks(), &observer);
+
+    let dt = create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dt,
+        Dialog::around(LinearLayout::vertical()
+            .child(list.with_name(names::LANDMARK_LIST).scrollable().fixed_height(10).min_width(50))
+            .child(DummyView{}.min_height(1))
+            .child(TextView::new(
+                "Landmarks are added with \"Save as landmark\" in the\n\"Calc. from lat., lon.\" dialog."
+            ))
+        )
+        .title("Choose landmark")
+        .button("OK", cclone!([config], move |curs| {
+            if let Some(idx) = selected_landmark(curs) { on_landmark_chosen(curs, idx, &observer, &config); }
+        }))
+        .button("Delete", cclone!([config], move |curs| {
+            upgrade!(config);
+            if let Some(idx) = selected_landmark(curs) {
+                let mut landmarks = config.borrow().landmarks();
+                if idx < landmarks.len() {
+                    log::info!("deleting landmark \"{}\"", landmarks[idx].name);
+                    landmarks.remove(idx);
+                    config.borrow_mut().set_landmarks(&landmarks);
+                }
+                curs.call_on_name(names::LANDMARK_LIST, |v: &mut SelectView<usize>| {
+                    fill_landmark_list(v, &landmarks, &observer)
+                });
+            }
+        }))
+        .dismiss_button("Cancel")
+        .wrap_with(OnEventView::new)
+        .on_event(event::Event::Key(event::Key::Esc), |curs| { curs.pop_layer(); })
+    )));
+}
+
+fn selected_landmark(curs: &mut cursive::Cursive) -> Option<usize> {
+    curs.call_on_name(names::LANDMARK_LIST, |v: &mut SelectView<usize>| v.selection().map(|idx| *idx)).flatten()
+}
+
+/// Reads the target location from the "Calculate mount position" dialog.
+fn read_target_location(curs: &mut cursive::Cursive) -> Result<data::ObserverLocation, String> {
+    let parse = |s: Rc<String>| (*s).parse::<f64>().map_err(|_| format!("invalid value: {}", *s));
+    Ok(data::ObserverLocation{
+        lat: deg(parse(get_edit_view_str(curs, names::TARGET_LAT))?),
+        lon: deg(parse(get_edit_view_str(curs, names::TARGET_LON))?),
+        elevation: f64::Length::new::<length::meter>(parse(get_edit_view_str(curs, names::TARGET_ELEVATION))?)
+    })
+}
+
+fn on_save_landmark(curs: &mut cursive::Cursive, config: Weak<RefCell<Configuration>>) {
+    let location = match read_target_location(curs) {
+        Ok(location) => location,
+        Err(e) => { msg_box(curs, &format!("Error: {}.", e), "Error"); return; }
+    };
+    tui::simple_dialog::show(
+        curs,
+        "Enter landmark name",
+        "",
+        25,
+        Rc::new(cclone!([config], move |_: &mut cursive::Cursive, name: &str| {
+            upgrade!(config);
+            let mut landmarks = config.borrow().landmarks();
+            landmarks.push(data::Landmark{ name: name.into(), location });
+            config.borrow_mut().set_landmarks(&landmarks);
+            log::info!("added landmark \"{}\"", name);
+        }))
+    );
+}
+
+fn on_calc_from_observer_and_target_pos(
+    curs: &mut cursive::Cursive,
+    observer: Option<data::ObserverLocation>,
+    config: Weak<RefCell<Configuration>>
+) {
+    let dt = create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dt,
+        Dialog::around(LinearLayout::vertical()
+            .child(tui::observer_dialog::panel(observer))
             .child(Panel::new(PaddedView::lrtb(0, 0, 1, 1,
                 LinearLayout::horizontal()
                     .child(TextView::new("lat.: "))
@@ -261,38 +557,16 @@ fn on_calc_from_observer_and_target_pos(curs: &mut cursive::Cursive) {
         )
         .title("Calculate mount position")
         .button("OK", |curs| {
-            let obs_lat_str = get_edit_view_str(curs, names::OBS_LAT);
-            let obs_lon_str = get_edit_view_str(curs, names::OBS_LON);
-            let obs_el_str = get_edit_view_str(curs, names::OBS_ELEVATION);
-
-            let target_lat_str = get_edit_view_str(curs, names::TARGET_LAT);
-            let target_lon_str = get_edit_view_str(curs, names::TARGET_LON);
-            let target_el_str = get_edit_view_str(curs, names::TARGET_ELEVATION);
+            let observer = tui::observer_dialog::read(curs);
+            let target = read_target_location(curs);
 
             if let Err(e) = || -> Result<(), String> {
-                let parse = |s: Rc<String>| (*s).parse::<f64>().map_err(|_| format!("invalid value: {}", *s));
-
-                let obs_lat = parse(obs_lat_str)?;
-                let obs_lon = parse(obs_lon_str)?;
-                let obs_el = parse(obs_el_str)?;
-
-                let target_lat = parse(target_lat_str)?;
-                let target_lon = parse(target_lon_str)?;
-                let target_el = parse(target_el_str)?;
-
-                let (az, alt) = data::calc_az_alt_between_points(
-                    &GeoPos{
-                        lat_lon: LatLon{ lat: Deg(obs_lat), lon: Deg(obs_lon) },
-                        elevation: f64::Length::new::<length::meter>(obs_el)
-                    },
-                    &GeoPos{
-                        lat_lon: LatLon{ lat: Deg(target_lat), lon: Deg(target_lon) },
-                        elevation: f64::Length::new::<length::meter>(target_el)
-                    }
-                );
+                let observer = observer?;
+                let target = target?;
+
+                let (az, alt) = data::calc_az_alt_between_points(&observer.to_geo_pos(), &target.to_geo_pos());
 
-                set_edit_view_str(curs, names::REF_POS_AZ, format!("{:.04}", as_deg(az)));
-                set_edit_view_str(curs, names::REF_POS_ALT, format!("{:.04}", as_deg(alt)));
+                set_az_alt(curs, az, alt, 4);
 
                 curs.pop_layer();
 
@@ -301,6 +575,7 @@ fn on_calc_from_observer_and_target_pos(curs: &mut cursive::Cursive) {
                 msg_box(curs, &format!("Error calculating position: {}.", e), "Error");
             }
         })
+        .button("Save as landmark", move |curs| on_save_landmark(curs, config.clone()))
         .dismiss_button("Cancel")
         .wrap_with(OnEventView::new)
         .on_event(event::Event::Key(event::Key::Esc), |curs| { curs.pop_layer(); })
@@ -0,0 +1,153 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{
+    cclone,
+    config::Configuration,
+    data_receiver,
+    target_source,
+    target_source::TargetSource,
+    tui,
+    tui::{close_dialog, get_edit_view_str, get_select_view_idx, msg_box, names, TuiData},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, Resizable, Scrollable, View},
+    views::{
+        Button,
+        Checkbox,
+        CircularFocus,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        Panel,
+        SelectView,
+        TextView,
+    },
+    With
+};
+use std::{cell::RefCell, path::Path, rc::{Rc, Weak}};
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    connection: data_receiver::Connection,
+    config: Weak<RefCell<Configuration>>,
+    target_source: Weak<RefCell<Option<Box<dyn TargetSource>>>>
+) -> impl View {
+    let satellites: Rc<RefCell<Vec<sgp4::Elements>>> = Rc::new(RefCell::new(vec![]));
+
+    Dialog::around(LinearLayout::vertical()
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new("TLE file:"))
+                .child(DummyView{}.min_width(1))
+                .child(tui::styled_edit_view()
+                    .content(config.upgrade().unwrap().borrow().tle_file().unwrap_or("".into()))
+                    .with_name(names::TLE_FILE)
+                    .fixed_width(40)
+                )
+                .child(DummyView{}.min_width(1))
+                .child(Button::new("Load", cclone!([satellites], move |curs| on_load_tle_file(curs, &satellites))))
+        )
+        .child(DummyView{}.min_height(1))
+        .child(Panel::new(
+            SelectView::<usize>::new().with_name(names::SATELLITE_LIST).scrollable().fixed_height(10)
+        ).title("Satellites").title_position(HAlign::Left))
+        .child(LinearLayout::horizontal()
+            .child(Checkbox::new()
+                .with_checked(config.upgrade().unwrap().borrow().satellite_optical_position())
+                .with_name(names::SATELLITE_OPTICAL)
+            )
+            .child(TextView::new(" Optical position (light-time & aberration corrected)"))
+        )
+    )
+    .button("OK", cclone!([tui, connection, config, target_source, satellites], move |curs| {
+        upgrade!(tui, config, target_source);
+        on_select_satellite(curs, &tui, &connection, &config, &target_source, &satellites);
+    }))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Track satellite")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn on_load_tle_file(curs: &mut cursive::Cursive, satellites: &Rc<RefCell<Vec<sgp4::Elements>>>) {
+    let path = get_edit_view_str(curs, names::TLE_FILE);
+    match target_source::load_tle_file(Path::new(path.as_str())) {
+        Ok(elements) => {
+            curs.call_on_name(names::SATELLITE_LIST, |v: &mut SelectView<usize>| {
+                v.clear();
+                for (idx, e) in elements.iter().enumerate() {
+                    v.add_item(target_source::satellite_name(e), idx);
+                }
+            });
+            *satellites.borrow_mut() = elements;
+        },
+
+        Err(e) => {
+            log::error!("error loading TLE file \"{}\": {}", path, e);
+            msg_box(curs, &format!("Failed to load TLE file:\n{}.", e), "Error");
+        }
+    }
+}
+
+fn on_select_satellite(
+    curs: &mut cursive::Cursive,
+    tui: &Rc<RefCell<Option<TuiData>>>,
+    connection: &data_receiver::Connection,
+    config: &Rc<RefCell<Configuration>>,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    satellites: &Rc<RefCell<Vec<sgp4::Elements>>>
+) {
+    if satellites.borrow().is_empty() {
+        msg_box(curs, "No satellites loaded.", "Error");
+        return;
+    }
+
+    if config.borrow().observer_location().is_none() {
+        msg_box(curs, "Observer location not set (press O to set it).", "Error");
+        return;
+    }
+
+    let idx = get_select_view_idx(curs, names::SATELLITE_LIST);
+    let optical = curs.call_on_name(names::SATELLITE_OPTICAL, |v: &mut Checkbox| v.is_checked()).unwrap();
+    match target_source::Satellite::new(&satellites.borrow()[idx]) {
+        Ok(mut satellite) => {
+            satellite.set_optical(optical);
+            log::info!("tracking satellite {}{}", satellite.name(), if optical { " (optical position)" } else { "" });
+            connection.disconnect();
+            tui!(tui).text_content.target_source.set_content(satellite.name());
+            *target_source.borrow_mut() = Some(Box::new(satellite));
+            config.borrow_mut().set_tle_file(&get_edit_view_str(curs, names::TLE_FILE));
+            config.borrow_mut().set_satellite_optical_position(optical);
+            close_dialog(curs, tui);
+        },
+
+        Err(e) => {
+            log::error!("error initializing satellite propagation: {}", e);
+            msg_box(curs, &format!("Invalid orbital elements:\n{}.", e), "Error");
+        }
+    }
+}
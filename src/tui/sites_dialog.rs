@@ -0,0 +1,153 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    config::Configuration,
+    data,
+    data::as_deg,
+    tui,
+    tui::{close_dialog, create_dialog_theme, msg_box, names, TuiData, WithShadow},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, LinearLayout, OnEventView, SelectView, ThemedView},
+    With
+};
+use pointing_utils::uom;
+use std::{cell::RefCell, rc::{Rc, Weak}};
+use uom::si::length;
+
+/// Shows the list of known sites on top of the observer dialog; switching to a site closes both.
+pub fn show(
+    curs: &mut cursive::Cursive,
+    tui: Weak<RefCell<Option<TuiData>>>,
+    observer: Weak<RefCell<Option<data::ObserverLocation>>>,
+    config: Weak<RefCell<Configuration>>
+) {
+    let mut list = SelectView::<usize>::new().on_submit(
+        cclone!([tui, observer, config], move |curs, idx| on_switch(curs, *idx, &tui, &observer, &config))
+    );
+    fill_list(&mut list, &config.upgrade().unwrap().borrow());
+
+    let dt = create_dialog_theme(curs);
+    curs.screen_mut().add_transparent_layer(WithShadow::new(ThemedView::new(
+        dt,
+        Dialog::around(LinearLayout::vertical()
+            .child(list.with_name(names::SITE_LIST).scrollable().fixed_height(10).min_width(60))
+        )
+        .title("Sites")
+        .button("Switch", cclone!([tui, observer, config], move |curs| {
+            if let Some(idx) = selected(curs) { on_switch(curs, idx, &tui, &observer, &config); }
+        }))
+        .button("Add current", cclone!([config], move |curs| on_add_current(curs, config.clone())))
+        .button("Delete", cclone!([config], move |curs| {
+            upgrade!(config);
+            if let Some(idx) = selected(curs) {
+                let mut sites = config.borrow().sites();
+                if idx < sites.len() {
+                    log::info!("deleting site \"{}\"", sites[idx].name);
+                    sites.remove(idx);
+                    config.borrow_mut().set_sites(&sites);
+                }
+                refresh_list(curs, &config.borrow());
+            }
+        }))
+        .dismiss_button("Close")
+        .wrap_with(OnEventView::new)
+        .on_event(event::Event::Key(event::Key::Esc), |curs| { curs.pop_layer(); })
+    )));
+}
+
+fn fill_list(list: &mut SelectView<usize>, config: &Configuration) {
+    let current = config.current_site().map(|site| site.name);
+    list.clear();
+    for (idx, site) in config.sites().iter().enumerate() {
+        list.add_item(
+            format!(
+                "{}{}  ({:.4}°, {:.4}°, {:.0} m, decl. {:+.1}°)",
+                if current.as_ref() == Some(&site.name) { "* " } else { "  " },
+                site.name,
+                as_deg(site.location.lat),
+                as_deg(site.location.lon),
+                site.location.elevation.get::<length::meter>(),
+                as_deg(site.magnetic_declination)
+            ),
+            idx
+        );
+    }
+}
+
+fn refresh_list(curs: &mut cursive::Cursive, config: &Configuration) {
+    curs.call_on_name(names::SITE_LIST, |v: &mut SelectView<usize>| fill_list(v, config));
+}
+
+fn selected(curs: &mut cursive::Cursive) -> Option<usize> {
+    curs.call_on_name(names::SITE_LIST, |v: &mut SelectView<usize>| v.selection().map(|idx| *idx)).flatten()
+}
+
+fn on_switch(
+    curs: &mut cursive::Cursive,
+    idx: usize,
+    tui: &Weak<RefCell<Option<TuiData>>>,
+    observer: &Weak<RefCell<Option<data::ObserverLocation>>>,
+    config: &Weak<RefCell<Configuration>>
+) {
+    upgrade!(tui, observer, config);
+    let Some(site) = config.borrow().sites().get(idx).cloned() else { return; };
+    log::info!(
+        "switched to site \"{}\": lat. {:.6}°, lon. {:.6}°, elev. {:.1} m, magnetic declination {:.1}°",
+        site.name,
+        as_deg(site.location.lat),
+        as_deg(site.location.lon),
+        site.location.elevation.get::<length::meter>(),
+        as_deg(site.magnetic_declination)
+    );
+    *observer.borrow_mut() = Some(site.location);
+    config.borrow_mut().use_site(&site);
+    curs.pop_layer();
+    close_dialog(curs, &tui);
+}
+
+/// Adds the location entered in the observer dialog as a new site (with the current magnetic declination).
+fn on_add_current(curs: &mut cursive::Cursive, config: Weak<RefCell<Configuration>>) {
+    let location = match tui::observer_dialog::read(curs) {
+        Ok(location) => location,
+        Err(e) => { msg_box(curs, &format!("Invalid observer location: {}.", e), "Error"); return; }
+    };
+    tui::simple_dialog::show(
+        curs,
+        "Enter site name",
+        "",
+        25,
+        Rc::new(cclone!([config], move |curs: &mut cursive::Cursive, name: &str| {
+            upgrade!(config);
+            let mut sites = config.borrow().sites();
+            if sites.iter().any(|site| site.name == name) {
+                msg_box(curs, &format!("Site \"{}\" already exists.", name), "Error");
+                return;
+            }
+            let magnetic_declination = config.borrow().magnetic_declination();
+            sites.push(data::Site{ name: name.into(), location, magnetic_declination });
+            config.borrow_mut().set_sites(&sites);
+            log::info!("added site \"{}\"", name);
+            refresh_list(curs, &config.borrow());
+        }))
+    );
+}
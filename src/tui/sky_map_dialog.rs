@@ -0,0 +1,120 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    clock,
+    config::Configuration,
+    data::{as_deg, PositionHistory},
+    data_receiver,
+    event_handling,
+    target_source::{TargetQueue, TargetSource},
+    tui::{close_dialog, msg_box, names, sky_map_view::{SkyMapView, SkyObject}, TuiData},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Nameable, View},
+    views::{CircularFocus, Dialog, LinearLayout, OnEventView, TextView},
+    With
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    pos_history: Weak<RefCell<PositionHistory>>,
+    connection: data_receiver::Connection,
+    config: Weak<RefCell<Configuration>>,
+    target_source: Weak<RefCell<Option<Box<dyn TargetSource>>>>,
+    queue: Weak<RefCell<TargetQueue>>
+) -> impl View {
+    let objects = sky_objects(&queue.upgrade().unwrap().borrow(), &config.upgrade().unwrap().borrow());
+    let on_submit = cclone!([tui, connection, config, target_source, queue], move |curs: &mut cursive::Cursive, idx| {
+        upgrade!(tui, config, target_source, queue);
+        on_select_object(curs, idx, &tui, &connection, &config, &target_source, &queue);
+    });
+
+    Dialog::around(
+        LinearLayout::vertical()
+            .child(SkyMapView::new(pos_history, objects, on_submit.clone()).with_name(names::SKY_MAP))
+            .child(TextView::new("* mount   + target   ←/→ or mouse: select   Enter: track"))
+    )
+    .button("Track", move |curs| {
+        let idx = curs.call_on_name(names::SKY_MAP, |v: &mut SkyMapView| v.selected()).flatten();
+        match idx {
+            Some(idx) => on_submit(curs, idx),
+            None => msg_box(curs, "No target selected.", "Error")
+        }
+    })
+    .button("Refresh", cclone!([config, queue], move |curs| {
+        upgrade!(config, queue);
+        let objects = sky_objects(&queue.borrow(), &config.borrow());
+        curs.call_on_name(names::SKY_MAP, |v: &mut SkyMapView| v.set_objects(objects));
+    }))
+    .button("Close", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Sky map")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+/// Returns current positions of the target queue's entries (none if the observer location is not set).
+fn sky_objects(queue: &TargetQueue, config: &Configuration) -> Vec<SkyObject> {
+    let Some(observer) = config.observer_location() else { return vec![]; };
+    let now = clock::utc_now();
+
+    queue.entries().iter().enumerate().filter_map(|(idx, entry)| {
+        let target = entry.source().and_then(|mut source| source.target(now, &observer));
+        match target {
+            Ok(target) => Some(SkyObject{
+                name: entry.name().into(),
+                idx,
+                azimuth: as_deg(target.azimuth).rem_euclid(360.0),
+                altitude: as_deg(target.altitude),
+                current: queue.current() == Some(idx)
+            }),
+            Err(e) => { log::warn!("cannot calculate position of {}: {}", entry.name(), e); None }
+        }
+    }).collect()
+}
+
+fn on_select_object(
+    curs: &mut cursive::Cursive,
+    idx: usize,
+    tui: &Rc<RefCell<Option<TuiData>>>,
+    connection: &data_receiver::Connection,
+    config: &Rc<RefCell<Configuration>>,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    queue: &Rc<RefCell<TargetQueue>>
+) {
+    if config.borrow().observer_location().is_none() {
+        msg_box(curs, "Observer location not set (press O to set it).", "Error");
+        return;
+    }
+
+    match event_handling::select_queue_entry(idx, queue, connection, target_source, tui) {
+        Ok(()) => close_dialog(curs, tui),
+
+        Err(e) => {
+            log::error!("error selecting target on sky map: {}", e);
+            msg_box(curs, &format!("Cannot track the selected target:\n{}.", e), "Error");
+        }
+    }
+}
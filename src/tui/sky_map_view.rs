@@ -0,0 +1,226 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{data::{as_deg, PositionHistory}, tui::color_scheme};
+use cursive::{
+    direction::Direction,
+    event::{Event, EventResult, Key, MouseButton, MouseEvent},
+    theme::{ColorStyle, Effect},
+    view::CannotFocus,
+    Printer,
+    Vec2,
+    View
+};
+use std::{cell::RefCell, rc::{Rc, Weak}};
+
+/// Horizon radius (in rows; horizontally twice as many columns are used).
+const RADIUS: usize = 10;
+const MAP_WIDTH: usize = 4 * RADIUS + 1;
+const MAP_HEIGHT: usize = 2 * RADIUS + 1;
+/// Max. distance (in rows) between a mouse click and an object for the object to be selected.
+const MAX_CLICK_DIST: f64 = 2.0;
+
+const MOUNT_CHAR: &str = "*";
+const TARGET_CHAR: &str = "+";
+const OBJECT_LABELS: &str = "123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Object shown on the sky map.
+pub struct SkyObject {
+    pub name: String,
+    /// Index of the corresponding target queue entry.
+    pub idx: usize,
+    /// Degrees.
+    pub azimuth: f64,
+    /// Degrees.
+    pub altitude: f64,
+    /// Whether the object is being tracked.
+    pub current: bool
+}
+
+/// Azimuthal projection of the upper hemisphere (north up, east to the right, zenith in the center) with known
+/// objects, the mount and the tracked target; an object can be selected with arrow keys or mouse.
+pub struct SkyMapView {
+    history: Weak<RefCell<PositionHistory>>,
+    objects: Vec<SkyObject>,
+    selected: Option<usize>,
+    /// Called with the selected object's queue entry index when Enter is pressed.
+    on_submit: Rc<dyn Fn(&mut cursive::Cursive, usize)>
+}
+
+impl SkyMapView {
+    pub fn new<F: Fn(&mut cursive::Cursive, usize) + 'static>(
+        history: Weak<RefCell<PositionHistory>>,
+        objects: Vec<SkyObject>,
+        on_submit: F
+    ) -> SkyMapView {
+        let mut view = SkyMapView{ history, objects: vec![], selected: None, on_submit: Rc::new(on_submit) };
+        view.set_objects(objects);
+        view
+    }
+
+    /// Replaces the objects; the current one (if any) becomes selected.
+    pub fn set_objects(&mut self, objects: Vec<SkyObject>) {
+        self.selected = objects.iter().position(|o| o.current).or(if objects.is_empty() { None } else { Some(0) });
+        self.objects = objects;
+    }
+
+    /// Returns the queue entry index of the selected object.
+    pub fn selected(&self) -> Option<usize> { self.selected.map(|s| self.objects[s].idx) }
+
+    fn select_next(&mut self, forward: bool) {
+        let n = self.objects.len();
+        if n == 0 { return; }
+        self.selected = Some(match self.selected {
+            Some(s) => if forward { (s + 1) % n } else { (s + n - 1) % n },
+            None => 0
+        });
+    }
+}
+
+impl View for SkyMapView {
+    fn draw(&self, printer: &Printer) {
+        draw_grid(printer);
+
+        let target_style = color_scheme::target_style(printer);
+        for (idx, object) in self.objects.iter().enumerate() {
+            let Some(cell) = map_cell(object.azimuth, object.altitude) else { continue; };
+            let style = if object.current { target_style } else { ColorStyle::primary() };
+            let effect = if self.selected == Some(idx) { Effect::Reverse } else { Effect::Simple };
+            printer.with_color(style, |p| p.with_effect(effect, |p| p.print(cell, object_label(idx))));
+        }
+
+        let latest = self.history.upgrade().and_then(|h| h.borrow().samples().back().copied()).unwrap_or_default();
+        let to_deg = |(az, alt)| (as_deg(az).rem_euclid(360.0), as_deg(alt));
+        if let Some(cell) = latest.target.map(to_deg).and_then(|(az, alt)| map_cell(az, alt)) {
+            printer.with_color(target_style, |p| p.print(cell, TARGET_CHAR));
+        }
+        if let Some(cell) = latest.mount.map(to_deg).and_then(|(az, alt)| map_cell(az, alt)) {
+            printer.with_color(color_scheme::mount_style(printer), |p| p.print(cell, MOUNT_CHAR));
+        }
+
+        let info = match self.selected.map(|s| (s, &self.objects[s])) {
+            Some((s, o)) => format!(
+                "{}: {}  az. {:.1}°  alt. {:.1}°{}",
+                object_label(s),
+                o.name,
+                o.azimuth,
+                o.altitude,
+                if o.altitude < 0.0 { " (below horizon)" } else { "" }
+            ),
+            None => "no targets (load a target queue with U)".into()
+        };
+        printer.print((0, MAP_HEIGHT), &info);
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(MAP_WIDTH + 2, MAP_HEIGHT + 1)
+    }
+
+    fn take_focus(&mut self, _: Direction) -> Result<EventResult, CannotFocus> {
+        Ok(EventResult::Consumed(None))
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(Key::Left) | Event::Key(Key::Up) => self.select_next(false),
+            Event::Key(Key::Right) | Event::Key(Key::Down) => self.select_next(true),
+
+            Event::Key(Key::Enter) => {
+                let Some(idx) = self.selected() else { return EventResult::Ignored; };
+                let on_submit = self.on_submit.clone();
+                return EventResult::with_cb(move |curs| on_submit(curs, idx));
+            },
+
+            Event::Mouse{ offset, position, event: MouseEvent::Press(MouseButton::Left) } => {
+                let Some(pos) = position.checked_sub(offset) else { return EventResult::Ignored; };
+                let cells = self.objects.iter().map(|o| map_cell(o.azimuth, o.altitude));
+                match nearest_cell(cells, (pos.x, pos.y)) {
+                    Some(idx) => self.selected = Some(idx),
+                    None => return EventResult::Ignored
+                }
+            },
+
+            _ => return EventResult::Ignored
+        }
+
+        EventResult::Consumed(None)
+    }
+}
+
+/// Returns the map cell corresponding to the given azimuth (clockwise from north) and altitude (in degrees);
+/// `None` if below the horizon.
+fn map_cell(azimuth: f64, altitude: f64) -> Option<(usize, usize)> {
+    if altitude < 0.0 { return None; }
+    let r = (90.0 - altitude.min(90.0)) / 90.0 * RADIUS as f64;
+    let (sin, cos) = azimuth.to_radians().sin_cos();
+    let x = (2 * RADIUS) as f64 + 2.0 * r * sin;
+    let y = RADIUS as f64 - r * cos;
+    Some((x.round() as usize, y.round() as usize))
+}
+
+/// Returns index of the cell nearest to `pos` (if within `MAX_CLICK_DIST`).
+fn nearest_cell<I: Iterator<Item = Option<(usize, usize)>>>(cells: I, pos: (usize, usize)) -> Option<usize> {
+    let dist = |(x, y): (usize, usize)| {
+        // columns are half as wide as rows are high
+        let dx = (x as f64 - pos.0 as f64) / 2.0;
+        let dy = y as f64 - pos.1 as f64;
+        (dx * dx + dy * dy).sqrt()
+    };
+
+    cells.enumerate()
+        .filter_map(|(idx, cell)| cell.map(|c| (idx, dist(c))))
+        .filter(|(_, d)| *d <= MAX_CLICK_DIST)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(idx, _)| idx)
+}
+
+fn object_label(idx: usize) -> &'static str {
+    OBJECT_LABELS.get(idx..idx + 1).unwrap_or("o")
+}
+
+fn draw_grid(printer: &Printer) {
+    printer.with_color(color_scheme::rim_style(printer), |p| {
+        for i in 0..72 {
+            let azimuth = i as f64 * 5.0;
+            p.print(map_cell(azimuth, 0.0).unwrap(), "·");
+            if i % 2 == 0 { p.print(map_cell(azimuth, 45.0).unwrap(), "."); }
+        }
+        p.print(map_cell(0.0, 90.0).unwrap(), "·");
+    });
+    for (azimuth, label) in [(0.0, "N"), (90.0, "E"), (180.0, "S"), (270.0, "W")] {
+        printer.print(map_cell(azimuth, 0.0).unwrap(), label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projection_and_picking() {
+        assert_eq!(Some((2 * RADIUS, RADIUS)), map_cell(123.0, 90.0));
+        assert_eq!(Some((4 * RADIUS, RADIUS)), map_cell(90.0, 0.0));
+        assert_eq!(Some((2 * RADIUS, 2 * RADIUS)), map_cell(180.0, 0.0));
+        assert_eq!(None, map_cell(0.0, -1.0));
+
+        let cells = [map_cell(0.0, 0.0), None, map_cell(90.0, 45.0)];
+        assert_eq!(Some(2), nearest_cell(cells.into_iter(), (3 * RADIUS + 1, RADIUS)));
+        assert_eq!(Some(0), nearest_cell(cells.into_iter(), (2 * RADIUS, 1)));
+        assert_eq!(None, nearest_cell(cells.into_iter(), (0, 2 * RADIUS)));
+    }
+}
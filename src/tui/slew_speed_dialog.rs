@@ -0,0 +1,147 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{
+    cclone,
+    config::Configuration,
+    data::{as_deg_per_s, deg_per_s},
+    event_handling,
+    mount::{Mount, MountWrapper},
+    tui,
+    tui::{close_dialog, get_edit_view_str, msg_box, names, set_edit_view_str, TuiData},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Nameable, Resizable, View},
+    views::{
+        CircularFocus,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        SelectView,
+        TextView,
+    },
+    With
+};
+use pointing_utils::uom;
+use std::{cell::RefCell, rc::Weak};
+use uom::si::f64;
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    slew_speed: Weak<RefCell<f64::AngularVelocity>>,
+    config: Weak<RefCell<Configuration>>,
+    mount: Weak<RefCell<Option<MountWrapper>>>
+) -> impl View {
+    let current = *slew_speed.upgrade().unwrap().borrow();
+    let mount_max = mount.upgrade().unwrap().borrow().as_ref().and_then(|m| m.max_slew_speed());
+    let (min, max) = config.upgrade().unwrap().borrow().slew_speed_limits(mount_max);
+
+    let mut presets = SelectView::<f64::AngularVelocity>::new();
+    fill_presets(&mut presets, &config.upgrade().unwrap().borrow().slew_speed_presets());
+    presets.set_on_submit(|curs, preset: &f64::AngularVelocity| {
+        set_edit_view_str(curs, names::SLEW_SPEED_VALUE, format!("{}", as_deg_per_s(*preset)));
+    });
+
+    Dialog::around(LinearLayout::vertical()
+        .child(LinearLayout::horizontal()
+            .child(TextView::new("Slew speed (°/s): "))
+            .child(tui::styled_edit_view()
+                .content(format!("{}", as_deg_per_s(current)))
+                .with_name(names::SLEW_SPEED_VALUE)
+                .fixed_width(8)
+            )
+        )
+        .child(TextView::new(format!(
+            "Allowed range: {}–{}°/s", as_deg_per_s(min), as_deg_per_s(max)
+        )))
+        .child(DummyView{})
+        .child(TextView::new("Presets (select with keys 1-9):"))
+        .child(presets.with_name(names::SLEW_SPEED_PRESET_LIST))
+    )
+    .button("OK", cclone!([tui, slew_speed, config, mount], move |curs| {
+        upgrade!(tui, slew_speed, config, mount);
+        match read_speed(curs) {
+            Ok(value) => {
+                event_handling::set_slew_speed(value, &slew_speed, &tui, &config, &mount);
+                close_dialog(curs, &tui);
+            },
+            Err(e) => msg_box(curs, &e, "Error")
+        }
+    }))
+    .button("Add preset", cclone!([config], move |curs| {
+        upgrade!(config);
+        match read_speed(curs) {
+            Ok(value) => {
+                let mut presets = config.borrow().slew_speed_presets();
+                if presets.len() >= 9 {
+                    msg_box(curs, "Too many presets.", "Error");
+                    return;
+                }
+                presets.push(value);
+                config.borrow_mut().set_slew_speed_presets(&presets);
+                update_presets(curs, &presets);
+            },
+            Err(e) => msg_box(curs, &e, "Error")
+        }
+    }))
+    .button("Remove preset", cclone!([config], move |curs| {
+        upgrade!(config);
+        let selected = curs.call_on_name(
+            names::SLEW_SPEED_PRESET_LIST,
+            |v: &mut SelectView<f64::AngularVelocity>| v.selected_id()
+        ).unwrap();
+        if let Some(idx) = selected {
+            let mut presets = config.borrow().slew_speed_presets();
+            presets.remove(idx);
+            config.borrow_mut().set_slew_speed_presets(&presets);
+            update_presets(curs, &presets);
+        }
+    }))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Slew speed")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn fill_presets(view: &mut SelectView<f64::AngularVelocity>, presets: &[f64::AngularVelocity]) {
+    view.clear();
+    for (i, preset) in presets.iter().enumerate() {
+        view.add_item(format!("{}: {}°/s", i + 1, as_deg_per_s(*preset)), *preset);
+    }
+}
+
+fn update_presets(curs: &mut cursive::Cursive, presets: &[f64::AngularVelocity]) {
+    curs.call_on_name(names::SLEW_SPEED_PRESET_LIST, |v: &mut SelectView<f64::AngularVelocity>| {
+        fill_presets(v, presets);
+    });
+}
+
+fn read_speed(curs: &mut cursive::Cursive) -> Result<f64::AngularVelocity, String> {
+    let s = get_edit_view_str(curs, names::SLEW_SPEED_VALUE);
+    match s.parse::<f64>() {
+        Ok(value) if value > 0.0 => Ok(deg_per_s(value)),
+        _ => Err(format!("Invalid slew speed: {}.", s))
+    }
+}
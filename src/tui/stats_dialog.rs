@@ -0,0 +1,56 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    event_handling,
+    tracking::TrackingController,
+    tui::{close_dialog, msg_box, TuiData},
+    upgrade
+};
+use cursive::{
+    event,
+    view::{Resizable, View},
+    views::{CircularFocus, Dialog, OnEventView, TextContent, TextView},
+    With
+};
+use std::{cell::RefCell, rc::Weak};
+
+pub fn dialog(tui: Weak<RefCell<Option<TuiData>>>, tracking: TrackingController) -> impl View {
+    let summary = TextContent::new(tracking.stats().summary());
+
+    Dialog::around(TextView::new_with_content(summary.clone()).min_width(45))
+        .button("Refresh", cclone!([tracking, summary], move |_| summary.set_content(tracking.stats().summary())))
+        .button("Reset", cclone!([tracking, summary], move |_| {
+            tracking.reset_stats();
+            summary.set_content(tracking.stats().summary());
+        }))
+        .button("Save", cclone!([tracking], move |curs| {
+            match event_handling::write_tracking_summary(&tracking.stats()) {
+                Ok(path) => msg_box(curs, &format!("Summary saved to:\n{}", path.to_string_lossy()), "Statistics"),
+                Err(e) => msg_box(curs, &format!("Failed to save summary:\n{}.", e), "Error")
+            }
+        }))
+        .button("Close", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+        .title("Tracking statistics")
+        .wrap_with(CircularFocus::new)
+        .wrap_tab()
+        .wrap_with(OnEventView::new)
+        .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+            move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+        ))
+}
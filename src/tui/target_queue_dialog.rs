@@ -0,0 +1,152 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+use crate::{
+    cclone,
+    config::Configuration,
+    data_receiver,
+    event_handling,
+    target_source,
+    target_source::{TargetQueue, TargetSource},
+    tui,
+    tui::{close_dialog, get_edit_view_str, get_select_view_idx, msg_box, names, TuiData},
+    upgrade
+};
+use cursive::{
+    align::HAlign,
+    event,
+    view::{Nameable, Resizable, Scrollable, View},
+    views::{
+        Button,
+        CircularFocus,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        Panel,
+        SelectView,
+        TextView,
+    },
+    With
+};
+use std::{cell::RefCell, path::Path, rc::{Rc, Weak}};
+
+pub fn dialog(
+    tui: Weak<RefCell<Option<TuiData>>>,
+    connection: data_receiver::Connection,
+    config: Weak<RefCell<Configuration>>,
+    target_source: Weak<RefCell<Option<Box<dyn TargetSource>>>>,
+    queue: Weak<RefCell<TargetQueue>>
+) -> impl View {
+    let mut entry_list = SelectView::<usize>::new();
+    fill_entry_list(&mut entry_list, &queue.upgrade().unwrap().borrow());
+
+    Dialog::around(LinearLayout::vertical()
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new("Queue file:"))
+                .child(DummyView{}.min_width(1))
+                .child(tui::styled_edit_view()
+                    .content(config.upgrade().unwrap().borrow().target_queue_file().unwrap_or("".into()))
+                    .with_name(names::TARGET_QUEUE_FILE)
+                    .fixed_width(40)
+                )
+                .child(DummyView{}.min_width(1))
+                .child(Button::new("Load", cclone!([config, queue], move |curs| {
+                    upgrade!(config, queue);
+                    on_load_queue_file(curs, &config, &queue);
+                })))
+        )
+        .child(DummyView{}.min_height(1))
+        .child(Panel::new(
+            entry_list.with_name(names::TARGET_QUEUE_LIST).scrollable().fixed_height(10)
+        ).title("Targets").title_position(HAlign::Left))
+    )
+    .button("OK", cclone!([tui, connection, config, target_source, queue], move |curs| {
+        upgrade!(tui, config, target_source, queue);
+        on_select_entry(curs, &tui, &connection, &config, &target_source, &queue);
+    }))
+    .button("Cancel", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Target queue")
+    .wrap_with(CircularFocus::new)
+    .wrap_tab()
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), crate::cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
+
+fn fill_entry_list(list: &mut SelectView<usize>, queue: &TargetQueue) {
+    list.clear();
+    for (idx, entry) in queue.entries().iter().enumerate() {
+        let marker = if queue.current() == Some(idx) { "*" } else { " " };
+        list.add_item(format!("{} {} ({})", marker, entry.name(), entry.kind()), idx);
+    }
+    if let Some(current) = queue.current() { list.set_selection(current); }
+}
+
+fn on_load_queue_file(
+    curs: &mut cursive::Cursive,
+    config: &Rc<RefCell<Configuration>>,
+    queue: &Rc<RefCell<TargetQueue>>
+) {
+    let path = get_edit_view_str(curs, names::TARGET_QUEUE_FILE);
+    match target_source::load_queue_file(Path::new(path.as_str())) {
+        Ok(entries) => {
+            log::info!("loaded {} target(s) from queue file \"{}\"", entries.len(), path);
+            *queue.borrow_mut() = TargetQueue::new(entries);
+            curs.call_on_name(names::TARGET_QUEUE_LIST, |v: &mut SelectView<usize>| {
+                fill_entry_list(v, &queue.borrow());
+            });
+            config.borrow_mut().set_target_queue_file(&path);
+        },
+
+        Err(e) => {
+            log::error!("error loading target queue file \"{}\": {}", path, e);
+            msg_box(curs, &format!("Failed to load target queue file:\n{}.", e), "Error");
+        }
+    }
+}
+
+fn on_select_entry(
+    curs: &mut cursive::Cursive,
+    tui: &Rc<RefCell<Option<TuiData>>>,
+    connection: &data_receiver::Connection,
+    config: &Rc<RefCell<Configuration>>,
+    target_source: &Rc<RefCell<Option<Box<dyn TargetSource>>>>,
+    queue: &Rc<RefCell<TargetQueue>>
+) {
+    if queue.borrow().entries().is_empty() {
+        msg_box(curs, "No targets loaded.", "Error");
+        return;
+    }
+
+    if config.borrow().observer_location().is_none() {
+        msg_box(curs, "Observer location not set (press O to set it).", "Error");
+        return;
+    }
+
+    let idx = get_select_view_idx(curs, names::TARGET_QUEUE_LIST);
+    match event_handling::select_queue_entry(idx, queue, connection, target_source, tui) {
+        Ok(()) => close_dialog(curs, tui),
+
+        Err(e) => {
+            log::error!("error selecting queued target: {}", e);
+            msg_box(curs, &format!("Cannot track the selected target:\n{}.", e), "Error");
+        }
+    }
+}
@@ -0,0 +1,40 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{cclone, data::PositionHistory, tui::{close_dialog, trend_view::TrendView, TuiData}, upgrade};
+use cursive::{
+    event,
+    View,
+    views::{Dialog, LinearLayout, OnEventView, TextView},
+    With
+};
+use std::{cell::RefCell, rc::Weak};
+
+pub fn dialog(tui: Weak<RefCell<Option<TuiData>>>, pos_history: Weak<RefCell<PositionHistory>>) -> impl View {
+    Dialog::around(
+        LinearLayout::vertical()
+            .child(TrendView::new(pos_history))
+            .child(TextView::new("* mount   + target   # both   (last 5 minutes)"))
+    )
+    .button("Close", cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
+    .title("Position trend")
+    .wrap_with(OnEventView::new)
+    .on_event(event::Event::Key(event::Key::Esc), cclone!([tui],
+        move |curs| { upgrade!(tui); close_dialog(curs, &tui); }
+    ))
+}
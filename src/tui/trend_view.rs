@@ -0,0 +1,131 @@
+// TPTool (Telescope Pointing Tool) — following a target in the sky
+// Copyright (C) 2024 Filip Szczerek <ga.software@yahoo.com>
+//
+// This file is part of TPTool
+//
+// TPTool is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3
+// as published by the Free Software Foundation.
+//
+// TPTool is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with TPTool.  If not, see <http://www.gnu.org/licenses/>.
+//
+
+use crate::{data::{as_deg, PositionHistory, PositionSample}, tui::color_scheme};
+use cursive::{Printer, Vec2, View};
+use std::{cell::RefCell, rc::Weak};
+
+const LABEL_WIDTH: usize = 9;
+/// Minimum vertical range of a plot (degrees).
+const MIN_RANGE: f64 = 0.1;
+
+const MOUNT_CHAR: &str = "*";
+const TARGET_CHAR: &str = "+";
+const BOTH_CHAR: &str = "#";
+
+/// Plots of mount and target azimuth and altitude over time (newest on the right).
+pub struct TrendView {
+    history: Weak<RefCell<PositionHistory>>
+}
+
+impl TrendView {
+    pub fn new(history: Weak<RefCell<PositionHistory>>) -> TrendView {
+        TrendView{ history }
+    }
+}
+
+impl View for TrendView {
+    fn draw(&self, printer: &Printer) {
+        let history = match self.history.upgrade() { Some(h) => h, None => return };
+        let history = history.borrow();
+        if printer.size.x <= LABEL_WIDTH || printer.size.y < 4 { return; }
+
+        let num_columns = printer.size.x - LABEL_WIDTH;
+        let columns = downsample(history.samples(), history.max_len(), num_columns);
+        let plot_height = printer.size.y / 2;
+
+        draw_plot(
+            &printer.offset((0, 0)).cropped((printer.size.x, plot_height)),
+            "az.",
+            &columns.iter().map(|s| (s.mount.map(|p| as_deg(p.0)), s.target.map(|p| as_deg(p.0)))).collect::<Vec<_>>(),
+            true
+        );
+        draw_plot(
+            &printer.offset((0, plot_height)).cropped((printer.size.x, printer.size.y - plot_height)),
+            "alt.",
+            &columns.iter().map(|s| (s.mount.map(|p| as_deg(p.1)), s.target.map(|p| as_deg(p.1)))).collect::<Vec<_>>(),
+            false
+        );
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(LABEL_WIDTH + 64, 16)
+    }
+}
+
+/// Returns one sample per column; the full history span corresponds to `num_columns`.
+fn downsample(
+    samples: &std::collections::VecDeque<PositionSample>,
+    max_len: usize,
+    num_columns: usize
+) -> Vec<PositionSample> {
+    let per_column = (max_len as f64 / num_columns as f64).max(1.0);
+    let mut result = vec![PositionSample::default(); num_columns];
+    for (col, column) in result.iter_mut().rev().enumerate() {
+        let idx_from_end = (col as f64 * per_column) as usize;
+        if idx_from_end >= samples.len() { break; }
+        *column = samples[samples.len() - 1 - idx_from_end];
+    }
+
+    result
+}
+
+/// Returns `value` shifted by a multiple of 360° to be closest to `reference`.
+fn unwrap_deg(value: f64, reference: f64) -> f64 {
+    reference + (value - reference + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Draws values of mount (first) and target (second) position.
+fn draw_plot(printer: &Printer, label: &str, values: &[(Option<f64>, Option<f64>)], wraps: bool) {
+    let reference = values.iter().rev().find_map(|(m, t)| m.or(*t));
+    let reference = match reference { Some(r) => r, None => { printer.print((0, 0), label); return; } };
+
+    let values: Vec<(Option<f64>, Option<f64>)> = if wraps {
+        values.iter().map(|(m, t)| (m.map(|v| unwrap_deg(v, reference)), t.map(|v| unwrap_deg(v, reference)))).collect()
+    } else {
+        values.to_vec()
+    };
+
+    let all = values.iter().flat_map(|(m, t)| m.iter().chain(t.iter()).copied());
+    let (mut min, mut max) = all.fold((f64::MAX, f64::MIN), |(min, max), v| (min.min(v), max.max(v)));
+    if max - min < MIN_RANGE {
+        let center = (min + max) / 2.0;
+        min = center - MIN_RANGE / 2.0;
+        max = center + MIN_RANGE / 2.0;
+    }
+
+    let height = printer.size.y;
+    let row = |v: f64| ((max - v) / (max - min) * (height - 1) as f64).round() as usize;
+
+    printer.print((0, 0), &format!("{:>7.2}°", max));
+    printer.print((0, height / 2), label);
+    printer.print((0, height - 1), &format!("{:>7.2}°", min));
+
+    let mount_style = color_scheme::mount_style(printer);
+    let target_style = color_scheme::target_style(printer);
+    for (col, (m, t)) in values.iter().enumerate() {
+        let x = LABEL_WIDTH + col;
+        match (m.map(row), t.map(row)) {
+            (Some(rm), Some(rt)) if rm == rt => printer.print((x, rm), BOTH_CHAR),
+            (rm, rt) => {
+                if let Some(rt) = rt { printer.with_color(target_style, |p| p.print((x, rt), TARGET_CHAR)); }
+                if let Some(rm) = rm { printer.with_color(mount_style, |p| p.print((x, rm), MOUNT_CHAR)); }
+            }
+        }
+    }
+}
@@ -47,6 +47,7 @@ pub fn dialog(
         LinearLayout::vertical()
             // TODO: give (and implement) the option of "go to zero position"
             .child(TextView::new("Mark the current mount position as the zero (home) position?"))
+            .child(TextView::new("(\"Reset travel\" only resets the total axis travel.)"))
     )
     .button("OK", cclone!([tui, mount], move |curs| {
         upgrade!(tui, mount);
@@ -57,6 +58,12 @@ pub fn dialog(
             close_dialog(curs, &tui)
         }
     }))
+    .button("Reset travel", cclone!([tui, mount], move |curs| {
+        upgrade!(tui, mount);
+        mount.borrow_mut().as_mut().unwrap().reset_axis_travel();
+        log::info!("total axis travel reset");
+        close_dialog(curs, &tui);
+    }))
     .button("Cancel", crate::cclone!([tui], move |curs| { upgrade!(tui); close_dialog(curs, &tui); }))
     .title("Zero position")
     .wrap_with(CircularFocus::new)